@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS torrust_tracker_jobs (
+    job_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    kind VARCHAR(32) NOT NULL,
+    info_hash CHAR(40) NOT NULL,
+    torrent_id INTEGER NULL,
+    status VARCHAR(16) NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT NULL,
+    next_attempt_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    INDEX idx_tracker_jobs_status_next_attempt (status, next_attempt_at)
+)
@@ -0,0 +1,3 @@
+-- Allow stats from multiple trackers per torrent. The unique key
+-- (torrent_id, tracker_url) is kept.
+ALTER TABLE torrust_torrent_tracker_stats DROP PRIMARY KEY;
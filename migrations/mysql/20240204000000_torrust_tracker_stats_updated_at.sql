@@ -0,0 +1 @@
+ALTER TABLE torrust_torrent_tracker_stats ADD COLUMN updated_at DATETIME NULL;
@@ -0,0 +1,3 @@
+ALTER TABLE torrust_torrents ADD COLUMN last_seeded_at DATETIME NULL;
+ALTER TABLE torrust_torrents ADD COLUMN dead_since DATETIME NULL;
+ALTER TABLE torrust_torrents ADD COLUMN archived_at DATETIME NULL;
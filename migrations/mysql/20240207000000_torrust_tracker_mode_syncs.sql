@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS torrust_tracker_mode_syncs (
+    sync_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    from_mode VARCHAR(32) NULL,
+    to_mode VARCHAR(32) NOT NULL,
+    action VARCHAR(32) NOT NULL,
+    status VARCHAR(16) NOT NULL,
+    last_torrent_id INTEGER NOT NULL DEFAULT 0,
+    torrents_total INTEGER NOT NULL DEFAULT 0,
+    torrents_processed INTEGER NOT NULL DEFAULT 0,
+    errors INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT NULL,
+    started_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    finished_at DATETIME NULL
+)
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_web_seeds (
+    web_seed_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    torrent_id INTEGER NOT NULL,
+    seed_type VARCHAR(16) NOT NULL,
+    url VARCHAR(2048) NOT NULL,
+    FOREIGN KEY(torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS torrust_images (
+    image_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    user_id INTEGER NOT NULL,
+    content_hash CHAR(40) NOT NULL,
+    content_type VARCHAR(32) NOT NULL,
+    size INTEGER NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    date_uploaded DATETIME NOT NULL,
+    FOREIGN KEY(user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS torrust_torrent_images (
+    torrent_id INTEGER NOT NULL,
+    image_id INTEGER NOT NULL,
+    image_type VARCHAR(16) NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY(torrent_id, image_type, image_id),
+    FOREIGN KEY(torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    FOREIGN KEY(image_id) REFERENCES torrust_images(image_id) ON DELETE CASCADE
+);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS torrust_image_cache_quotas (
+    user_id INTEGER NOT NULL PRIMARY KEY,
+    usage_bytes BIGINT NOT NULL DEFAULT 0,
+    variant_usage_bytes BIGINT NOT NULL DEFAULT 0,
+    date_start_secs BIGINT NOT NULL,
+    FOREIGN KEY(user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS torrust_mail_queue (
+    mail_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    kind VARCHAR(32) NOT NULL,
+    user_id INTEGER NULL,
+    sender VARCHAR(320) NOT NULL,
+    recipient VARCHAR(320) NOT NULL,
+    subject TEXT NOT NULL,
+    message MEDIUMBLOB NOT NULL,
+    status VARCHAR(16) NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT NULL,
+    next_attempt_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    INDEX idx_mail_queue_status_next_attempt (status, next_attempt_at),
+    FOREIGN KEY(user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+)
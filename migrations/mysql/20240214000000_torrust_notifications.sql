@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS torrust_notifications (
+    notification_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    user_id INTEGER NOT NULL,
+    kind VARCHAR(32) NOT NULL,
+    torrent_id INTEGER NULL,
+    info_hash CHAR(40) NULL,
+    torrent_title VARCHAR(256) NULL,
+    detail TEXT NULL,
+    in_app BOOL NOT NULL DEFAULT TRUE,
+    digest_pending BOOL NOT NULL DEFAULT FALSE,
+    is_read BOOL NOT NULL DEFAULT FALSE,
+    created_at DATETIME NOT NULL,
+    INDEX idx_notifications_user_read (user_id, is_read),
+    FOREIGN KEY(user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS torrust_notification_preferences (
+    user_id INTEGER NOT NULL PRIMARY KEY,
+    in_app BOOL NOT NULL DEFAULT TRUE,
+    email_digest BOOL NOT NULL DEFAULT FALSE,
+    immediate_email BOOL NOT NULL DEFAULT FALSE,
+    FOREIGN KEY(user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+ALTER TABLE torrust_torrents ADD COLUMN has_seeders BOOL NOT NULL DEFAULT FALSE;
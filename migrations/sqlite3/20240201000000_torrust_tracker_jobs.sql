@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS torrust_tracker_jobs (
+    job_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    info_hash TEXT NOT NULL,
+    torrent_id INTEGER NULL,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT NULL,
+    next_attempt_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+)
@@ -0,0 +1,20 @@
+-- Step 1: Create a new table that allows stats from multiple trackers per torrent
+CREATE TABLE IF NOT EXISTS torrust_torrent_tracker_stats_new (
+    torrent_id INTEGER NOT NULL,
+    tracker_url VARCHAR(256) NOT NULL,
+    seeders INTEGER NOT NULL DEFAULT 0,
+    leechers INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY(torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    UNIQUE(torrent_id, tracker_url)
+);
+
+-- Step 2: Copy rows from the current table to the new table
+INSERT INTO torrust_torrent_tracker_stats_new (torrent_id, tracker_url, seeders, leechers)
+SELECT torrent_id, tracker_url, seeders, leechers
+FROM torrust_torrent_tracker_stats;
+
+-- Step 3: Delete the current table
+DROP TABLE torrust_torrent_tracker_stats;
+
+-- Step 4: Rename the new table
+ALTER TABLE torrust_torrent_tracker_stats_new RENAME TO torrust_torrent_tracker_stats;
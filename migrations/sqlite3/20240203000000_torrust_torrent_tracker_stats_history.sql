@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_tracker_stats_history (
+    snapshot_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    torrent_id INTEGER NOT NULL,
+    seeders INTEGER NOT NULL DEFAULT 0,
+    leechers INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY(torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_torrent_tracker_stats_history_torrent_created_at ON torrust_torrent_tracker_stats_history (torrent_id, created_at);
@@ -0,0 +1,3 @@
+ALTER TABLE torrust_torrents ADD COLUMN last_seeded_at TEXT NULL;
+ALTER TABLE torrust_torrents ADD COLUMN dead_since TEXT NULL;
+ALTER TABLE torrust_torrents ADD COLUMN archived_at TEXT NULL;
@@ -0,0 +1,13 @@
+ALTER TABLE torrust_torrents ADD COLUMN times_downloaded INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE torrust_torrent_tracker_stats ADD COLUMN completed INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS torrust_user_torrent_downloads (
+    user_id INTEGER NOT NULL,
+    torrent_id INTEGER NOT NULL,
+    times_downloaded INTEGER NOT NULL DEFAULT 0,
+    last_downloaded_at TEXT NOT NULL,
+    PRIMARY KEY (user_id, torrent_id),
+    FOREIGN KEY(user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE,
+    FOREIGN KEY(torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS torrust_tracker_mode_syncs (
+    sync_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    from_mode TEXT NULL,
+    to_mode TEXT NOT NULL,
+    action TEXT NOT NULL,
+    status TEXT NOT NULL,
+    last_torrent_id INTEGER NOT NULL DEFAULT 0,
+    torrents_total INTEGER NOT NULL DEFAULT 0,
+    torrents_processed INTEGER NOT NULL DEFAULT 0,
+    errors INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT NULL,
+    started_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    finished_at TEXT NULL
+)
@@ -0,0 +1 @@
+ALTER TABLE torrust_user_profiles ADD COLUMN locale TEXT NULL;
@@ -0,0 +1 @@
+ALTER TABLE torrust_image_cache_quotas ADD COLUMN variant_usages TEXT NULL;
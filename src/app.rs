@@ -8,24 +8,37 @@ use crate::cache::image::manager::ImageCacheService;
 use crate::common::AppData;
 use crate::config::Configuration;
 use crate::databases::database;
+use crate::mailer::queue::MailQueue;
 use crate::services::authentication::{DbUserAuthenticationRepository, JsonWebToken, Service};
 use crate::services::category::{self, DbCategoryRepository};
+use crate::services::image::{self, DbImageRepository, DbTorrentImageRepository};
+use crate::services::notification::{self, DbNotificationRepository, Notifier};
 use crate::services::tag::{self, DbTagRepository};
 use crate::services::torrent::{
     DbCanonicalInfoHashGroupRepository, DbTorrentAnnounceUrlRepository, DbTorrentFileRepository, DbTorrentInfoRepository,
-    DbTorrentListingGenerator, DbTorrentRepository, DbTorrentTagRepository,
+    DbTorrentListingGenerator, DbTorrentRepository, DbTorrentTagRepository, DbTorrentWebSeedRepository,
+};
+use crate::services::user::{
+    self, DbBannedUserList, DbEmailVerificationTokenRepository, DbUserProfileRepository, DbUserRepository,
 };
-use crate::services::user::{self, DbBannedUserList, DbUserProfileRepository, DbUserRepository};
 use crate::services::{proxy, settings, torrent};
+use crate::tracker::job_queue::JobQueue;
 use crate::tracker::statistics_importer::StatisticsImporter;
 use crate::web::api::server::v1::auth::Authentication;
 use crate::web::api::Version;
-use crate::{console, mailer, tracker, web};
+use crate::{console, mailer, services, tracker, web};
 
 pub struct Running {
     pub api_socket_addr: SocketAddr,
     pub api_server: Option<JoinHandle<std::result::Result<(), std::io::Error>>>,
     pub tracker_data_importer_handle: tokio::task::JoinHandle<()>,
+    pub tracker_jobs_worker_handle: tokio::task::JoinHandle<()>,
+    pub mail_queue_worker_handle: tokio::task::JoinHandle<()>,
+    pub notification_digest_handle: tokio::task::JoinHandle<()>,
+    pub dead_torrents_handle: tokio::task::JoinHandle<()>,
+    pub unverified_accounts_handle: tokio::task::JoinHandle<()>,
+    pub tracker_mode_sync_handle: tokio::task::JoinHandle<()>,
+    pub config_watcher_handle: tokio::task::JoinHandle<()>,
 }
 
 /// Runs the application.
@@ -39,6 +52,10 @@ pub async fn run(configuration: Configuration, api_version: &Version) -> Running
 
     logging::setup(&log_level);
 
+    for key in configuration.overridden_settings() {
+        log::info!("Setting `{key}` overridden with an env var");
+    }
+
     configuration.validate().await.expect("invalid configuration");
 
     let configuration = Arc::new(configuration);
@@ -73,22 +90,46 @@ pub async fn run(configuration: Configuration, api_version: &Version) -> Running
     let user_repository = Arc::new(DbUserRepository::new(database.clone()));
     let user_authentication_repository = Arc::new(DbUserAuthenticationRepository::new(database.clone()));
     let user_profile_repository = Arc::new(DbUserProfileRepository::new(database.clone()));
+    let email_verification_token_repository = Arc::new(DbEmailVerificationTokenRepository::new(database.clone()));
     let torrent_repository = Arc::new(DbTorrentRepository::new(database.clone()));
     let canonical_info_hash_group_repository = Arc::new(DbCanonicalInfoHashGroupRepository::new(database.clone()));
     let torrent_info_repository = Arc::new(DbTorrentInfoRepository::new(database.clone()));
     let torrent_file_repository = Arc::new(DbTorrentFileRepository::new(database.clone()));
     let torrent_announce_url_repository = Arc::new(DbTorrentAnnounceUrlRepository::new(database.clone()));
+    let torrent_web_seed_repository = Arc::new(DbTorrentWebSeedRepository::new(database.clone()));
+    let torrent_image_repository = Arc::new(DbTorrentImageRepository::new(database.clone()));
+    let image_repository = Arc::new(DbImageRepository::new(database.clone()));
     let torrent_tag_repository = Arc::new(DbTorrentTagRepository::new(database.clone()));
     let torrent_listing_generator = Arc::new(DbTorrentListingGenerator::new(database.clone()));
     let banned_user_list = Arc::new(DbBannedUserList::new(database.clone()));
+    let notification_repository = Arc::new(DbNotificationRepository::new(database.clone()));
 
     // Services
     let tracker_service = Arc::new(tracker::service::Service::new(configuration.clone(), database.clone()).await);
     let tracker_statistics_importer =
         Arc::new(StatisticsImporter::new(configuration.clone(), tracker_service.clone(), database.clone()).await);
-    let mailer_service = Arc::new(mailer::Service::new(configuration.clone()).await);
-    let image_cache_service: Arc<ImageCacheService> = Arc::new(ImageCacheService::new(configuration.clone()).await);
-    let category_service = Arc::new(category::Service::new(category_repository.clone(), user_repository.clone()));
+    let tracker_job_queue = Arc::new(JobQueue::new(
+        configuration.clone(),
+        database.clone(),
+        tracker_service.clone(),
+        tracker_statistics_importer.clone(),
+    ));
+    let mail_queue = Arc::new(MailQueue::new(configuration.clone(), database.clone()));
+    let mailer_service = Arc::new(mailer::Service::new(configuration.clone(), mail_queue.clone()).await);
+    let notifier = Arc::new(Notifier::new(
+        configuration.clone(),
+        notification_repository.clone(),
+        user_profile_repository.clone(),
+        mailer_service.clone(),
+    ));
+    let notification_service = Arc::new(notification::Service::new(notification_repository.clone()));
+    let image_cache_service: Arc<ImageCacheService> =
+        Arc::new(ImageCacheService::new(configuration.clone(), database.clone()).await);
+    let category_service = Arc::new(category::Service::new(
+        category_repository.clone(),
+        user_repository.clone(),
+        notifier.clone(),
+    ));
     let tag_service = Arc::new(tag::Service::new(tag_repository.clone(), user_repository.clone()));
     let proxy_service = Arc::new(proxy::Service::new(image_cache_service.clone(), user_repository.clone()));
     let settings_service = Arc::new(settings::Service::new(configuration.clone(), user_repository.clone()));
@@ -96,26 +137,87 @@ pub async fn run(configuration: Configuration, api_version: &Version) -> Running
         configuration.clone(),
         tracker_statistics_importer.clone(),
         tracker_service.clone(),
+        tracker_job_queue.clone(),
         user_repository.clone(),
+        user_profile_repository.clone(),
         category_repository.clone(),
         torrent_repository.clone(),
         canonical_info_hash_group_repository.clone(),
         torrent_info_repository.clone(),
         torrent_file_repository.clone(),
         torrent_announce_url_repository.clone(),
+        torrent_web_seed_repository.clone(),
+        torrent_image_repository.clone(),
         torrent_tag_repository.clone(),
         torrent_listing_generator.clone(),
+        notifier.clone(),
+    ));
+    let image_service = Arc::new(image::Service::new(
+        configuration.clone(),
+        user_repository.clone(),
+        user_profile_repository.clone(),
+        image_repository.clone(),
+        torrent_image_repository.clone(),
+        torrent_listing_generator.clone(),
     ));
     let registration_service = Arc::new(user::RegistrationService::new(
         configuration.clone(),
         mailer_service.clone(),
         user_repository.clone(),
         user_profile_repository.clone(),
+        email_verification_token_repository.clone(),
     ));
     let ban_service = Arc::new(user::BanService::new(
         user_repository.clone(),
         user_profile_repository.clone(),
         banned_user_list.clone(),
+        tracker_service.clone(),
+    ));
+    let deletion_service = Arc::new(user::DeletionService::new(
+        configuration.clone(),
+        database.clone(),
+        user_repository.clone(),
+        user_profile_repository.clone(),
+        tracker_service.clone(),
+        torrent_index.clone(),
+        image_service.clone(),
+    ));
+    let tracker_key_service = Arc::new(user::TrackerKeyService::new(
+        user_repository.clone(),
+        user_profile_repository.clone(),
+        tracker_service.clone(),
+        notifier.clone(),
+    ));
+    let profile_service = Arc::new(user::ProfileService::new(user_profile_repository.clone()));
+    let mail_admin_service = Arc::new(services::mail::Service::new(mail_queue.clone(), user_repository.clone()));
+    let whitelist_reconciler = Arc::new(services::tracker::WhitelistReconciler::new(
+        configuration.clone(),
+        database.clone(),
+        tracker_service.clone(),
+        tracker_job_queue.clone(),
+    ));
+    let tracker_mode_synchronizer = Arc::new(services::tracker::TrackerModeSynchronizer::new(
+        configuration.clone(),
+        database.clone(),
+        tracker_service.clone(),
+        tracker_job_queue.clone(),
+    ));
+    let tracker_admin_service = Arc::new(services::tracker::Service::new(
+        whitelist_reconciler.clone(),
+        tracker_job_queue.clone(),
+        tracker_mode_synchronizer.clone(),
+        user_repository.clone(),
+    ));
+    let dead_torrent_policy = Arc::new(services::dead_torrent::DeadTorrentPolicy::new(
+        configuration.clone(),
+        database.clone(),
+        mailer_service.clone(),
+        tracker_job_queue.clone(),
+        torrent_index.clone(),
+    ));
+    let dead_torrent_service = Arc::new(services::dead_torrent::Service::new(
+        dead_torrent_policy.clone(),
+        user_repository.clone(),
     ));
     let authentication_service = Arc::new(Service::new(
         configuration.clone(),
@@ -147,16 +249,27 @@ pub async fn run(configuration: Configuration, api_version: &Version) -> Running
         torrent_info_repository,
         torrent_file_repository,
         torrent_announce_url_repository,
+        torrent_web_seed_repository,
+        torrent_image_repository,
+        image_repository,
         torrent_tag_repository,
         torrent_listing_generator,
         banned_user_list,
         category_service,
         tag_service,
         proxy_service,
+        image_service,
         settings_service,
         torrent_index,
-        registration_service,
+        registration_service.clone(),
         ban_service,
+        tracker_admin_service,
+        dead_torrent_service,
+        deletion_service.clone(),
+        tracker_key_service,
+        profile_service,
+        mail_admin_service,
+        notification_service,
     ));
 
     // Start cronjob to import tracker torrent data and updating
@@ -165,8 +278,31 @@ pub async fn run(configuration: Configuration, api_version: &Version) -> Running
         importer_port,
         importer_torrent_info_update_interval,
         &tracker_statistics_importer,
+        &notifier,
     );
 
+    // Start worker to execute the pending tracker jobs like whitelisting
+    // torrents.
+    let tracker_jobs_worker_handle = console::cronjobs::tracker_jobs_worker::start(&configuration, &tracker_job_queue);
+
+    // Start worker to deliver the queued emails.
+    let mail_queue_worker_handle = console::cronjobs::mail_queue_worker::start(&configuration, &mail_queue);
+
+    // Start cronjob to send the notification digests.
+    let notification_digest_handle = console::cronjobs::notification_digest::start(&configuration, &notifier);
+
+    // Start cronjob to apply the dead torrent policy.
+    let dead_torrents_handle = console::cronjobs::dead_torrents::start(&configuration, &dead_torrent_policy);
+
+    // Start cronjob to delete the accounts that never verified their email.
+    let unverified_accounts_handle = console::cronjobs::unverified_accounts::start(&configuration, &deletion_service);
+
+    // Start worker to resume and run the tracker mode syncs.
+    let tracker_mode_sync_handle = console::cronjobs::tracker_mode_sync::start(&tracker_mode_synchronizer);
+
+    // Start job to reload the configuration when the config file changes.
+    let config_watcher_handle = console::cronjobs::config_watcher::start(&configuration);
+
     // Start API server
     let running_api = web::api::start(app_data, &net_ip, net_port, api_version).await;
 
@@ -175,5 +311,12 @@ pub async fn run(configuration: Configuration, api_version: &Version) -> Running
         api_socket_addr: running_api.socket_addr,
         api_server: running_api.api_server,
         tracker_data_importer_handle: tracker_statistics_importer_handle,
+        tracker_jobs_worker_handle,
+        mail_queue_worker_handle,
+        notification_digest_handle,
+        dead_torrents_handle,
+        unverified_accounts_handle,
+        tracker_mode_sync_handle,
+        config_watcher_handle,
     }
 }
@@ -0,0 +1,11 @@
+//! Print Config command.
+//!
+//! It prints the effective configuration with secrets masked.
+//!
+//! You can execute it with: `cargo run --bin print_config`
+use torrust_index::console::commands::print_config::app::run;
+
+#[tokio::main]
+async fn main() {
+    run().await;
+}
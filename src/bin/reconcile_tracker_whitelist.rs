@@ -0,0 +1,12 @@
+//! Reconcile Tracker Whitelist command.
+//!
+//! It adds the missing torrents to the tracker whitelist and removes the ones
+//! that do not exist in the index.
+//!
+//! You can execute it with: `cargo run --bin reconcile_tracker_whitelist`
+use torrust_index::console::commands::tracker_whitelist_reconciler::app::run;
+
+#[tokio::main]
+async fn main() {
+    run().await;
+}
@@ -4,7 +4,7 @@
 
 // Environment variables
 
-use crate::config::{Configuration, Info};
+use crate::config::{Configuration, Error, Info, TorrustIndex};
 
 /// The whole `index.toml` file content. It has priority over the config file.
 /// Even if the file is not on the default path.
@@ -16,6 +16,18 @@ const ENV_VAR_API_ADMIN_TOKEN: &str = "TORRUST_INDEX_TRACKER_API_TOKEN";
 /// Secret key used to encrypt and decrypt
 const ENV_VAR_AUTH_SECRET_KEY: &str = "TORRUST_INDEX_AUTH_SECRET_KEY";
 
+/// Prefix for the env vars overriding a single setting. The section and the
+/// setting name are appended using `__` as separator. For example:
+///
+/// - `TORRUST_INDEX_CONFIG__TRACKER__TOKEN=MyAccessToken`
+/// - `TORRUST_INDEX_CONFIG__NET__PORT=3001`
+///
+/// Add the `_FILE` suffix to load the value from a file. It's useful for
+/// Docker or Kubernetes secrets. For example:
+///
+/// - `TORRUST_INDEX_CONFIG__TRACKER__TOKEN_FILE=/run/secrets/tracker_token`
+const ENV_VAR_PREFIX_OVERRIDES: &str = "TORRUST_INDEX_CONFIG";
+
 /// The `index.toml` file location.
 pub const ENV_VAR_PATH_CONFIG: &str = "TORRUST_INDEX_PATH_CONFIG";
 
@@ -34,6 +46,9 @@ pub const ENV_VAR_CORS_PERMISSIVE: &str = "TORRUST_INDEX_API_CORS_PERMISSIVE";
 ///
 /// Environment variable has priority over the config file.
 ///
+/// Missing values are taken from the default configuration, and any single
+/// value can be overridden with env vars like `TORRUST_INDEX_CONFIG__TRACKER__TOKEN`.
+///
 /// Refer to the [configuration documentation](https://docs.rs/torrust-index-configuration) for the configuration options.
 ///
 /// # Panics
@@ -42,16 +57,47 @@ pub const ENV_VAR_CORS_PERMISSIVE: &str = "TORRUST_INDEX_API_CORS_PERMISSIVE";
 /// `./index.toml` file or the env var `TORRUST_INDEX_CONFIG`.
 #[must_use]
 pub fn initialize_configuration() -> Configuration {
+    load_configuration().unwrap()
+}
+
+/// It loads the application configuration from the environment like
+/// [`initialize_configuration`] but it returns the error instead of panicking.
+///
+/// # Errors
+///
+/// Will return an error if it can't load the configuration from either
+/// `./index.toml` file or the env var `TORRUST_INDEX_CONFIG`.
+pub fn load_configuration() -> Result<Configuration, Error> {
     let info = Info::new(
         ENV_VAR_CONFIG.to_string(),
         ENV_VAR_PATH_CONFIG.to_string(),
         DEFAULT_PATH_CONFIG.to_string(),
         ENV_VAR_API_ADMIN_TOKEN.to_string(),
         ENV_VAR_AUTH_SECRET_KEY.to_string(),
-    )
-    .unwrap();
+        ENV_VAR_PREFIX_OVERRIDES.to_string(),
+    )?;
+
+    Configuration::load(&info)
+}
+
+/// It loads the application settings from a configuration file. It's used to
+/// reload the configuration while the application is running.
+///
+/// Environment variables overriding values in the file are also applied.
+///
+/// # Errors
+///
+/// Will return an error if the file can't be read or it does not contain a
+/// valid configuration.
+pub fn load_settings_from_file(config_path: &str) -> Result<TorrustIndex, Error> {
+    let info = Info::from_file(
+        config_path,
+        ENV_VAR_API_ADMIN_TOKEN,
+        ENV_VAR_AUTH_SECRET_KEY,
+        ENV_VAR_PREFIX_OVERRIDES,
+    )?;
 
-    Configuration::load(&info).unwrap()
+    Ok(Configuration::load(&info)?.settings.into_inner())
 }
 
 #[cfg(test)]
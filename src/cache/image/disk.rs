@@ -0,0 +1,476 @@
+//! Disk tier of the image cache.
+//!
+//! Images are stored in content-addressed files named after the SHA-1 hash
+//! of their bytes, so an image proxied from different URLs is only stored
+//! once. An index file maps every URL to its image and keeps the entries in
+//! least recently used order, so cached images survive restarts.
+//!
+//! ```text
+//! <path>/index.json
+//! <path>/blobs/<first two chars of the hash>/<hash>
+//! ```
+//!
+//! The index file is only written when entries are added, revalidated or
+//! removed. Reads change the LRU order in memory only, so the order after a
+//! restart can be slightly outdated.
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+use indexmap::IndexMap;
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use tokio::fs;
+use tokio::sync::Mutex;
+
+use crate::utils::hex::from_bytes;
+
+const INDEX_FILE_NAME: &str = "index.json";
+const BLOBS_DIR_NAME: &str = "blobs";
+
+/// An image cached on disk.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskCacheEntry {
+    pub url: String,
+    /// SHA-1 hash of the image bytes. It's the name of the image file.
+    pub content_hash: String,
+    /// Size of the image in bytes.
+    pub size: usize,
+    pub content_type: String,
+    /// `ETag` returned by the original server, used to revalidate the image.
+    pub etag: Option<String>,
+    /// `Last-Modified` returned by the original server, used to revalidate
+    /// the image.
+    pub last_modified: Option<String>,
+    /// When the image was fetched or revalidated for the last time, in
+    /// seconds since the Unix epoch.
+    pub validated_at: u64,
+}
+
+impl DiskCacheEntry {
+    /// Whether the image has to be revalidated with the original server.
+    #[must_use]
+    pub fn is_expired(&self, ttl_secs: u64, now_secs: u64) -> bool {
+        self.validated_at.saturating_add(ttl_secs) <= now_secs
+    }
+}
+
+#[derive(Default)]
+struct Index {
+    /// Entries by URL, from the least to the most recently used.
+    entries: IndexMap<String, DiskCacheEntry>,
+    /// Number of entries using every image file.
+    blob_references: HashMap<String, usize>,
+    /// Size of all the image files combined.
+    total_size: usize,
+    /// Number of entries removed to make room for new images.
+    evictions: u64,
+}
+
+impl Index {
+    fn insert(&mut self, entry: DiskCacheEntry) -> Vec<String> {
+        let mut unused_blobs = self.remove(&entry.url).into_iter().collect::<Vec<String>>();
+
+        let references = self.blob_references.entry(entry.content_hash.clone()).or_insert(0);
+
+        if *references == 0 {
+            self.total_size += entry.size;
+        }
+
+        *references += 1;
+
+        unused_blobs.retain(|content_hash| *content_hash != entry.content_hash);
+
+        self.entries.insert(entry.url.clone(), entry);
+
+        unused_blobs
+    }
+
+    /// It removes the entry for the URL. It returns the hash of the image
+    /// file if no other entry uses it.
+    fn remove(&mut self, url: &str) -> Option<String> {
+        let entry = self.entries.shift_remove(url)?;
+
+        let references = self.blob_references.get_mut(&entry.content_hash)?;
+
+        *references -= 1;
+
+        if *references > 0 {
+            return None;
+        }
+
+        self.blob_references.remove(&entry.content_hash);
+        self.total_size -= entry.size;
+
+        Some(entry.content_hash)
+    }
+
+    fn least_recently_used(&self) -> Option<String> {
+        self.entries.first().map(|(url, _)| url.clone())
+    }
+
+    fn mark_as_used(&mut self, url: &str) -> Option<DiskCacheEntry> {
+        let entry = self.entries.shift_remove(url)?;
+
+        self.entries.insert(url.to_owned(), entry.clone());
+
+        Some(entry)
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub struct DiskCache {
+    path: PathBuf,
+    /// Maximum size in bytes of all the image files combined.
+    capacity: usize,
+    index: Mutex<Index>,
+}
+
+impl DiskCache {
+    /// It opens the disk cache in the directory `path`, creating it if it
+    /// does not exist, and loads the index of cached images.
+    ///
+    /// Entries whose image file is missing are discarded.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the cache directory can't be created.
+    pub async fn open(path: &Path, capacity: usize) -> Result<Self, io::Error> {
+        fs::create_dir_all(path.join(BLOBS_DIR_NAME)).await?;
+
+        let mut index = Index::default();
+
+        match fs::read(path.join(INDEX_FILE_NAME)).await {
+            Ok(contents) => match serde_json::from_slice::<Vec<DiskCacheEntry>>(&contents) {
+                Ok(entries) => {
+                    for entry in entries {
+                        if fs::try_exists(blob_path(path, &entry.content_hash)).await.unwrap_or(false) {
+                            drop(index.insert(entry));
+                        }
+                    }
+                }
+                Err(err) => warn!("Discarding the corrupted image cache index {}: {err}", path.display()),
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+
+        let disk_cache = Self {
+            path: path.to_owned(),
+            capacity,
+            index: Mutex::new(index),
+        };
+
+        // The capacity could have been reduced since the last time.
+        let mut index = disk_cache.index.lock().await;
+        disk_cache.evict(&mut index).await;
+        drop(index);
+
+        Ok(disk_cache)
+    }
+
+    /// It returns the entry for the URL and marks it as the most recently
+    /// used.
+    pub async fn get(&self, url: &str) -> Option<DiskCacheEntry> {
+        self.index.lock().await.mark_as_used(url)
+    }
+
+    /// It reads the image of an entry. It returns `None` and removes the
+    /// entry if the image file can't be read or it was modified.
+    pub async fn read(&self, entry: &DiskCacheEntry) -> Option<Bytes> {
+        match fs::read(blob_path(&self.path, &entry.content_hash)).await {
+            Ok(contents) if content_hash(&contents) == entry.content_hash => Some(Bytes::from(contents)),
+            Ok(_) => {
+                error!("Image cache file for {} is corrupted", entry.url);
+                self.remove(&entry.url).await;
+                None
+            }
+            Err(err) => {
+                error!("Failed to read image cache file for {}: {err}", entry.url);
+                self.remove(&entry.url).await;
+                None
+            }
+        }
+    }
+
+    /// It stores the image for an URL, replacing the previous one. The least
+    /// recently used images are removed when the cache is full.
+    ///
+    /// The `content_hash` and `size` of the entry are calculated from the
+    /// image bytes.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the image is bigger than the cache capacity or
+    /// it can't be written to disk.
+    pub async fn insert(&self, mut entry: DiskCacheEntry, bytes: &Bytes) -> Result<(), io::Error> {
+        if bytes.len() > self.capacity {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "image bigger than the cache capacity",
+            ));
+        }
+
+        entry.content_hash = content_hash(bytes);
+        entry.size = bytes.len();
+
+        let mut index = self.index.lock().await;
+
+        let blob_path = blob_path(&self.path, &entry.content_hash);
+
+        if !index.blob_references.contains_key(&entry.content_hash) {
+            write_atomically(&blob_path, bytes).await?;
+        }
+
+        for unused_blob in index.insert(entry) {
+            self.remove_blob(&unused_blob).await;
+        }
+
+        self.evict(&mut index).await;
+
+        self.save(&index).await;
+
+        Ok(())
+    }
+
+    /// It updates the time the image for an URL was validated with the
+    /// original server.
+    pub async fn mark_as_validated(&self, url: &str, now_secs: u64) {
+        let mut index = self.index.lock().await;
+
+        if let Some(entry) = index.entries.get_mut(url) {
+            entry.validated_at = now_secs;
+
+            self.save(&index).await;
+        }
+    }
+
+    /// Number of cached images.
+    pub async fn len(&self) -> usize {
+        self.index.lock().await.entries.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.index.lock().await.entries.is_empty()
+    }
+
+    /// Size in bytes of all the image files combined.
+    pub async fn total_size(&self) -> usize {
+        self.index.lock().await.total_size
+    }
+
+    /// Maximum size in bytes of all the image files combined.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of images removed to make room for new images since the cache
+    /// was opened.
+    pub async fn evictions(&self) -> u64 {
+        self.index.lock().await.evictions
+    }
+
+    /// It removes the image for an URL.
+    pub async fn remove(&self, url: &str) {
+        let mut index = self.index.lock().await;
+
+        if let Some(unused_blob) = index.remove(url) {
+            self.remove_blob(&unused_blob).await;
+        }
+
+        self.save(&index).await;
+    }
+
+    /// It removes the images for all the keys starting with the prefix. It
+    /// returns the number of removed images.
+    pub async fn remove_with_prefix(&self, prefix: &str) -> usize {
+        let mut index = self.index.lock().await;
+
+        let keys: Vec<String> = index.entries.keys().filter(|key| key.starts_with(prefix)).cloned().collect();
+
+        for key in &keys {
+            if let Some(unused_blob) = index.remove(key) {
+                self.remove_blob(&unused_blob).await;
+            }
+        }
+
+        self.save(&index).await;
+
+        keys.len()
+    }
+
+    /// It removes all the images. It returns the number of removed images.
+    pub async fn clear(&self) -> usize {
+        let mut index = self.index.lock().await;
+
+        let keys: Vec<String> = index.entries.keys().cloned().collect();
+
+        for key in &keys {
+            if let Some(unused_blob) = index.remove(key) {
+                self.remove_blob(&unused_blob).await;
+            }
+        }
+
+        self.save(&index).await;
+
+        keys.len()
+    }
+
+    async fn evict(&self, index: &mut Index) {
+        while index.total_size > self.capacity {
+            // The size of the blobs is only counted while they are referenced,
+            // but stop anyway if the accounting is ever wrong.
+            let Some(url) = index.least_recently_used() else {
+                error!("Image disk cache is empty but its size is {} bytes", index.total_size);
+                index.total_size = 0;
+                break;
+            };
+
+            index.evictions += 1;
+
+            if let Some(unused_blob) = index.remove(&url) {
+                self.remove_blob(&unused_blob).await;
+            }
+        }
+    }
+
+    async fn remove_blob(&self, content_hash: &str) {
+        if let Err(err) = fs::remove_file(blob_path(&self.path, content_hash)).await {
+            error!("Failed to remove image cache file {content_hash}: {err}");
+        }
+    }
+
+    async fn save(&self, index: &Index) {
+        let entries: Vec<&DiskCacheEntry> = index.entries.values().collect();
+
+        let result = match serde_json::to_vec(&entries) {
+            Ok(contents) => write_atomically(&self.path.join(INDEX_FILE_NAME), &contents).await,
+            Err(err) => Err(err.into()),
+        };
+
+        if let Err(err) = result {
+            error!("Failed to save the image cache index {}: {err}", self.path.display());
+        }
+    }
+}
+
+/// It returns the SHA-1 hash of the image bytes, used to address the images.
+#[must_use]
+pub fn content_hash(bytes: &[u8]) -> String {
+    from_bytes(&Sha1::digest(bytes))
+}
+
+fn blob_path(path: &Path, content_hash: &str) -> PathBuf {
+    path.join(BLOBS_DIR_NAME).join(&content_hash[..2]).join(content_hash)
+}
+
+/// It writes a file to a temporary file first, so the file is never left
+/// half written.
+///
+/// # Errors
+///
+/// Will return an error if the file can't be written.
+pub async fn write_atomically(path: &Path, contents: &[u8]) -> Result<(), io::Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    let temp_path = path.with_extension("tmp");
+
+    fs::write(&temp_path, contents).await?;
+
+    fs::rename(&temp_path, path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use tempfile::TempDir;
+
+    use super::{DiskCache, DiskCacheEntry};
+
+    fn entry(url: &str) -> DiskCacheEntry {
+        DiskCacheEntry {
+            url: url.to_owned(),
+            content_hash: String::new(),
+            size: 0,
+            content_type: "image/png".to_owned(),
+            etag: None,
+            last_modified: None,
+            validated_at: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_the_images_after_reopening_the_cache() {
+        let dir = TempDir::new().unwrap();
+
+        let disk_cache = DiskCache::open(dir.path(), 10).await.unwrap();
+        disk_cache.insert(entry("a"), &Bytes::from("12345")).await.unwrap();
+        drop(disk_cache);
+
+        let disk_cache = DiskCache::open(dir.path(), 10).await.unwrap();
+        let cached_entry = disk_cache.get("a").await.unwrap();
+
+        assert_eq!(disk_cache.read(&cached_entry).await, Some(Bytes::from("12345")));
+    }
+
+    #[tokio::test]
+    async fn it_should_store_identical_images_only_once() {
+        let dir = TempDir::new().unwrap();
+
+        let disk_cache = DiskCache::open(dir.path(), 5).await.unwrap();
+        disk_cache.insert(entry("a"), &Bytes::from("12345")).await.unwrap();
+        disk_cache.insert(entry("b"), &Bytes::from("12345")).await.unwrap();
+
+        assert!(disk_cache.get("a").await.is_some());
+        assert!(disk_cache.get("b").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn it_should_evict_the_least_recently_used_images_when_the_cache_is_full() {
+        let dir = TempDir::new().unwrap();
+
+        let disk_cache = DiskCache::open(dir.path(), 10).await.unwrap();
+        disk_cache.insert(entry("a"), &Bytes::from("aaaaa")).await.unwrap();
+        disk_cache.insert(entry("b"), &Bytes::from("bbbbb")).await.unwrap();
+
+        // `a` becomes the most recently used image
+        assert!(disk_cache.get("a").await.is_some());
+
+        disk_cache.insert(entry("c"), &Bytes::from("ccccc")).await.unwrap();
+
+        assert!(disk_cache.get("a").await.is_some());
+        assert!(disk_cache.get("b").await.is_none());
+        assert!(disk_cache.get("c").await.is_some());
+        assert_eq!(disk_cache.evictions().await, 1);
+    }
+
+    #[tokio::test]
+    async fn it_should_remove_all_the_images() {
+        let dir = TempDir::new().unwrap();
+
+        let disk_cache = DiskCache::open(dir.path(), 10).await.unwrap();
+        disk_cache.insert(entry("a"), &Bytes::from("aaaaa")).await.unwrap();
+        disk_cache.insert(entry("b"), &Bytes::from("bbbbb")).await.unwrap();
+
+        assert_eq!(disk_cache.clear().await, 2);
+        assert_eq!(disk_cache.len().await, 0);
+        assert_eq!(disk_cache.total_size().await, 0);
+    }
+
+    #[test]
+    fn an_entry_should_expire_after_the_ttl() {
+        let entry = DiskCacheEntry {
+            validated_at: 100,
+            ..entry("a")
+        };
+
+        assert!(!entry.is_expired(60, 159));
+        assert!(entry.is_expired(60, 160));
+    }
+}
@@ -0,0 +1,84 @@
+//! Image formats supported by the image proxy.
+//!
+//! The format is detected from the first bytes of the image (the "magic
+//! bytes"). The `Content-Type` header returned by the original source is not
+//! trusted.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Gif,
+    WebP,
+    Avif,
+}
+
+impl ImageFormat {
+    /// It detects the image format from the image bytes. It returns `None`
+    /// if the bytes are not an image in one of the supported formats.
+    #[must_use]
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            return Some(Self::Jpeg);
+        }
+
+        if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+            return Some(Self::Png);
+        }
+
+        if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            return Some(Self::Gif);
+        }
+
+        if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+            return Some(Self::WebP);
+        }
+
+        // ISO Base Media File: a `ftyp` box with the `avif` (image) or `avis`
+        // (image sequence) major brand.
+        if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" && (&bytes[8..12] == b"avif" || &bytes[8..12] == b"avis") {
+            return Some(Self::Avif);
+        }
+
+        None
+    }
+
+    #[must_use]
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+            Self::Gif => "image/gif",
+            Self::WebP => "image/webp",
+            Self::Avif => "image/avif",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ImageFormat;
+
+    #[test]
+    fn it_should_detect_the_supported_image_formats() {
+        assert_eq!(ImageFormat::detect(&[0xFF, 0xD8, 0xFF, 0xE0]), Some(ImageFormat::Jpeg));
+        assert_eq!(
+            ImageFormat::detect(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0x00]),
+            Some(ImageFormat::Png)
+        );
+        assert_eq!(ImageFormat::detect(b"GIF89a\x01\x00"), Some(ImageFormat::Gif));
+        assert_eq!(ImageFormat::detect(b"RIFF\x24\x00\x00\x00WEBPVP8 "), Some(ImageFormat::WebP));
+        assert_eq!(
+            ImageFormat::detect(b"\x00\x00\x00\x20ftypavif\x00\x00\x00\x00"),
+            Some(ImageFormat::Avif)
+        );
+    }
+
+    #[test]
+    fn it_should_not_detect_other_contents_as_images() {
+        assert_eq!(ImageFormat::detect(b"<html><body></body></html>"), None);
+        assert_eq!(ImageFormat::detect(b"RIFF\x24\x00\x00\x00WAVEfmt "), None);
+        assert_eq!(ImageFormat::detect(b"\x00\x00\x00\x20ftypisom\x00\x00\x00\x00"), None);
+        assert_eq!(ImageFormat::detect(&[]), None);
+    }
+}
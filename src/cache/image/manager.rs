@@ -1,24 +1,53 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
+use indexmap::IndexMap;
+use log::{error, warn};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, LOCATION};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
+use url::Url;
 
+use super::disk::{content_hash, DiskCache, DiskCacheEntry};
+use super::format::ImageFormat;
+use super::network;
+use super::variant::{cache_key_prefix, ImageVariant, VariantSpec};
 use crate::cache::BytesCache;
 use crate::config::Configuration;
-use crate::models::user::UserCompact;
+use crate::databases::database::Database;
+use crate::models::image_cache_quota::DbImageCacheQuota;
+use crate::models::user::{UserCompact, UserId};
 
+#[derive(Debug)]
 pub enum Error {
     UrlIsUnreachable,
     UrlIsNotAnImage,
     ImageTooBig,
     UserQuotaMet,
     Unauthenticated,
+    InvalidVariant,
 }
 
 type UserQuotas = HashMap<i64, ImageCacheQuota>;
 
+/// Maximum number of URLs whose requests are counted for the statistics.
+/// When the limit is reached, the least recently requested URL is discarded.
+const MAX_TRACKED_URLS: usize = 1000;
+
+/// Number of users and URLs included in the statistics rankings.
+const TOP_ENTRIES: usize = 10;
+
+/// Maximum number of variants whose usage is accounted separately in a user
+/// quota. The usage of other variants is added to [`OTHER_VARIANTS`].
+const MAX_ACCOUNTED_VARIANTS: usize = 32;
+
+/// Key of the quota usage of the variants not accounted separately.
+const OTHER_VARIANTS: &str = "other";
+
 /// Returns the current time in seconds.
 ///
 /// # Panics
@@ -36,6 +65,10 @@ pub fn now_in_secs() -> u64 {
 pub struct ImageCacheQuota {
     pub user_id: i64,
     pub usage: usize,
+    /// Part of the `usage` spent on resized variants of the images.
+    pub variant_usage: usize,
+    /// The `variant_usage` of every variant. See [`VariantSpec::label`].
+    pub variant_usages: BTreeMap<String, usize>,
     pub max_usage: usize,
     pub date_start_secs: u64,
     pub period_secs: u64,
@@ -47,6 +80,8 @@ impl ImageCacheQuota {
         Self {
             user_id,
             usage: 0,
+            variant_usage: 0,
+            variant_usages: BTreeMap::new(),
             max_usage,
             date_start_secs: now_in_secs(),
             period_secs,
@@ -60,7 +95,7 @@ impl ImageCacheQuota {
     /// This function will return a `Error::UserQuotaMet` if user quota has been met.
     pub fn add_usage(&mut self, amount: usize) -> Result<(), Error> {
         // Check if quota needs to be reset.
-        if now_in_secs() - self.date_start_secs > self.period_secs {
+        if self.is_expired() {
             self.reset();
         }
 
@@ -73,8 +108,32 @@ impl ImageCacheQuota {
         Ok(())
     }
 
+    /// Add Usage Quota for a resized variant of an image.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `Error::UserQuotaMet` if user quota has been met.
+    pub fn add_variant_usage(&mut self, variant: &str, amount: usize) -> Result<(), Error> {
+        self.add_usage(amount)?;
+
+        self.variant_usage = self.variant_usage.saturating_add(amount);
+
+        let variant = if self.variant_usages.contains_key(variant) || self.variant_usages.len() < MAX_ACCOUNTED_VARIANTS {
+            variant
+        } else {
+            OTHER_VARIANTS
+        };
+
+        let variant_usage = self.variant_usages.entry(variant.to_owned()).or_default();
+        *variant_usage = variant_usage.saturating_add(amount);
+
+        Ok(())
+    }
+
     pub fn reset(&mut self) {
         self.usage = 0;
+        self.variant_usage = 0;
+        self.variant_usages.clear();
         self.date_start_secs = now_in_secs();
     }
 
@@ -82,39 +141,213 @@ impl ImageCacheQuota {
     pub fn is_reached(&self) -> bool {
         self.usage >= self.max_usage
     }
+
+    /// Whether the quota period is over, so the usage has to be reset.
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        now_in_secs().saturating_sub(self.date_start_secs) > self.period_secs
+    }
+
+    /// It restores the quota usage stored in the database.
+    #[must_use]
+    pub fn from_db(quota: &DbImageCacheQuota, max_usage: usize, period_secs: u64) -> Self {
+        Self {
+            user_id: quota.user_id,
+            usage: usize::try_from(quota.usage_bytes).unwrap_or_default(),
+            variant_usage: usize::try_from(quota.variant_usage_bytes).unwrap_or_default(),
+            variant_usages: quota
+                .variant_usages
+                .as_deref()
+                .and_then(|variant_usages| serde_json::from_str(variant_usages).ok())
+                .unwrap_or_default(),
+            max_usage,
+            date_start_secs: u64::try_from(quota.date_start_secs).unwrap_or_default(),
+            period_secs,
+        }
+    }
+
+    #[must_use]
+    pub fn to_db(&self) -> DbImageCacheQuota {
+        DbImageCacheQuota {
+            user_id: self.user_id,
+            usage_bytes: i64::try_from(self.usage).unwrap_or(i64::MAX),
+            variant_usage_bytes: i64::try_from(self.variant_usage).unwrap_or(i64::MAX),
+            variant_usages: serde_json::to_string(&self.variant_usages).ok(),
+            date_start_secs: i64::try_from(self.date_start_secs).unwrap_or(i64::MAX),
+        }
+    }
+}
+
+/// Requests served by the image cache since the index was started.
+#[derive(Default)]
+struct RequestCounters {
+    /// Requests served from the cache.
+    hits: u64,
+    /// Requests that required downloading or resizing an image, or that
+    /// could not be served.
+    misses: u64,
+    /// Number of served requests for every URL, from the least to the most
+    /// recently requested.
+    url_requests: IndexMap<String, u64>,
 }
 
+impl RequestCounters {
+    fn record(&mut self, hit: bool) {
+        if hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+    }
+
+    fn record_url_request(&mut self, url: &str) {
+        let requests = self.url_requests.shift_remove(url).unwrap_or(0) + 1;
+
+        if self.url_requests.len() >= MAX_TRACKED_URLS {
+            self.url_requests.shift_remove_index(0);
+        }
+
+        self.url_requests.insert(url.to_owned(), requests);
+    }
+}
+
+/// Statistics of one of the image cache tiers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TierStatistics {
+    /// Number of cached images, including resized variants.
+    pub entries: usize,
+    /// Size in bytes of all the cached images combined.
+    pub total_size: usize,
+    /// Maximum size in bytes of all the cached images combined.
+    pub capacity: usize,
+    /// Number of images removed to make room for new images.
+    pub evictions: u64,
+}
+
+/// Image cache quota usage of a user in the current period.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserUsage {
+    pub user_id: UserId,
+    pub usage: usize,
+    pub variant_usage: usize,
+    pub variant_usages: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UrlRequests {
+    pub url: String,
+    pub requests: u64,
+}
+
+/// Image cache statistics.
+///
+/// Request counters are kept in memory, so they are reset when the index is
+/// restarted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Statistics {
+    pub memory_cache: TierStatistics,
+    pub disk_cache: Option<TierStatistics>,
+    pub hits: u64,
+    pub misses: u64,
+    /// Ratio of requests served from the cache, from `0.0` to `1.0`.
+    pub hit_ratio: f64,
+    pub miss_ratio: f64,
+    /// Users who used the biggest part of their quota in the current period.
+    pub top_users: Vec<UserUsage>,
+    /// Most requested image URLs, among the recently served ones.
+    pub top_urls: Vec<UrlRequests>,
+}
+
+/// An image downloaded from its original source.
+struct FetchedImage {
+    bytes: Bytes,
+    content_type: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// The image cache has two tiers:
+///
+/// - An in-memory cache with the most recently used images.
+/// - An optional disk cache, so cached images survive restarts. Images are
+/// revalidated with the original source when they are older than the
+/// configured TTL.
+///
+/// User quotas are stored in the database, so they are not reset when the
+/// index is restarted.
 pub struct ImageCacheService {
     image_cache: RwLock<BytesCache>,
+    disk_cache: Option<DiskCache>,
     user_quotas: RwLock<UserQuotas>,
-    reqwest_client: reqwest::Client,
+    request_counters: RwLock<RequestCounters>,
+    http_clients: network::Clients,
+    database: Arc<Box<dyn Database>>,
     cfg: Arc<Configuration>,
 }
 
 impl ImageCacheService {
-    /// Create a new image cache service.
+    /// Create a new image cache service. It loads the user quotas from the
+    /// database.
     ///
     /// # Panics
     ///
     /// This function will panic if the image cache could not be created.
-    pub async fn new(cfg: Arc<Configuration>) -> Self {
+    pub async fn new(cfg: Arc<Configuration>, database: Arc<Box<dyn Database>>) -> Self {
         let settings = cfg.settings.read().await;
 
         let image_cache =
             BytesCache::with_capacity_and_entry_size_limit(settings.image_cache.capacity, settings.image_cache.entry_size_limit)
                 .expect("Could not create image cache.");
 
-        let reqwest_client = reqwest::Client::builder()
-            .timeout(Duration::from_millis(settings.image_cache.max_request_timeout_ms))
-            .build()
-            .expect("unable to build client request");
+        let disk_cache = if settings.image_cache.disk_cache_enabled {
+            match DiskCache::open(
+                Path::new(&settings.image_cache.disk_cache_path),
+                settings.image_cache.disk_capacity,
+            )
+            .await
+            {
+                Ok(disk_cache) => Some(disk_cache),
+                Err(err) => {
+                    error!(
+                        "Failed to open the image disk cache {}: {err}. Images will only be cached in memory.",
+                        settings.image_cache.disk_cache_path
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let user_quotas = match database.get_image_cache_quotas().await {
+            Ok(quotas) => quotas
+                .iter()
+                .map(|quota| {
+                    (
+                        quota.user_id,
+                        ImageCacheQuota::from_db(
+                            quota,
+                            settings.image_cache.user_quota_bytes,
+                            settings.image_cache.user_quota_period_seconds,
+                        ),
+                    )
+                })
+                .collect(),
+            Err(err) => {
+                error!("Failed to load the image cache user quotas: {err:?}");
+                HashMap::new()
+            }
+        };
 
         drop(settings);
 
         Self {
             image_cache: RwLock::new(image_cache),
-            user_quotas: RwLock::new(HashMap::new()),
-            reqwest_client,
+            disk_cache,
+            user_quotas: RwLock::new(user_quotas),
+            request_counters: RwLock::new(RequestCounters::default()),
+            http_clients: network::Clients::new(),
+            database,
             cfg,
         }
     }
@@ -122,63 +355,425 @@ impl ImageCacheService {
     /// Get an image from the url and insert it into the cache if it isn't cached already.
     /// Unauthenticated users can only get already cached images.
     ///
+    /// When a resized variant is requested, the variant is generated from the
+    /// original image and cached too. Variants count towards the user quota.
+    /// AVIF images can't be resized, so the original image is returned.
+    ///
     /// # Errors
     ///
     /// Return a `Error::Unauthenticated` if the user has not been authenticated.
-    pub async fn get_image_by_url(&self, url: &str, opt_user: Option<UserCompact>) -> Result<Bytes, Error> {
+    pub async fn get_image_by_url(
+        &self,
+        url: &str,
+        variant: &ImageVariant,
+        opt_user: Option<UserCompact>,
+    ) -> Result<Bytes, Error> {
+        let result = self.get_image(url, variant, opt_user).await;
+
+        let mut request_counters = self.request_counters.write().await;
+
+        request_counters.record(matches!(result, Ok((_, true))));
+
+        // Only the images that could be served are ranked, so that failed
+        // requests for arbitrary URLs don't push the popular ones out.
+        if result.is_ok() {
+            request_counters.record_url_request(url);
+        }
+
+        drop(request_counters);
+
+        result.map(|(image_bytes, _)| image_bytes)
+    }
+
+    /// It returns the image and whether it was served from the cache.
+    async fn get_image(&self, url: &str, variant: &ImageVariant, opt_user: Option<UserCompact>) -> Result<(Bytes, bool), Error> {
+        let (original, original_is_cached) = self.get_original_image(url, opt_user.as_ref()).await?;
+
+        if variant.is_original() || ImageFormat::detect(&original) == Some(ImageFormat::Avif) {
+            return Ok((original, original_is_cached));
+        }
+
+        let settings = self.cfg.settings.read().await;
+
+        let spec = VariantSpec::new(
+            variant,
+            settings.image_cache.variant_format,
+            settings.image_cache.variant_quality,
+            settings.image_cache.max_variant_dimension,
+        );
+
+        drop(settings);
+
+        let key = spec.cache_key(&content_hash(&original));
+
+        if let Some(variant_bytes) = self.get_cached_variant(&key).await {
+            return Ok((variant_bytes, original_is_cached));
+        }
+
+        let Some(user) = opt_user else {
+            return Err(Error::Unauthenticated);
+        };
+
+        self.check_user_quota(&user).await?;
+
+        // Resizing is CPU intensive, so it must not block the async runtime.
+        let variant_bytes = tokio::task::spawn_blocking(move || spec.generate(&original))
+            .await
+            .map_err(|_| Error::UrlIsNotAnImage)??;
+
+        self.check_image_size(&variant_bytes).await?;
+
+        self.update_image_cache(
+            &key,
+            &FetchedImage {
+                bytes: variant_bytes.clone(),
+                content_type: spec.mime_type().to_owned(),
+                etag: None,
+                last_modified: None,
+            },
+        )
+        .await?;
+
+        self.update_user_quota(&user, variant_bytes.len(), Some(&spec.label()))
+            .await?;
+
+        Ok((variant_bytes, false))
+    }
+
+    /// It returns the original image and whether it was served from the
+    /// cache.
+    async fn get_original_image(&self, url: &str, opt_user: Option<&UserCompact>) -> Result<(Bytes, bool), Error> {
+        if let Some(disk_cache) = &self.disk_cache {
+            if let Some(image_bytes) = self.get_image_from_disk_cache(disk_cache, url).await {
+                return Ok((image_bytes, true));
+            }
+        }
+
         if let Some(entry) = self.image_cache.read().await.get(url).await {
-            return Ok(entry.bytes);
+            return Ok((entry.bytes, true));
         }
 
         match opt_user {
             None => Err(Error::Unauthenticated),
 
             Some(user) => {
-                self.check_user_quota(&user).await?;
+                self.check_user_quota(user).await?;
 
-                let image_bytes = self.get_image_from_url_as_bytes(url).await?;
+                let Some(image) = self.fetch_image(url, None).await? else {
+                    return Err(Error::UrlIsUnreachable);
+                };
 
-                self.check_image_size(&image_bytes).await?;
+                self.check_image_size(&image.bytes).await?;
 
                 // These two functions could be executed after returning the image to the client,
                 // but than we would need a dedicated task or thread that executes these functions.
                 // This can be problematic if a task is spawned after every user request.
                 // Since these functions execute very fast, I don't see a reason to further optimize this.
                 // For now.
-                self.update_image_cache(url, &image_bytes).await?;
+                self.update_image_cache(url, &image).await?;
 
-                self.update_user_quota(&user, image_bytes.len()).await?;
+                self.update_user_quota(user, image.bytes.len(), None).await?;
 
-                Ok(image_bytes)
+                Ok((image.bytes, false))
             }
         }
     }
 
-    async fn get_image_from_url_as_bytes(&self, url: &str) -> Result<Bytes, Error> {
-        let res = self
-            .reqwest_client
-            .clone()
-            .get(url)
-            .send()
+    /// It returns the cache statistics.
+    pub async fn statistics(&self) -> Statistics {
+        let image_cache = self.image_cache.read().await;
+
+        let memory_cache = TierStatistics {
+            entries: image_cache.len().await,
+            total_size: image_cache.total_size(),
+            capacity: image_cache.capacity(),
+            evictions: image_cache.evictions(),
+        };
+
+        drop(image_cache);
+
+        let disk_cache = match &self.disk_cache {
+            Some(disk_cache) => Some(TierStatistics {
+                entries: disk_cache.len().await,
+                total_size: disk_cache.total_size().await,
+                capacity: disk_cache.capacity(),
+                evictions: disk_cache.evictions().await,
+            }),
+            None => None,
+        };
+
+        let mut top_users: Vec<UserUsage> = self
+            .user_quotas
+            .read()
             .await
-            .map_err(|_| Error::UrlIsUnreachable)?;
+            .values()
+            .filter(|quota| !quota.is_expired() && quota.usage > 0)
+            .map(|quota| UserUsage {
+                user_id: quota.user_id,
+                usage: quota.usage,
+                variant_usage: quota.variant_usage,
+                variant_usages: quota.variant_usages.clone(),
+            })
+            .collect();
+
+        top_users.sort_by(|a, b| b.usage.cmp(&a.usage).then(a.user_id.cmp(&b.user_id)));
+        top_users.truncate(TOP_ENTRIES);
+
+        let request_counters = self.request_counters.read().await;
+
+        let mut top_urls: Vec<UrlRequests> = request_counters
+            .url_requests
+            .iter()
+            .map(|(url, requests)| UrlRequests {
+                url: url.clone(),
+                requests: *requests,
+            })
+            .collect();
+
+        top_urls.sort_by(|a, b| b.requests.cmp(&a.requests).then(a.url.cmp(&b.url)));
+        top_urls.truncate(TOP_ENTRIES);
+
+        let hits = request_counters.hits;
+        let misses = request_counters.misses;
+
+        drop(request_counters);
+
+        let (hit_ratio, miss_ratio) = ratios(hits, misses);
+
+        Statistics {
+            memory_cache,
+            disk_cache,
+            hits,
+            misses,
+            hit_ratio,
+            miss_ratio,
+            top_users,
+            top_urls,
+        }
+    }
 
-        // code-review: we could get a HTTP 304 response, which doesn't contain a body (the image bytes).
+    /// It removes an image and its resized variants from all the cache
+    /// tiers. It returns the number of removed entries.
+    pub async fn purge_url(&self, url: &str) -> usize {
+        let mut image_cache = self.image_cache.write().await;
 
-        if let Some(content_type) = res.headers().get("Content-Type") {
-            if content_type != "image/jpeg" && content_type != "image/png" {
-                return Err(Error::UrlIsNotAnImage);
+        let mut original_hash = image_cache.get(url).await.map(|entry| content_hash(&entry.bytes));
+
+        let mut removed = usize::from(image_cache.remove(url).is_some());
+
+        drop(image_cache);
+
+        if let Some(disk_cache) = &self.disk_cache {
+            if let Some(entry) = disk_cache.get(url).await {
+                original_hash = Some(entry.content_hash);
+
+                disk_cache.remove(url).await;
+
+                removed += 1;
             }
-        } else {
-            return Err(Error::UrlIsNotAnImage);
         }
 
-        res.bytes().await.map_err(|_| Error::UrlIsNotAnImage)
+        if let Some(original_hash) = original_hash {
+            let prefix = cache_key_prefix(&original_hash);
+
+            removed += self.image_cache.write().await.remove_with_prefix(&prefix);
+
+            if let Some(disk_cache) = &self.disk_cache {
+                removed += disk_cache.remove_with_prefix(&prefix).await;
+            }
+        }
+
+        removed
+    }
+
+    /// It removes all the images from all the cache tiers. It returns the
+    /// number of removed entries.
+    pub async fn purge_all(&self) -> usize {
+        let mut removed = self.image_cache.write().await.clear();
+
+        if let Some(disk_cache) = &self.disk_cache {
+            removed += disk_cache.clear().await;
+        }
+
+        removed
+    }
+
+    /// It gets a resized variant of an image from the cache. Variants don't
+    /// expire because their key depends on the original image contents.
+    async fn get_cached_variant(&self, key: &str) -> Option<Bytes> {
+        match &self.disk_cache {
+            Some(disk_cache) => self.get_from_disk_cache(disk_cache, key).await.map(|(_, bytes)| bytes),
+            None => self.image_cache.read().await.get(key).await.map(|entry| entry.bytes),
+        }
+    }
+
+    /// It gets an entry from the disk cache, using the in-memory copy of the
+    /// image if there is one. Images read from disk are promoted to the
+    /// in-memory cache.
+    async fn get_from_disk_cache(&self, disk_cache: &DiskCache, key: &str) -> Option<(DiskCacheEntry, Bytes)> {
+        let entry = disk_cache.get(key).await?;
+
+        let cached_bytes = self.image_cache.read().await.get(key).await.map(|entry| entry.bytes);
+
+        let image_bytes = match cached_bytes {
+            Some(image_bytes) => image_bytes,
+            None => {
+                let image_bytes = disk_cache.read(&entry).await?;
+
+                // Promote the image to the in-memory cache.
+                let mut image_cache = self.image_cache.write().await;
+                drop(image_cache.set(key.to_string(), image_bytes.clone()).await);
+                drop(image_cache);
+
+                image_bytes
+            }
+        };
+
+        Some((entry, image_bytes))
+    }
+
+    /// It gets an image from the disk cache. Expired images are revalidated
+    /// with the original source. If the original source can't be reached,
+    /// the expired image is used.
+    async fn get_image_from_disk_cache(&self, disk_cache: &DiskCache, url: &str) -> Option<Bytes> {
+        let (entry, image_bytes) = self.get_from_disk_cache(disk_cache, url).await?;
+
+        let ttl_seconds = self.cfg.settings.read().await.image_cache.ttl_seconds;
+
+        if !entry.is_expired(ttl_seconds, now_in_secs()) {
+            return Some(image_bytes);
+        }
+
+        match self.fetch_image(url, Some(&entry)).await {
+            Ok(None) => {
+                disk_cache.mark_as_validated(url, now_in_secs()).await;
+
+                Some(image_bytes)
+            }
+            Ok(Some(image)) => {
+                if self.check_image_size(&image.bytes).await.is_err() || self.update_image_cache(url, &image).await.is_err() {
+                    disk_cache.remove(url).await;
+
+                    return Some(image_bytes);
+                }
+
+                Some(image.bytes)
+            }
+            Err(_) => {
+                warn!("Failed to revalidate the cached image {url}, using the expired image");
+
+                Some(image_bytes)
+            }
+        }
+    }
+
+    /// It downloads an image from its original source.
+    ///
+    /// When a cached entry is given, the request is conditional and it
+    /// returns `None` if the image has not been modified.
+    ///
+    /// Only public addresses can be reached, see [`network`](super::network).
+    /// Redirects are followed up to `image_cache.max_redirects` and the
+    /// download is aborted when the image is bigger than
+    /// `image_cache.entry_size_limit`. The image format is detected from the
+    /// image bytes.
+    async fn fetch_image(&self, url: &str, cached_entry: Option<&DiskCacheEntry>) -> Result<Option<FetchedImage>, Error> {
+        let settings = self.cfg.settings.read().await;
+
+        let timeout = Duration::from_millis(settings.image_cache.max_request_timeout_ms);
+        let max_redirects = settings.image_cache.max_redirects;
+        let entry_size_limit = settings.image_cache.entry_size_limit;
+        let allowed_private_hosts = settings.image_cache.allowed_private_hosts.clone();
+
+        drop(settings);
+
+        let mut url = Url::parse(url).map_err(|_| Error::UrlIsUnreachable)?;
+        let mut redirects = 0;
+
+        let mut res = loop {
+            let client = self.http_clients.client_for(&url, &allowed_private_hosts).await?;
+
+            let mut request = client.get(url.clone()).timeout(timeout);
+
+            if let Some(entry) = cached_entry {
+                if let Some(etag) = &entry.etag {
+                    request = request.header(IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) = &entry.last_modified {
+                    request = request.header(IF_MODIFIED_SINCE, last_modified);
+                }
+            }
+
+            let res = request.send().await.map_err(|_| Error::UrlIsUnreachable)?;
+
+            if !res.status().is_redirection() || res.status() == StatusCode::NOT_MODIFIED {
+                break res;
+            }
+
+            if redirects >= max_redirects {
+                return Err(Error::UrlIsUnreachable);
+            }
+
+            redirects += 1;
+
+            let location = res
+                .headers()
+                .get(LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .ok_or(Error::UrlIsUnreachable)?;
+
+            url = url.join(location).map_err(|_| Error::UrlIsUnreachable)?;
+        };
+
+        if res.status() == StatusCode::NOT_MODIFIED {
+            return if cached_entry.is_some() {
+                Ok(None)
+            } else {
+                Err(Error::UrlIsNotAnImage)
+            };
+        }
+
+        if !res.status().is_success() {
+            return Err(Error::UrlIsUnreachable);
+        }
+
+        if res.content_length().is_some_and(|length| length > entry_size_limit as u64) {
+            return Err(Error::ImageTooBig);
+        }
+
+        let header_value = |name| {
+            res.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToOwned::to_owned)
+        };
+
+        let etag = header_value(ETAG);
+        let last_modified = header_value(LAST_MODIFIED);
+
+        let mut bytes = BytesMut::new();
+
+        while let Some(chunk) = res.chunk().await.map_err(|_| Error::UrlIsUnreachable)? {
+            if bytes.len() + chunk.len() > entry_size_limit {
+                return Err(Error::ImageTooBig);
+            }
+
+            bytes.extend_from_slice(&chunk);
+        }
+
+        let format = ImageFormat::detect(&bytes).ok_or(Error::UrlIsNotAnImage)?;
+
+        Ok(Some(FetchedImage {
+            bytes: bytes.freeze(),
+            content_type: format.mime_type().to_owned(),
+            etag,
+            last_modified,
+        }))
     }
 
     async fn check_user_quota(&self, user: &UserCompact) -> Result<(), Error> {
         if let Some(quota) = self.user_quotas.read().await.get(&user.user_id) {
-            if quota.is_reached() {
+            if quota.is_reached() && !quota.is_expired() {
                 return Err(Error::UserQuotaMet);
             }
         }
@@ -196,22 +791,41 @@ impl ImageCacheService {
         Ok(())
     }
 
-    async fn update_image_cache(&self, url: &str, image_bytes: &Bytes) -> Result<(), Error> {
+    async fn update_image_cache(&self, url: &str, image: &FetchedImage) -> Result<(), Error> {
         if self
             .image_cache
             .write()
             .await
-            .set(url.to_string(), image_bytes.clone())
+            .set(url.to_string(), image.bytes.clone())
             .await
             .is_err()
         {
             return Err(Error::ImageTooBig);
         }
 
+        if let Some(disk_cache) = &self.disk_cache {
+            let entry = DiskCacheEntry {
+                url: url.to_string(),
+                content_hash: String::new(),
+                size: image.bytes.len(),
+                content_type: image.content_type.clone(),
+                etag: image.etag.clone(),
+                last_modified: image.last_modified.clone(),
+                validated_at: now_in_secs(),
+            };
+
+            // The image is still cached in memory if it can't be cached on disk.
+            if let Err(err) = disk_cache.insert(entry, &image.bytes).await {
+                error!("Failed to cache the image {url} on disk: {err}");
+            }
+        }
+
         Ok(())
     }
 
-    async fn update_user_quota(&self, user: &UserCompact, amount: usize) -> Result<(), Error> {
+    /// It adds the downloaded bytes to the user quota usage. The `variant`
+    /// label is given for resized variants.
+    async fn update_user_quota(&self, user: &UserCompact, amount: usize, variant: Option<&str>) -> Result<(), Error> {
         let settings = self.cfg.settings.read().await;
 
         let mut quota = self
@@ -226,10 +840,78 @@ impl ImageCacheService {
                 settings.image_cache.user_quota_period_seconds,
             ));
 
-        let _ = quota.add_usage(amount);
+        let _ = match variant {
+            Some(variant) => quota.add_variant_usage(variant, amount),
+            None => quota.add_usage(amount),
+        };
 
-        let _ = self.user_quotas.write().await.insert(user.user_id, quota);
+        if let Err(err) = self.database.update_image_cache_quota(&quota.to_db()).await {
+            error!("Failed to save the image cache quota of user {}: {err:?}", user.user_id);
+        }
+
+        self.user_quotas.write().await.insert(user.user_id, quota);
 
         Ok(())
     }
 }
+
+/// It returns the hit and miss ratios.
+#[allow(clippy::cast_precision_loss)]
+fn ratios(hits: u64, misses: u64) -> (f64, f64) {
+    let requests = hits + misses;
+
+    if requests == 0 {
+        return (0.0, 0.0);
+    }
+
+    (hits as f64 / requests as f64, misses as f64 / requests as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImageCacheQuota, RequestCounters, MAX_ACCOUNTED_VARIANTS, MAX_TRACKED_URLS, OTHER_VARIANTS};
+
+    #[test]
+    fn it_should_discard_the_least_recently_requested_url_when_the_limit_is_reached() {
+        let mut request_counters = RequestCounters::default();
+
+        for i in 0..MAX_TRACKED_URLS {
+            request_counters.record_url_request(&format!("https://example.com/{i}.png"));
+        }
+
+        request_counters.record_url_request("https://example.com/0.png");
+        request_counters.record_url_request("https://example.com/new.png");
+
+        assert_eq!(request_counters.url_requests.len(), MAX_TRACKED_URLS);
+        assert_eq!(request_counters.url_requests.get("https://example.com/0.png"), Some(&2));
+        assert_eq!(request_counters.url_requests.get("https://example.com/1.png"), None);
+        assert_eq!(request_counters.url_requests.get("https://example.com/new.png"), Some(&1));
+    }
+
+    #[test]
+    fn it_should_account_the_quota_usage_of_every_variant() {
+        let mut quota = ImageCacheQuota::new(1, 1_000_000, 3600);
+
+        quota.add_usage(100).unwrap();
+        quota.add_variant_usage("200x2048:WebP", 10).unwrap();
+        quota.add_variant_usage("200x2048:WebP", 20).unwrap();
+        quota.add_variant_usage("100x100:Jpeg", 5).unwrap();
+
+        assert_eq!(quota.usage, 135);
+        assert_eq!(quota.variant_usage, 35);
+        assert_eq!(quota.variant_usages.get("200x2048:WebP"), Some(&30));
+        assert_eq!(quota.variant_usages.get("100x100:Jpeg"), Some(&5));
+    }
+
+    #[test]
+    fn it_should_group_the_quota_usage_of_the_variants_over_the_limit() {
+        let mut quota = ImageCacheQuota::new(1, 1_000_000, 3600);
+
+        for width in 0..=MAX_ACCOUNTED_VARIANTS {
+            quota.add_variant_usage(&format!("{width}x2048:WebP"), 1).unwrap();
+        }
+
+        assert_eq!(quota.variant_usages.len(), MAX_ACCOUNTED_VARIANTS + 1);
+        assert_eq!(quota.variant_usages.get(OTHER_VARIANTS), Some(&1));
+    }
+}
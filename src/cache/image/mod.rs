@@ -1 +1,5 @@
+pub mod disk;
+pub mod format;
 pub mod manager;
+pub mod network;
+pub mod variant;
@@ -0,0 +1,256 @@
+//! Protection against Server-Side Request Forgery (SSRF).
+//!
+//! Image URLs are provided by users, so the proxy could be used to send
+//! requests to hosts in the server's internal network. Only public addresses
+//! can be reached, unless the host is in the `image_cache.allowed_private_hosts`
+//! setting.
+//!
+//! Domain names are resolved before sending the request and the HTTP client
+//! is pinned to the public addresses found. That way, a domain can't resolve
+//! to a public address when it's checked and to a private address when the
+//! request is sent (DNS rebinding).
+//!
+//! The pinned clients are reused for [`CLIENT_TTL`], so the connections to a
+//! host are kept alive between requests and DNS changes are picked up
+//! afterwards.
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use reqwest::redirect::Policy;
+use tokio::net::lookup_host;
+use tokio::sync::Mutex;
+use url::{Host, Url};
+
+use super::manager::Error;
+
+/// Time a client pinned to the addresses of a host is reused.
+pub const CLIENT_TTL: Duration = Duration::from_secs(60);
+
+/// Maximum number of hosts with a client. When it's reached, all the clients
+/// are discarded.
+const MAX_CLIENTS: usize = 1000;
+
+/// The host, the port and whether the host is an allowed private host.
+type ClientKey = (String, u16, bool);
+
+/// HTTP clients pinned to the public addresses of every host.
+#[derive(Default)]
+pub struct Clients {
+    clients: Mutex<HashMap<ClientKey, (reqwest::Client, Instant)>>,
+}
+
+impl Clients {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// It returns an HTTP client that can only connect to the public
+    /// addresses of the URL host. The client is built the first time the
+    /// host is requested and reused for [`CLIENT_TTL`].
+    ///
+    /// Redirects are not followed, so every redirect target can be checked.
+    ///
+    /// # Errors
+    ///
+    /// It returns `Error::UrlIsUnreachable` if the URL is not an HTTP URL or
+    /// its host does not have any public address.
+    pub async fn client_for(&self, url: &Url, allowed_private_hosts: &[String]) -> Result<reqwest::Client, Error> {
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return Err(Error::UrlIsUnreachable);
+        }
+
+        let host = url.host().ok_or(Error::UrlIsUnreachable)?;
+        let port = url.port_or_known_default().ok_or(Error::UrlIsUnreachable)?;
+        let allowed = is_allowed(&host, allowed_private_hosts);
+
+        let key = (host.to_string(), port, allowed);
+
+        if let Some((client, created_at)) = self.clients.lock().await.get(&key) {
+            if created_at.elapsed() < CLIENT_TTL {
+                return Ok(client.clone());
+            }
+        }
+
+        let client = build_client(&host, port, allowed).await?;
+
+        let mut clients = self.clients.lock().await;
+
+        if clients.len() >= MAX_CLIENTS {
+            clients.clear();
+        }
+
+        clients.insert(key, (client.clone(), Instant::now()));
+
+        Ok(client)
+    }
+}
+
+/// It builds an HTTP client that can only connect to the public addresses of
+/// the host, unless it's an allowed private host.
+async fn build_client(host: &Host<&str>, port: u16, allowed: bool) -> Result<reqwest::Client, Error> {
+    // Proxies would connect to the host instead of the pinned addresses
+    let builder = reqwest::Client::builder().redirect(Policy::none()).no_proxy();
+
+    let builder = match *host {
+        _ if allowed => builder,
+        Host::Ipv4(ip) if is_public_address(&IpAddr::V4(ip)) => builder,
+        Host::Ipv6(ip) if is_public_address(&IpAddr::V6(ip)) => builder,
+        Host::Ipv4(_) | Host::Ipv6(_) => return Err(Error::UrlIsUnreachable),
+        Host::Domain(domain) => {
+            let public_addrs: Vec<SocketAddr> = lookup_host((domain, port))
+                .await
+                .map_err(|_| Error::UrlIsUnreachable)?
+                .filter(|addr| is_public_address(&addr.ip()))
+                .collect();
+
+            if public_addrs.is_empty() {
+                return Err(Error::UrlIsUnreachable);
+            }
+
+            builder.resolve_to_addrs(domain, &public_addrs)
+        }
+    };
+
+    builder.build().map_err(|_| Error::UrlIsUnreachable)
+}
+
+fn is_allowed(host: &Host<&str>, allowed_private_hosts: &[String]) -> bool {
+    let host = match host {
+        Host::Domain(domain) => (*domain).to_string(),
+        Host::Ipv4(ip) => ip.to_string(),
+        Host::Ipv6(ip) => ip.to_string(),
+    };
+
+    allowed_private_hosts
+        .iter()
+        .any(|allowed_host| allowed_host.trim_matches(['[', ']']).eq_ignore_ascii_case(&host))
+}
+
+/// Whether the address can be reached from the proxy. Private, loopback,
+/// link-local and other special-purpose addresses are not public.
+#[must_use]
+pub fn is_public_address(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(&ip),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: &Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // "This network" (0.0.0.0/8)
+        || first == 0
+        // Shared address space (100.64.0.0/10)
+        || (first == 100 && (second & 0b1100_0000) == 64)
+        // Benchmarking (198.18.0.0/15)
+        || (first == 198 && (second & 0b1111_1110) == 18)
+        // Reserved (240.0.0.0/4)
+        || first >= 240)
+}
+
+fn is_public_ipv6(ip: &Ipv6Addr) -> bool {
+    let segments = ip.segments();
+
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // Unique local (fc00::/7)
+        || (segments[0] & 0xfe00) == 0xfc00
+        // Link-local (fe80::/10)
+        || (segments[0] & 0xffc0) == 0xfe80
+        // Site-local, deprecated (fec0::/10)
+        || (segments[0] & 0xffc0) == 0xfec0
+        // IPv4/IPv6 translation (64:ff9b::/96), it can embed any IPv4 address
+        || segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0]
+        // 6to4 (2002::/16), it can embed any IPv4 address
+        || segments[0] == 0x2002)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use url::Url;
+
+    use super::{is_public_address, Clients};
+
+    fn is_public(ip: &str) -> bool {
+        is_public_address(&ip.parse::<IpAddr>().unwrap())
+    }
+
+    #[test]
+    fn private_loopback_and_link_local_addresses_should_not_be_public() {
+        for ip in [
+            "10.0.0.1",
+            "172.16.0.1",
+            "192.168.1.1",
+            "127.0.0.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "100.64.0.1",
+            "::1",
+            "::",
+            "fc00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "198.18.0.1",
+            "198.19.255.255",
+            "240.0.0.1",
+            "64:ff9b::7f00:1",
+            "2002:7f00:1::1",
+            "fec0::1",
+        ] {
+            assert!(!is_public(ip), "{ip} should not be public");
+        }
+    }
+
+    #[test]
+    fn other_addresses_should_be_public() {
+        for ip in ["1.1.1.1", "93.184.216.34", "198.20.0.1", "2606:2800:220:1:248:1893:25c8:1946"] {
+            assert!(is_public(ip), "{ip} should be public");
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_urls_with_private_hosts() {
+        let url = Url::parse("http://127.0.0.1:8080/image.png").unwrap();
+
+        assert!(Clients::new().client_for(&url, &[]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_should_allow_urls_with_allowed_private_hosts() {
+        let url = Url::parse("http://127.0.0.1:8080/image.png").unwrap();
+
+        assert!(Clients::new().client_for(&url, &["127.0.0.1".to_string()]).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn it_should_not_reuse_the_client_of_an_allowed_private_host_once_it_is_not_allowed() {
+        let clients = Clients::new();
+        let url = Url::parse("http://127.0.0.1:8080/image.png").unwrap();
+
+        assert!(clients.client_for(&url, &["127.0.0.1".to_string()]).await.is_ok());
+        assert!(clients.client_for(&url, &[]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_should_only_allow_http_urls() {
+        let url = Url::parse("file:///etc/passwd").unwrap();
+
+        assert!(Clients::new().client_for(&url, &[]).await.is_err());
+    }
+}
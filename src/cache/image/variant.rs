@@ -0,0 +1,269 @@
+//! Resized versions of the proxied images.
+//!
+//! Clients can request an image with a maximum width and height, and in a
+//! given format. The image is resized keeping its aspect ratio. It's never
+//! enlarged. Variants are always re-encoded, so metadata like EXIF is removed.
+use std::io::Cursor;
+
+use bytes::Bytes;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::io::{Limits, Reader};
+use image::{ColorType, DynamicImage, GenericImageView, ImageEncoder};
+
+use super::format::ImageFormat;
+use super::manager::Error;
+use crate::config::ImageVariantFormat;
+
+/// Maximum width and height of the original images that can be resized.
+const MAX_ORIGINAL_DIMENSION: u32 = 16_384;
+
+/// The requested version of an image. An image without width, height and
+/// format is the original image.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImageVariant {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub format: Option<ImageVariantFormat>,
+}
+
+impl ImageVariant {
+    #[must_use]
+    pub fn is_original(&self) -> bool {
+        self.width.is_none() && self.height.is_none() && self.format.is_none()
+    }
+}
+
+/// The variant to generate, with the default settings applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VariantSpec {
+    pub max_width: u32,
+    pub max_height: u32,
+    pub format: ImageVariantFormat,
+    /// Quality (1-100) of the JPEG variants. PNG and WebP variants are
+    /// lossless, so it does not apply to them.
+    pub quality: u8,
+}
+
+impl VariantSpec {
+    /// It applies the default format and quality, and the dimension limit to
+    /// the requested variant.
+    #[must_use]
+    pub fn new(variant: &ImageVariant, default_format: ImageVariantFormat, quality: u8, max_dimension: u32) -> Self {
+        let limit = |dimension: Option<u32>| dimension.unwrap_or(max_dimension).clamp(1, max_dimension);
+
+        Self {
+            max_width: limit(variant.width),
+            max_height: limit(variant.height),
+            format: variant.format.unwrap_or(default_format),
+            quality: quality.clamp(1, 100),
+        }
+    }
+
+    /// The cache key of the variant of the original image with the
+    /// `original_hash` content hash.
+    ///
+    /// The key depends on the original image contents, so variants don't
+    /// have to be revalidated. A new variant is generated when the original
+    /// image changes.
+    ///
+    /// The quality is only part of the key of JPEG variants, so changing it
+    /// does not regenerate the lossless ones.
+    #[must_use]
+    pub fn cache_key(&self, original_hash: &str) -> String {
+        let key = format!(
+            "{}{}x{}:{:?}",
+            cache_key_prefix(original_hash),
+            self.max_width,
+            self.max_height,
+            self.format
+        );
+
+        match self.format {
+            ImageVariantFormat::Jpeg => format!("{key}:{}", self.quality),
+            ImageVariantFormat::Png | ImageVariantFormat::WebP => key,
+        }
+    }
+
+    /// The variant name used to account its quota usage. For example,
+    /// `200x2048:WebP`.
+    #[must_use]
+    pub fn label(&self) -> String {
+        format!("{}x{}:{:?}", self.max_width, self.max_height, self.format)
+    }
+
+    #[must_use]
+    pub fn mime_type(&self) -> &'static str {
+        match self.format {
+            ImageVariantFormat::Jpeg => ImageFormat::Jpeg.mime_type(),
+            ImageVariantFormat::Png => ImageFormat::Png.mime_type(),
+            ImageVariantFormat::WebP => ImageFormat::WebP.mime_type(),
+        }
+    }
+
+    /// It generates the variant of the original image.
+    ///
+    /// # Errors
+    ///
+    /// It returns `Error::UrlIsNotAnImage` if the original image can't be
+    /// decoded, for example, AVIF images, or the variant can't be encoded.
+    pub fn generate(&self, original: &Bytes) -> Result<Bytes, Error> {
+        let image = decode(original)?;
+
+        let (width, height) = image.dimensions();
+
+        let image = if width > self.max_width || height > self.max_height {
+            image.resize(self.max_width, self.max_height, FilterType::Lanczos3)
+        } else {
+            image
+        };
+
+        let mut bytes = Vec::new();
+
+        let result = match self.format {
+            ImageVariantFormat::Jpeg => JpegEncoder::new_with_quality(&mut bytes, self.quality).encode_image(&image.to_rgb8()),
+            ImageVariantFormat::Png => {
+                let image = image.to_rgba8();
+                PngEncoder::new(&mut bytes).write_image(&image, image.width(), image.height(), ColorType::Rgba8)
+            }
+            ImageVariantFormat::WebP => {
+                let image = image.to_rgba8();
+                WebPEncoder::new_lossless(&mut bytes).write_image(&image, image.width(), image.height(), ColorType::Rgba8)
+            }
+        };
+
+        result.map_err(|_| Error::UrlIsNotAnImage)?;
+
+        Ok(Bytes::from(bytes))
+    }
+}
+
+/// The prefix of the cache keys of all the variants of the original image
+/// with the `original_hash` content hash.
+#[must_use]
+pub fn cache_key_prefix(original_hash: &str) -> String {
+    format!("variant:{original_hash}:")
+}
+
+fn decode(bytes: &Bytes) -> Result<DynamicImage, Error> {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_ORIGINAL_DIMENSION);
+    limits.max_image_height = Some(MAX_ORIGINAL_DIMENSION);
+
+    let mut reader = Reader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|_| Error::UrlIsNotAnImage)?;
+
+    reader.limits(limits);
+
+    reader.decode().map_err(|_| Error::UrlIsNotAnImage)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use bytes::Bytes;
+    use image::{DynamicImage, GenericImageView, ImageOutputFormat};
+
+    use super::{ImageVariant, VariantSpec};
+    use crate::cache::image::format::ImageFormat;
+    use crate::config::ImageVariantFormat;
+
+    fn png_image(width: u32, height: u32) -> Bytes {
+        let mut bytes = Vec::new();
+
+        DynamicImage::new_rgba8(width, height)
+            .write_to(&mut Cursor::new(&mut bytes), ImageOutputFormat::Png)
+            .unwrap();
+
+        Bytes::from(bytes)
+    }
+
+    #[test]
+    fn it_should_resize_images_keeping_the_aspect_ratio() {
+        let variant = ImageVariant {
+            width: Some(100),
+            ..ImageVariant::default()
+        };
+
+        let spec = VariantSpec::new(&variant, ImageVariantFormat::Png, 80, 2048);
+
+        let resized = image::load_from_memory(&spec.generate(&png_image(400, 200)).unwrap()).unwrap();
+
+        assert_eq!(resized.dimensions(), (100, 50));
+    }
+
+    #[test]
+    fn it_should_not_enlarge_images() {
+        let variant = ImageVariant {
+            width: Some(1000),
+            height: Some(1000),
+            ..ImageVariant::default()
+        };
+
+        let spec = VariantSpec::new(&variant, ImageVariantFormat::Png, 80, 2048);
+
+        let resized = image::load_from_memory(&spec.generate(&png_image(40, 20)).unwrap()).unwrap();
+
+        assert_eq!(resized.dimensions(), (40, 20));
+    }
+
+    #[test]
+    fn it_should_encode_images_in_the_requested_format() {
+        for (format, expected_format) in [
+            (ImageVariantFormat::Jpeg, ImageFormat::Jpeg),
+            (ImageVariantFormat::Png, ImageFormat::Png),
+            (ImageVariantFormat::WebP, ImageFormat::WebP),
+        ] {
+            let variant = ImageVariant {
+                format: Some(format),
+                ..ImageVariant::default()
+            };
+
+            let spec = VariantSpec::new(&variant, ImageVariantFormat::Jpeg, 80, 2048);
+
+            assert_eq!(
+                ImageFormat::detect(&spec.generate(&png_image(10, 10)).unwrap()),
+                Some(expected_format)
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_limit_the_variant_dimensions() {
+        let variant = ImageVariant {
+            width: Some(10_000),
+            height: Some(0),
+            ..ImageVariant::default()
+        };
+
+        let spec = VariantSpec::new(&variant, ImageVariantFormat::Jpeg, 80, 2048);
+
+        assert_eq!((spec.max_width, spec.max_height), (2048, 1));
+    }
+
+    #[test]
+    fn it_should_only_include_the_quality_in_the_cache_key_of_jpeg_variants() {
+        for (format, quality_changes_the_key) in [
+            (ImageVariantFormat::Jpeg, true),
+            (ImageVariantFormat::Png, false),
+            (ImageVariantFormat::WebP, false),
+        ] {
+            let variant = ImageVariant {
+                format: Some(format),
+                ..ImageVariant::default()
+            };
+
+            let low_quality = VariantSpec::new(&variant, ImageVariantFormat::Jpeg, 50, 2048);
+            let high_quality = VariantSpec::new(&variant, ImageVariantFormat::Jpeg, 90, 2048);
+
+            assert_eq!(
+                low_quality.cache_key("hash") != high_quality.cache_key("hash"),
+                quality_changes_the_key
+            );
+        }
+    }
+}
@@ -26,6 +26,8 @@ pub struct BytesCache {
     bytes_table: IndexMap<String, BytesCacheEntry>,
     total_capacity: usize,
     entry_size_limit: usize,
+    /// Number of entries removed to make room for new entries.
+    evictions: u64,
 }
 
 impl BytesCache {
@@ -35,6 +37,7 @@ impl BytesCache {
             bytes_table: IndexMap::new(),
             total_capacity: 0,
             entry_size_limit: 0,
+            evictions: 0,
         }
     }
 
@@ -105,6 +108,18 @@ impl BytesCache {
         size
     }
 
+    // Total capacity in bytes.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.total_capacity
+    }
+
+    // Number of entries removed to make room for new entries.
+    #[must_use]
+    pub fn evictions(&self) -> u64 {
+        self.evictions
+    }
+
     /// Adds a image to the cache.
     ///
     /// # Errors
@@ -147,11 +162,35 @@ impl BytesCache {
                 .expect("bytes cache has no more entries, yet there isn't enough space.");
 
             size_freed += oldest_entry.bytes.len();
+            self.evictions += 1;
         }
 
         Ok(())
     }
 
+    // Remove and return the entry for the key.
+    pub fn remove(&mut self, key: &str) -> Option<BytesCacheEntry> {
+        self.bytes_table.shift_remove(key)
+    }
+
+    // Remove all the entries whose key starts with the prefix. It returns the number of removed entries.
+    pub fn remove_with_prefix(&mut self, prefix: &str) -> usize {
+        let len = self.bytes_table.len();
+
+        self.bytes_table.retain(|key, _| !key.starts_with(prefix));
+
+        len - self.bytes_table.len()
+    }
+
+    // Remove all the entries. It returns the number of removed entries.
+    pub fn clear(&mut self) -> usize {
+        let len = self.bytes_table.len();
+
+        self.bytes_table.clear();
+
+        len
+    }
+
     // Remove and return the oldest entry.
     pub fn pop(&mut self) -> Option<BytesCacheEntry> {
         self.bytes_table.shift_remove_index(0).map(|(_, entry)| entry)
@@ -212,6 +251,32 @@ mod tests {
         assert_eq!(bytes_cache.len().await, 1);
     }
 
+    #[tokio::test]
+    async fn it_should_count_the_entries_evicted_to_make_room_for_new_entries() {
+        let bytes: Bytes = Bytes::from("abcdef");
+
+        let mut bytes_cache = BytesCache::with_capacity_and_entry_size_limit(bytes.len(), bytes.len()).unwrap();
+
+        assert!(bytes_cache.set("key1".to_string(), bytes.clone()).await.is_ok());
+        assert!(bytes_cache.set("key2".to_string(), bytes).await.is_ok());
+
+        assert_eq!(bytes_cache.evictions(), 1);
+    }
+
+    #[tokio::test]
+    async fn it_should_remove_the_entries_whose_key_starts_with_a_prefix() {
+        let bytes: Bytes = Bytes::from("abc");
+
+        let mut bytes_cache = BytesCache::with_capacity_and_entry_size_limit(bytes.len() * 3, bytes.len()).unwrap();
+
+        assert!(bytes_cache.set("variant:1:a".to_string(), bytes.clone()).await.is_ok());
+        assert!(bytes_cache.set("variant:1:b".to_string(), bytes.clone()).await.is_ok());
+        assert!(bytes_cache.set("variant:2:a".to_string(), bytes).await.is_ok());
+
+        assert_eq!(bytes_cache.remove_with_prefix("variant:1:"), 2);
+        assert!(bytes_cache.get("variant:2:a").await.is_some());
+    }
+
     #[tokio::test]
     async fn set_bytes_cache_with_capacity_and_entry_size_limit_should_fail() {
         let mut bytes_cache = BytesCache::with_capacity_and_entry_size_limit(6, 5).unwrap();
@@ -5,10 +5,11 @@ use crate::config::Configuration;
 use crate::databases::database::Database;
 use crate::services::authentication::{DbUserAuthenticationRepository, JsonWebToken, Service};
 use crate::services::category::{self, DbCategoryRepository};
+use crate::services::image::{self, DbImageRepository, DbTorrentImageRepository};
 use crate::services::tag::{self, DbTagRepository};
 use crate::services::torrent::{
     DbCanonicalInfoHashGroupRepository, DbTorrentAnnounceUrlRepository, DbTorrentFileRepository, DbTorrentInfoRepository,
-    DbTorrentListingGenerator, DbTorrentRepository, DbTorrentTagRepository,
+    DbTorrentListingGenerator, DbTorrentRepository, DbTorrentTagRepository, DbTorrentWebSeedRepository,
 };
 use crate::services::user::{self, DbBannedUserList, DbUserProfileRepository, DbUserRepository};
 use crate::services::{proxy, settings, torrent};
@@ -38,6 +39,9 @@ pub struct AppData {
     pub torrent_info_repository: Arc<DbTorrentInfoRepository>,
     pub torrent_file_repository: Arc<DbTorrentFileRepository>,
     pub torrent_announce_url_repository: Arc<DbTorrentAnnounceUrlRepository>,
+    pub torrent_web_seed_repository: Arc<DbTorrentWebSeedRepository>,
+    pub torrent_image_repository: Arc<DbTorrentImageRepository>,
+    pub image_repository: Arc<DbImageRepository>,
     pub torrent_tag_repository: Arc<DbTorrentTagRepository>,
     pub torrent_listing_generator: Arc<DbTorrentListingGenerator>,
     pub banned_user_list: Arc<DbBannedUserList>,
@@ -45,10 +49,18 @@ pub struct AppData {
     pub category_service: Arc<category::Service>,
     pub tag_service: Arc<tag::Service>,
     pub proxy_service: Arc<proxy::Service>,
+    pub image_service: Arc<image::Service>,
     pub settings_service: Arc<settings::Service>,
     pub torrent_service: Arc<torrent::Index>,
     pub registration_service: Arc<user::RegistrationService>,
     pub ban_service: Arc<user::BanService>,
+    pub tracker_admin_service: Arc<crate::services::tracker::Service>,
+    pub dead_torrent_service: Arc<crate::services::dead_torrent::Service>,
+    pub deletion_service: Arc<user::DeletionService>,
+    pub tracker_key_service: Arc<user::TrackerKeyService>,
+    pub profile_service: Arc<user::ProfileService>,
+    pub mail_admin_service: Arc<crate::services::mail::Service>,
+    pub notification_service: Arc<crate::services::notification::Service>,
 }
 
 impl AppData {
@@ -74,6 +86,9 @@ impl AppData {
         torrent_info_repository: Arc<DbTorrentInfoRepository>,
         torrent_file_repository: Arc<DbTorrentFileRepository>,
         torrent_announce_url_repository: Arc<DbTorrentAnnounceUrlRepository>,
+        torrent_web_seed_repository: Arc<DbTorrentWebSeedRepository>,
+        torrent_image_repository: Arc<DbTorrentImageRepository>,
+        image_repository: Arc<DbImageRepository>,
         torrent_tag_repository: Arc<DbTorrentTagRepository>,
         torrent_listing_generator: Arc<DbTorrentListingGenerator>,
         banned_user_list: Arc<DbBannedUserList>,
@@ -81,10 +96,18 @@ impl AppData {
         category_service: Arc<category::Service>,
         tag_service: Arc<tag::Service>,
         proxy_service: Arc<proxy::Service>,
+        image_service: Arc<image::Service>,
         settings_service: Arc<settings::Service>,
         torrent_service: Arc<torrent::Index>,
         registration_service: Arc<user::RegistrationService>,
         ban_service: Arc<user::BanService>,
+        tracker_admin_service: Arc<crate::services::tracker::Service>,
+        dead_torrent_service: Arc<crate::services::dead_torrent::Service>,
+        deletion_service: Arc<user::DeletionService>,
+        tracker_key_service: Arc<user::TrackerKeyService>,
+        profile_service: Arc<user::ProfileService>,
+        mail_admin_service: Arc<crate::services::mail::Service>,
+        notification_service: Arc<crate::services::notification::Service>,
     ) -> AppData {
         AppData {
             cfg,
@@ -107,6 +130,9 @@ impl AppData {
             torrent_info_repository,
             torrent_file_repository,
             torrent_announce_url_repository,
+            torrent_web_seed_repository,
+            torrent_image_repository,
+            image_repository,
             torrent_tag_repository,
             torrent_listing_generator,
             banned_user_list,
@@ -114,10 +140,18 @@ impl AppData {
             category_service,
             tag_service,
             proxy_service,
+            image_service,
             settings_service,
             torrent_service,
             registration_service,
             ban_service,
+            tracker_admin_service,
+            dead_torrent_service,
+            deletion_service,
+            tracker_key_service,
+            profile_service,
+            mail_admin_service,
+            notification_service,
         }
     }
 }
@@ -1,13 +1,15 @@
 //! Configuration for the application.
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
+use std::str::FromStr;
 use std::sync::Arc;
-use std::{env, fs};
+use std::{env, fmt, fs};
 
 use config::{Config, ConfigError, File, FileFormat};
 use log::warn;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use torrust_index_located_error::{Located, LocatedError};
 use url::{ParseError, Url};
 
@@ -15,8 +17,18 @@ use url::{ParseError, Url};
 #[derive(Debug, Default, Clone)]
 pub struct Info {
     index_toml: String,
+    config_path: Option<String>,
     tracker_api_token: Option<String>,
     auth_secret_key: Option<String>,
+    overrides: Vec<Override>,
+}
+
+/// A setting overridden with an environment variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Override {
+    /// The setting key in dot notation. For example: `tracker.token`.
+    key: String,
+    value: String,
 }
 
 impl Info {
@@ -26,8 +38,8 @@ impl Info {
     ///
     /// ```no_run
     /// # use torrust_index::config::Info;
-    /// # let (env_var_config, env_var_path_config, default_path_config, env_var_tracker_api_token, env_var_auth_secret_key) = ("".to_string(), "".to_string(), "".to_string(), "".to_string(), "".to_string());
-    /// let result = Info::new(env_var_config, env_var_path_config, default_path_config, env_var_tracker_api_token, env_var_auth_secret_key);
+    /// # let (env_var_config, env_var_path_config, default_path_config, env_var_tracker_api_token, env_var_auth_secret_key, env_var_prefix_overrides) = ("".to_string(), "".to_string(), "".to_string(), "".to_string(), "".to_string(), "".to_string());
+    /// let result = Info::new(env_var_config, env_var_path_config, default_path_config, env_var_tracker_api_token, env_var_auth_secret_key, env_var_prefix_overrides);
     /// ```
     ///
     /// # Errors
@@ -41,41 +53,140 @@ impl Info {
         default_path_config: String,
         env_var_tracker_api_token: String,
         env_var_auth_secret_key: String,
+        env_var_prefix_overrides: String,
     ) -> Result<Self, Error> {
-        let index_toml = if let Ok(index_toml) = env::var(&env_var_config) {
-            println!("Loading configuration from env var {env_var_config} ...");
+        let (index_toml, config_path) = if let Ok(index_toml) = env::var(&env_var_config) {
+            eprintln!("Loading configuration from env var {env_var_config} ...");
 
-            index_toml
+            (index_toml, None)
         } else {
             let config_path = if let Ok(config_path) = env::var(env_var_path_config) {
-                println!("Loading configuration file: `{config_path}` ...");
+                eprintln!("Loading configuration file: `{config_path}` ...");
 
                 config_path
             } else {
-                println!("Loading default configuration file: `{default_path_config}` ...");
+                eprintln!("Loading default configuration file: `{default_path_config}` ...");
 
                 default_path_config
             };
 
-            fs::read_to_string(config_path)
+            let index_toml = fs::read_to_string(&config_path)
                 .map_err(|e| Error::UnableToLoadFromConfigFile {
                     source: (Arc::new(e) as Arc<dyn std::error::Error + Send + Sync>).into(),
                 })?
                 .parse()
-                .map_err(|_e: std::convert::Infallible| Error::Infallible)?
+                .map_err(|_e: std::convert::Infallible| Error::Infallible)?;
+
+            (index_toml, Some(config_path))
         };
 
         let tracker_api_token = env::var(env_var_tracker_api_token).ok();
         let auth_secret_key = env::var(env_var_auth_secret_key).ok();
+        let overrides = overrides_from_env_vars(&env_var_prefix_overrides, env::vars())?;
 
         Ok(Self {
             index_toml,
+            config_path,
             tracker_api_token,
             auth_secret_key,
+            overrides,
+        })
+    }
+
+    /// Build Configuration Info from a configuration file. It's used to reload
+    /// the configuration when the file changes.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to read the configuration file or the
+    /// secrets files.
+    pub fn from_file(
+        config_path: &str,
+        env_var_tracker_api_token: &str,
+        env_var_auth_secret_key: &str,
+        env_var_prefix_overrides: &str,
+    ) -> Result<Self, Error> {
+        let index_toml = fs::read_to_string(config_path).map_err(|e| Error::UnableToLoadFromConfigFile {
+            source: (Arc::new(e) as Arc<dyn std::error::Error + Send + Sync>).into(),
+        })?;
+
+        Ok(Self {
+            index_toml,
+            config_path: Some(config_path.to_owned()),
+            tracker_api_token: env::var(env_var_tracker_api_token).ok(),
+            auth_secret_key: env::var(env_var_auth_secret_key).ok(),
+            overrides: overrides_from_env_vars(env_var_prefix_overrides, env::vars())?,
         })
     }
 }
 
+/// The separator used in the environment variables overriding settings. It
+/// separates the prefix, the section and the setting name. For example:
+/// `TORRUST_INDEX_CONFIG__TRACKER__TOKEN`.
+const ENV_VAR_OVERRIDE_SEPARATOR: &str = "__";
+
+/// The suffix for environment variables containing the path to a file with the
+/// setting value instead of the value itself. For example:
+/// `TORRUST_INDEX_CONFIG__TRACKER__TOKEN_FILE=/run/secrets/tracker_token`.
+const ENV_VAR_OVERRIDE_FILE_SUFFIX: &str = "_FILE";
+
+/// It collects the settings overridden with environment variables.
+///
+/// Given the prefix `TORRUST_INDEX_CONFIG`:
+///
+/// - `TORRUST_INDEX_CONFIG__TRACKER__TOKEN=MyAccessToken` overrides the
+///   `tracker.token` setting with the value `MyAccessToken`.
+/// - `TORRUST_INDEX_CONFIG__TRACKER__TOKEN_FILE=/run/secrets/tracker_token`
+///   overrides the `tracker.token` setting with the content of the file.
+///   Trailing line breaks are removed.
+///
+/// Overrides from files are applied after the ones with values.
+fn overrides_from_env_vars(prefix: &str, env_vars: impl Iterator<Item = (String, String)>) -> Result<Vec<Override>, Error> {
+    let prefix = format!("{prefix}{ENV_VAR_OVERRIDE_SEPARATOR}");
+
+    let mut overrides = vec![];
+    let mut overrides_from_files = vec![];
+
+    let mut env_vars: Vec<(String, String)> = env_vars.filter(|(name, _)| name.starts_with(&prefix)).collect();
+
+    env_vars.sort();
+
+    for (name, value) in env_vars {
+        let path = &name[prefix.len()..];
+
+        if let Some(path) = path.strip_suffix(ENV_VAR_OVERRIDE_FILE_SUFFIX) {
+            let content = fs::read_to_string(&value).map_err(|e| Error::UnableToLoadSecretFromFile {
+                env_var: name.clone(),
+                source: (Arc::new(e) as Arc<dyn std::error::Error + Send + Sync>).into(),
+            })?;
+
+            overrides_from_files.push(Override {
+                key: setting_key(path),
+                value: content.trim_end_matches(['\n', '\r']).to_owned(),
+            });
+        } else {
+            overrides.push(Override {
+                key: setting_key(path),
+                value,
+            });
+        }
+    }
+
+    overrides.append(&mut overrides_from_files);
+
+    Ok(overrides)
+}
+
+/// It converts the environment variable path into the setting key. For
+/// example: `TRACKER__TOKEN` into `tracker.token`.
+fn setting_key(env_var_path: &str) -> String {
+    env_var_path
+        .split(ENV_VAR_OVERRIDE_SEPARATOR)
+        .map(str::to_lowercase)
+        .collect::<Vec<String>>()
+        .join(".")
+}
+
 /// Errors that can occur when loading the configuration.
 #[derive(Error, Debug)]
 pub enum Error {
@@ -92,10 +203,21 @@ pub enum Error {
         source: LocatedError<'static, dyn std::error::Error + Send + Sync>,
     },
 
+    /// Unable to read the file referenced by a `_FILE` environment variable.
+    #[error("Unable to load secret from the file in env var {env_var}: {source}")]
+    UnableToLoadSecretFromFile {
+        env_var: String,
+        source: LocatedError<'static, dyn std::error::Error + Send + Sync>,
+    },
+
     /// Unable to load the configuration from the configuration file.
     #[error("Failed processing the configuration: {source}")]
     ConfigError { source: LocatedError<'static, ConfigError> },
 
+    /// The loaded configuration is not valid.
+    #[error("Invalid configuration: {source}")]
+    InvalidConfiguration { source: ValidationError },
+
     #[error("The error for errors that can never happen.")]
     Infallible,
 }
@@ -109,6 +231,48 @@ pub enum ValidationError {
 
     #[error("UDP private trackers are not supported. URL schemes for private tracker URLs must be HTTP ot HTTPS")]
     UdpTrackersInPrivateModeNotSupported,
+
+    #[error("Tracker URL scheme not supported for scraping: {scheme}. It must be UDP, HTTP or HTTPS")]
+    ScrapeNotSupportedForTrackerUrl { scheme: String },
+
+    #[error("Invalid extra tracker URL: {url}. It must be a UDP, HTTP or HTTPS URL")]
+    InvalidExtraTrackerUrl { url: String },
+
+    #[error("Statistics can't be imported from the API of the extra tracker {url}. Use Scrape or None")]
+    ApiStatisticsNotSupportedForExtraTracker { url: String },
+
+    #[error("The InMemory tracker backend is only available for testing. Use Api or None")]
+    InMemoryTrackerBackendNotAllowed,
+}
+
+/// Errors that can occur updating the configuration while the application is
+/// running.
+#[derive(Error, Debug)]
+pub enum UpdateError {
+    /// The new configuration is not valid. The current configuration is kept.
+    #[error("Invalid configuration: {source}")]
+    InvalidConfiguration { source: ValidationError },
+
+    /// The new configuration could not be written to the configuration file.
+    /// The current configuration is kept.
+    #[error("Unable to save the configuration into the config file: {source}")]
+    UnableToSaveToConfigFile {
+        source: LocatedError<'static, dyn std::error::Error + Send + Sync>,
+    },
+
+    /// The setting can only be changed by restarting the application.
+    #[error("The setting {key} can't be changed without restarting the application")]
+    SettingNotApplicableLive { key: String },
+
+    /// The setting is overridden with an environment variable, so the new
+    /// value would be lost after restarting the application.
+    #[error("The setting {key} is overridden with an environment variable")]
+    SettingOverridden { key: String },
+
+    /// The setting can only be changed in the configuration file or with an
+    /// environment variable.
+    #[error("The setting {key} can only be changed in the configuration file or with an environment variable")]
+    SettingNotEditable { key: String },
 }
 
 impl From<ConfigError> for Error {
@@ -137,7 +301,7 @@ impl Default for Website {
 
 /// See `TrackerMode` in [`torrust-tracker-primitives`](https://docs.rs/torrust-tracker-primitives)
 /// crate for more information.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TrackerMode {
     // todo: use https://crates.io/crates/torrust-tracker-primitives
     /// Will track every new info hash and serve every peer.
@@ -166,6 +330,148 @@ impl TrackerMode {
     pub fn is_close(&self) -> bool {
         !self.is_open()
     }
+
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrackerMode::Public => "Public",
+            TrackerMode::Private => "Private",
+            TrackerMode::Whitelisted => "Whitelisted",
+            TrackerMode::PrivateWhitelisted => "PrivateWhitelisted",
+        }
+    }
+
+    #[must_use]
+    pub fn is_whitelisted(&self) -> bool {
+        matches!(self, TrackerMode::Whitelisted | TrackerMode::PrivateWhitelisted)
+    }
+}
+
+impl FromStr for TrackerMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Public" => Ok(TrackerMode::Public),
+            "Private" => Ok(TrackerMode::Private),
+            "Whitelisted" => Ok(TrackerMode::Whitelisted),
+            "PrivateWhitelisted" => Ok(TrackerMode::PrivateWhitelisted),
+            _ => Err(format!("unknown tracker mode: {s}")),
+        }
+    }
+}
+
+/// Where the torrent statistics (seeders and leechers) are obtained from. It's
+/// configured for each tracker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatisticsSource {
+    /// The tracker backend (`backend`). Only available for the index tracker.
+    Api,
+    /// Scraping the tracker `url`. UDP trackers are scraped using the
+    /// BEP 15 protocol and HTTP trackers using the `scrape` endpoint. It works
+    /// with any tracker.
+    Scrape,
+    /// Statistics are not imported from the tracker.
+    None,
+}
+
+impl Default for StatisticsSource {
+    fn default() -> Self {
+        Self::Api
+    }
+}
+
+/// The tracker backend used to manage the whitelist and the user keys, and to
+/// get the torrent statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrackerBackendKind {
+    /// The Torrust Tracker REST API (`api_url`).
+    Api,
+    /// There is no tracker integrated with the index, for example, a public
+    /// index using an open tracker. Whitelist changes are ignored and user
+    /// keys can't be issued.
+    None,
+    /// A tracker simulated in memory. It's used for testing, so it can't be
+    /// selected in the configuration file or with the settings API.
+    InMemory,
+}
+
+impl Default for TrackerBackendKind {
+    fn default() -> Self {
+        Self::Api
+    }
+}
+
+/// How emails are delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MailTransport {
+    /// Emails are sent to the SMTP server `server`.
+    Smtp,
+    /// Emails are sent with the local `sendmail_command` binary.
+    Sendmail,
+    /// Emails are written to the `file_path` directory, in Maildir format.
+    /// It's useful for development and testing.
+    File,
+    /// Emails are written to the application log. It's useful for
+    /// development.
+    Stdout,
+}
+
+impl Default for MailTransport {
+    fn default() -> Self {
+        Self::Smtp
+    }
+}
+
+/// TLS mode for the SMTP transport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SmtpTlsMode {
+    /// Plain text connection.
+    None,
+    /// STARTTLS is used if the server supports it, otherwise the connection
+    /// is in plain text.
+    Opportunistic,
+    /// STARTTLS is required. The connection fails if the server does not
+    /// support it.
+    StartTls,
+    /// The connection is encrypted from the start (implicit TLS), usually on
+    /// port 465.
+    Tls,
+}
+
+impl Default for SmtpTlsMode {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+/// The format resized images are encoded in by the image proxy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageVariantFormat {
+    /// Lossy JPEG, with the quality `variant_quality`. Transparency is lost.
+    Jpeg,
+    Png,
+    /// Lossless WebP.
+    WebP,
+}
+
+impl Default for ImageVariantFormat {
+    fn default() -> Self {
+        Self::Jpeg
+    }
+}
+
+/// A tracker not managed by the index, for example, a public tracker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtraTracker {
+    /// The announce URL. For example: `udp://tracker.example.com:6969/announce`.
+    pub url: String,
+    /// Where the torrent statistics are imported from: `Scrape` or `None`.
+    /// The index does not manage this tracker, so `Api` is not supported.
+    pub statistics_source: StatisticsSource,
+    /// Whether the tracker is added to the announce list of the downloaded
+    /// torrents. It's never added to private torrents.
+    pub add_to_announce_list: bool,
 }
 
 /// Configuration for the associated tracker.
@@ -183,12 +489,14 @@ pub struct Tracker {
     pub token: String,
     /// The amount of seconds the token is valid.
     pub token_valid_seconds: u64,
-}
-
-impl Tracker {
-    fn override_tracker_api_token(&mut self, tracker_api_token: &str) {
-        self.token = tracker_api_token.to_string();
-    }
+    /// The tracker integrated with the index: the Torrust Tracker `Api`,
+    /// `None` for indexes without an integrated tracker or `InMemory`.
+    pub backend: TrackerBackendKind,
+    /// Where the torrent statistics are imported from: the tracker `Api`,
+    /// `Scrape` or `None`.
+    pub statistics_source: StatisticsSource,
+    /// Other trackers for the indexed torrents.
+    pub extra_trackers: Vec<ExtraTracker>,
 }
 
 impl Default for Tracker {
@@ -199,6 +507,9 @@ impl Default for Tracker {
             api_url: "http://localhost:1212".to_string(),
             token: "MyAccessToken".to_string(),
             token_valid_seconds: 7_257_600,
+            backend: TrackerBackendKind::default(),
+            statistics_source: StatisticsSource::default(),
+            extra_trackers: vec![],
         }
     }
 }
@@ -268,12 +579,6 @@ impl Default for Auth {
     }
 }
 
-impl Auth {
-    fn override_secret_key(&mut self, secret_key: &str) {
-        self.secret_key = secret_key.to_string();
-    }
-}
-
 /// Database configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Database {
@@ -306,6 +611,21 @@ pub struct Mail {
     pub server: String,
     /// The SMTP port to use.
     pub port: u16,
+    /// How emails are delivered.
+    pub transport: MailTransport,
+    /// TLS mode for the `Smtp` transport.
+    pub smtp_tls: SmtpTlsMode,
+    /// The command used by the `Sendmail` transport. It can only be set in
+    /// the configuration file or with an environment variable.
+    pub sendmail_command: String,
+    /// Directory where the `File` transport writes the emails, in Maildir
+    /// format. It can only be set in the configuration file or with an
+    /// environment variable.
+    pub file_path: String,
+    /// Directory with the email templates overriding the built-in ones. It
+    /// can only be set in the configuration file or with an environment
+    /// variable.
+    pub templates_path: String,
 }
 
 impl Default for Mail {
@@ -318,6 +638,11 @@ impl Default for Mail {
             password: String::default(),
             server: String::default(),
             port: 25,
+            transport: MailTransport::default(),
+            smtp_tls: SmtpTlsMode::default(),
+            sendmail_command: "sendmail".to_string(),
+            file_path: "./storage/index/lib/mail".to_string(),
+            templates_path: "./storage/index/etc/templates".to_string(),
         }
     }
 }
@@ -344,6 +669,43 @@ pub struct ImageCache {
     /// Users have a cache quota per period. For example: 100MB per day.
     /// This is the maximum size in bytes (100MB in bytes).    
     pub user_quota_bytes: usize,
+    /// Whether images are also cached on disk, so they survive restarts.
+    pub disk_cache_enabled: bool,
+    /// Directory where the images are cached on disk.
+    pub disk_cache_path: String,
+    /// Disk cache size in bytes.
+    pub disk_capacity: usize,
+    /// Time in seconds a cached image is used before revalidating it with the
+    /// original source.
+    pub ttl_seconds: u64,
+    /// Maximum number of redirects followed when downloading an image.
+    pub max_redirects: usize,
+    /// Hosts (domain names or IP addresses) images can be downloaded from
+    /// even if they are private, loopback or link-local addresses. For
+    /// example, an image server in the local network.
+    pub allowed_private_hosts: Vec<String>,
+    /// Default format of the resized images when the format is not requested.
+    pub variant_format: ImageVariantFormat,
+    /// Quality (1-100) of the resized images encoded as JPEG.
+    pub variant_quality: u8,
+    /// Maximum width and height in pixels of the resized images.
+    pub max_variant_dimension: u32,
+}
+
+/// Configuration for the images uploaded by users, used as torrent posters
+/// and screenshots, and as user avatars.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageUploads {
+    /// Directory where the uploaded images are stored.
+    pub storage_path: String,
+    /// Maximum size in bytes of an uploaded image.
+    pub max_image_size: usize,
+    /// Maximum width and height in pixels of an uploaded image.
+    pub max_image_dimension: u32,
+    /// Maximum size in bytes of all the images uploaded by a user.
+    pub user_quota_bytes: usize,
+    /// Maximum number of screenshots of a torrent.
+    pub max_screenshots: usize,
 }
 
 /// Core configuration for the API
@@ -371,6 +733,21 @@ pub struct TrackerStatisticsImporter {
     pub torrent_info_update_interval: u64,
     /// The port the Importer API is listening on. Default to `3002`.
     pub port: u16,
+    /// The number of days the statistics history is kept. A snapshot of the
+    /// statistics is added to the history after each import. `0` disables
+    /// the history.
+    pub stats_history_retention_days: u32,
+    /// The number of days all the snapshots are kept. Older snapshots are
+    /// downsampled to one per day.
+    pub stats_history_full_resolution_days: u32,
+    /// The maximum number of torrents imported from each tracker on each
+    /// run. The remaining torrents are imported in the next runs.
+    pub max_torrents_per_run: u32,
+    /// The maximum number of concurrent requests to each tracker.
+    pub max_concurrent_requests: u32,
+    /// Whether to import the number of completed downloads reported by the
+    /// trackers.
+    pub import_completed_counts: bool,
 }
 
 impl Default for TrackerStatisticsImporter {
@@ -378,6 +755,188 @@ impl Default for TrackerStatisticsImporter {
         Self {
             torrent_info_update_interval: 3600,
             port: 3002,
+            stats_history_retention_days: 90,
+            stats_history_full_resolution_days: 7,
+            max_torrents_per_run: 10_000,
+            max_concurrent_requests: 10,
+            import_completed_counts: true,
+        }
+    }
+}
+
+/// Configuration for the background jobs that apply changes to the tracker,
+/// like whitelisting torrents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackerJobs {
+    /// The interval in seconds to check for pending jobs.
+    pub worker_interval_seconds: u64,
+    /// The maximum number of jobs executed on each run.
+    pub batch_size: u32,
+    /// The number of attempts after which a job is marked as failed.
+    pub max_attempts: u32,
+    /// The delay in seconds before the first retry. It's doubled after each
+    /// failed attempt.
+    pub initial_backoff_seconds: u64,
+    /// The maximum delay in seconds between retries.
+    pub max_backoff_seconds: u64,
+}
+
+impl Default for TrackerJobs {
+    fn default() -> Self {
+        Self {
+            worker_interval_seconds: 5,
+            batch_size: 50,
+            max_attempts: 10,
+            initial_backoff_seconds: 5,
+            max_backoff_seconds: 3600,
+        }
+    }
+}
+
+/// Configuration for the outbound mail queue. Emails are stored in the
+/// database and delivered in the background, so they are retried when the
+/// mail server is not available.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailQueue {
+    /// The interval in seconds to check for pending emails.
+    pub worker_interval_seconds: u64,
+    /// The maximum number of emails delivered on each run.
+    pub batch_size: u32,
+    /// The number of attempts after which an email is marked as failed.
+    pub max_attempts: u32,
+    /// The delay in seconds before the first retry. It's doubled after each
+    /// failed attempt.
+    pub initial_backoff_seconds: u64,
+    /// The maximum delay in seconds between retries.
+    pub max_backoff_seconds: u64,
+    /// The number of days the sent and failed emails are kept. `0` keeps
+    /// them forever.
+    pub retention_days: u32,
+}
+
+impl Default for MailQueue {
+    fn default() -> Self {
+        Self {
+            worker_interval_seconds: 5,
+            batch_size: 20,
+            max_attempts: 10,
+            initial_backoff_seconds: 60,
+            max_backoff_seconds: 21600,
+            retention_days: 30,
+        }
+    }
+}
+
+/// Email verification configuration. It only applies when
+/// `mail.email_verification_enabled` is `true`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailVerification {
+    /// The number of seconds a verification link is valid.
+    pub token_lifetime_seconds: u64,
+    /// Whether users can't log in until they verify their email.
+    pub required_for_login: bool,
+    /// Whether users can't upload torrents until they verify their email.
+    pub required_for_upload: bool,
+    /// The number of days after which the accounts that were never verified
+    /// are deleted. `0` disables it.
+    pub purge_unverified_after_days: u32,
+    /// The interval in seconds to check for unverified accounts to delete.
+    pub purge_interval_seconds: u64,
+    /// The minimum number of seconds between two verification emails sent
+    /// to the same user.
+    pub resend_interval_seconds: u64,
+}
+
+impl Default for EmailVerification {
+    fn default() -> Self {
+        Self {
+            token_lifetime_seconds: 86400,
+            required_for_login: true,
+            required_for_upload: false,
+            purge_unverified_after_days: 0,
+            purge_interval_seconds: 3600,
+            resend_interval_seconds: 300,
+        }
+    }
+}
+
+/// What to do with dead torrents after the grace period.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeadTorrentAction {
+    /// Keep the torrent in the database but do not list it anymore.
+    Archive,
+    /// Delete the torrent.
+    Delete,
+}
+
+impl Default for DeadTorrentAction {
+    fn default() -> Self {
+        Self::Archive
+    }
+}
+
+/// Configuration for the dead torrent policy.
+///
+/// Torrents without seeders for `dead_after_days` days are marked as dead.
+/// After `grace_period_days` more days without seeders they are removed from
+/// the tracker whitelist and archived or deleted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadTorrents {
+    /// Whether the policy is applied.
+    pub enabled: bool,
+    /// The interval in seconds to check for dead torrents.
+    pub check_interval_seconds: u64,
+    /// The number of days without seeders after which a torrent is marked as
+    /// dead.
+    pub dead_after_days: u32,
+    /// Whether dead torrents are hidden in the torrent listing.
+    pub hide_dead_torrents: bool,
+    /// Whether the uploader is notified by email when the torrent is marked
+    /// as dead.
+    pub notify_uploader: bool,
+    /// The number of days a torrent can stay dead before it's pruned.
+    pub grace_period_days: u32,
+    /// What to do with dead torrents after the grace period: `Archive` or
+    /// `Delete`.
+    pub prune_action: DeadTorrentAction,
+    /// Whether the changes are only reported but not applied.
+    pub dry_run: bool,
+}
+
+impl Default for DeadTorrents {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_interval_seconds: 86400,
+            dead_after_days: 30,
+            hide_dead_torrents: false,
+            notify_uploader: true,
+            grace_period_days: 30,
+            prune_action: DeadTorrentAction::default(),
+            dry_run: false,
+        }
+    }
+}
+
+/// Configuration for the user notifications.
+///
+/// Users get notifications when something happens to their torrents, for
+/// example, when an administrator edits or deletes them. Each user chooses how
+/// they are delivered: in the app, in a periodic email digest or in an
+/// immediate email.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notifications {
+    /// The interval in seconds between email digests.
+    pub digest_interval_seconds: u64,
+    /// The number of days notifications are kept. `0` keeps them forever.
+    pub retention_days: u32,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self {
+            digest_interval_seconds: 86400,
+            retention_days: 90,
         }
     }
 }
@@ -390,6 +949,27 @@ impl Default for ImageCache {
             entry_size_limit: 4_000_000,
             user_quota_period_seconds: 3600,
             user_quota_bytes: 64_000_000,
+            disk_cache_enabled: true,
+            disk_cache_path: "./storage/index/lib/image_cache".to_string(),
+            disk_capacity: 1_000_000_000,
+            ttl_seconds: 604_800,
+            max_redirects: 3,
+            allowed_private_hosts: vec![],
+            variant_format: ImageVariantFormat::default(),
+            variant_quality: 80,
+            max_variant_dimension: 2048,
+        }
+    }
+}
+
+impl Default for ImageUploads {
+    fn default() -> Self {
+        Self {
+            storage_path: "./storage/index/lib/images".to_string(),
+            max_image_size: 4_000_000,
+            max_image_dimension: 4096,
+            user_quota_bytes: 100_000_000,
+            max_screenshots: 10,
         }
     }
 }
@@ -414,27 +994,274 @@ pub struct TorrustIndex {
     pub mail: Mail,
     /// The image proxy cache configuration.
     pub image_cache: ImageCache,
+    /// The uploaded images configuration.
+    pub image_uploads: ImageUploads,
     /// The API configuration.
     pub api: Api,
     /// The tracker statistics importer job configuration.
     pub tracker_statistics_importer: TrackerStatisticsImporter,
+    /// The tracker background jobs configuration.
+    pub tracker_jobs: TrackerJobs,
+    /// The outbound mail queue configuration.
+    pub mail_queue: MailQueue,
+    /// The email verification configuration.
+    pub email_verification: EmailVerification,
+    /// The dead torrent policy configuration.
+    pub dead_torrents: DeadTorrents,
+    /// The user notifications configuration.
+    pub notifications: Notifications,
 }
 
 impl TorrustIndex {
-    fn override_tracker_api_token(&mut self, tracker_api_token: &str) {
-        self.tracker.override_tracker_api_token(tracker_api_token);
+    pub fn remove_secrets(&mut self) {
+        self.tracker.token = MASKED_SECRET.to_owned();
+        self.database.connect_url = MASKED_SECRET.to_owned();
+        self.mail.password = MASKED_SECRET.to_owned();
+        self.mail.sendmail_command = MASKED_SECRET.to_owned();
+        self.mail.file_path = MASKED_SECRET.to_owned();
+        self.mail.templates_path = MASKED_SECRET.to_owned();
+        self.auth.secret_key = MASKED_SECRET.to_owned();
     }
 
-    fn override_auth_secret_key(&mut self, auth_secret_key: &str) {
-        self.auth.override_secret_key(auth_secret_key);
+    /// It replaces the secrets that were masked with [`remove_secrets`](Self::remove_secrets)
+    /// with the values from the `current` configuration. That allows clients
+    /// to send back the settings they got from the API without the secrets.
+    fn restore_masked_secrets(&mut self, current: &TorrustIndex) {
+        if self.tracker.token == MASKED_SECRET {
+            self.tracker.token = current.tracker.token.clone();
+        }
+        if self.database.connect_url == MASKED_SECRET {
+            self.database.connect_url = current.database.connect_url.clone();
+        }
+        if self.mail.password == MASKED_SECRET {
+            self.mail.password = current.mail.password.clone();
+        }
+        if self.mail.sendmail_command == MASKED_SECRET {
+            self.mail.sendmail_command = current.mail.sendmail_command.clone();
+        }
+        if self.mail.file_path == MASKED_SECRET {
+            self.mail.file_path = current.mail.file_path.clone();
+        }
+        if self.mail.templates_path == MASKED_SECRET {
+            self.mail.templates_path = current.mail.templates_path.clone();
+        }
+        if self.auth.secret_key == MASKED_SECRET {
+            self.auth.secret_key = current.auth.secret_key.clone();
+        }
     }
 
-    pub fn remove_secrets(&mut self) {
-        self.tracker.token = "***".to_owned();
-        self.database.connect_url = "***".to_owned();
-        self.mail.password = "***".to_owned();
-        self.auth.secret_key = "***".to_owned();
+    /// It returns the settings whose values are different in the `new`
+    /// configuration. Keys use dot notation, for example: `tracker.url`.
+    ///
+    /// Secret values are masked, so the result can be safely logged.
+    #[must_use]
+    pub fn diff(&self, new: &TorrustIndex) -> Vec<SettingChange> {
+        let old_values = flatten(self);
+        let new_values = flatten(new);
+
+        let mut masked_old = self.clone();
+        masked_old.remove_secrets();
+        let masked_old_values = flatten(&masked_old);
+
+        let mut masked_new = new.clone();
+        masked_new.remove_secrets();
+        let masked_new_values = flatten(&masked_new);
+
+        let keys: BTreeSet<&String> = old_values.keys().chain(new_values.keys()).collect();
+
+        keys.into_iter()
+            .filter(|key| old_values.get(*key) != new_values.get(*key))
+            .map(|key| SettingChange {
+                key: key.clone(),
+                old_value: masked_old_values.get(key).map(ToString::to_string),
+                new_value: masked_new_values.get(key).map(ToString::to_string),
+            })
+            .collect()
+    }
+
+    /// It returns the new values of the settings that are different in the
+    /// `new` configuration. Unlike [`diff`](Self::diff), secrets are not
+    /// masked. The value is `None` when the setting is not defined anymore.
+    fn changed_values(&self, new: &TorrustIndex) -> BTreeMap<String, Option<serde_json::Value>> {
+        let old_values = flatten(self);
+        let new_values = flatten(new);
+
+        let keys: BTreeSet<&String> = old_values.keys().chain(new_values.keys()).collect();
+
+        keys.into_iter()
+            .filter(|key| old_values.get(*key) != new_values.get(*key))
+            .map(|key| (key.clone(), new_values.get(key).filter(|value| !value.is_null()).cloned()))
+            .collect()
+    }
+
+    /// It overwrites the settings that can't be applied while the application
+    /// is running with the values from the `current` configuration.
+    fn keep_settings_not_applicable_live(&mut self, current: &TorrustIndex) {
+        self.log_level.clone_from(&current.log_level);
+        self.net.port = current.net.port;
+        self.database.connect_url = current.database.connect_url.clone();
+        self.tracker_statistics_importer.port = current.tracker_statistics_importer.port;
+        self.tracker_statistics_importer.torrent_info_update_interval =
+            current.tracker_statistics_importer.torrent_info_update_interval;
+        self.image_cache.capacity = current.image_cache.capacity;
+        self.image_cache.disk_cache_enabled = current.image_cache.disk_cache_enabled;
+        self.image_cache.disk_cache_path = current.image_cache.disk_cache_path.clone();
+        self.image_cache.disk_capacity = current.image_cache.disk_capacity;
+        self.image_uploads.storage_path = current.image_uploads.storage_path.clone();
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the configuration is invalid.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.validate_tracker_config()
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the `tracker` configuration section is invalid.
+    fn validate_tracker_config(&self) -> Result<(), ValidationError> {
+        let tracker_url = match parse_url(&self.tracker.url) {
+            Ok(url) => url,
+            Err(err) => {
+                return Err(ValidationError::InvalidTrackerUrl {
+                    source: Located(err).into(),
+                })
+            }
+        };
+
+        if self.tracker.mode.is_close() && (tracker_url.scheme() != "http" && tracker_url.scheme() != "https") {
+            return Err(ValidationError::UdpTrackersInPrivateModeNotSupported);
+        }
+
+        if self.tracker.statistics_source == StatisticsSource::Scrape && !matches!(tracker_url.scheme(), "udp" | "http" | "https")
+        {
+            return Err(ValidationError::ScrapeNotSupportedForTrackerUrl {
+                scheme: tracker_url.scheme().to_owned(),
+            });
+        }
+
+        for extra_tracker in &self.tracker.extra_trackers {
+            let is_valid = parse_url(&extra_tracker.url).is_ok_and(|url| matches!(url.scheme(), "udp" | "http" | "https"));
+
+            if !is_valid {
+                return Err(ValidationError::InvalidExtraTrackerUrl {
+                    url: extra_tracker.url.clone(),
+                });
+            }
+
+            if extra_tracker.statistics_source == StatisticsSource::Api {
+                return Err(ValidationError::ApiStatisticsNotSupportedForExtraTracker {
+                    url: extra_tracker.url.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the `new` settings select the `InMemory`
+    /// tracker backend and the current ones don't. The `InMemory` backend can
+    /// only be set when the application is started from the tests.
+    fn validate_tracker_backend_change(&self, new: &TorrustIndex) -> Result<(), ValidationError> {
+        if new.tracker.backend == TrackerBackendKind::InMemory && self.tracker.backend != TrackerBackendKind::InMemory {
+            return Err(ValidationError::InMemoryTrackerBackendNotAllowed);
+        }
+
+        Ok(())
+    }
+}
+
+/// Settings that can only be changed by restarting the application. For
+/// example, the ports the servers are bound to, or the image caches and
+/// storage, which are set up on startup.
+///
+/// They must be kept unchanged in
+/// [`TorrustIndex::keep_settings_not_applicable_live`].
+const SETTINGS_NOT_APPLICABLE_LIVE: [&str; 10] = [
+    "log_level",
+    "net.port",
+    "database.connect_url",
+    "tracker_statistics_importer.port",
+    "tracker_statistics_importer.torrent_info_update_interval",
+    "image_cache.capacity",
+    "image_cache.disk_cache_enabled",
+    "image_cache.disk_cache_path",
+    "image_cache.disk_capacity",
+    "image_uploads.storage_path",
+];
+
+/// Settings that can't be changed with the API, because they could be used to
+/// run arbitrary commands or write files anywhere the application can. They
+/// are masked like secrets when the settings are exposed.
+const SETTINGS_NOT_EDITABLE: [&str; 3] = ["mail.sendmail_command", "mail.file_path", "mail.templates_path"];
+
+/// The value used to hide secrets when the configuration is exposed.
+const MASKED_SECRET: &str = "***";
+
+/// A setting whose value has changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingChange {
+    /// The setting key in dot notation. For example: `tracker.url`.
+    pub key: String,
+    /// The previous value in JSON format, if the setting was defined.
+    pub old_value: Option<String>,
+    /// The new value in JSON format, if the setting is defined.
+    pub new_value: Option<String>,
+}
+
+impl fmt::Display for SettingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.key,
+            self.old_value.as_deref().unwrap_or("(none)"),
+            self.new_value.as_deref().unwrap_or("(none)")
+        )
+    }
+}
+
+/// The result of reloading the configuration.
+#[derive(Debug, Default)]
+pub struct ReloadReport {
+    /// The changes that were applied.
+    pub applied: Vec<SettingChange>,
+    /// The changes that were ignored because they require restarting the
+    /// application.
+    pub rejected: Vec<SettingChange>,
+}
+
+/// It converts the settings into a flat map of keys in dot notation and JSON
+/// values.
+fn flatten(settings: &TorrustIndex) -> BTreeMap<String, serde_json::Value> {
+    fn flatten_value(prefix: &str, value: &serde_json::Value, flat: &mut BTreeMap<String, serde_json::Value>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    let key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{prefix}.{key}")
+                    };
+                    flatten_value(&key, value, flat);
+                }
+            }
+            _ => {
+                flat.insert(prefix.to_owned(), value.clone());
+            }
+        }
     }
+
+    let mut flat = BTreeMap::new();
+
+    let value = serde_json::to_value(settings).expect("settings should be serializable to JSON");
+
+    flatten_value("", &value, &mut flat);
+
+    flat
 }
 
 /// The configuration service.
@@ -445,18 +1272,31 @@ pub struct Configuration {
     /// The path to the configuration file. This is `None` if the configuration
     /// was loaded from the environment.
     pub config_path: Option<String>,
+    /// The settings overridden with environment variables, in dot notation.
+    /// They are never written into the configuration file.
+    overridden_settings: BTreeSet<String>,
+    /// It serializes the changes to the settings, so the configuration file
+    /// is always written in the same order the changes are applied.
+    changes_lock: Mutex<()>,
 }
 
 impl Default for Configuration {
     fn default() -> Configuration {
-        Configuration {
-            settings: RwLock::new(TorrustIndex::default()),
-            config_path: None,
-        }
+        Configuration::new(TorrustIndex::default(), None)
     }
 }
 
 impl Configuration {
+    #[must_use]
+    pub fn new(settings: TorrustIndex, config_path: Option<String>) -> Configuration {
+        Configuration {
+            settings: RwLock::new(settings),
+            config_path,
+            overridden_settings: BTreeSet::new(),
+            changes_lock: Mutex::new(()),
+        }
+    }
+
     /// Loads the configuration from the configuration file.
     ///
     /// # Errors
@@ -470,7 +1310,10 @@ impl Configuration {
         let mut config = Config::default();
 
         if Path::new(config_path).exists() {
-            config = config_builder.add_source(File::with_name(config_path)).build()?;
+            config = config_builder
+                .add_source(File::from_str(&default_settings_toml(), FileFormat::Toml))
+                .add_source(File::with_name(config_path))
+                .build()?;
         } else {
             warn!("No config file found. Creating default config file ...");
 
@@ -487,41 +1330,58 @@ impl Configuration {
             Err(e) => Err(ConfigError::Message(format!("Errors while processing config: {e}."))),
         }?;
 
-        Ok(Configuration {
-            settings: RwLock::new(torrust_config),
-            config_path: Some(config_path.to_string()),
-        })
+        Ok(Configuration::new(torrust_config, Some(config_path.to_string())))
     }
 
     /// Loads the configuration from the `Info` struct. The whole
     /// configuration in toml format is included in the `info.index_toml` string.
     ///
-    /// Optionally will override the:
+    /// The configuration is built with these layers. Each layer overrides the
+    /// values from the previous ones:
     ///
-    /// - Tracker api token.
-    /// - The auth secret key.
+    /// 1. The built-in default values. See [`TorrustIndex::default`].
+    /// 2. The `info.index_toml` content.
+    /// 3. The tracker api token and the auth secret key env vars.
+    /// 4. The env vars overriding a single setting. For example:
+    ///    `TORRUST_INDEX_CONFIG__TRACKER__TOKEN`.
     ///
     /// # Errors
     ///
     /// Will return `Err` if the environment variable does not exist or has a bad configuration.
     pub fn load(info: &Info) -> Result<Configuration, Error> {
-        let config_builder = Config::builder()
-            .add_source(File::from_str(&info.index_toml, FileFormat::Toml))
-            .build()?;
-        let mut index_config: TorrustIndex = config_builder.try_deserialize()?;
+        let mut config_builder = Config::builder()
+            .add_source(File::from_str(&default_settings_toml(), FileFormat::Toml))
+            .add_source(File::from_str(&info.index_toml, FileFormat::Toml));
+
+        let mut overridden_settings = BTreeSet::new();
 
         if let Some(ref token) = info.tracker_api_token {
-            index_config.override_tracker_api_token(token);
+            config_builder = config_builder.set_override("tracker.token", token.clone())?;
+            overridden_settings.insert("tracker.token".to_owned());
         };
 
         if let Some(ref secret_key) = info.auth_secret_key {
-            index_config.override_auth_secret_key(secret_key);
+            config_builder = config_builder.set_override("auth.secret_key", secret_key.clone())?;
+            overridden_settings.insert("auth.secret_key".to_owned());
         };
 
-        Ok(Configuration {
-            settings: RwLock::new(index_config),
-            config_path: None,
-        })
+        for setting_override in &info.overrides {
+            config_builder = config_builder.set_override(setting_override.key.clone(), setting_override.value.clone())?;
+            overridden_settings.insert(setting_override.key.clone());
+        }
+
+        let index_config: TorrustIndex = config_builder.build()?.try_deserialize()?;
+
+        if index_config.tracker.backend == TrackerBackendKind::InMemory {
+            return Err(Error::InvalidConfiguration {
+                source: ValidationError::InMemoryTrackerBackendNotAllowed,
+            });
+        }
+
+        let mut configuration = Configuration::new(index_config, info.config_path.clone());
+        configuration.overridden_settings = overridden_settings;
+
+        Ok(configuration)
     }
 
     /// Returns the save to file of this [`Configuration`].
@@ -572,33 +1432,223 @@ impl Configuration {
     ///
     /// Will return an error if the configuration is invalid.
     pub async fn validate(&self) -> Result<(), ValidationError> {
-        self.validate_tracker_config().await
+        self.settings.read().await.validate()
     }
 
+    /// It replaces the current settings with new ones.
+    ///
+    /// The new settings are validated and, if the configuration was loaded
+    /// from a file, the changed settings are also written to that file. Only
+    /// the settings that changed are written, so values coming from
+    /// environment variables are never persisted. The file is replaced
+    /// atomically: the new content is written into a temporary file which is
+    /// then renamed.
+    ///
+    /// Masked secrets (`***`) are replaced with the current values.
+    ///
+    /// Services read the settings every time they need them, so the new values
+    /// are applied without restarting the application.
+    ///
     /// # Errors
     ///
-    /// Will return an error if the `tracker` configuration section is invalid.    
-    pub async fn validate_tracker_config(&self) -> Result<(), ValidationError> {
-        let settings_lock = self.settings.read().await;
+    /// Will return an error if:
+    ///
+    /// - The new settings are invalid.
+    /// - They change a setting that can only be changed by restarting the
+    ///   application, a setting overridden with an environment variable or a
+    ///   setting that can only be set in the configuration file.
+    /// - They can't be saved into the configuration file.
+    ///
+    /// The current settings are kept in all cases.
+    pub async fn update(&self, mut new_settings: TorrustIndex) -> Result<(), UpdateError> {
+        let _changes_guard = self.changes_lock.lock().await;
 
-        let tracker_mode = settings_lock.tracker.mode.clone();
-        let tracker_url = settings_lock.tracker.url.clone();
+        let current_settings = self.get_all().await;
 
-        let tracker_url = match parse_url(&tracker_url) {
-            Ok(url) => url,
-            Err(err) => {
-                return Err(ValidationError::InvalidTrackerUrl {
-                    source: Located(err).into(),
-                })
+        new_settings.restore_masked_secrets(&current_settings);
+
+        new_settings
+            .validate()
+            .and_then(|()| current_settings.validate_tracker_backend_change(&new_settings))
+            .map_err(|source| UpdateError::InvalidConfiguration { source })?;
+
+        let changes = current_settings.changed_values(&new_settings);
+
+        if let Some(key) = changes.keys().find(|key| SETTINGS_NOT_EDITABLE.contains(&key.as_str())) {
+            return Err(UpdateError::SettingNotEditable { key: key.clone() });
+        }
+
+        if let Some(key) = changes
+            .keys()
+            .find(|key| SETTINGS_NOT_APPLICABLE_LIVE.contains(&key.as_str()))
+        {
+            return Err(UpdateError::SettingNotApplicableLive { key: key.clone() });
+        }
+
+        if let Some(key) = changes.keys().find(|key| self.is_overridden(key)) {
+            return Err(UpdateError::SettingOverridden { key: key.clone() });
+        }
+
+        if let Some(config_path) = &self.config_path {
+            self.save_changes_to_file(&current_settings, &changes, config_path)
+                .await
+                .map_err(|e| UpdateError::UnableToSaveToConfigFile {
+                    source: (Arc::new(e) as Arc<dyn std::error::Error + Send + Sync>).into(),
+                })?;
+        } else {
+            warn!("The configuration was not loaded from a file. New settings will be lost after restarting the application.");
+        }
+
+        *self.settings.write().await = new_settings;
+
+        Ok(())
+    }
+
+    /// It replaces the current settings with the ones reloaded from the
+    /// configuration file.
+    ///
+    /// Settings that can't be applied while the application is running (like
+    /// the API port or the database URL) are not changed. They are returned
+    /// in the report as rejected changes.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the new settings are invalid. The current
+    /// settings are kept in that case.
+    pub async fn reload(&self, mut new_settings: TorrustIndex) -> Result<ReloadReport, ValidationError> {
+        let _changes_guard = self.changes_lock.lock().await;
+
+        let mut settings = self.settings.write().await;
+
+        new_settings.validate()?;
+
+        settings.validate_tracker_backend_change(&new_settings)?;
+
+        let (rejected, applied) = settings
+            .diff(&new_settings)
+            .into_iter()
+            .partition(|change| SETTINGS_NOT_APPLICABLE_LIVE.contains(&change.key.as_str()));
+
+        new_settings.keep_settings_not_applicable_live(&settings);
+
+        *settings = new_settings;
+
+        Ok(ReloadReport { applied, rejected })
+    }
+
+    /// It returns the settings overridden with environment variables, in dot
+    /// notation.
+    ///
+    /// The configuration is loaded before logging is set up, so the
+    /// application logs them once it starts.
+    #[must_use]
+    pub fn overridden_settings(&self) -> &BTreeSet<String> {
+        &self.overridden_settings
+    }
+
+    /// It returns true if the setting, or the section containing it, is
+    /// overridden with an environment variable.
+    fn is_overridden(&self, key: &str) -> bool {
+        self.overridden_settings.iter().any(|overridden| {
+            key == overridden || key.starts_with(&format!("{overridden}.")) || overridden.starts_with(&format!("{key}."))
+        })
+    }
+
+    /// It writes the changed settings into the configuration file.
+    ///
+    /// The other values in the file are kept. If the file does not exist yet,
+    /// it's created with the current settings, except the ones overridden with
+    /// environment variables.
+    ///
+    /// The settings are written into a temporary file next to the destination
+    /// which is then renamed, so the configuration file is never left
+    /// half-written.
+    async fn save_changes_to_file(
+        &self,
+        current_settings: &TorrustIndex,
+        changes: &BTreeMap<String, Option<serde_json::Value>>,
+        config_path: &str,
+    ) -> Result<(), std::io::Error> {
+        let mut document = match tokio::fs::read_to_string(config_path).await {
+            Ok(content) => content.parse::<toml::Table>().map_err(invalid_data)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let mut document = toml::Table::try_from(current_settings).map_err(invalid_data)?;
+                for key in &self.overridden_settings {
+                    remove_toml_key(&mut document, key);
+                }
+                document
             }
+            Err(e) => return Err(e),
         };
 
-        if tracker_mode.is_close() && (tracker_url.scheme() != "http" && tracker_url.scheme() != "https") {
-            return Err(ValidationError::UdpTrackersInPrivateModeNotSupported);
+        for (key, value) in changes {
+            match value {
+                Some(value) => set_toml_key(&mut document, key, toml::Value::try_from(value).map_err(invalid_data)?),
+                None => remove_toml_key(&mut document, key),
+            }
         }
 
-        Ok(())
+        let toml_string = toml::to_string(&document).map_err(invalid_data)?;
+
+        let tmp_path = format!("{config_path}.tmp");
+
+        tokio::fs::write(&tmp_path, toml_string).await?;
+        tokio::fs::rename(&tmp_path, config_path).await
+    }
+}
+
+fn invalid_data(e: impl ToString) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())
+}
+
+/// It sets the value of a setting in dot notation, creating the missing
+/// sections.
+fn set_toml_key(document: &mut toml::Table, key: &str, value: toml::Value) {
+    let mut path: Vec<&str> = key.split('.').collect();
+    let Some(last) = path.pop() else {
+        return;
+    };
+
+    let mut table = document;
+
+    for section in path {
+        let entry = table.entry(section).or_insert_with(|| toml::Value::Table(toml::Table::new()));
+
+        if !entry.is_table() {
+            *entry = toml::Value::Table(toml::Table::new());
+        }
+
+        table = entry.as_table_mut().expect("the entry should be a table");
+    }
+
+    table.insert(last.to_owned(), value);
+}
+
+/// It removes a setting in dot notation. It does nothing if the setting is
+/// not defined.
+fn remove_toml_key(document: &mut toml::Table, key: &str) {
+    let mut path: Vec<&str> = key.split('.').collect();
+    let Some(last) = path.pop() else {
+        return;
+    };
+
+    let mut table = document;
+
+    for section in path {
+        match table.get_mut(section).and_then(toml::Value::as_table_mut) {
+            Some(section) => table = section,
+            None => return,
+        }
     }
+
+    table.remove(last);
+}
+
+/// The built-in default settings in TOML format. They are the first layer when
+/// loading the configuration, so the configuration files only need to contain
+/// the values that are different from the defaults.
+fn default_settings_toml() -> String {
+    toml::to_string(&TorrustIndex::default()).expect("default settings should be serializable to TOML")
 }
 
 fn parse_url(url_str: &str) -> Result<Url, url::ParseError> {
@@ -614,3 +1664,269 @@ pub struct ConfigurationPublic {
     tracker_mode: TrackerMode,
     email_on_signup: EmailOnSignup,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use crate::config::{
+        flatten, overrides_from_env_vars, Configuration, Error, ExtraTracker, Info, Override, SettingChange, StatisticsSource,
+        TorrustIndex, TrackerBackendKind, UpdateError, ValidationError, SETTINGS_NOT_APPLICABLE_LIVE,
+    };
+
+    #[test]
+    fn it_should_keep_all_the_settings_that_can_not_be_applied_live() {
+        let current_settings = TorrustIndex::default();
+
+        for key in SETTINGS_NOT_APPLICABLE_LIVE {
+            let mut new_settings = serde_json::to_value(&current_settings).unwrap();
+
+            let value = new_settings.pointer_mut(&format!("/{}", key.replace('.', "/"))).unwrap();
+
+            *value = match value {
+                serde_json::Value::Number(number) => serde_json::json!(number.as_u64().unwrap() + 1),
+                serde_json::Value::Bool(boolean) => serde_json::json!(!*boolean),
+                _ => serde_json::json!("changed"),
+            };
+
+            let mut new_settings: TorrustIndex = serde_json::from_value(new_settings).unwrap();
+
+            assert_ne!(flatten(&new_settings).get(key), flatten(&current_settings).get(key), "{key}");
+
+            new_settings.keep_settings_not_applicable_live(&current_settings);
+
+            assert_eq!(flatten(&new_settings).get(key), flatten(&current_settings).get(key), "{key}");
+        }
+    }
+
+    #[test]
+    fn it_should_list_the_settings_that_have_changed() {
+        let old_settings = TorrustIndex::default();
+
+        let mut new_settings = TorrustIndex::default();
+        new_settings.website.name = "New name".to_string();
+
+        assert_eq!(
+            old_settings.diff(&new_settings),
+            vec![SettingChange {
+                key: "website.name".to_string(),
+                old_value: Some("\"Torrust\"".to_string()),
+                new_value: Some("\"New name\"".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn it_should_mask_secrets_when_listing_the_settings_that_have_changed() {
+        let old_settings = TorrustIndex::default();
+
+        let mut new_settings = TorrustIndex::default();
+        new_settings.tracker.token = "NewToken".to_string();
+
+        assert_eq!(
+            old_settings.diff(&new_settings),
+            vec![SettingChange {
+                key: "tracker.token".to_string(),
+                old_value: Some("\"***\"".to_string()),
+                new_value: Some("\"***\"".to_string()),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_not_apply_changes_that_require_restarting_the_application_when_reloading() {
+        let configuration = Configuration::default();
+
+        let mut new_settings = TorrustIndex::default();
+        new_settings.website.name = "New name".to_string();
+        new_settings.net.port = 3005;
+
+        let report = configuration.reload(new_settings).await.unwrap();
+
+        let rejected_keys: Vec<String> = report.rejected.into_iter().map(|change| change.key).collect();
+        let applied_keys: Vec<String> = report.applied.into_iter().map(|change| change.key).collect();
+
+        assert_eq!(rejected_keys, vec!["net.port".to_string()]);
+        assert_eq!(applied_keys, vec!["website.name".to_string()]);
+
+        let settings = configuration.get_all().await;
+
+        assert_eq!(settings.net.port, TorrustIndex::default().net.port);
+        assert_eq!(settings.website.name, "New name");
+    }
+
+    #[test]
+    fn it_should_not_allow_importing_the_statistics_of_extra_trackers_from_the_api() {
+        let mut settings = TorrustIndex::default();
+        settings.tracker.extra_trackers = vec![ExtraTracker {
+            url: "udp://tracker.example.com:6969/announce".to_string(),
+            statistics_source: StatisticsSource::Api,
+            add_to_announce_list: true,
+        }];
+
+        assert!(matches!(
+            settings.validate(),
+            Err(ValidationError::ApiStatisticsNotSupportedForExtraTracker { .. })
+        ));
+
+        settings.tracker.extra_trackers[0].statistics_source = StatisticsSource::Scrape;
+
+        assert!(settings.validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_updating_settings_that_require_restarting_the_application() {
+        let configuration = Configuration::default();
+
+        let mut new_settings = TorrustIndex::default();
+        new_settings.net.port = 3005;
+
+        assert!(matches!(
+            configuration.update(new_settings).await,
+            Err(UpdateError::SettingNotApplicableLive { key }) if key == "net.port"
+        ));
+        assert_eq!(configuration.get_all().await.net.port, TorrustIndex::default().net.port);
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_updating_the_settings_that_can_only_be_set_in_the_config_file() {
+        let configuration = Configuration::default();
+
+        let mut new_settings = configuration.get_all().await;
+        new_settings.mail.sendmail_command = "/bin/sh".to_string();
+
+        assert!(matches!(
+            configuration.update(new_settings).await,
+            Err(UpdateError::SettingNotEditable { key }) if key == "mail.sendmail_command"
+        ));
+        assert_eq!(configuration.get_all().await.mail.sendmail_command, "sendmail");
+
+        let mut masked_settings = configuration.get_all().await;
+        masked_settings.remove_secrets();
+
+        assert_eq!(masked_settings.mail.templates_path, "***");
+        assert!(configuration.update(masked_settings).await.is_ok());
+        assert_eq!(
+            configuration.get_all().await.mail.templates_path,
+            TorrustIndex::default().mail.templates_path
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_selecting_the_in_memory_tracker_backend_outside_the_tests() {
+        let info = Info {
+            index_toml: "[tracker]\nbackend = \"InMemory\"\n".to_string(),
+            ..Info::default()
+        };
+
+        assert!(matches!(
+            Configuration::load(&info),
+            Err(Error::InvalidConfiguration {
+                source: ValidationError::InMemoryTrackerBackendNotAllowed
+            })
+        ));
+
+        let configuration = Configuration::default();
+
+        let mut new_settings = TorrustIndex::default();
+        new_settings.tracker.backend = TrackerBackendKind::InMemory;
+
+        assert!(matches!(
+            configuration.update(new_settings).await,
+            Err(UpdateError::InvalidConfiguration {
+                source: ValidationError::InMemoryTrackerBackendNotAllowed
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_updating_settings_overridden_with_env_vars() {
+        let info = Info {
+            index_toml: String::new(),
+            tracker_api_token: Some("TokenFromEnvVar".to_string()),
+            ..Info::default()
+        };
+
+        let configuration = Configuration::load(&info).unwrap();
+
+        let mut new_settings = configuration.get_all().await;
+        new_settings.tracker.token = "NewToken".to_string();
+
+        assert!(matches!(
+            configuration.update(new_settings).await,
+            Err(UpdateError::SettingOverridden { key }) if key == "tracker.token"
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_should_only_write_the_changed_settings_into_the_config_file() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let config_path = config_dir.path().join("config.toml").to_string_lossy().to_string();
+
+        std::fs::write(&config_path, "[website]\nname = \"Old name\"\n").unwrap();
+
+        let info = Info {
+            index_toml: std::fs::read_to_string(&config_path).unwrap(),
+            config_path: Some(config_path.clone()),
+            tracker_api_token: Some("TokenFromEnvVar".to_string()),
+            ..Info::default()
+        };
+
+        let configuration = Configuration::load(&info).unwrap();
+
+        let mut new_settings = configuration.get_all().await;
+        new_settings.website.name = "New name".to_string();
+        new_settings.api.max_torrent_page_size = 50;
+
+        configuration.update(new_settings).await.unwrap();
+
+        let saved: toml::Table = std::fs::read_to_string(&config_path).unwrap().parse().unwrap();
+
+        assert_eq!(
+            saved,
+            "[website]\nname = \"New name\"\n\n[api]\nmax_torrent_page_size = 50\n"
+                .parse::<toml::Table>()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn it_should_override_settings_with_env_vars() {
+        let env_vars = vec![
+            ("TORRUST_INDEX_CONFIG__TRACKER__TOKEN".to_string(), "NewToken".to_string()),
+            ("TORRUST_INDEX_CONFIG__NET__PORT".to_string(), "3005".to_string()),
+            ("OTHER_ENV_VAR".to_string(), "value".to_string()),
+        ];
+
+        let info = Info {
+            index_toml: String::new(),
+            overrides: overrides_from_env_vars("TORRUST_INDEX_CONFIG", env_vars.into_iter()).unwrap(),
+            ..Info::default()
+        };
+
+        let settings = Configuration::load(&info).unwrap().settings.into_inner();
+
+        assert_eq!(settings.tracker.token, "NewToken");
+        assert_eq!(settings.net.port, 3005);
+        assert_eq!(settings.website.name, TorrustIndex::default().website.name);
+    }
+
+    #[test]
+    fn it_should_override_settings_with_the_content_of_the_file_in_env_vars_with_the_file_suffix() {
+        let mut secret_file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(secret_file, "SecretFromFile").unwrap();
+
+        let env_vars = vec![(
+            "TORRUST_INDEX_CONFIG__AUTH__SECRET_KEY_FILE".to_string(),
+            secret_file.path().to_string_lossy().to_string(),
+        )];
+
+        assert_eq!(
+            overrides_from_env_vars("TORRUST_INDEX_CONFIG", env_vars.into_iter()).unwrap(),
+            vec![Override {
+                key: "auth.secret_key".to_string(),
+                value: "SecretFromFile".to_string()
+            }]
+        );
+    }
+}
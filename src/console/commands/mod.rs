@@ -1,3 +1,5 @@
 //! Console commands that can be run manually.
+pub mod print_config;
 pub mod seeder;
 pub mod tracker_statistics_importer;
+pub mod tracker_whitelist_reconciler;
@@ -0,0 +1,103 @@
+//! It prints the effective configuration.
+//!
+//! The configuration is loaded the same way the application does it. It
+//! includes the default values, the values from the configuration file (or the
+//! `TORRUST_INDEX_CONFIG` env var) and the values overridden with env vars.
+//!
+//! Secrets are masked with asterisks.
+//!
+//! You can execute it with: `cargo run --bin print_config`.
+//!
+//! After running it you will see the configuration in TOML format. The
+//! configuration file being loaded is written to `stderr`, so the output can
+//! be redirected to a file to get a valid configuration file:
+//!
+//! ```text
+//! log_level = "info"
+//!
+//! [website]
+//! name = "Torrust"
+//!
+//! [tracker]
+//! url = "udp://localhost:6969"
+//! mode = "Public"
+//! api_url = "http://localhost:1212"
+//! token = "***"
+//! token_valid_seconds = 7257600
+//! backend = "Api"
+//! statistics_source = "Api"
+//! extra_trackers = []
+//! ...
+//! ```
+use std::{env, process};
+
+use derive_more::{Display, Error};
+use text_colorizer::Colorize;
+
+use crate::bootstrap::config::load_configuration;
+use crate::config;
+
+const NUMBER_OF_ARGUMENTS: usize = 0;
+
+#[derive(Debug, Display, Error)]
+pub enum PrintConfigError {
+    #[display(fmt = "wrong number of arguments: expected {expected}, got {got}")]
+    WrongNumberOfArguments { expected: usize, got: usize },
+
+    #[display(fmt = "unable to load the configuration: {source}")]
+    UnableToLoadConfiguration { source: config::Error },
+}
+
+fn parse_args() -> Result<(), PrintConfigError> {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.len() != NUMBER_OF_ARGUMENTS {
+        return Err(PrintConfigError::WrongNumberOfArguments {
+            expected: NUMBER_OF_ARGUMENTS,
+            got: args.len(),
+        });
+    }
+
+    Ok(())
+}
+
+fn print_usage() {
+    eprintln!(
+        "{} - prints the effective configuration with secrets masked.
+
+        cargo run --bin print_config
+
+        ",
+        "Print Config".green()
+    );
+}
+
+/// Print Config Command
+///
+/// It exits with a non-zero status code if the arguments are wrong or the
+/// configuration cannot be loaded.
+pub async fn run() {
+    if let Err(e) = print_config().await {
+        eprintln!("{} {e}", "Error".red().bold());
+
+        if matches!(e, PrintConfigError::WrongNumberOfArguments { .. }) {
+            print_usage();
+        }
+
+        process::exit(1);
+    }
+}
+
+async fn print_config() -> Result<(), PrintConfigError> {
+    parse_args()?;
+
+    let configuration = load_configuration().map_err(|source| PrintConfigError::UnableToLoadConfiguration { source })?;
+
+    let mut settings = configuration.get_all().await;
+
+    settings.remove_secrets();
+
+    println!("{}", toml::to_string(&settings).expect("settings should be serializable to TOML"));
+
+    Ok(())
+}
@@ -112,8 +112,13 @@ pub async fn import() {
     let tracker_statistics_importer =
         Arc::new(StatisticsImporter::new(cfg.clone(), tracker_service.clone(), database.clone()).await);
 
-    tracker_statistics_importer
-        .import_all_torrents_statistics()
+    let progress = tracker_statistics_importer
+        .import_torrents_statistics()
         .await
-        .expect("should import all torrents statistics");
+        .expect("should import torrents statistics");
+
+    println!(
+        "Imported {} torrents statistics with {} errors",
+        progress.torrents_processed, progress.errors
+    );
 }
@@ -0,0 +1,159 @@
+//! It reconciles the tracker whitelist with the torrents in the index.
+//!
+//! It whitelists the index torrents the tracker is not tracking and removes
+//! the tracked torrents that do not exist in the index from the whitelist.
+//! The changes are enqueued as tracker jobs, which are executed by the
+//! running index.
+//! It only works when the tracker runs in a whitelisted mode. Refer to the
+//! [`WhitelistReconciler`](crate::services::tracker::WhitelistReconciler)
+//! for its limitations.
+//!
+//! You can execute it with: `cargo run --bin reconcile_tracker_whitelist`.
+//!
+//! Use the `--dry-run` option to only report the differences without
+//! modifying the tracker whitelist:
+//!
+//! ```text
+//! cargo run --bin reconcile_tracker_whitelist -- --dry-run
+//! ```
+//!
+//! After running it you will see a summary like this:
+//!
+//! ```text
+//! Reconciling tracker whitelist ...
+//! Tracker url: udp://localhost:6969
+//! Torrents in index: 12
+//! Torrents in tracker: 10
+//! Not seen by tracker: 3
+//! Stale in tracker: 1
+//! Jobs enqueued to add: 3
+//! Jobs enqueued to remove: 1
+//! Failed: 0
+//! ```
+//!
+//! The whitelist can also be reconciled with the admin API endpoint. See the
+//! [`tracker`](crate::web::api::server::v1::contexts::tracker) API context.
+use std::env;
+use std::sync::Arc;
+
+use derive_more::{Display, Error};
+use text_colorizer::Colorize;
+
+use crate::bootstrap::config::initialize_configuration;
+use crate::bootstrap::logging;
+use crate::databases::database;
+use crate::services::tracker::WhitelistReconciler;
+use crate::tracker::job_queue::JobQueue;
+use crate::tracker::service::Service;
+use crate::tracker::statistics_importer::StatisticsImporter;
+
+const DRY_RUN_OPTION: &str = "--dry-run";
+
+#[derive(Debug, Display, PartialEq, Error)]
+#[allow(dead_code)]
+pub enum ReconcileError {
+    #[display(fmt = "wrong arguments")]
+    WrongArgumentsError,
+}
+
+/// It returns whether the command runs in dry-run mode.
+fn parse_args() -> Result<bool, ReconcileError> {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.as_slice() {
+        [] => Ok(false),
+        [option] if option == DRY_RUN_OPTION => Ok(true),
+        _ => {
+            eprintln!("{} wrong arguments: {}", "Error".red().bold(), args.join(" "));
+            print_usage();
+            Err(ReconcileError::WrongArgumentsError)
+        }
+    }
+}
+
+fn print_usage() {
+    eprintln!(
+        "{} - reconciles the tracker whitelist with the torrents in the index.
+
+        cargo run --bin reconcile_tracker_whitelist [-- {DRY_RUN_OPTION}]
+
+        ",
+        "Tracker Whitelist Reconciler".green()
+    );
+}
+
+/// Reconcile Tracker Whitelist Command
+///
+/// # Panics
+///
+/// Panics if arguments cannot be parsed.
+pub async fn run() {
+    let dry_run = parse_args().expect("unable to parse command arguments");
+    reconcile(dry_run).await;
+}
+
+/// It reconciles the tracker whitelist and prints the summary.
+///
+/// # Panics
+///
+/// Panics if it can't connect to the database or the tracker.
+pub async fn reconcile(dry_run: bool) {
+    println!("Reconciling tracker whitelist ...");
+
+    let configuration = initialize_configuration();
+
+    let log_level = configuration.settings.read().await.log_level.clone();
+
+    logging::setup(&log_level);
+
+    let cfg = Arc::new(configuration);
+
+    let settings = cfg.settings.read().await;
+
+    eprintln!("Tracker url: {}", settings.tracker.url.green());
+
+    let database = Arc::new(
+        database::connect(&settings.database.connect_url)
+            .await
+            .expect("unable to connect to db"),
+    );
+
+    drop(settings);
+
+    let tracker_service = Arc::new(Service::new(cfg.clone(), database.clone()).await);
+    let tracker_statistics_importer =
+        Arc::new(StatisticsImporter::new(cfg.clone(), tracker_service.clone(), database.clone()).await);
+    let job_queue = Arc::new(JobQueue::new(
+        cfg.clone(),
+        database.clone(),
+        tracker_service.clone(),
+        tracker_statistics_importer,
+    ));
+    let whitelist_reconciler = WhitelistReconciler::new(cfg.clone(), database.clone(), tracker_service.clone(), job_queue);
+
+    let report = whitelist_reconciler
+        .reconcile(dry_run)
+        .await
+        .expect("should reconcile the tracker whitelist");
+
+    if report.dry_run {
+        println!("{}", "Dry run. No tracker jobs were enqueued.".yellow());
+    }
+    if !report.whitelisted_mode {
+        println!(
+            "{}",
+            "The tracker does not run in a whitelisted mode. Nothing to reconcile.".yellow()
+        );
+    }
+    println!("Torrents in index: {}", report.torrents_in_index);
+    println!("Torrents in tracker: {}", report.torrents_in_tracker);
+    println!("Not seen by tracker: {}", report.not_seen_by_tracker);
+    println!("Stale in tracker: {}", report.stale);
+    println!("Jobs enqueued to add: {}", report.added);
+    println!("Jobs enqueued to remove: {}", report.removed);
+    println!("Failed: {}", report.failed.len());
+
+    for info_hash in &report.failed {
+        println!("  {}", info_hash.red());
+    }
+}
@@ -0,0 +1,150 @@
+//! Job to reload the configuration when the configuration file changes.
+//!
+//! The configuration is reloaded when:
+//!
+//! - The process receives a `SIGHUP` signal (only on Unix).
+//! - The modification time of the configuration file changes. The file is
+//!   checked every [`FILE_CHECK_INTERVAL_IN_SECS`] seconds.
+//!
+//! The new configuration is validated before being applied. If it's not valid
+//! the current configuration is kept.
+//!
+//! Some settings can't be applied while the application is running, for
+//! example, the port the API is listening on or the database URL. Changes to
+//! those settings are ignored and a warning is logged. You have to restart the
+//! application to apply them.
+//!
+//! The job does nothing if the configuration was not loaded from a file.
+use std::fs;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use log::{error, info, warn};
+use tokio::task::JoinHandle;
+
+use crate::bootstrap::config::load_settings_from_file;
+use crate::config::Configuration;
+
+const LOG_TARGET: &str = "Config Watcher";
+
+/// Interval in seconds to check if the configuration file was modified.
+pub const FILE_CHECK_INTERVAL_IN_SECS: u64 = 5;
+
+/// It starts the job that watches the configuration file.
+#[must_use]
+pub fn start(configuration: &Arc<Configuration>) -> JoinHandle<()> {
+    let weak_configuration = Arc::downgrade(configuration);
+    let config_path = configuration.config_path.clone();
+
+    tokio::spawn(async move {
+        let Some(config_path) = config_path else {
+            info!(target: LOG_TARGET, "Configuration not loaded from a file. It will not be reloaded.");
+            return;
+        };
+
+        info!(target: LOG_TARGET, "Watching configuration file: {config_path}");
+
+        let mut hangup_signal = hangup_signal();
+
+        let mut interval = tokio::time::interval(Duration::from_secs(FILE_CHECK_INTERVAL_IN_SECS));
+
+        let mut last_modified = modification_time(&config_path);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let modified = modification_time(&config_path);
+
+                    if modified == last_modified {
+                        continue;
+                    }
+
+                    last_modified = modified;
+
+                    info!(target: LOG_TARGET, "Configuration file modified");
+                }
+                _ = recv(&mut hangup_signal) => {
+                    info!(target: LOG_TARGET, "SIGHUP received");
+                }
+            }
+
+            let Some(configuration) = weak_configuration.upgrade() else {
+                break;
+            };
+
+            reload(&configuration, &config_path).await;
+        }
+    })
+}
+
+/// It reloads the configuration from the file and logs the changes.
+async fn reload(configuration: &Configuration, config_path: &str) {
+    let new_settings = match load_settings_from_file(config_path) {
+        Ok(new_settings) => new_settings,
+        Err(e) => {
+            error!(target: LOG_TARGET, "Failed to load configuration file {config_path}: {e}");
+            return;
+        }
+    };
+
+    match configuration.reload(new_settings).await {
+        Ok(report) => {
+            for change in &report.rejected {
+                warn!(target: LOG_TARGET, "Setting can't be changed without restarting the application. Change ignored: {change}");
+            }
+
+            if report.applied.is_empty() {
+                info!(target: LOG_TARGET, "Configuration reloaded. No changes applied.");
+            } else {
+                for change in &report.applied {
+                    info!(target: LOG_TARGET, "Setting changed: {change}");
+                }
+                info!(target: LOG_TARGET, "Configuration reloaded. {} changes applied.", report.applied.len());
+            }
+        }
+        Err(e) => {
+            error!(target: LOG_TARGET, "Invalid configuration in file {config_path}. Current configuration kept: {e}");
+        }
+    }
+}
+
+fn modification_time(config_path: &str) -> Option<SystemTime> {
+    fs::metadata(config_path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(unix)]
+type HangupSignal = Option<tokio::signal::unix::Signal>;
+
+#[cfg(not(unix))]
+type HangupSignal = ();
+
+#[cfg(unix)]
+fn hangup_signal() -> HangupSignal {
+    match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+        Ok(signal) => Some(signal),
+        Err(e) => {
+            error!(target: LOG_TARGET, "Failed to listen for SIGHUP signals: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn hangup_signal() -> HangupSignal {}
+
+/// It waits for the next `SIGHUP` signal. It never returns if signals are not
+/// available.
+#[cfg(unix)]
+async fn recv(hangup_signal: &mut HangupSignal) {
+    match hangup_signal {
+        Some(signal) => {
+            signal.recv().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(not(unix))]
+async fn recv(_hangup_signal: &mut HangupSignal) {
+    std::future::pending().await
+}
@@ -0,0 +1,84 @@
+//! Cronjob to apply the [dead torrent policy](crate::services::dead_torrent).
+//!
+//! It runs every `dead_torrents.check_interval_seconds` seconds when
+//! `dead_torrents.enabled` is `true`. The first run is executed after the
+//! first interval, so the tracker statistics importer has time to import the
+//! seeders.
+//!
+//! With `dead_torrents.dry_run` the changes are only logged, which is useful
+//! to check the policy settings before enabling it.
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{error, info};
+use tokio::task::JoinHandle;
+
+use crate::config::Configuration;
+use crate::services::dead_torrent::{DeadTorrentPolicy, DeadTorrentsReport};
+
+const LOG_TARGET: &str = "Dead Torrents";
+
+/// It starts the cronjob that applies the dead torrent policy.
+#[must_use]
+pub fn start(configuration: &Arc<Configuration>, dead_torrent_policy: &Arc<DeadTorrentPolicy>) -> JoinHandle<()> {
+    let weak_configuration = Arc::downgrade(configuration);
+    let weak_dead_torrent_policy = Arc::downgrade(dead_torrent_policy);
+
+    tokio::spawn(async move {
+        info!(target: LOG_TARGET, "Dead torrents cronjob started");
+
+        loop {
+            let Some(configuration) = weak_configuration.upgrade() else {
+                break;
+            };
+
+            let settings = configuration.settings.read().await;
+            let interval = Duration::from_secs(settings.dead_torrents.check_interval_seconds);
+            drop(settings);
+
+            drop(configuration);
+
+            tokio::time::sleep(interval).await;
+
+            let Some(configuration) = weak_configuration.upgrade() else {
+                break;
+            };
+
+            let settings = configuration.settings.read().await;
+            let enabled = settings.dead_torrents.enabled;
+            let dry_run = settings.dead_torrents.dry_run;
+            drop(settings);
+
+            drop(configuration);
+
+            if !enabled {
+                continue;
+            }
+
+            let Some(dead_torrent_policy) = weak_dead_torrent_policy.upgrade() else {
+                break;
+            };
+
+            match dead_torrent_policy.apply(dry_run).await {
+                Ok(report) => log_report(&report),
+                Err(e) => error!(target: LOG_TARGET, "Failed to apply the dead torrent policy: {e}"),
+            }
+        }
+    })
+}
+
+fn log_report(report: &DeadTorrentsReport) {
+    let prefix = if report.dry_run { "[dry run] " } else { "" };
+
+    for torrent in &report.revived {
+        info!(target: LOG_TARGET, "{prefix}Torrent {} \"{}\" has seeders again", torrent.info_hash, torrent.title);
+    }
+
+    for torrent in &report.marked_as_dead {
+        info!(target: LOG_TARGET, "{prefix}Torrent {} \"{}\" marked as dead", torrent.info_hash, torrent.title);
+    }
+
+    for torrent in &report.pruned {
+        info!(target: LOG_TARGET, "{prefix}Torrent {} \"{}\" pruned: {:?}", torrent.info_hash, torrent.title, report.prune_action);
+    }
+}
@@ -0,0 +1,69 @@
+//! Worker that delivers the queued emails.
+//!
+//! Emails are stored in the database by the [`MailQueue`](crate::mailer::queue::MailQueue)
+//! when the index sends an email, for example, the verification email after
+//! the user registration.
+//!
+//! The worker checks for due emails every `mail_queue.worker_interval_seconds`
+//! seconds and right after a new email is enqueued. Once an hour, it also
+//! deletes the sent and failed emails older than `mail_queue.retention_days`
+//! days.
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use log::{error, info};
+use tokio::task::JoinHandle;
+
+use crate::config::Configuration;
+use crate::mailer::queue::MailQueue;
+
+const LOG_TARGET: &str = "Mail Queue Worker";
+
+/// The interval between the deletions of the old sent and failed emails.
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// It starts the worker that delivers the queued emails.
+#[must_use]
+pub fn start(configuration: &Arc<Configuration>, mail_queue: &Arc<MailQueue>) -> JoinHandle<()> {
+    let weak_configuration = Arc::downgrade(configuration);
+    let weak_mail_queue = Arc::downgrade(mail_queue);
+
+    tokio::spawn(async move {
+        info!(target: LOG_TARGET, "Mail queue worker started");
+
+        let mut last_cleanup: Option<Instant> = None;
+
+        loop {
+            let Some(mail_queue) = weak_mail_queue.upgrade() else {
+                break;
+            };
+
+            if let Err(e) = mail_queue.process_due_mails().await {
+                error!(target: LOG_TARGET, "Failed to load pending emails: {e:?}");
+            }
+
+            if last_cleanup.map_or(true, |last_cleanup| last_cleanup.elapsed() >= CLEANUP_INTERVAL) {
+                match mail_queue.delete_finished_mails().await {
+                    Ok(0) => {}
+                    Ok(deleted) => info!(target: LOG_TARGET, "Deleted {deleted} old sent and failed emails"),
+                    Err(e) => error!(target: LOG_TARGET, "Failed to delete old sent and failed emails: {e:?}"),
+                }
+
+                last_cleanup = Some(Instant::now());
+            }
+
+            let Some(configuration) = weak_configuration.upgrade() else {
+                break;
+            };
+
+            let interval = Duration::from_secs(configuration.settings.read().await.mail_queue.worker_interval_seconds);
+
+            drop(configuration);
+
+            tokio::select! {
+                () = tokio::time::sleep(interval) => {}
+                () = mail_queue.wait_for_new_mails() => {}
+            }
+        }
+    })
+}
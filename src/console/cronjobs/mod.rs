@@ -1,2 +1,9 @@
 //! Cronjobs that are executed automatically.
+pub mod config_watcher;
+pub mod dead_torrents;
+pub mod mail_queue_worker;
+pub mod notification_digest;
+pub mod tracker_jobs_worker;
+pub mod tracker_mode_sync;
 pub mod tracker_statistics_importer;
+pub mod unverified_accounts;
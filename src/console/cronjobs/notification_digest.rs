@@ -0,0 +1,55 @@
+//! Cronjob to send the notification email digests.
+//!
+//! It runs every `notifications.digest_interval_seconds` seconds. It sends
+//! one email to each user who chose to receive the notifications in a digest,
+//! with all the notifications generated since the previous one. It also
+//! deletes the notifications older than `notifications.retention_days` days.
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{error, info};
+use tokio::task::JoinHandle;
+
+use crate::config::Configuration;
+use crate::services::notification::Notifier;
+
+const LOG_TARGET: &str = "Notification Digest";
+
+/// It starts the cronjob that sends the notification digests.
+#[must_use]
+pub fn start(configuration: &Arc<Configuration>, notifier: &Arc<Notifier>) -> JoinHandle<()> {
+    let weak_configuration = Arc::downgrade(configuration);
+    let weak_notifier = Arc::downgrade(notifier);
+
+    tokio::spawn(async move {
+        info!(target: LOG_TARGET, "Notification digest cronjob started");
+
+        loop {
+            let Some(configuration) = weak_configuration.upgrade() else {
+                break;
+            };
+
+            let interval = Duration::from_secs(configuration.settings.read().await.notifications.digest_interval_seconds);
+
+            drop(configuration);
+
+            tokio::time::sleep(interval).await;
+
+            let Some(notifier) = weak_notifier.upgrade() else {
+                break;
+            };
+
+            match notifier.send_digests().await {
+                Ok(0) => {}
+                Ok(sent) => info!(target: LOG_TARGET, "Sent {sent} notification digests"),
+                Err(e) => error!(target: LOG_TARGET, "Failed to send the notification digests: {e}"),
+            }
+
+            match notifier.delete_expired_notifications().await {
+                Ok(0) => {}
+                Ok(deleted) => info!(target: LOG_TARGET, "Deleted {deleted} expired notifications"),
+                Err(e) => error!(target: LOG_TARGET, "Failed to delete the expired notifications: {e}"),
+            }
+        }
+    })
+}
@@ -0,0 +1,52 @@
+//! Worker that executes the pending tracker jobs.
+//!
+//! Jobs are stored in the database by the [`JobQueue`](crate::tracker::job_queue::JobQueue)
+//! when the index needs to apply a change to the tracker, for example, when a
+//! torrent is uploaded and it has to be whitelisted.
+//!
+//! The worker checks for due jobs every `tracker_jobs.worker_interval_seconds`
+//! seconds and right after a new job is enqueued.
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{error, info};
+use tokio::task::JoinHandle;
+
+use crate::config::Configuration;
+use crate::tracker::job_queue::JobQueue;
+
+const LOG_TARGET: &str = "Tracker Jobs Worker";
+
+/// It starts the worker that executes the tracker jobs.
+#[must_use]
+pub fn start(configuration: &Arc<Configuration>, job_queue: &Arc<JobQueue>) -> JoinHandle<()> {
+    let weak_configuration = Arc::downgrade(configuration);
+    let weak_job_queue = Arc::downgrade(job_queue);
+
+    tokio::spawn(async move {
+        info!(target: LOG_TARGET, "Tracker jobs worker started");
+
+        loop {
+            let Some(job_queue) = weak_job_queue.upgrade() else {
+                break;
+            };
+
+            if let Err(e) = job_queue.process_due_jobs().await {
+                error!(target: LOG_TARGET, "Failed to load pending tracker jobs: {e:?}");
+            }
+
+            let Some(configuration) = weak_configuration.upgrade() else {
+                break;
+            };
+
+            let interval = Duration::from_secs(configuration.settings.read().await.tracker_jobs.worker_interval_seconds);
+
+            drop(configuration);
+
+            tokio::select! {
+                () = tokio::time::sleep(interval) => {}
+                () = job_queue.wait_for_new_jobs() => {}
+            }
+        }
+    })
+}
@@ -0,0 +1,42 @@
+//! Worker that runs the [tracker mode syncs](crate::services::tracker::TrackerModeSynchronizer).
+//!
+//! A sync interrupted by a restart is resumed when the worker starts. A sync
+//! that failed because of a database error is retried after [`RETRY_INTERVAL`]
+//! or when a new sync is started.
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{error, info};
+use tokio::task::JoinHandle;
+
+use crate::services::tracker::TrackerModeSynchronizer;
+
+const LOG_TARGET: &str = "Tracker Mode Sync Worker";
+
+/// Interval to retry a sync that failed because of a database error.
+pub const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// It starts the worker that runs the tracker mode syncs.
+#[must_use]
+pub fn start(tracker_mode_synchronizer: &Arc<TrackerModeSynchronizer>) -> JoinHandle<()> {
+    let weak_tracker_mode_synchronizer = Arc::downgrade(tracker_mode_synchronizer);
+
+    tokio::spawn(async move {
+        info!(target: LOG_TARGET, "Tracker mode sync worker started");
+
+        loop {
+            let Some(tracker_mode_synchronizer) = weak_tracker_mode_synchronizer.upgrade() else {
+                break;
+            };
+
+            if let Err(e) = tracker_mode_synchronizer.run_pending().await {
+                error!(target: LOG_TARGET, "Failed to run the tracker mode sync: {e}");
+            }
+
+            tokio::select! {
+                () = tokio::time::sleep(RETRY_INTERVAL) => {}
+                () = tracker_mode_synchronizer.wait_for_new_sync() => {}
+            }
+        }
+    })
+}
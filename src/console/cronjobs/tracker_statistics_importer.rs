@@ -10,8 +10,30 @@
 //! The last heartbeat signal time is used to determine whether the cronjob was
 //! executed successfully or not. The API has a `health_check` endpoint which is
 //! used when the application is running in containers.
+//!
+//! After each import, the uploaders of the torrents that lost all their
+//! seeders are notified.
+//!
+//! While the import is running, the cronjob also sends a heartbeat every
+//! [`PROGRESS_HEARTBEAT_INTERVAL`] with the import
+//! [progress](crate::tracker::statistics_importer::ImportProgress). The
+//! `health_check` endpoint returns the last progress received:
+//!
+//! ```json
+//! {
+//!   "status": "Ok",
+//!   "progress": {
+//!     "torrents_to_import": 100,
+//!     "torrents_processed": 50,
+//!     "errors": 1,
+//!     "last_error": "Error scraping tracker udp://tracker.example.com:6969/announce: request timed out",
+//!     "finished": false
+//!   }
+//! }
+//! ```
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use axum::extract::State;
 use axum::routing::{get, post};
@@ -22,15 +44,21 @@ use serde_json::{json, Value};
 use tokio::net::TcpListener;
 use tokio::task::JoinHandle;
 
-use crate::tracker::statistics_importer::StatisticsImporter;
+use crate::services::notification::Notifier;
+use crate::tracker::statistics_importer::{ImportProgress, StatisticsImporter};
 
 const IMPORTER_API_IP: &str = "127.0.0.1";
 
+/// Interval between heartbeats while an import is running.
+pub const PROGRESS_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
 #[derive(Clone)]
 struct ImporterState {
     /// Shared variable to store the timestamp of the last heartbeat sent
     /// by the cronjob.
     pub last_heartbeat: Arc<Mutex<DateTime<Utc>>>,
+    /// The import progress sent with the last heartbeat.
+    pub last_progress: Arc<Mutex<Option<ImportProgress>>>,
     /// Interval between importation executions
     pub torrent_info_update_interval: u64,
 }
@@ -43,8 +71,10 @@ pub fn start(
     importer_port: u16,
     torrent_info_update_interval: u64,
     tracker_statistics_importer: &Arc<StatisticsImporter>,
+    notifier: &Arc<Notifier>,
 ) -> JoinHandle<()> {
     let weak_tracker_statistics_importer = Arc::downgrade(tracker_statistics_importer);
+    let weak_notifier = Arc::downgrade(notifier);
 
     tokio::spawn(async move {
         info!("Tracker statistics importer launcher started");
@@ -54,6 +84,7 @@ pub fn start(
         let _importer_api_handle = tokio::spawn(async move {
             let import_state = Arc::new(ImporterState {
                 last_heartbeat: Arc::new(Mutex::new(Utc::now())),
+                last_progress: Arc::new(Mutex::new(None)),
                 torrent_info_update_interval,
             });
 
@@ -91,49 +122,89 @@ pub fn start(
 
             info!("Running tracker statistics importer ...");
 
-            if let Err(e) = send_heartbeat(importer_port).await {
-                error!("Failed to send heartbeat from importer cronjob: {}", e);
-            }
-
             if let Some(tracker) = weak_tracker_statistics_importer.upgrade() {
-                drop(tracker.import_all_torrents_statistics().await);
+                run_import(&tracker, importer_port).await;
             } else {
                 break;
             }
+
+            if let Some(notifier) = weak_notifier.upgrade() {
+                match notifier.notify_torrents_without_seeders().await {
+                    Ok(0) => {}
+                    Ok(notified) => info!("Notified the uploaders of {notified} torrents without seeders"),
+                    Err(e) => error!("Failed to notify the uploaders of the torrents without seeders: {:?}", e),
+                }
+            }
         }
     })
 }
 
+/// It runs the import sending a heartbeat with the progress when it starts,
+/// periodically while it's running, and when it finishes.
+async fn run_import(tracker_statistics_importer: &StatisticsImporter, importer_port: u16) {
+    let import = tracker_statistics_importer.import_torrents_statistics();
+    tokio::pin!(import);
+
+    let mut heartbeat_interval = tokio::time::interval(PROGRESS_HEARTBEAT_INTERVAL);
+
+    loop {
+        tokio::select! {
+            result = &mut import => {
+                if let Err(e) = result {
+                    error!("Failed to import tracker statistics: {:?}", e);
+                }
+
+                if let Err(e) = send_heartbeat(importer_port, &tracker_statistics_importer.progress()).await {
+                    error!("Failed to send heartbeat from importer cronjob: {}", e);
+                }
+
+                break;
+            }
+            _ = heartbeat_interval.tick() => {
+                if let Err(e) = send_heartbeat(importer_port, &tracker_statistics_importer.progress()).await {
+                    error!("Failed to send heartbeat from importer cronjob: {}", e);
+                }
+            }
+        }
+    }
+}
+
 /// Endpoint for container health check.
 async fn health_check_handler(State(state): State<Arc<ImporterState>>) -> Json<Value> {
     let margin_in_seconds = 10;
     let now = Utc::now();
     let last_heartbeat = state.last_heartbeat.lock().unwrap();
+    let last_progress = state.last_progress.lock().unwrap().clone();
 
     if now.signed_duration_since(*last_heartbeat).num_seconds()
         <= (state.torrent_info_update_interval + margin_in_seconds).try_into().unwrap()
     {
-        Json(json!({ "status": "Ok" }))
+        Json(json!({ "status": "Ok", "progress": last_progress }))
     } else {
-        Json(json!({ "status": "Error" }))
+        Json(json!({ "status": "Error", "progress": last_progress }))
     }
 }
 
 /// The tracker statistics importer cronjob sends a heartbeat on each execution
-/// to inform that it's alive. This endpoint handles receiving that signal.
-async fn heartbeat_handler(State(state): State<Arc<ImporterState>>) -> Json<Value> {
+/// to inform that it's alive. This endpoint handles receiving that signal and
+/// the import progress, if included.
+async fn heartbeat_handler(State(state): State<Arc<ImporterState>>, progress: Option<Json<ImportProgress>>) -> Json<Value> {
     let now = Utc::now();
     let mut last_heartbeat = state.last_heartbeat.lock().unwrap();
     *last_heartbeat = now;
+    if let Some(Json(progress)) = progress {
+        *state.last_progress.lock().unwrap() = Some(progress);
+    }
     Json(json!({ "status": "Heartbeat received" }))
 }
 
-/// Send a heartbeat from the importer cronjob to the importer API.
-async fn send_heartbeat(importer_port: u16) -> Result<(), reqwest::Error> {
+/// Send a heartbeat with the import progress from the importer cronjob to the
+/// importer API.
+async fn send_heartbeat(importer_port: u16, progress: &ImportProgress) -> Result<(), reqwest::Error> {
     let client = reqwest::Client::new();
     let url = format!("http://{IMPORTER_API_IP}:{importer_port}/heartbeat"); // # DevSkim: ignore DS137138
 
-    client.post(url).send().await?;
+    client.post(url).json(progress).send().await?;
 
     Ok(())
 }
@@ -0,0 +1,51 @@
+//! Cronjob to delete the accounts that never verified their email.
+//!
+//! It runs every `email_verification.purge_interval_seconds` seconds. Accounts
+//! are deleted `email_verification.purge_unverified_after_days` days after the
+//! registration if the user never followed the link in the verification
+//! email. The accounts are deleted with their torrents and images, like the
+//! ones deleted by an administrator. When email verification is disabled or
+//! the number of days is `0`, it only deletes the expired verification tokens.
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{error, info};
+use tokio::task::JoinHandle;
+
+use crate::config::Configuration;
+use crate::services::user::DeletionService;
+
+const LOG_TARGET: &str = "Unverified Accounts";
+
+/// It starts the cronjob that deletes the unverified accounts.
+#[must_use]
+pub fn start(configuration: &Arc<Configuration>, deletion_service: &Arc<DeletionService>) -> JoinHandle<()> {
+    let weak_configuration = Arc::downgrade(configuration);
+    let weak_deletion_service = Arc::downgrade(deletion_service);
+
+    tokio::spawn(async move {
+        info!(target: LOG_TARGET, "Unverified accounts cronjob started");
+
+        loop {
+            let Some(configuration) = weak_configuration.upgrade() else {
+                break;
+            };
+
+            let interval = Duration::from_secs(configuration.settings.read().await.email_verification.purge_interval_seconds);
+
+            drop(configuration);
+
+            tokio::time::sleep(interval).await;
+
+            let Some(deletion_service) = weak_deletion_service.upgrade() else {
+                break;
+            };
+
+            match deletion_service.purge_unverified_accounts().await {
+                Ok(0) => {}
+                Ok(deleted) => info!(target: LOG_TARGET, "Deleted {deleted} accounts that never verified their email"),
+                Err(e) => error!(target: LOG_TARGET, "Failed to delete the unverified accounts: {e}"),
+            }
+        }
+    })
+}
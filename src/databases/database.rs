@@ -5,23 +5,42 @@ use serde::{Deserialize, Serialize};
 use crate::databases::mysql::Mysql;
 use crate::databases::sqlite::Sqlite;
 use crate::models::category::CategoryId;
+use crate::models::image::{DbTorrentImage, Image, ImageId};
+use crate::models::image_cache_quota::DbImageCacheQuota;
 use crate::models::info_hash::InfoHash;
+use crate::models::notification::{NewNotification, Notification, NotificationId, NotificationPreferences, UploadedTorrent};
+use crate::models::queued_mail::{MailId, NewMail, QueuedMail};
 use crate::models::response::TorrentsResponse;
-use crate::models::torrent::{Metadata, TorrentListing};
-use crate::models::torrent_file::{DbTorrent, Torrent, TorrentFile};
+use crate::models::torrent::{DeadTorrent, Metadata, TorrentListing, TorrentStatsSnapshot, TorrentTrackerStats};
+use crate::models::torrent_file::{DbTorrent, DbTorrentWebSeed, Torrent, TorrentFile, WebSeedType};
 use crate::models::torrent_tag::{TagId, TorrentTag};
+use crate::models::tracker_job::{TrackerJob, TrackerJobId};
 use crate::models::tracker_key::TrackerKey;
+use crate::models::tracker_mode_sync::{TrackerModeSync, TrackerModeSyncId};
 use crate::models::user::{User, UserAuthentication, UserCompact, UserId, UserProfile};
 use crate::services::torrent::CanonicalInfoHashGroup;
 
 /// Database tables to be truncated when upgrading from v1.0.0 to v2.0.0.
 /// They must be in the correct order to avoid foreign key errors.
 pub const TABLES_TO_TRUNCATE: &[&str] = &[
+    "torrust_notification_preferences",
+    "torrust_notifications",
+    "torrust_tracker_mode_syncs",
+    "torrust_tracker_jobs",
+    "torrust_email_verification_tokens",
+    "torrust_mail_queue",
+    "torrust_image_cache_quotas",
+    "torrust_torrent_images",
+    "torrust_images",
     "torrust_torrent_announce_urls",
+    "torrust_torrent_web_seeds",
     "torrust_torrent_files",
     "torrust_torrent_info",
     "torrust_torrent_tag_links",
+    "torrust_torrent_tracker_stats_history",
     "torrust_torrent_tracker_stats",
+    "torrust_user_torrent_downloads",
+    "torrust_torrent_info_hashes",
     "torrust_torrents",
     "torrust_tracker_keys",
     "torrust_user_authentication",
@@ -57,19 +76,33 @@ pub struct Category {
     pub num_torrents: i64,
 }
 
+/// Period used to calculate the seeders growth for the `Trending` sorting.
+pub const TRENDING_PERIOD_HOURS: i64 = 24;
+
 /// Sorting options for torrents.
+///
+/// `Trending` sorts by the seeders growth in the last
+/// [`TRENDING_PERIOD_HOURS`], calculated from the statistics history.
 #[derive(Clone, Copy, Debug, Deserialize)]
 pub enum Sorting {
     UploadedAsc,
     UploadedDesc,
     SeedersAsc,
     SeedersDesc,
+    TotalSeedersAsc,
+    TotalSeedersDesc,
+    TrendingAsc,
+    TrendingDesc,
     LeechersAsc,
     LeechersDesc,
     NameAsc,
     NameDesc,
     SizeAsc,
     SizeDesc,
+    TimesDownloadedAsc,
+    TimesDownloadedDesc,
+    TimesCompletedAsc,
+    TimesCompletedDesc,
 }
 
 /// Database errors.
@@ -88,6 +121,12 @@ pub enum Error {
     TorrentAlreadyExists, // when uploading an already uploaded info_hash
     TorrentTitleAlreadyExists,
     TorrentInfoHashNotFound,
+    TrackerJobNotFound,
+    ImageNotFound,
+    ImageQuotaExceeded,
+    MailNotFound,
+    EmailVerificationTokenNotFound,
+    NotificationNotFound,
 }
 
 /// Get the Driver of the Database from the Connection String
@@ -139,6 +178,9 @@ pub trait Database: Sync + Send {
     /// Get `UserProfile` from `username`.
     async fn get_user_profile_from_username(&self, username: &str) -> Result<UserProfile, Error>;
 
+    /// Get `UserProfile` from `user_id`.
+    async fn get_user_profile_from_id(&self, user_id: UserId) -> Result<UserProfile, Error>;
+
     /// Get `UserCompact` from `user_id`.
     async fn get_user_compact_from_id(&self, user_id: i64) -> Result<UserCompact, Error>;
 
@@ -154,12 +196,42 @@ pub trait Database: Sync + Send {
     /// Grant a user the administrator role.
     async fn grant_admin_role(&self, user_id: i64) -> Result<(), Error>;
 
-    /// Verify a user's email with `user_id`.
-    async fn verify_email(&self, user_id: i64) -> Result<(), Error>;
+    /// Add an email verification token for a user, replacing the previous
+    /// ones. Only the hash of the token is stored.
+    async fn add_email_verification_token(
+        &self,
+        user_id: UserId,
+        token_hash: &str,
+        expires_at: NaiveDateTime,
+        now: NaiveDateTime,
+    ) -> Result<(), Error>;
+
+    /// Verify the email of the user owning a token that has not expired at
+    /// `now`, deleting all the verification tokens of the user, so they can
+    /// be used only once. It returns the `user_id`.
+    async fn verify_email_with_token(&self, token_hash: &str, now: NaiveDateTime) -> Result<UserId, Error>;
+
+    /// Check if a verification token was added for a user after `since`.
+    async fn email_verification_token_added_after(&self, user_id: UserId, since: NaiveDateTime) -> Result<bool, Error>;
+
+    /// Get the users, except administrators, registered before
+    /// `registered_before` who were sent a verification email but never
+    /// verified it.
+    async fn get_unverified_users(&self, registered_before: NaiveDateTime) -> Result<Vec<UserId>, Error>;
+
+    /// Delete the email verification tokens expired at `now`. It returns the
+    /// number of deleted tokens.
+    async fn delete_expired_email_verification_tokens(&self, now: NaiveDateTime) -> Result<u64, Error>;
 
     /// Link a `TrackerKey` to a certain user with `user_id`.
     async fn add_tracker_key(&self, user_id: i64, tracker_key: &TrackerKey) -> Result<(), Error>;
 
+    /// Get all the not expired `TrackerKey`s of a user with `user_id`.
+    async fn get_user_tracker_keys(&self, user_id: i64) -> Result<Vec<TrackerKey>, Error>;
+
+    /// Delete all the tracker keys of a user with `user_id`.
+    async fn delete_user_tracker_keys(&self, user_id: i64) -> Result<(), Error>;
+
     /// Delete user and all related user data with `user_id`.
     async fn delete_user(&self, user_id: i64) -> Result<(), Error>;
 
@@ -179,6 +251,7 @@ pub trait Database: Sync + Send {
     async fn delete_category(&self, category_name: &str) -> Result<(), Error>;
 
     /// Get results of a torrent search in a paginated and sorted form as `TorrentsResponse` from `search`, `categories`, `sort`, `offset` and `page_size`.
+    #[allow(clippy::too_many_arguments)]
     async fn get_torrents_search_sorted_paginated(
         &self,
         search: &Option<String>,
@@ -187,6 +260,7 @@ pub trait Database: Sync + Send {
         sort: &Sorting,
         offset: u64,
         page_size: u8,
+        include_dead: bool,
     ) -> Result<TorrentsResponse, Error>;
 
     /// Add new torrent and return the newly inserted `torrent_id` with `torrent`, `uploader_id`, `category_id`, `title` and `description`.
@@ -206,7 +280,14 @@ pub trait Database: Sync + Send {
 
         let torrent_announce_urls = self.get_torrent_announce_urls_from_id(db_torrent.torrent_id).await?;
 
-        Ok(Torrent::from_database(&db_torrent, &torrent_files, torrent_announce_urls))
+        let torrent_web_seeds = self.get_torrent_web_seeds_from_id(db_torrent.torrent_id).await?;
+
+        Ok(Torrent::from_database(
+            &db_torrent,
+            &torrent_files,
+            torrent_announce_urls,
+            &torrent_web_seeds,
+        ))
     }
 
     /// Get `Torrent` from `torrent_id`.
@@ -217,7 +298,14 @@ pub trait Database: Sync + Send {
 
         let torrent_announce_urls = self.get_torrent_announce_urls_from_id(torrent_id).await?;
 
-        Ok(Torrent::from_database(&db_torrent, &torrent_files, torrent_announce_urls))
+        let torrent_web_seeds = self.get_torrent_web_seeds_from_id(torrent_id).await?;
+
+        Ok(Torrent::from_database(
+            &db_torrent,
+            &torrent_files,
+            torrent_announce_urls,
+            &torrent_web_seeds,
+        ))
     }
 
     /// It returns the list of all infohashes producing the same canonical
@@ -257,15 +345,36 @@ pub trait Database: Sync + Send {
     /// Get all torrent's announce urls as `Vec<Vec<String>>` from `torrent_id`.
     async fn get_torrent_announce_urls_from_id(&self, torrent_id: i64) -> Result<Vec<Vec<String>>, Error>;
 
+    /// Get all torrent's web seeds and HTTP seeds from `torrent_id`, in the
+    /// order they were added.
+    async fn get_torrent_web_seeds_from_id(&self, torrent_id: i64) -> Result<Vec<DbTorrentWebSeed>, Error>;
+
+    /// Replace the torrent's web seeds of type `seed_type` with `urls`.
+    async fn update_torrent_web_seeds(&self, torrent_id: i64, seed_type: WebSeedType, urls: &[String]) -> Result<(), Error>;
+
     /// Get `TorrentListing` from `torrent_id`.
     async fn get_torrent_listing_from_id(&self, torrent_id: i64) -> Result<TorrentListing, Error>;
 
     /// Get `TorrentListing` from `InfoHash`.
     async fn get_torrent_listing_from_info_hash(&self, info_hash: &InfoHash) -> Result<TorrentListing, Error>;
 
-    /// Get all torrents as `Vec<TorrentCompact>`.
+    /// Get all torrents, except the archived ones, as `Vec<TorrentCompact>`.
     async fn get_all_torrents_compact(&self) -> Result<Vec<TorrentCompact>, Error>;
 
+    /// Get up to `limit` torrents whose statistics from `tracker_url` were
+    /// never imported or were updated before `updated_before`.
+    ///
+    /// Torrents are sorted by priority: first the ones never imported, then the
+    /// ones uploaded after `uploaded_after`, then the ones with more peers and
+    /// finally the ones updated longer ago.
+    async fn get_torrents_compact_for_statistics_import(
+        &self,
+        tracker_url: &str,
+        updated_before: NaiveDateTime,
+        uploaded_after: NaiveDateTime,
+        limit: u32,
+    ) -> Result<Vec<TorrentCompact>, Error>;
+
     /// Update a torrent's title with `torrent_id` and `title`.
     async fn update_torrent_title(&self, torrent_id: i64, title: &str) -> Result<(), Error>;
 
@@ -302,12 +411,280 @@ pub trait Database: Sync + Send {
     /// Get tags for `torrent_id`.
     async fn get_tags_for_torrent_id(&self, torrent_id: i64) -> Result<Vec<TorrentTag>, Error>;
 
-    /// Update the seeders and leechers info for a torrent with `torrent_id`, `tracker_url`, `seeders` and `leechers`.
-    async fn update_tracker_info(&self, torrent_id: i64, tracker_url: &str, seeders: i64, leechers: i64) -> Result<(), Error>;
+    /// Update the seeders, leechers and completed downloads info for a torrent with `torrent_id`, `tracker_url`, `seeders`, `leechers` and `completed`.
+    async fn update_tracker_info(
+        &self,
+        torrent_id: i64,
+        tracker_url: &str,
+        seeders: i64,
+        leechers: i64,
+        completed: i64,
+    ) -> Result<(), Error>;
+
+    /// Get the seeders and leechers info from each tracker for a torrent with `torrent_id`.
+    async fn get_torrent_tracker_stats(&self, torrent_id: i64) -> Result<Vec<TorrentTrackerStats>, Error>;
+
+    /// Get the URLs of all the trackers with torrent stats.
+    async fn get_tracker_urls_with_stats(&self) -> Result<Vec<String>, Error>;
+
+    /// Delete the seeders and leechers info imported from `tracker_url` for all torrents.
+    async fn delete_tracker_stats_for_tracker_url(&self, tracker_url: &str) -> Result<(), Error>;
+
+    /// Add a snapshot of the current seeders and leechers of every torrent to
+    /// the statistics history. It returns the number of snapshots added.
+    async fn insert_torrent_stats_snapshots(&self, now: NaiveDateTime) -> Result<u64, Error>;
+
+    /// Get the statistics history for a torrent with `torrent_id`, oldest first.
+    async fn get_torrent_stats_history(&self, torrent_id: i64) -> Result<Vec<TorrentStatsSnapshot>, Error>;
+
+    /// Keep only the last snapshot of each day for snapshots taken before `before`.
+    async fn downsample_torrent_stats_history(&self, before: NaiveDateTime) -> Result<(), Error>;
+
+    /// Delete the snapshots taken before `before`.
+    async fn delete_torrent_stats_history_before(&self, before: NaiveDateTime) -> Result<(), Error>;
+
+    /// Set `last_seeded_at` to `now` for all the torrents with seeders.
+    async fn update_last_seeded_at_for_seeded_torrents(&self, now: NaiveDateTime) -> Result<(), Error>;
+
+    /// Get the alive torrents without seeders that were seeded for the last
+    /// time, or uploaded if they were never seeded, before `before`.
+    async fn get_torrents_to_mark_as_dead(&self, before: NaiveDateTime) -> Result<Vec<DeadTorrent>, Error>;
+
+    /// Get the dead torrents that have seeders again.
+    async fn get_revived_dead_torrents(&self) -> Result<Vec<DeadTorrent>, Error>;
+
+    /// Get the dead torrents without seeders that were marked as dead before `before`.
+    async fn get_dead_torrents_to_prune(&self, before: NaiveDateTime) -> Result<Vec<DeadTorrent>, Error>;
+
+    /// Mark a torrent with `torrent_id` as dead since `dead_since`, or as alive if `None`.
+    async fn update_torrent_dead_since(&self, torrent_id: i64, dead_since: Option<NaiveDateTime>) -> Result<(), Error>;
+
+    /// Archive a torrent with `torrent_id`. Archived torrents are not listed.
+    async fn archive_torrent(&self, torrent_id: i64, now: NaiveDateTime) -> Result<(), Error>;
+
+    /// Increase the number of times a torrent with `torrent_id` was downloaded,
+    /// and the number of times the user with `user_id` downloaded it.
+    async fn increase_torrent_downloads(&self, torrent_id: i64, user_id: Option<UserId>, now: NaiveDateTime)
+        -> Result<(), Error>;
 
     /// Delete a torrent with `torrent_id`.
     async fn delete_torrent(&self, torrent_id: i64) -> Result<(), Error>;
 
+    /// Get all the torrents uploaded by a user with `user_id`, including the
+    /// archived ones, as `Vec<TorrentCompact>`.
+    async fn get_user_torrents(&self, user_id: UserId) -> Result<Vec<TorrentCompact>, Error>;
+
+    /// Add a new job to the tracker job queue and return its `job_id`.
+    async fn insert_tracker_job(
+        &self,
+        kind: &str,
+        info_hash: &str,
+        torrent_id: Option<i64>,
+        now: NaiveDateTime,
+    ) -> Result<TrackerJobId, Error>;
+
+    /// Get up to `limit` pending tracker jobs whose next attempt is due at `now`.
+    async fn get_due_tracker_jobs(&self, now: NaiveDateTime, limit: u32) -> Result<Vec<TrackerJob>, Error>;
+
+    /// Get all tracker jobs, optionally filtered by `status`.
+    async fn get_tracker_jobs(&self, status: Option<&str>) -> Result<Vec<TrackerJob>, Error>;
+
+    /// Update a tracker job after a failed attempt.
+    async fn update_failed_tracker_job(
+        &self,
+        job_id: TrackerJobId,
+        status: &str,
+        attempts: i64,
+        last_error: &str,
+        next_attempt_at: NaiveDateTime,
+    ) -> Result<(), Error>;
+
+    /// Reset a tracker job so it's executed again at `now`.
+    async fn reset_tracker_job(&self, job_id: TrackerJobId, now: NaiveDateTime) -> Result<(), Error>;
+
+    /// Delete a tracker job with `job_id`.
+    async fn delete_tracker_job(&self, job_id: TrackerJobId) -> Result<(), Error>;
+
+    /// Delete all tracker jobs of a given `kind` for an `info_hash`.
+    async fn delete_tracker_jobs_for_info_hash(&self, info_hash: &str, kind: &str) -> Result<(), Error>;
+
+    /// Add a new email to the mail queue and return its `mail_id`.
+    async fn insert_mail(&self, mail: &NewMail<'_>, now: NaiveDateTime) -> Result<MailId, Error>;
+
+    /// Get up to `limit` pending emails whose next attempt is due at `now`.
+    async fn get_due_mails(&self, now: NaiveDateTime, limit: u32) -> Result<Vec<QueuedMail>, Error>;
+
+    /// Get all queued emails, optionally filtered by `status`.
+    async fn get_mails(&self, status: Option<&str>) -> Result<Vec<QueuedMail>, Error>;
+
+    /// Update a queued email after a failed delivery attempt.
+    async fn update_failed_mail(
+        &self,
+        mail_id: MailId,
+        status: &str,
+        attempts: i64,
+        last_error: &str,
+        next_attempt_at: NaiveDateTime,
+    ) -> Result<(), Error>;
+
+    /// Reset a queued email so it's delivered again at `now`.
+    async fn reset_mail(&self, mail_id: MailId, now: NaiveDateTime) -> Result<(), Error>;
+
+    /// Mark a queued email as sent at `now`, so it's not delivered again.
+    async fn mark_mail_as_sent(&self, mail_id: MailId, now: NaiveDateTime) -> Result<(), Error>;
+
+    /// Delete a queued email with `mail_id`.
+    async fn delete_mail(&self, mail_id: MailId) -> Result<(), Error>;
+
+    /// Delete all queued emails of a given `kind` for a user.
+    async fn delete_mails_for_user(&self, user_id: UserId, kind: &str) -> Result<(), Error>;
+
+    /// Delete the sent and failed emails last updated before `before`. It
+    /// returns the number of deleted emails.
+    async fn delete_finished_mails_before(&self, before: NaiveDateTime) -> Result<u64, Error>;
+
+    /// Add a new notification for a user and return its `notification_id`.
+    ///
+    /// Notifications with `in_app` set to `false` are not listed in the app.
+    /// They are only kept until they are sent in the email digest.
+    async fn insert_notification(
+        &self,
+        user_id: UserId,
+        notification: &NewNotification,
+        in_app: bool,
+        digest_pending: bool,
+        now: NaiveDateTime,
+    ) -> Result<NotificationId, Error>;
+
+    /// Get the latest `limit` notifications listed in the app for a user,
+    /// newest first.
+    async fn get_notifications(&self, user_id: UserId, unread_only: bool, limit: u32) -> Result<Vec<Notification>, Error>;
+
+    /// Count the unread notifications listed in the app for a user.
+    async fn count_unread_notifications(&self, user_id: UserId) -> Result<i64, Error>;
+
+    /// Mark a notification of a user as read.
+    async fn mark_notification_as_read(&self, user_id: UserId, notification_id: NotificationId) -> Result<(), Error>;
+
+    /// Mark all the notifications of a user as read. It returns the number of
+    /// notifications marked.
+    async fn mark_all_notifications_as_read(&self, user_id: UserId) -> Result<u64, Error>;
+
+    /// Get the notification preferences of a user, or `None` if the user
+    /// never changed the default ones.
+    async fn get_notification_preferences(&self, user_id: UserId) -> Result<Option<NotificationPreferences>, Error>;
+
+    /// Insert or update the notification preferences of a user.
+    async fn update_notification_preferences(&self, user_id: UserId, preferences: &NotificationPreferences) -> Result<(), Error>;
+
+    /// Get the users with notifications waiting to be sent in the email
+    /// digest.
+    async fn get_users_with_pending_digest(&self) -> Result<Vec<UserId>, Error>;
+
+    /// Get the notifications of a user waiting to be sent in the email
+    /// digest, oldest first.
+    async fn get_pending_digest_notifications(&self, user_id: UserId) -> Result<Vec<Notification>, Error>;
+
+    /// Mark the notifications of a user up to `notification_id` as sent in
+    /// the email digest. The ones that are not listed in the app are deleted.
+    async fn clear_pending_digest(&self, user_id: UserId, notification_id: NotificationId) -> Result<(), Error>;
+
+    /// Delete the notifications created before `before`, except the ones
+    /// waiting to be sent in the email digest.
+    async fn delete_notifications_before(&self, before: NaiveDateTime) -> Result<u64, Error>;
+
+    /// Update the `has_seeders` flag of the not archived torrents. It returns
+    /// the torrents that had seeders on the previous update but not anymore.
+    async fn update_torrents_has_seeders(&self) -> Result<Vec<UploadedTorrent>, Error>;
+
+    /// Get the torrents in a category.
+    async fn get_uploaded_torrents_in_category(&self, category_name: &str) -> Result<Vec<UploadedTorrent>, Error>;
+
+    /// Get up to `limit` not archived torrents with an id greater than `torrent_id`, ordered by id.
+    async fn get_torrents_compact_after(&self, torrent_id: i64, limit: u32) -> Result<Vec<TorrentCompact>, Error>;
+
+    /// Get the ids of the users with tracker keys, including the expired ones.
+    async fn get_users_with_tracker_keys(&self) -> Result<Vec<UserId>, Error>;
+
+    /// Add a new tracker mode sync and return its `sync_id`.
+    async fn insert_tracker_mode_sync(
+        &self,
+        from_mode: Option<&str>,
+        to_mode: &str,
+        action: &str,
+        torrents_total: i64,
+        now: NaiveDateTime,
+    ) -> Result<TrackerModeSyncId, Error>;
+
+    /// Get the last tracker mode sync.
+    async fn get_last_tracker_mode_sync(&self) -> Result<Option<TrackerModeSync>, Error>;
+
+    /// Get the last completed tracker mode sync.
+    async fn get_last_completed_tracker_mode_sync(&self) -> Result<Option<TrackerModeSync>, Error>;
+
+    /// Update the progress of a tracker mode sync.
+    #[allow(clippy::too_many_arguments)]
+    async fn update_tracker_mode_sync_progress(
+        &self,
+        sync_id: TrackerModeSyncId,
+        last_torrent_id: i64,
+        torrents_processed: i64,
+        errors: i64,
+        last_error: Option<&str>,
+        now: NaiveDateTime,
+    ) -> Result<(), Error>;
+
+    /// Mark a tracker mode sync as completed.
+    async fn complete_tracker_mode_sync(&self, sync_id: TrackerModeSyncId, now: NaiveDateTime) -> Result<(), Error>;
+
+    /// Add a new uploaded image and return its `image_id`, unless the images
+    /// uploaded by the user would take more than `user_quota` bytes.
+    #[allow(clippy::too_many_arguments)]
+    async fn insert_image(
+        &self,
+        user_id: UserId,
+        content_hash: &str,
+        content_type: &str,
+        size: i64,
+        width: i64,
+        height: i64,
+        user_quota: i64,
+        now: NaiveDateTime,
+    ) -> Result<ImageId, Error>;
+
+    /// Get an uploaded image from its `image_id`.
+    async fn get_image_from_id(&self, image_id: ImageId) -> Result<Image, Error>;
+
+    /// Get all the images uploaded by a user.
+    async fn get_user_images(&self, user_id: UserId) -> Result<Vec<Image>, Error>;
+
+    /// Delete an uploaded image with `image_id` and return how many uploaded
+    /// images still have the same `content_hash`, that is, use the same image
+    /// file.
+    async fn delete_image(&self, image_id: ImageId) -> Result<i64, Error>;
+
+    /// Get the images attached to a torrent, in display order.
+    async fn get_torrent_images_from_id(&self, torrent_id: i64) -> Result<Vec<DbTorrentImage>, Error>;
+
+    /// Replace the poster and the screenshots of a torrent.
+    async fn update_torrent_images(&self, torrent_id: i64, poster: Option<ImageId>, screenshots: &[ImageId])
+        -> Result<(), Error>;
+
+    /// Set the `avatar` of a user profile, or remove it if `None`.
+    async fn update_user_avatar(&self, user_id: UserId, avatar: Option<&str>) -> Result<(), Error>;
+
+    /// Set the preferred `locale` of a user profile, or remove it if `None`.
+    async fn update_user_locale(&self, user_id: UserId, locale: Option<&str>) -> Result<(), Error>;
+
+    /// Remove the `avatar` from all the user profiles using it.
+    async fn delete_avatar(&self, avatar: &str) -> Result<(), Error>;
+
+    /// Get the image cache proxy quota usage of all the users.
+    async fn get_image_cache_quotas(&self) -> Result<Vec<DbImageCacheQuota>, Error>;
+
+    /// Insert or update the image cache proxy quota usage of a user.
+    async fn update_image_cache_quota(&self, quota: &DbImageCacheQuota) -> Result<(), Error>;
+
     /// DELETES ALL DATABASE ROWS, ONLY CALL THIS IF YOU KNOW WHAT YOU'RE DOING!
     async fn delete_all_database_rows(&self) -> Result<(), Error>;
 }
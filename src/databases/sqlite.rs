@@ -2,7 +2,7 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use async_trait::async_trait;
-use chrono::NaiveDateTime;
+use chrono::{NaiveDateTime, Utc};
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use sqlx::{query, query_as, Acquire, ConnectOptions, SqlitePool};
 
@@ -10,17 +10,57 @@ use super::database::TABLES_TO_TRUNCATE;
 use crate::databases::database;
 use crate::databases::database::{Category, Database, Driver, Sorting, TorrentCompact};
 use crate::models::category::CategoryId;
+use crate::models::image::{DbTorrentImage, Image, ImageId, TorrentImageType};
+use crate::models::image_cache_quota::DbImageCacheQuota;
 use crate::models::info_hash::InfoHash;
+use crate::models::notification::{NewNotification, Notification, NotificationId, NotificationPreferences, UploadedTorrent};
+use crate::models::queued_mail::{MailId, NewMail, QueuedMail, Status as MailStatus};
 use crate::models::response::TorrentsResponse;
-use crate::models::torrent::{Metadata, TorrentListing};
-use crate::models::torrent_file::{DbTorrent, DbTorrentAnnounceUrl, DbTorrentFile, Torrent, TorrentFile};
+use crate::models::torrent::{DeadTorrent, Metadata, TorrentListing, TorrentStatsSnapshot, TorrentTrackerStats};
+use crate::models::torrent_file::{
+    DbTorrent, DbTorrentAnnounceUrl, DbTorrentFile, DbTorrentWebSeed, Torrent, TorrentFile, WebSeedType,
+};
 use crate::models::torrent_tag::{TagId, TorrentTag};
+use crate::models::tracker_job::{Status, TrackerJob, TrackerJobId};
 use crate::models::tracker_key::TrackerKey;
+use crate::models::tracker_mode_sync::{Status as TrackerModeSyncStatus, TrackerModeSync, TrackerModeSyncId};
 use crate::models::user::{User, UserAuthentication, UserCompact, UserId, UserProfile};
 use crate::services::torrent::{CanonicalInfoHashGroup, DbTorrentInfoHash};
 use crate::utils::clock;
 use crate::utils::hex::from_bytes;
 
+/// Columns selected when loading a [`TrackerModeSync`].
+const TRACKER_MODE_SYNC_COLUMNS: &str = "sync_id, from_mode, to_mode, action, status, last_torrent_id, torrents_total, torrents_processed, errors, last_error, started_at, updated_at, finished_at";
+
+/// Columns selected when loading a [`TrackerJob`].
+const TRACKER_JOB_COLUMNS: &str =
+    "job_id, kind, info_hash, torrent_id, status, attempts, last_error, next_attempt_at, created_at, updated_at";
+
+/// Columns selected when loading a [`QueuedMail`].
+const QUEUED_MAIL_COLUMNS: &str = "mail_id, kind, user_id, sender, recipient, subject, message, status, attempts, last_error, next_attempt_at, created_at, updated_at";
+
+/// Columns selected when loading an [`Image`] from the `torrust_images i` table.
+const IMAGE_COLUMNS: &str = "i.image_id, i.user_id, i.content_hash, i.content_type, i.size, i.width, i.height, i.date_uploaded";
+
+/// Columns selected when loading a [`Notification`].
+const NOTIFICATION_COLUMNS: &str = "notification_id, kind, torrent_id, info_hash, torrent_title, detail, is_read, created_at";
+
+/// Columns and joins selected when loading an [`UploadedTorrent`].
+const UPLOADED_TORRENT_SELECT: &str = "SELECT tt.torrent_id, tt.info_hash, ti.title, tt.uploader_id
+    FROM torrust_torrents tt
+    INNER JOIN torrust_torrent_info ti ON tt.torrent_id = ti.torrent_id";
+
+/// Columns and joins selected when loading a [`DeadTorrent`].
+const DEAD_TORRENT_SELECT: &str =
+    "SELECT tt.torrent_id, tt.info_hash, ti.title, tp.username AS uploader, tp.email AS uploader_email, tp.email_verified AS uploader_email_verified, tp.locale AS uploader_locale, tt.dead_since
+    FROM torrust_torrents tt
+    INNER JOIN torrust_torrent_info ti ON tt.torrent_id = ti.torrent_id
+    INNER JOIN torrust_user_profiles tp ON tt.uploader_id = tp.user_id";
+
+/// Condition for torrents with seeders in at least one tracker.
+const IS_SEEDED: &str =
+    "EXISTS (SELECT 1 FROM torrust_torrent_tracker_stats ts WHERE ts.torrent_id = tt.torrent_id AND ts.seeders > 0)";
+
 pub struct Sqlite {
     pub pool: SqlitePool,
 }
@@ -136,6 +176,14 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::UserNotFound)
     }
 
+    async fn get_user_profile_from_id(&self, user_id: UserId) -> Result<UserProfile, database::Error> {
+        query_as::<_, UserProfile>("SELECT * FROM torrust_user_profiles WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|_| database::Error::UserNotFound)
+    }
+
     async fn get_user_compact_from_id(&self, user_id: i64) -> Result<UserCompact, database::Error> {
         query_as::<_, UserCompact>("SELECT tu.user_id, tp.username, tu.administrator FROM torrust_users tu INNER JOIN torrust_user_profiles tp ON tu.user_id = tp.user_id WHERE tu.user_id = ?")
             .bind(user_id)
@@ -196,12 +244,99 @@ impl Database for Sqlite {
             })
     }
 
-    async fn verify_email(&self, user_id: i64) -> Result<(), database::Error> {
+    async fn add_email_verification_token(
+        &self,
+        user_id: UserId,
+        token_hash: &str,
+        expires_at: NaiveDateTime,
+        now: NaiveDateTime,
+    ) -> Result<(), database::Error> {
+        let mut conn = self.pool.acquire().await.map_err(|_| database::Error::Error)?;
+
+        let mut tx = conn.begin().await.map_err(|_| database::Error::Error)?;
+
+        // Only the last link sent to the user is valid
+        query("DELETE FROM torrust_email_verification_tokens WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        query("INSERT INTO torrust_email_verification_tokens (token_hash, user_id, expires_at, created_at) VALUES (?, ?, ?, ?)")
+            .bind(token_hash)
+            .bind(user_id)
+            .bind(expires_at.format("%Y-%m-%d %H:%M:%S").to_string())
+            .bind(now.format("%Y-%m-%d %H:%M:%S").to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        tx.commit().await.map_err(|_| database::Error::Error)
+    }
+
+    async fn email_verification_token_added_after(&self, user_id: UserId, since: NaiveDateTime) -> Result<bool, database::Error> {
+        query_as::<_, (i64,)>("SELECT COUNT(*) FROM torrust_email_verification_tokens WHERE user_id = ? AND created_at > ?")
+            .bind(user_id)
+            .bind(since.format("%Y-%m-%d %H:%M:%S").to_string())
+            .fetch_one(&self.pool)
+            .await
+            .map(|(count,)| count > 0)
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn verify_email_with_token(&self, token_hash: &str, now: NaiveDateTime) -> Result<UserId, database::Error> {
+        let mut conn = self.pool.acquire().await.map_err(|_| database::Error::Error)?;
+
+        let mut tx = conn.begin().await.map_err(|_| database::Error::Error)?;
+
+        let (user_id,): (UserId,) =
+            query_as("SELECT user_id FROM torrust_email_verification_tokens WHERE token_hash = ? AND expires_at > ?")
+                .bind(token_hash)
+                .bind(now.format("%Y-%m-%d %H:%M:%S").to_string())
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|_| database::Error::Error)?
+                .ok_or(database::Error::EmailVerificationTokenNotFound)?;
+
+        let deleted = query("DELETE FROM torrust_email_verification_tokens WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        // Another request used the token in the meantime
+        if deleted.rows_affected() == 0 {
+            return Err(database::Error::EmailVerificationTokenNotFound);
+        }
+
         query("UPDATE torrust_user_profiles SET email_verified = TRUE WHERE user_id = ?")
             .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        tx.commit().await.map_err(|_| database::Error::Error)?;
+
+        Ok(user_id)
+    }
+
+    async fn get_unverified_users(&self, registered_before: NaiveDateTime) -> Result<Vec<UserId>, database::Error> {
+        query_as::<_, (UserId,)>(
+            "SELECT user_id FROM torrust_users WHERE administrator = FALSE AND date_registered < ? AND user_id IN (SELECT user_id FROM torrust_user_profiles WHERE email_verified = FALSE) AND user_id IN (SELECT user_id FROM torrust_email_verification_tokens)",
+        )
+        .bind(registered_before.format("%Y-%m-%d %H:%M:%S").to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map(|v| v.into_iter().map(|(user_id,)| user_id).collect())
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn delete_expired_email_verification_tokens(&self, now: NaiveDateTime) -> Result<u64, database::Error> {
+        query("DELETE FROM torrust_email_verification_tokens WHERE expires_at <= ?")
+            .bind(now.format("%Y-%m-%d %H:%M:%S").to_string())
             .execute(&self.pool)
             .await
-            .map(|_| ())
+            .map(|v| v.rows_affected())
             .map_err(|_| database::Error::Error)
     }
 
@@ -218,6 +353,29 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::Error)
     }
 
+    async fn get_user_tracker_keys(&self, user_id: i64) -> Result<Vec<TrackerKey>, database::Error> {
+        // casting current_time() to i64 will overflow in the year 2262
+        let current_time = i64::try_from(clock::now()).unwrap();
+
+        query_as::<_, TrackerKey>(
+            "SELECT tracker_key AS key, date_expiry AS valid_until FROM torrust_tracker_keys WHERE user_id = ? AND date_expiry > ? ORDER BY date_expiry DESC",
+        )
+        .bind(user_id)
+        .bind(current_time)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn delete_user_tracker_keys(&self, user_id: i64) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_tracker_keys WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
     async fn delete_user(&self, user_id: i64) -> Result<(), database::Error> {
         query("DELETE FROM torrust_users WHERE user_id = ?")
             .bind(user_id)
@@ -282,6 +440,7 @@ impl Database for Sqlite {
 
     // todo: refactor this
     #[allow(clippy::too_many_lines)]
+    #[allow(clippy::too_many_arguments)]
     async fn get_torrents_search_sorted_paginated(
         &self,
         search: &Option<String>,
@@ -290,6 +449,7 @@ impl Database for Sqlite {
         sort: &Sorting,
         offset: u64,
         limit: u8,
+        include_dead: bool,
     ) -> Result<TorrentsResponse, database::Error> {
         let title = match search {
             None => "%".to_string(),
@@ -301,12 +461,20 @@ impl Database for Sqlite {
             Sorting::UploadedDesc => "date_uploaded DESC".to_string(),
             Sorting::SeedersAsc => "seeders ASC".to_string(),
             Sorting::SeedersDesc => "seeders DESC".to_string(),
+            Sorting::TotalSeedersAsc => "total_seeders ASC".to_string(),
+            Sorting::TotalSeedersDesc => "total_seeders DESC".to_string(),
+            Sorting::TrendingAsc => format!("{} ASC", seeders_growth_expression()),
+            Sorting::TrendingDesc => format!("{} DESC", seeders_growth_expression()),
             Sorting::LeechersAsc => "leechers ASC".to_string(),
             Sorting::LeechersDesc => "leechers DESC".to_string(),
             Sorting::NameAsc => "title ASC".to_string(),
             Sorting::NameDesc => "title DESC".to_string(),
             Sorting::SizeAsc => "size ASC".to_string(),
             Sorting::SizeDesc => "size DESC".to_string(),
+            Sorting::TimesDownloadedAsc => "times_downloaded ASC".to_string(),
+            Sorting::TimesDownloadedDesc => "times_downloaded DESC".to_string(),
+            Sorting::TimesCompletedAsc => "times_completed ASC".to_string(),
+            Sorting::TimesCompletedDesc => "times_completed DESC".to_string(),
         };
 
         let category_filter_query = if let Some(c) = categories {
@@ -355,6 +523,8 @@ impl Database for Sqlite {
             String::new()
         };
 
+        let dead_filter_query = if include_dead { "" } else { "AND tt.dead_since IS NULL" };
+
         let mut query_string = format!(
             "SELECT
             tt.torrent_id,
@@ -370,15 +540,20 @@ impl Database for Sqlite {
             tt.creation_date,
             tt.created_by,
             tt.`encoding`,
-            CAST(COALESCE(sum(ts.seeders),0) as signed) as seeders,
-            CAST(COALESCE(sum(ts.leechers),0) as signed) as leechers
+            tt.dead_since,
+            CAST(COALESCE(max(ts.seeders),0) as signed) as seeders,
+            CAST(COALESCE(max(ts.leechers),0) as signed) as leechers,
+            CAST(COALESCE(sum(ts.seeders),0) as signed) as total_seeders,
+            CAST(COALESCE(sum(ts.leechers),0) as signed) as total_leechers,
+            tt.times_downloaded,
+            CAST(COALESCE(max(ts.completed),0) as signed) as times_completed
             FROM torrust_torrents tt
             {category_filter_query}
             {tag_filter_query}
             INNER JOIN torrust_user_profiles tp ON tt.uploader_id = tp.user_id
             INNER JOIN torrust_torrent_info ti ON tt.torrent_id = ti.torrent_id
             LEFT JOIN torrust_torrent_tracker_stats ts ON tt.torrent_id = ts.torrent_id
-            WHERE title LIKE ?
+            WHERE title LIKE ? AND tt.archived_at IS NULL {dead_filter_query}
             GROUP BY tt.torrent_id"
         );
 
@@ -572,6 +747,30 @@ impl Database for Sqlite {
             return Err(e);
         }
 
+        // Insert web seeds
+
+        for (seed_type, urls) in [
+            (WebSeedType::UrlList, &torrent.url_list),
+            (WebSeedType::HttpSeed, &torrent.httpseeds),
+        ] {
+            for url in urls.iter().flatten() {
+                let insert_torrent_web_seed_result =
+                    query("INSERT INTO torrust_torrent_web_seeds (torrent_id, seed_type, url) VALUES (?, ?, ?)")
+                        .bind(torrent_id)
+                        .bind(seed_type.as_str())
+                        .bind(url)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|_| database::Error::Error);
+
+                // rollback transaction on error
+                if let Err(e) = insert_torrent_web_seed_result {
+                    drop(tx.rollback().await);
+                    return Err(e);
+                }
+            }
+        }
+
         // Insert tags
 
         for tag_id in &metadata.tags {
@@ -730,6 +929,44 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::TorrentNotFound)
     }
 
+    async fn get_torrent_web_seeds_from_id(&self, torrent_id: i64) -> Result<Vec<DbTorrentWebSeed>, database::Error> {
+        query_as::<_, DbTorrentWebSeed>(
+            "SELECT seed_type, url FROM torrust_torrent_web_seeds WHERE torrent_id = ? ORDER BY web_seed_id ASC",
+        )
+        .bind(torrent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn update_torrent_web_seeds(
+        &self,
+        torrent_id: i64,
+        seed_type: WebSeedType,
+        urls: &[String],
+    ) -> Result<(), database::Error> {
+        let mut tx = self.pool.begin().await.map_err(|_| database::Error::Error)?;
+
+        query("DELETE FROM torrust_torrent_web_seeds WHERE torrent_id = ? AND seed_type = ?")
+            .bind(torrent_id)
+            .bind(seed_type.as_str())
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        for url in urls {
+            query("INSERT INTO torrust_torrent_web_seeds (torrent_id, seed_type, url) VALUES (?, ?, ?)")
+                .bind(torrent_id)
+                .bind(seed_type.as_str())
+                .bind(url)
+                .execute(&mut *tx)
+                .await
+                .map_err(|_| database::Error::Error)?;
+        }
+
+        tx.commit().await.map_err(|_| database::Error::Error)
+    }
+
     async fn get_torrent_listing_from_id(&self, torrent_id: i64) -> Result<TorrentListing, database::Error> {
         query_as::<_, TorrentListing>(
             "SELECT 
@@ -745,8 +982,13 @@ impl Database for Sqlite {
             tt.creation_date,
             tt.created_by,
             tt.`encoding`,
-            CAST(COALESCE(sum(ts.seeders),0) as signed) as seeders,
-            CAST(COALESCE(sum(ts.leechers),0) as signed) as leechers
+            tt.dead_since,
+            CAST(COALESCE(max(ts.seeders),0) as signed) as seeders,
+            CAST(COALESCE(max(ts.leechers),0) as signed) as leechers,
+            CAST(COALESCE(sum(ts.seeders),0) as signed) as total_seeders,
+            CAST(COALESCE(sum(ts.leechers),0) as signed) as total_leechers,
+            tt.times_downloaded,
+            CAST(COALESCE(max(ts.completed),0) as signed) as times_completed
             FROM torrust_torrents tt
             INNER JOIN torrust_user_profiles tp ON tt.uploader_id = tp.user_id
             INNER JOIN torrust_torrent_info ti ON tt.torrent_id = ti.torrent_id
@@ -775,8 +1017,13 @@ impl Database for Sqlite {
             tt.creation_date,
             tt.created_by,
             tt.`encoding`,
-            CAST(COALESCE(sum(ts.seeders),0) as signed) as seeders,
-            CAST(COALESCE(sum(ts.leechers),0) as signed) as leechers
+            tt.dead_since,
+            CAST(COALESCE(max(ts.seeders),0) as signed) as seeders,
+            CAST(COALESCE(max(ts.leechers),0) as signed) as leechers,
+            CAST(COALESCE(sum(ts.seeders),0) as signed) as total_seeders,
+            CAST(COALESCE(sum(ts.leechers),0) as signed) as total_leechers,
+            tt.times_downloaded,
+            CAST(COALESCE(max(ts.completed),0) as signed) as times_completed
             FROM torrust_torrents tt
             INNER JOIN torrust_user_profiles tp ON tt.uploader_id = tp.user_id
             INNER JOIN torrust_torrent_info ti ON tt.torrent_id = ti.torrent_id
@@ -791,12 +1038,43 @@ impl Database for Sqlite {
     }
 
     async fn get_all_torrents_compact(&self) -> Result<Vec<TorrentCompact>, database::Error> {
-        query_as::<_, TorrentCompact>("SELECT torrent_id, info_hash FROM torrust_torrents")
+        query_as::<_, TorrentCompact>("SELECT torrent_id, info_hash FROM torrust_torrents WHERE archived_at IS NULL")
             .fetch_all(&self.pool)
             .await
             .map_err(|_| database::Error::Error)
     }
 
+    async fn get_torrents_compact_for_statistics_import(
+        &self,
+        tracker_url: &str,
+        updated_before: NaiveDateTime,
+        uploaded_after: NaiveDateTime,
+        limit: u32,
+    ) -> Result<Vec<TorrentCompact>, database::Error> {
+        let updated_before = updated_before.format("%Y-%m-%d %H:%M:%S").to_string();
+        let uploaded_after = uploaded_after.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query_as::<_, TorrentCompact>(
+            "SELECT tt.torrent_id, tt.info_hash
+            FROM torrust_torrents tt
+            LEFT JOIN torrust_torrent_tracker_stats ts ON tt.torrent_id = ts.torrent_id AND ts.tracker_url = ?
+            WHERE tt.archived_at IS NULL AND (ts.updated_at IS NULL OR ts.updated_at < ?)
+            ORDER BY
+                ts.updated_at IS NULL DESC,
+                tt.date_uploaded >= ? DESC,
+                COALESCE(ts.seeders, 0) + COALESCE(ts.leechers, 0) DESC,
+                ts.updated_at ASC
+            LIMIT ?",
+        )
+        .bind(tracker_url)
+        .bind(updated_before)
+        .bind(uploaded_after)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
     async fn update_torrent_title(&self, torrent_id: i64, title: &str) -> Result<(), database::Error> {
         query("UPDATE torrust_torrent_info SET title = $1 WHERE torrent_id = $2")
             .bind(title)
@@ -967,18 +1245,226 @@ impl Database for Sqlite {
         tracker_url: &str,
         seeders: i64,
         leechers: i64,
+        completed: i64,
     ) -> Result<(), database::Error> {
-        query("REPLACE INTO torrust_torrent_tracker_stats (torrent_id, tracker_url, seeders, leechers) VALUES ($1, $2, $3, $4)")
+        query(
+            "REPLACE INTO torrust_torrent_tracker_stats (torrent_id, tracker_url, seeders, leechers, completed, updated_at) VALUES (?, ?, ?, ?, ?, DATETIME('now'))",
+        )
             .bind(torrent_id)
             .bind(tracker_url)
             .bind(seeders)
             .bind(leechers)
+            .bind(completed)
             .execute(&self.pool)
             .await
             .map(|_| ())
             .map_err(|_| database::Error::TorrentNotFound)
     }
 
+    async fn get_torrent_tracker_stats(&self, torrent_id: i64) -> Result<Vec<TorrentTrackerStats>, database::Error> {
+        query_as::<_, TorrentTrackerStats>(
+            "SELECT tracker_url, seeders, leechers, completed FROM torrust_torrent_tracker_stats WHERE torrent_id = ? ORDER BY tracker_url ASC",
+        )
+        .bind(torrent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_tracker_urls_with_stats(&self) -> Result<Vec<String>, database::Error> {
+        query_as::<_, (String,)>("SELECT DISTINCT tracker_url FROM torrust_torrent_tracker_stats")
+            .fetch_all(&self.pool)
+            .await
+            .map(|v| v.into_iter().map(|(tracker_url,)| tracker_url).collect())
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn delete_tracker_stats_for_tracker_url(&self, tracker_url: &str) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_torrent_tracker_stats WHERE tracker_url = ?")
+            .bind(tracker_url)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn insert_torrent_stats_snapshots(&self, now: NaiveDateTime) -> Result<u64, database::Error> {
+        let now = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query(
+            "INSERT INTO torrust_torrent_tracker_stats_history (torrent_id, seeders, leechers, created_at)
+            SELECT torrent_id, MAX(seeders), MAX(leechers), ? FROM torrust_torrent_tracker_stats GROUP BY torrent_id",
+        )
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map(|v| v.rows_affected())
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_torrent_stats_history(&self, torrent_id: i64) -> Result<Vec<TorrentStatsSnapshot>, database::Error> {
+        query_as::<_, TorrentStatsSnapshot>(
+            "SELECT seeders, leechers, created_at FROM torrust_torrent_tracker_stats_history WHERE torrent_id = ? ORDER BY created_at ASC, snapshot_id ASC",
+        )
+        .bind(torrent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn downsample_torrent_stats_history(&self, before: NaiveDateTime) -> Result<(), database::Error> {
+        let before = before.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query(
+            "DELETE FROM torrust_torrent_tracker_stats_history
+            WHERE created_at < ?
+            AND EXISTS (
+                SELECT 1 FROM torrust_torrent_tracker_stats_history newer
+                WHERE newer.torrent_id = torrust_torrent_tracker_stats_history.torrent_id
+                AND DATE(newer.created_at) = DATE(torrust_torrent_tracker_stats_history.created_at)
+                AND newer.snapshot_id > torrust_torrent_tracker_stats_history.snapshot_id
+            )",
+        )
+        .bind(before)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn delete_torrent_stats_history_before(&self, before: NaiveDateTime) -> Result<(), database::Error> {
+        let before = before.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query("DELETE FROM torrust_torrent_tracker_stats_history WHERE created_at < ?")
+            .bind(before)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn update_last_seeded_at_for_seeded_torrents(&self, now: NaiveDateTime) -> Result<(), database::Error> {
+        let now = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query(&format!(
+            "UPDATE torrust_torrents AS tt SET last_seeded_at = ? WHERE {IS_SEEDED}"
+        ))
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_torrents_to_mark_as_dead(&self, before: NaiveDateTime) -> Result<Vec<DeadTorrent>, database::Error> {
+        let before = before.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query_as::<_, DeadTorrent>(&format!(
+            "{DEAD_TORRENT_SELECT}
+            WHERE tt.dead_since IS NULL AND tt.archived_at IS NULL AND NOT {IS_SEEDED}
+            AND COALESCE(tt.last_seeded_at, tt.date_uploaded) < ?
+            ORDER BY tt.torrent_id ASC"
+        ))
+        .bind(before)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_revived_dead_torrents(&self) -> Result<Vec<DeadTorrent>, database::Error> {
+        query_as::<_, DeadTorrent>(&format!(
+            "{DEAD_TORRENT_SELECT}
+            WHERE tt.dead_since IS NOT NULL AND tt.archived_at IS NULL AND {IS_SEEDED}
+            ORDER BY tt.torrent_id ASC"
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_dead_torrents_to_prune(&self, before: NaiveDateTime) -> Result<Vec<DeadTorrent>, database::Error> {
+        let before = before.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query_as::<_, DeadTorrent>(&format!(
+            "{DEAD_TORRENT_SELECT}
+            WHERE tt.dead_since < ? AND tt.archived_at IS NULL AND NOT {IS_SEEDED}
+            ORDER BY tt.torrent_id ASC"
+        ))
+        .bind(before)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn update_torrent_dead_since(&self, torrent_id: i64, dead_since: Option<NaiveDateTime>) -> Result<(), database::Error> {
+        let dead_since = dead_since.map(|dead_since| dead_since.format("%Y-%m-%d %H:%M:%S").to_string());
+
+        query("UPDATE torrust_torrents SET dead_since = ? WHERE torrent_id = ?")
+            .bind(dead_since)
+            .bind(torrent_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::TorrentNotFound)
+                }
+            })
+    }
+
+    async fn archive_torrent(&self, torrent_id: i64, now: NaiveDateTime) -> Result<(), database::Error> {
+        let now = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query("UPDATE torrust_torrents SET archived_at = ? WHERE torrent_id = ?")
+            .bind(now)
+            .bind(torrent_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::TorrentNotFound)
+                }
+            })
+    }
+
+    async fn increase_torrent_downloads(
+        &self,
+        torrent_id: i64,
+        user_id: Option<UserId>,
+        now: NaiveDateTime,
+    ) -> Result<(), database::Error> {
+        let mut tx = self.pool.begin().await.map_err(|_| database::Error::Error)?;
+
+        let result = query("UPDATE torrust_torrents SET times_downloaded = times_downloaded + 1 WHERE torrent_id = ?")
+            .bind(torrent_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(database::Error::TorrentNotFound);
+        }
+
+        if let Some(user_id) = user_id {
+            query(
+                "INSERT INTO torrust_user_torrent_downloads (user_id, torrent_id, times_downloaded, last_downloaded_at) VALUES (?, ?, 1, ?) ON CONFLICT (user_id, torrent_id) DO UPDATE SET times_downloaded = times_downloaded + 1, last_downloaded_at = excluded.last_downloaded_at",
+            )
+            .bind(user_id)
+            .bind(torrent_id)
+            .bind(now.format("%Y-%m-%d %H:%M:%S").to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+        }
+
+        tx.commit().await.map_err(|_| database::Error::Error)
+    }
+
     async fn delete_torrent(&self, torrent_id: i64) -> Result<(), database::Error> {
         query("DELETE FROM torrust_torrents WHERE torrent_id = ?")
             .bind(torrent_id)
@@ -994,14 +1480,805 @@ impl Database for Sqlite {
             })
     }
 
-    async fn delete_all_database_rows(&self) -> Result<(), database::Error> {
-        for table in TABLES_TO_TRUNCATE {
-            query(&format!("DELETE FROM {table};"))
-                .execute(&self.pool)
-                .await
-                .map_err(|_| database::Error::Error)?;
-        }
+    async fn get_user_torrents(&self, user_id: UserId) -> Result<Vec<TorrentCompact>, database::Error> {
+        query_as::<_, TorrentCompact>("SELECT torrent_id, info_hash FROM torrust_torrents WHERE uploader_id = ?")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+    }
 
-        Ok(())
+    async fn insert_tracker_job(
+        &self,
+        kind: &str,
+        info_hash: &str,
+        torrent_id: Option<i64>,
+        now: NaiveDateTime,
+    ) -> Result<TrackerJobId, database::Error> {
+        // date needs to be in ISO 8601 format
+        let now_string = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query(
+            "INSERT INTO torrust_tracker_jobs (kind, info_hash, torrent_id, status, attempts, next_attempt_at, created_at, updated_at) VALUES (?, ?, ?, ?, 0, ?, ?, ?)",
+        )
+        .bind(kind)
+        .bind(info_hash)
+        .bind(torrent_id)
+        .bind(Status::Pending.as_str())
+        .bind(&now_string)
+        .bind(&now_string)
+        .bind(&now_string)
+        .execute(&self.pool)
+        .await
+        .map(|v| v.last_insert_rowid())
+        .map_err(|_| database::Error::Error)
     }
+
+    async fn get_due_tracker_jobs(&self, now: NaiveDateTime, limit: u32) -> Result<Vec<TrackerJob>, database::Error> {
+        let now_string = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query_as::<_, TrackerJob>(&format!(
+            "SELECT {TRACKER_JOB_COLUMNS} FROM torrust_tracker_jobs WHERE status = ? AND next_attempt_at <= ? ORDER BY next_attempt_at ASC, job_id ASC LIMIT ?"
+        ))
+        .bind(Status::Pending.as_str())
+        .bind(now_string)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_tracker_jobs(&self, status: Option<&str>) -> Result<Vec<TrackerJob>, database::Error> {
+        match status {
+            Some(status) => query_as::<_, TrackerJob>(&format!(
+                "SELECT {TRACKER_JOB_COLUMNS} FROM torrust_tracker_jobs WHERE status = ? ORDER BY job_id ASC"
+            ))
+            .bind(status)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error),
+            None => query_as::<_, TrackerJob>(&format!(
+                "SELECT {TRACKER_JOB_COLUMNS} FROM torrust_tracker_jobs ORDER BY job_id ASC"
+            ))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error),
+        }
+    }
+
+    async fn update_failed_tracker_job(
+        &self,
+        job_id: TrackerJobId,
+        status: &str,
+        attempts: i64,
+        last_error: &str,
+        next_attempt_at: NaiveDateTime,
+    ) -> Result<(), database::Error> {
+        let next_attempt_at_string = next_attempt_at.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query("UPDATE torrust_tracker_jobs SET status = ?, attempts = ?, last_error = ?, next_attempt_at = ?, updated_at = strftime('%Y-%m-%d %H:%M:%S',DATETIME('now', 'utc')) WHERE job_id = ?")
+        .bind(status)
+        .bind(attempts)
+        .bind(last_error)
+        .bind(next_attempt_at_string)
+        .bind(job_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+        .and_then(|v| {
+            if v.rows_affected() > 0 {
+                Ok(())
+            } else {
+                Err(database::Error::TrackerJobNotFound)
+            }
+        })
+    }
+
+    async fn reset_tracker_job(&self, job_id: TrackerJobId, now: NaiveDateTime) -> Result<(), database::Error> {
+        let now_string = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query("UPDATE torrust_tracker_jobs SET status = ?, attempts = 0, next_attempt_at = ?, updated_at = ? WHERE job_id = ?")
+            .bind(Status::Pending.as_str())
+            .bind(&now_string)
+            .bind(&now_string)
+            .bind(job_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::TrackerJobNotFound)
+                }
+            })
+    }
+
+    async fn delete_tracker_job(&self, job_id: TrackerJobId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_tracker_jobs WHERE job_id = ?")
+            .bind(job_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::TrackerJobNotFound)
+                }
+            })
+    }
+
+    async fn delete_tracker_jobs_for_info_hash(&self, info_hash: &str, kind: &str) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_tracker_jobs WHERE info_hash = ? AND kind = ?")
+            .bind(info_hash)
+            .bind(kind)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn insert_mail(&self, mail: &NewMail<'_>, now: NaiveDateTime) -> Result<MailId, database::Error> {
+        // date needs to be in ISO 8601 format
+        let now_string = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query(
+            "INSERT INTO torrust_mail_queue (kind, user_id, sender, recipient, subject, message, status, attempts, next_attempt_at, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, 0, ?, ?, ?)",
+        )
+        .bind(mail.kind.as_str())
+        .bind(mail.user_id)
+        .bind(mail.sender)
+        .bind(mail.recipient)
+        .bind(mail.subject)
+        .bind(mail.message)
+        .bind(MailStatus::Pending.as_str())
+        .bind(&now_string)
+        .bind(&now_string)
+        .bind(&now_string)
+        .execute(&self.pool)
+        .await
+        .map(|v| v.last_insert_rowid())
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_due_mails(&self, now: NaiveDateTime, limit: u32) -> Result<Vec<QueuedMail>, database::Error> {
+        let now_string = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query_as::<_, QueuedMail>(&format!(
+            "SELECT {QUEUED_MAIL_COLUMNS} FROM torrust_mail_queue WHERE status = ? AND next_attempt_at <= ? ORDER BY next_attempt_at ASC, mail_id ASC LIMIT ?"
+        ))
+        .bind(MailStatus::Pending.as_str())
+        .bind(now_string)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_mails(&self, status: Option<&str>) -> Result<Vec<QueuedMail>, database::Error> {
+        match status {
+            Some(status) => query_as::<_, QueuedMail>(&format!(
+                "SELECT {QUEUED_MAIL_COLUMNS} FROM torrust_mail_queue WHERE status = ? ORDER BY mail_id ASC"
+            ))
+            .bind(status)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error),
+            None => query_as::<_, QueuedMail>(&format!(
+                "SELECT {QUEUED_MAIL_COLUMNS} FROM torrust_mail_queue ORDER BY mail_id ASC"
+            ))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error),
+        }
+    }
+
+    async fn update_failed_mail(
+        &self,
+        mail_id: MailId,
+        status: &str,
+        attempts: i64,
+        last_error: &str,
+        next_attempt_at: NaiveDateTime,
+    ) -> Result<(), database::Error> {
+        let next_attempt_at_string = next_attempt_at.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query("UPDATE torrust_mail_queue SET status = ?, attempts = ?, last_error = ?, next_attempt_at = ?, updated_at = strftime('%Y-%m-%d %H:%M:%S',DATETIME('now', 'utc')) WHERE mail_id = ?")
+        .bind(status)
+        .bind(attempts)
+        .bind(last_error)
+        .bind(next_attempt_at_string)
+        .bind(mail_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+        .and_then(|v| {
+            if v.rows_affected() > 0 {
+                Ok(())
+            } else {
+                Err(database::Error::MailNotFound)
+            }
+        })
+    }
+
+    async fn reset_mail(&self, mail_id: MailId, now: NaiveDateTime) -> Result<(), database::Error> {
+        let now_string = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query("UPDATE torrust_mail_queue SET status = ?, attempts = 0, next_attempt_at = ?, updated_at = ? WHERE mail_id = ?")
+            .bind(MailStatus::Pending.as_str())
+            .bind(&now_string)
+            .bind(&now_string)
+            .bind(mail_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::MailNotFound)
+                }
+            })
+    }
+
+    async fn mark_mail_as_sent(&self, mail_id: MailId, now: NaiveDateTime) -> Result<(), database::Error> {
+        query("UPDATE torrust_mail_queue SET status = ?, last_error = NULL, updated_at = ? WHERE mail_id = ?")
+            .bind(MailStatus::Sent.as_str())
+            .bind(now.format("%Y-%m-%d %H:%M:%S").to_string())
+            .bind(mail_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::MailNotFound)
+                }
+            })
+    }
+
+    async fn delete_mail(&self, mail_id: MailId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_mail_queue WHERE mail_id = ?")
+            .bind(mail_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::MailNotFound)
+                }
+            })
+    }
+
+    async fn delete_mails_for_user(&self, user_id: UserId, kind: &str) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_mail_queue WHERE user_id = ? AND kind = ?")
+            .bind(user_id)
+            .bind(kind)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn delete_finished_mails_before(&self, before: NaiveDateTime) -> Result<u64, database::Error> {
+        query("DELETE FROM torrust_mail_queue WHERE status IN (?, ?) AND updated_at < ?")
+            .bind(MailStatus::Sent.as_str())
+            .bind(MailStatus::Failed.as_str())
+            .bind(before.format("%Y-%m-%d %H:%M:%S").to_string())
+            .execute(&self.pool)
+            .await
+            .map(|v| v.rows_affected())
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn insert_notification(
+        &self,
+        user_id: UserId,
+        notification: &NewNotification,
+        in_app: bool,
+        digest_pending: bool,
+        now: NaiveDateTime,
+    ) -> Result<NotificationId, database::Error> {
+        query(
+            "INSERT INTO torrust_notifications (user_id, kind, torrent_id, info_hash, torrent_title, detail, in_app, digest_pending, is_read, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, FALSE, ?)",
+        )
+        .bind(user_id)
+        .bind(notification.kind.as_str())
+        .bind(notification.torrent_id)
+        .bind(notification.info_hash.as_deref())
+        .bind(notification.torrent_title.as_deref())
+        .bind(notification.detail.as_deref())
+        .bind(in_app)
+        .bind(digest_pending)
+        .bind(now.format("%Y-%m-%d %H:%M:%S").to_string())
+        .execute(&self.pool)
+        .await
+        .map(|v| v.last_insert_rowid())
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_notifications(
+        &self,
+        user_id: UserId,
+        unread_only: bool,
+        limit: u32,
+    ) -> Result<Vec<Notification>, database::Error> {
+        let unread_condition = if unread_only { " AND is_read = FALSE" } else { "" };
+
+        query_as::<_, Notification>(&format!(
+            "SELECT {NOTIFICATION_COLUMNS} FROM torrust_notifications WHERE user_id = ? AND in_app = TRUE{unread_condition} ORDER BY notification_id DESC LIMIT ?"
+        ))
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn count_unread_notifications(&self, user_id: UserId) -> Result<i64, database::Error> {
+        query_as("SELECT COUNT(*) FROM torrust_notifications WHERE user_id = ? AND in_app = TRUE AND is_read = FALSE")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map(|(v,)| v)
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn mark_notification_as_read(&self, user_id: UserId, notification_id: NotificationId) -> Result<(), database::Error> {
+        let (found,): (i64,) =
+            query_as("SELECT COUNT(*) FROM torrust_notifications WHERE notification_id = ? AND user_id = ? AND in_app = TRUE")
+                .bind(notification_id)
+                .bind(user_id)
+                .fetch_one(&self.pool)
+                .await
+                .map_err(|_| database::Error::Error)?;
+
+        if found == 0 {
+            return Err(database::Error::NotificationNotFound);
+        }
+
+        query("UPDATE torrust_notifications SET is_read = TRUE WHERE notification_id = ? AND user_id = ?")
+            .bind(notification_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn mark_all_notifications_as_read(&self, user_id: UserId) -> Result<u64, database::Error> {
+        query("UPDATE torrust_notifications SET is_read = TRUE WHERE user_id = ? AND in_app = TRUE AND is_read = FALSE")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map(|v| v.rows_affected())
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_notification_preferences(&self, user_id: UserId) -> Result<Option<NotificationPreferences>, database::Error> {
+        query_as::<_, NotificationPreferences>(
+            "SELECT in_app, email_digest, immediate_email FROM torrust_notification_preferences WHERE user_id = ?",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn update_notification_preferences(
+        &self,
+        user_id: UserId,
+        preferences: &NotificationPreferences,
+    ) -> Result<(), database::Error> {
+        query(
+            "REPLACE INTO torrust_notification_preferences (user_id, in_app, email_digest, immediate_email) VALUES (?, ?, ?, ?)",
+        )
+        .bind(user_id)
+        .bind(preferences.in_app)
+        .bind(preferences.email_digest)
+        .bind(preferences.immediate_email)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_users_with_pending_digest(&self) -> Result<Vec<UserId>, database::Error> {
+        query_as("SELECT DISTINCT user_id FROM torrust_notifications WHERE digest_pending = TRUE ORDER BY user_id ASC")
+            .fetch_all(&self.pool)
+            .await
+            .map(|v| v.into_iter().map(|(user_id,)| user_id).collect())
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_pending_digest_notifications(&self, user_id: UserId) -> Result<Vec<Notification>, database::Error> {
+        query_as::<_, Notification>(&format!(
+            "SELECT {NOTIFICATION_COLUMNS} FROM torrust_notifications WHERE user_id = ? AND digest_pending = TRUE ORDER BY notification_id ASC"
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn clear_pending_digest(&self, user_id: UserId, notification_id: NotificationId) -> Result<(), database::Error> {
+        let mut conn = self.pool.acquire().await.map_err(|_| database::Error::Error)?;
+
+        let mut tx = conn.begin().await.map_err(|_| database::Error::Error)?;
+
+        // Notifications not listed in the app are only kept for the digest
+        query("DELETE FROM torrust_notifications WHERE user_id = ? AND notification_id <= ? AND digest_pending = TRUE AND in_app = FALSE")
+            .bind(user_id)
+            .bind(notification_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        query("UPDATE torrust_notifications SET digest_pending = FALSE WHERE user_id = ? AND notification_id <= ?")
+            .bind(user_id)
+            .bind(notification_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        tx.commit().await.map_err(|_| database::Error::Error)
+    }
+
+    async fn delete_notifications_before(&self, before: NaiveDateTime) -> Result<u64, database::Error> {
+        query("DELETE FROM torrust_notifications WHERE created_at < ? AND digest_pending = FALSE")
+            .bind(before.format("%Y-%m-%d %H:%M:%S").to_string())
+            .execute(&self.pool)
+            .await
+            .map(|v| v.rows_affected())
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn update_torrents_has_seeders(&self) -> Result<Vec<UploadedTorrent>, database::Error> {
+        let mut conn = self.pool.acquire().await.map_err(|_| database::Error::Error)?;
+
+        let mut tx = conn.begin().await.map_err(|_| database::Error::Error)?;
+
+        let torrents = query_as::<_, UploadedTorrent>(&format!(
+            "{UPLOADED_TORRENT_SELECT}
+            WHERE tt.has_seeders = TRUE AND tt.archived_at IS NULL AND NOT {IS_SEEDED}
+            ORDER BY tt.torrent_id ASC"
+        ))
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|_| database::Error::Error)?;
+
+        query(&format!(
+            "UPDATE torrust_torrents AS tt SET has_seeders = {IS_SEEDED} WHERE tt.archived_at IS NULL"
+        ))
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| database::Error::Error)?;
+
+        tx.commit().await.map_err(|_| database::Error::Error)?;
+
+        Ok(torrents)
+    }
+
+    async fn get_uploaded_torrents_in_category(&self, category_name: &str) -> Result<Vec<UploadedTorrent>, database::Error> {
+        query_as::<_, UploadedTorrent>(&format!(
+            "{UPLOADED_TORRENT_SELECT}
+            INNER JOIN torrust_categories tc ON tt.category_id = tc.category_id
+            WHERE tc.name = ?
+            ORDER BY tt.torrent_id ASC"
+        ))
+        .bind(category_name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_torrents_compact_after(&self, torrent_id: i64, limit: u32) -> Result<Vec<TorrentCompact>, database::Error> {
+        query_as::<_, TorrentCompact>(
+            "SELECT torrent_id, info_hash FROM torrust_torrents WHERE torrent_id > ? AND archived_at IS NULL ORDER BY torrent_id ASC LIMIT ?",
+        )
+        .bind(torrent_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_users_with_tracker_keys(&self) -> Result<Vec<UserId>, database::Error> {
+        query_as::<_, (UserId,)>("SELECT DISTINCT user_id FROM torrust_tracker_keys")
+            .fetch_all(&self.pool)
+            .await
+            .map(|rows| rows.into_iter().map(|(user_id,)| user_id).collect())
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn insert_tracker_mode_sync(
+        &self,
+        from_mode: Option<&str>,
+        to_mode: &str,
+        action: &str,
+        torrents_total: i64,
+        now: NaiveDateTime,
+    ) -> Result<TrackerModeSyncId, database::Error> {
+        // date needs to be in ISO 8601 format
+        let now_string = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query(
+            "INSERT INTO torrust_tracker_mode_syncs (from_mode, to_mode, action, status, torrents_total, started_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(from_mode)
+        .bind(to_mode)
+        .bind(action)
+        .bind(TrackerModeSyncStatus::Running.as_str())
+        .bind(torrents_total)
+        .bind(&now_string)
+        .bind(&now_string)
+        .execute(&self.pool)
+        .await
+        .map(|v| v.last_insert_rowid())
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_last_tracker_mode_sync(&self) -> Result<Option<TrackerModeSync>, database::Error> {
+        query_as::<_, TrackerModeSync>(&format!(
+            "SELECT {TRACKER_MODE_SYNC_COLUMNS} FROM torrust_tracker_mode_syncs ORDER BY sync_id DESC LIMIT 1"
+        ))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_last_completed_tracker_mode_sync(&self) -> Result<Option<TrackerModeSync>, database::Error> {
+        query_as::<_, TrackerModeSync>(&format!(
+            "SELECT {TRACKER_MODE_SYNC_COLUMNS} FROM torrust_tracker_mode_syncs WHERE status = ? ORDER BY sync_id DESC LIMIT 1"
+        ))
+        .bind(TrackerModeSyncStatus::Completed.as_str())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn update_tracker_mode_sync_progress(
+        &self,
+        sync_id: TrackerModeSyncId,
+        last_torrent_id: i64,
+        torrents_processed: i64,
+        errors: i64,
+        last_error: Option<&str>,
+        now: NaiveDateTime,
+    ) -> Result<(), database::Error> {
+        query(
+            "UPDATE torrust_tracker_mode_syncs SET last_torrent_id = ?, torrents_processed = ?, errors = ?, last_error = COALESCE(?, last_error), updated_at = ? WHERE sync_id = ?",
+        )
+        .bind(last_torrent_id)
+        .bind(torrents_processed)
+        .bind(errors)
+        .bind(last_error)
+        .bind(now.format("%Y-%m-%d %H:%M:%S").to_string())
+        .bind(sync_id)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn complete_tracker_mode_sync(&self, sync_id: TrackerModeSyncId, now: NaiveDateTime) -> Result<(), database::Error> {
+        let now_string = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        query("UPDATE torrust_tracker_mode_syncs SET status = ?, updated_at = ?, finished_at = ? WHERE sync_id = ?")
+            .bind(TrackerModeSyncStatus::Completed.as_str())
+            .bind(&now_string)
+            .bind(&now_string)
+            .bind(sync_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[allow(clippy::too_many_arguments)]
+    async fn insert_image(
+        &self,
+        user_id: UserId,
+        content_hash: &str,
+        content_type: &str,
+        size: i64,
+        width: i64,
+        height: i64,
+        user_quota: i64,
+        now: NaiveDateTime,
+    ) -> Result<ImageId, database::Error> {
+        // date needs to be in ISO 8601 format
+        let date_uploaded = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        // The quota is checked in the same statement, so concurrent uploads
+        // can't exceed it.
+        query(
+            "INSERT INTO torrust_images (user_id, content_hash, content_type, size, width, height, date_uploaded) SELECT ?, ?, ?, ?, ?, ?, ? FROM (SELECT COALESCE(SUM(size),0) AS used FROM torrust_images WHERE user_id = ?) AS usage_ WHERE usage_.used + ? <= ?",
+        )
+        .bind(user_id)
+        .bind(content_hash)
+        .bind(content_type)
+        .bind(size)
+        .bind(width)
+        .bind(height)
+        .bind(date_uploaded)
+        .bind(user_id)
+        .bind(size)
+        .bind(user_quota)
+        .execute(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+        .and_then(|v| {
+            if v.rows_affected() > 0 {
+                Ok(v.last_insert_rowid())
+            } else {
+                Err(database::Error::ImageQuotaExceeded)
+            }
+        })
+    }
+
+    async fn get_image_from_id(&self, image_id: ImageId) -> Result<Image, database::Error> {
+        query_as::<_, Image>(&format!("SELECT {IMAGE_COLUMNS} FROM torrust_images i WHERE i.image_id = ?"))
+            .bind(image_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|_| database::Error::ImageNotFound)
+    }
+
+    async fn get_user_images(&self, user_id: UserId) -> Result<Vec<Image>, database::Error> {
+        query_as::<_, Image>(&format!("SELECT {IMAGE_COLUMNS} FROM torrust_images i WHERE i.user_id = ?"))
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn delete_image(&self, image_id: ImageId) -> Result<i64, database::Error> {
+        let mut tx = self.pool.begin().await.map_err(|_| database::Error::Error)?;
+
+        let (content_hash,) = query_as::<_, (String,)>("SELECT content_hash FROM torrust_images WHERE image_id = ?")
+            .bind(image_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?
+            .ok_or(database::Error::ImageNotFound)?;
+
+        query("DELETE FROM torrust_images WHERE image_id = ?")
+            .bind(image_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        let (count,) = query_as::<_, (i64,)>("SELECT COUNT(*) FROM torrust_images WHERE content_hash = ?")
+            .bind(&content_hash)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        tx.commit().await.map_err(|_| database::Error::Error)?;
+
+        Ok(count)
+    }
+
+    async fn get_torrent_images_from_id(&self, torrent_id: i64) -> Result<Vec<DbTorrentImage>, database::Error> {
+        query_as::<_, DbTorrentImage>(&format!(
+            "SELECT tti.image_type, {IMAGE_COLUMNS} FROM torrust_torrent_images tti INNER JOIN torrust_images i ON tti.image_id = i.image_id WHERE tti.torrent_id = ? ORDER BY tti.position ASC"
+        ))
+        .bind(torrent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn update_torrent_images(
+        &self,
+        torrent_id: i64,
+        poster: Option<ImageId>,
+        screenshots: &[ImageId],
+    ) -> Result<(), database::Error> {
+        let mut tx = self.pool.begin().await.map_err(|_| database::Error::Error)?;
+
+        query("DELETE FROM torrust_torrent_images WHERE torrent_id = ?")
+            .bind(torrent_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        let images = poster
+            .map(|image_id| (image_id, TorrentImageType::Poster))
+            .into_iter()
+            .chain(screenshots.iter().map(|image_id| (*image_id, TorrentImageType::Screenshot)));
+
+        for (position, (image_id, image_type)) in images.enumerate() {
+            query("INSERT INTO torrust_torrent_images (torrent_id, image_id, image_type, position) VALUES (?, ?, ?, ?)")
+                .bind(torrent_id)
+                .bind(image_id)
+                .bind(image_type.as_str())
+                .bind(i64::try_from(position).unwrap_or(i64::MAX))
+                .execute(&mut *tx)
+                .await
+                .map_err(|_| database::Error::Error)?;
+        }
+
+        tx.commit().await.map_err(|_| database::Error::Error)
+    }
+
+    async fn update_user_avatar(&self, user_id: UserId, avatar: Option<&str>) -> Result<(), database::Error> {
+        query("UPDATE torrust_user_profiles SET avatar = ? WHERE user_id = ?")
+            .bind(avatar)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn update_user_locale(&self, user_id: UserId, locale: Option<&str>) -> Result<(), database::Error> {
+        query("UPDATE torrust_user_profiles SET locale = ? WHERE user_id = ?")
+            .bind(locale)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn delete_avatar(&self, avatar: &str) -> Result<(), database::Error> {
+        query("UPDATE torrust_user_profiles SET avatar = NULL WHERE avatar = ?")
+            .bind(avatar)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    async fn get_image_cache_quotas(&self) -> Result<Vec<DbImageCacheQuota>, database::Error> {
+        query_as::<_, DbImageCacheQuota>(
+            "SELECT user_id, usage_bytes, variant_usage_bytes, variant_usages, date_start_secs FROM torrust_image_cache_quotas",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn update_image_cache_quota(&self, quota: &DbImageCacheQuota) -> Result<(), database::Error> {
+        query(
+            "REPLACE INTO torrust_image_cache_quotas (user_id, usage_bytes, variant_usage_bytes, variant_usages, date_start_secs) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(quota.user_id)
+        .bind(quota.usage_bytes)
+        .bind(quota.variant_usage_bytes)
+        .bind(&quota.variant_usages)
+        .bind(quota.date_start_secs)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    async fn delete_all_database_rows(&self) -> Result<(), database::Error> {
+        for table in TABLES_TO_TRUNCATE {
+            query(&format!("DELETE FROM {table};"))
+                .execute(&self.pool)
+                .await
+                .map_err(|_| database::Error::Error)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// SQL expression for the seeders growth in the last [`TRENDING_PERIOD_HOURS`](database::TRENDING_PERIOD_HOURS).
+/// It compares the current seeders with the oldest snapshot in that period.
+fn seeders_growth_expression() -> String {
+    let since = (Utc::now().naive_utc() - chrono::Duration::hours(database::TRENDING_PERIOD_HOURS)).format("%Y-%m-%d %H:%M:%S");
+
+    format!(
+        "(COALESCE(max(ts.seeders),0) - COALESCE((SELECT th.seeders FROM torrust_torrent_tracker_stats_history th WHERE th.torrent_id = tt.torrent_id AND th.created_at >= '{since}' ORDER BY th.created_at ASC, th.snapshot_id ASC LIMIT 1), COALESCE(max(ts.seeders),0)))"
+    )
 }
@@ -4,6 +4,7 @@ use std::error;
 use derive_more::{Display, Error};
 use hyper::StatusCode;
 
+use crate::config;
 use crate::databases::database;
 use crate::models::torrent::MetadataError;
 use crate::tracker::service::TrackerAPIError;
@@ -25,6 +26,9 @@ pub enum ServiceError {
     #[display(fmt = "Please enter a valid email address")] //405j
     EmailInvalid,
 
+    #[display(fmt = "Invalid locale. It must be a language tag like `en` or `pt-BR`.")]
+    InvalidLocale,
+
     #[display(fmt = "The value you entered for URL is not a URL")] //405j
     NotAUrl,
 
@@ -72,6 +76,12 @@ pub enum ServiceError {
     #[display(fmt = "Please verify your email before logging in")]
     EmailNotVerified,
 
+    #[display(fmt = "Please verify your email before uploading torrents")]
+    UploadRequiresVerifiedEmail,
+
+    #[display(fmt = "The email verification link is invalid or has expired.")]
+    EmailVerificationTokenInvalid,
+
     /// when the a token name is already taken
     /// token not found
     #[display(fmt = "Token not found. Please sign in.")]
@@ -106,6 +116,30 @@ pub enum ServiceError {
     #[display(fmt = "Selected tag does not exist.")]
     InvalidTag,
 
+    #[display(fmt = "Invalid web seed URL. It must be an absolute http or https URL.")]
+    InvalidWebSeedUrl,
+
+    #[display(fmt = "Only JPEG, PNG, GIF and WebP images can be uploaded.")]
+    InvalidImage,
+
+    #[display(fmt = "Uploaded image is too big.")]
+    ImageTooBig,
+
+    #[display(fmt = "Uploaded image dimensions are too big.")]
+    ImageDimensionsTooBig,
+
+    #[display(fmt = "Image upload quota exceeded.")]
+    ImageQuotaExceeded,
+
+    #[display(fmt = "Too many screenshots.")]
+    TooManyScreenshots,
+
+    #[display(fmt = "Either the url of the image to purge or all=true is required.")]
+    MissingPurgeTarget,
+
+    #[display(fmt = "Image not found.")]
+    ImageNotFound,
+
     #[display(fmt = "Unauthorized action.")]
     Unauthorized,
 
@@ -124,6 +158,18 @@ pub enum ServiceError {
     #[display(fmt = "Failed to send verification email.")]
     FailedToSendVerificationEmail,
 
+    #[display(fmt = "Email verification is not enabled.")]
+    EmailVerificationNotEnabled,
+
+    #[display(fmt = "Your email is already verified.")]
+    EmailAlreadyVerified,
+
+    #[display(fmt = "A verification email was sent recently. Please wait before requesting a new one.")]
+    VerificationEmailSentRecently,
+
+    #[display(fmt = "Failed to send email.")]
+    FailedToSendEmail,
+
     #[display(fmt = "Category already exists.")]
     CategoryAlreadyExists,
 
@@ -166,7 +212,39 @@ pub enum ServiceError {
 
     #[display(fmt = "Invalid tracker API token.")]
     InvalidTrackerToken,
+
+    #[display(fmt = "Tracker job not found.")]
+    TrackerJobNotFound,
+
+    #[display(fmt = "Email not found in the mail queue.")]
+    MailNotFound,
+
+    #[display(fmt = "Notification not found.")]
+    NotificationNotFound,
+
+    #[display(fmt = "Tracker key not found.")]
+    TrackerKeyNotFound,
+
+    #[display(fmt = "The tracker mode did not change since the last sync.")]
+    TrackerModeUnchanged,
     // End tracker errors
+
+    // Begin settings errors
+    #[display(fmt = "Invalid settings.")]
+    InvalidSettings,
+
+    #[display(fmt = "Settings could not be saved.")]
+    FailedToSaveSettings,
+
+    #[display(fmt = "This setting can't be changed without restarting the application.")]
+    SettingNotApplicableLive,
+
+    #[display(fmt = "This setting is overridden with an environment variable.")]
+    SettingOverridden,
+
+    #[display(fmt = "This setting can only be changed in the configuration file.")]
+    SettingNotEditable,
+    // End settings errors
 }
 
 impl From<sqlx::Error> for ServiceError {
@@ -251,23 +329,39 @@ impl From<TrackerAPIError> for ServiceError {
         eprintln!("{e}");
         match e {
             TrackerAPIError::TrackerOffline => ServiceError::TrackerOffline,
-            TrackerAPIError::InternalServerError => ServiceError::TrackerResponseError,
+            TrackerAPIError::InternalServerError | TrackerAPIError::UnsupportedOperation => ServiceError::TrackerResponseError,
             TrackerAPIError::TorrentNotFound => ServiceError::TorrentNotFoundInTracker,
             TrackerAPIError::UnexpectedResponseStatus
             | TrackerAPIError::MissingResponseBody
             | TrackerAPIError::FailedToParseTrackerResponse { body: _ } => ServiceError::TrackerUnknownResponse,
-            TrackerAPIError::CannotSaveUserKey => ServiceError::DatabaseError,
+            TrackerAPIError::CannotSaveUserKey | TrackerAPIError::CannotGetUserKeys | TrackerAPIError::CannotDeleteUserKeys => {
+                ServiceError::DatabaseError
+            }
             TrackerAPIError::InvalidToken => ServiceError::InvalidTrackerToken,
         }
     }
 }
 
+impl From<config::UpdateError> for ServiceError {
+    fn from(e: config::UpdateError) -> Self {
+        eprintln!("{e}");
+        match e {
+            config::UpdateError::InvalidConfiguration { source: _ } => ServiceError::InvalidSettings,
+            config::UpdateError::UnableToSaveToConfigFile { source: _ } => ServiceError::FailedToSaveSettings,
+            config::UpdateError::SettingNotApplicableLive { key: _ } => ServiceError::SettingNotApplicableLive,
+            config::UpdateError::SettingOverridden { key: _ } => ServiceError::SettingOverridden,
+            config::UpdateError::SettingNotEditable { key: _ } => ServiceError::SettingNotEditable,
+        }
+    }
+}
+
 #[must_use]
 pub fn http_status_code_for_service_error(error: &ServiceError) -> StatusCode {
     #[allow(clippy::match_same_arms)]
     match error {
         ServiceError::ClosedForRegistration => StatusCode::FORBIDDEN,
         ServiceError::EmailInvalid => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidLocale => StatusCode::BAD_REQUEST,
         ServiceError::NotAUrl => StatusCode::BAD_REQUEST,
         ServiceError::WrongPasswordOrUsername => StatusCode::FORBIDDEN,
         ServiceError::UsernameNotFound => StatusCode::NOT_FOUND,
@@ -283,6 +377,8 @@ pub fn http_status_code_for_service_error(error: &ServiceError) -> StatusCode {
         ServiceError::UsernameInvalid => StatusCode::BAD_REQUEST,
         ServiceError::EmailTaken => StatusCode::BAD_REQUEST,
         ServiceError::EmailNotVerified => StatusCode::FORBIDDEN,
+        ServiceError::UploadRequiresVerifiedEmail => StatusCode::FORBIDDEN,
+        ServiceError::EmailVerificationTokenInvalid => StatusCode::BAD_REQUEST,
         ServiceError::TokenNotFound => StatusCode::UNAUTHORIZED,
         ServiceError::TokenExpired => StatusCode::UNAUTHORIZED,
         ServiceError::TokenInvalid => StatusCode::UNAUTHORIZED,
@@ -294,6 +390,14 @@ pub fn http_status_code_for_service_error(error: &ServiceError) -> StatusCode {
         ServiceError::MissingMandatoryMetadataFields => StatusCode::BAD_REQUEST,
         ServiceError::InvalidCategory => StatusCode::BAD_REQUEST,
         ServiceError::InvalidTag => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidWebSeedUrl => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidImage => StatusCode::BAD_REQUEST,
+        ServiceError::ImageTooBig => StatusCode::BAD_REQUEST,
+        ServiceError::ImageDimensionsTooBig => StatusCode::BAD_REQUEST,
+        ServiceError::ImageQuotaExceeded => StatusCode::FORBIDDEN,
+        ServiceError::TooManyScreenshots => StatusCode::BAD_REQUEST,
+        ServiceError::MissingPurgeTarget => StatusCode::BAD_REQUEST,
+        ServiceError::ImageNotFound => StatusCode::NOT_FOUND,
         ServiceError::Unauthorized => StatusCode::FORBIDDEN,
         ServiceError::InfoHashAlreadyExists => StatusCode::BAD_REQUEST,
         ServiceError::CanonicalInfoHashAlreadyExists => StatusCode::BAD_REQUEST,
@@ -306,6 +410,10 @@ pub fn http_status_code_for_service_error(error: &ServiceError) -> StatusCode {
         ServiceError::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
         ServiceError::EmailMissing => StatusCode::NOT_FOUND,
         ServiceError::FailedToSendVerificationEmail => StatusCode::INTERNAL_SERVER_ERROR,
+        ServiceError::EmailVerificationNotEnabled => StatusCode::BAD_REQUEST,
+        ServiceError::EmailAlreadyVerified => StatusCode::BAD_REQUEST,
+        ServiceError::VerificationEmailSentRecently => StatusCode::TOO_MANY_REQUESTS,
+        ServiceError::FailedToSendEmail => StatusCode::INTERNAL_SERVER_ERROR,
         ServiceError::WhitelistingError => StatusCode::INTERNAL_SERVER_ERROR,
         ServiceError::DatabaseError => StatusCode::INTERNAL_SERVER_ERROR,
         ServiceError::CategoryNotFound => StatusCode::NOT_FOUND,
@@ -314,7 +422,17 @@ pub fn http_status_code_for_service_error(error: &ServiceError) -> StatusCode {
         ServiceError::TrackerUnknownResponse => StatusCode::INTERNAL_SERVER_ERROR,
         ServiceError::TorrentNotFoundInTracker => StatusCode::NOT_FOUND,
         ServiceError::InvalidTrackerToken => StatusCode::INTERNAL_SERVER_ERROR,
+        ServiceError::TrackerJobNotFound => StatusCode::NOT_FOUND,
+        ServiceError::MailNotFound => StatusCode::NOT_FOUND,
+        ServiceError::NotificationNotFound => StatusCode::NOT_FOUND,
+        ServiceError::TrackerKeyNotFound => StatusCode::NOT_FOUND,
+        ServiceError::TrackerModeUnchanged => StatusCode::BAD_REQUEST,
         ServiceError::LoggedInUserNotFound => StatusCode::UNAUTHORIZED,
+        ServiceError::InvalidSettings => StatusCode::BAD_REQUEST,
+        ServiceError::FailedToSaveSettings => StatusCode::INTERNAL_SERVER_ERROR,
+        ServiceError::SettingNotApplicableLive => StatusCode::BAD_REQUEST,
+        ServiceError::SettingOverridden => StatusCode::BAD_REQUEST,
+        ServiceError::SettingNotEditable => StatusCode::BAD_REQUEST,
     }
 }
 
@@ -335,5 +453,11 @@ pub fn map_database_error_to_service_error(error: &database::Error) -> ServiceEr
         database::Error::TorrentTitleAlreadyExists => ServiceError::TorrentTitleAlreadyExists,
         database::Error::UnrecognizedDatabaseDriver => ServiceError::InternalServerError,
         database::Error::TorrentInfoHashNotFound => ServiceError::TorrentNotFound,
+        database::Error::TrackerJobNotFound => ServiceError::TrackerJobNotFound,
+        database::Error::ImageNotFound => ServiceError::ImageNotFound,
+        database::Error::ImageQuotaExceeded => ServiceError::ImageQuotaExceeded,
+        database::Error::MailNotFound => ServiceError::MailNotFound,
+        database::Error::EmailVerificationTokenNotFound => ServiceError::EmailVerificationTokenInvalid,
+        database::Error::NotificationNotFound => ServiceError::NotificationNotFound,
     }
 }
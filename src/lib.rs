@@ -62,6 +62,9 @@
 //! api_url = "http://localhost:1212"
 //! token = "MyAccessToken"
 //! token_valid_seconds = 7257600
+//! backend = "Api"
+//! statistics_source = "Api"
+//! extra_trackers = []
 //! ```
 //!
 //! Refer to the [`config::tracker`](crate::config::Tracker) documentation for more information.
@@ -175,6 +178,9 @@
 //! api_url = "http://localhost:1212"
 //! token = "MyAccessToken"
 //! token_valid_seconds = 7257600
+//! backend = "Api"
+//! statistics_source = "Api"
+//! extra_trackers = []
 //!
 //! [net]
 //! port = 3001
@@ -196,6 +202,11 @@
 //! password = ""
 //! server = ""
 //! port = 25
+//! transport = "Smtp"
+//! smtp_tls = "None"
+//! sendmail_command = "sendmail"
+//! file_path = "./storage/index/lib/mail"
+//! templates_path = "./storage/index/etc/templates"
 //!
 //! [image_cache]
 //! max_request_timeout_ms = 1000
@@ -203,6 +214,22 @@
 //! entry_size_limit = 4000000
 //! user_quota_period_seconds = 3600
 //! user_quota_bytes = 64000000
+//! disk_cache_enabled = true
+//! disk_cache_path = "./storage/index/lib/image_cache"
+//! disk_capacity = 1000000000
+//! ttl_seconds = 604800
+//! max_redirects = 3
+//! allowed_private_hosts = []
+//! variant_format = "Jpeg"
+//! variant_quality = 80
+//! max_variant_dimension = 2048
+//!
+//! [image_uploads]
+//! storage_path = "./storage/index/lib/images"
+//! max_image_size = 4000000
+//! max_image_dimension = 4096
+//! user_quota_bytes = 100000000
+//! max_screenshots = 10
 //!
 //! [api]
 //! default_torrent_page_size = 10
@@ -211,6 +238,48 @@
 //! [tracker_statistics_importer]
 //! torrent_info_update_interval = 3600
 //! port = 3002
+//! stats_history_retention_days = 90
+//! stats_history_full_resolution_days = 7
+//! max_torrents_per_run = 10000
+//! max_concurrent_requests = 10
+//! import_completed_counts = true
+//!
+//! [tracker_jobs]
+//! worker_interval_seconds = 5
+//! batch_size = 50
+//! max_attempts = 10
+//! initial_backoff_seconds = 5
+//! max_backoff_seconds = 3600
+//!
+//! [mail_queue]
+//! worker_interval_seconds = 5
+//! batch_size = 20
+//! max_attempts = 10
+//! initial_backoff_seconds = 60
+//! max_backoff_seconds = 21600
+//! retention_days = 30
+//!
+//! [email_verification]
+//! token_lifetime_seconds = 86400
+//! required_for_login = true
+//! required_for_upload = false
+//! purge_unverified_after_days = 0
+//! purge_interval_seconds = 3600
+//! resend_interval_seconds = 300
+//!
+//! [dead_torrents]
+//! enabled = false
+//! check_interval_seconds = 86400
+//! dead_after_days = 30
+//! hide_dead_torrents = false
+//! notify_uploader = true
+//! grace_period_days = 30
+//! prune_action = "Archive"
+//! dry_run = false
+//!
+//! [notifications]
+//! digest_interval_seconds = 86400
+//! retention_days = 90
 //! ```
 //!
 //! For more information about configuration you can visit the documentation for the [`config`]) module.
@@ -230,6 +299,22 @@
 //!
 //! > **NOTICE**: You can also change the location for the configuration file with the `TORRUST_INDEX_CONFIG_PATH` env var.
 //!
+//! Values missing in the configuration are taken from the default configuration. Any single value can be overridden with an
+//! env var with the `TORRUST_INDEX_CONFIG__` prefix followed by the section and the setting name separated with `__`.
+//! Adding the `_FILE` suffix the value is read from a file, which is useful for Docker or Kubernetes secrets:
+//!
+//! ```text
+//! TORRUST_INDEX_CONFIG__NET__PORT=3005 \
+//! TORRUST_INDEX_CONFIG__TRACKER__TOKEN_FILE=/run/secrets/tracker_token \
+//! cargo run
+//! ```
+//!
+//! You can print the effective configuration, with secrets masked, with `cargo run --bin print_config`.
+//!
+//! When the configuration is loaded from a file, the index reloads it when the file changes or when the process receives a
+//! `SIGHUP` signal. Some settings like the API port or the database URL can only be changed by restarting the index.
+//! Refer to the [`config_watcher`](crate::console::cronjobs::config_watcher) module for more information.
+//!
 //! # Usage
 //!
 //! ## API
@@ -243,6 +328,14 @@
 //! For more information about this command you can visit the documentation for
 //! the [`Import tracker statistics`](crate::console::commands::tracker_statistics_importer) module.
 //!
+//! ## Tracker Whitelist Reconciler
+//!
+//! This console command allows you to add the missing torrents to the tracker
+//! whitelist and remove the ones that do not exist in the index.
+//!
+//! For more information about this command you can visit the documentation for
+//! the [`Reconcile tracker whitelist`](crate::console::commands::tracker_whitelist_reconciler) module.
+//!
 //! ## Upgrader
 //!
 //! This console command allows you to manually upgrade the application from one
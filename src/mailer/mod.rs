@@ -0,0 +1,404 @@
+//! Email delivery.
+//!
+//! Emails are delivered with the transport configured in `mail.transport`:
+//!
+//! - `Smtp`: emails are sent to an SMTP server, optionally using TLS
+//!   (`mail.smtp_tls`).
+//! - `Sendmail`: emails are sent with the local `sendmail` binary.
+//! - `File`: emails are written to a directory in Maildir format. It's useful
+//!   for development and testing, because no mail server is needed.
+//! - `Stdout`: emails are written to the application log, with the `info` level.
+//!
+//! Emails are rendered from overridable templates, see [`templates`], and
+//! delivered in the background by the [`queue`], which retries them when the
+//! delivery fails.
+pub mod queue;
+pub mod templates;
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use derive_more::Display;
+use lettre::address::{AddressError, Envelope};
+use lettre::message::{MessageBuilder, MultiPart, SinglePart};
+use lettre::{AsyncSendmailTransport, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde_json::json;
+use serde_json::value::{to_value, Value};
+use tera::{try_get_value, Context};
+use tokio::fs;
+
+use self::queue::MailQueue;
+use self::templates::{RenderedMail, Template, DEAD_TORRENT, NOTIFICATION, VERIFY_EMAIL};
+use crate::config::Configuration;
+use crate::errors::ServiceError;
+use crate::models::notification::{Kind as NotificationKind, NotificationSummary};
+use crate::models::user::UserId;
+use crate::utils::clock;
+use crate::web::api::server::v1::routes::API_VERSION_URL_PREFIX;
+
+/// This function is a dummy filter for tera.
+///
+/// # Panics
+///
+/// Panics if unable to convert values.
+///
+/// # Errors
+///
+/// This function will return an error if...
+#[allow(clippy::implicit_hasher)]
+pub fn do_nothing_filter(value: &Value, _: &HashMap<String, Value>) -> tera::Result<Value> {
+    let s = try_get_value!("do_nothing_filter", "value", String, value);
+    Ok(to_value(s).unwrap())
+}
+
+pub struct Service {
+    cfg: Arc<Configuration>,
+    mail_queue: Arc<MailQueue>,
+}
+
+impl Service {
+    pub async fn new(cfg: Arc<Configuration>, mail_queue: Arc<MailQueue>) -> Service {
+        Self { cfg, mail_queue }
+    }
+
+    /// Send Verification Email.
+    ///
+    /// The email contains a link with the `verification_token`. The email is
+    /// rendered in the user's preferred `locale` if there are
+    /// templates for it, and added to the mail queue. Verification emails
+    /// still waiting in the queue for the same user are discarded.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if unable to queue the email.
+    pub async fn send_verification_mail(
+        &self,
+        to: &str,
+        username: &str,
+        user_id: i64,
+        verification_token: &str,
+        base_url: &str,
+        locale: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        let verification_url = self.get_verification_url(verification_token, base_url).await;
+
+        let mut context = Context::new();
+        context.insert("username", username);
+        context.insert("verification_url", &verification_url);
+
+        if let Err(e) = self.mail_queue.discard_for_user(user_id, VERIFY_EMAIL.kind).await {
+            log::error!("Failed to discard the previous verification emails: {e:?}");
+        }
+
+        self.enqueue_mail(to, Some(user_id), &VERIFY_EMAIL, locale, Some(base_url), context)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to queue email: {e}");
+                ServiceError::FailedToSendVerificationEmail
+            })
+    }
+
+    /// Send an email to notify the uploader that the torrent was marked as
+    /// dead because it has no seeders. The email is added to the mail queue.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if unable to queue the email.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send_dead_torrent_mail(
+        &self,
+        to: &str,
+        username: &str,
+        torrent_title: &str,
+        info_hash: &str,
+        grace_period_days: u32,
+        locale: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        let mut context = Context::new();
+        context.insert("username", username);
+        context.insert("torrent_title", torrent_title);
+        context.insert("info_hash", info_hash);
+        context.insert("grace_period_days", &grace_period_days);
+
+        self.enqueue_mail(to, None, &DEAD_TORRENT, locale, None, context)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to queue email: {e}");
+                ServiceError::FailedToSendEmail
+            })
+    }
+
+    /// Send an email with the user's `notifications`. It's used for the
+    /// immediate notification emails and for the digests. The email is added
+    /// to the mail queue.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if unable to queue the email.
+    pub async fn send_notification_mail(
+        &self,
+        to: &str,
+        username: &str,
+        user_id: UserId,
+        notifications: &[NotificationSummary],
+        locale: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        let notifications: Vec<Value> = notifications
+            .iter()
+            .map(|notification| {
+                json!({
+                    "description": notification
+                        .kind
+                        .parse::<NotificationKind>()
+                        .map_or(notification.kind.as_str(), |kind| kind.description()),
+                    "torrent_title": notification.torrent_title,
+                    "info_hash": notification.info_hash,
+                    "detail": notification.detail,
+                    "created_at": notification.created_at,
+                })
+            })
+            .collect();
+
+        let mut context = Context::new();
+        context.insert("username", username);
+        context.insert("notifications", &notifications);
+
+        self.enqueue_mail(to, Some(user_id), &NOTIFICATION, locale, None, context)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to queue email: {e}");
+                ServiceError::FailedToSendEmail
+            })
+    }
+
+    /// It renders an email from its templates and adds it to the mail queue.
+    /// The `site_name` and `base_url` variables are added to the template
+    /// `context`.
+    async fn enqueue_mail(
+        &self,
+        to: &str,
+        user_id: Option<UserId>,
+        template: &Template,
+        locale: Option<&str>,
+        api_base_url: Option<&str>,
+        mut context: Context,
+    ) -> Result<(), String> {
+        let settings = self.cfg.settings.read().await;
+
+        let templates_path = PathBuf::from(&settings.mail.templates_path);
+
+        context.insert("site_name", &settings.website.name);
+        context.insert(
+            "base_url",
+            settings.net.base_url.as_deref().or(api_base_url).unwrap_or_default(),
+        );
+
+        drop(settings);
+
+        let mail = templates::render(template, &templates_path, locale, &context)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let letter = build_letter(&mail, self.get_builder(to).await).map_err(|e| e.to_string())?;
+
+        self.mail_queue
+            .enqueue(template.kind, user_id, &mail.subject, &letter)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("{e:?}"))
+    }
+
+    async fn get_builder(&self, to: &str) -> MessageBuilder {
+        let settings = self.cfg.settings.read().await;
+
+        Message::builder()
+            .from(settings.mail.from.parse().unwrap())
+            .reply_to(settings.mail.reply_to.parse().unwrap())
+            .to(to.parse().unwrap())
+    }
+
+    async fn get_verification_url(&self, verification_token: &str, base_url: &str) -> String {
+        let settings = self.cfg.settings.read().await;
+
+        let mut base_url = &base_url.to_string();
+        if let Some(cfg_base_url) = &settings.net.base_url {
+            base_url = cfg_base_url;
+        }
+
+        format!("{base_url}/{API_VERSION_URL_PREFIX}/user/email/verify/{verification_token}")
+    }
+}
+
+/// It builds an email with the plain-text and the HTML parts.
+fn build_letter(mail: &RenderedMail, builder: MessageBuilder) -> Result<Message, lettre::error::Error> {
+    builder.subject(mail.subject.clone()).multipart(
+        MultiPart::alternative()
+            .singlepart(
+                SinglePart::builder()
+                    .header(lettre::message::header::ContentType::TEXT_PLAIN)
+                    .body(mail.text.clone()),
+            )
+            .singlepart(
+                SinglePart::builder()
+                    .header(lettre::message::header::ContentType::TEXT_HTML)
+                    .body(mail.html.clone()),
+            ),
+    )
+}
+
+#[derive(Debug, Display)]
+pub enum Error {
+    #[display(fmt = "SMTP error: {_0}")]
+    Smtp(lettre::transport::smtp::Error),
+    #[display(fmt = "sendmail error: {_0}")]
+    Sendmail(lettre::transport::sendmail::Error),
+    #[display(fmt = "failed to write the email: {_0}")]
+    File(io::Error),
+    #[display(fmt = "invalid email address: {_0}")]
+    Address(AddressError),
+    #[display(fmt = "invalid email envelope: {_0}")]
+    Envelope(lettre::error::Error),
+}
+
+/// The transport used to deliver emails. See [`MailTransport`].
+pub enum Mailer {
+    Smtp(AsyncSmtpTransport<Tokio1Executor>),
+    Sendmail(AsyncSendmailTransport<Tokio1Executor>),
+    /// Directory where the emails are written, in Maildir format.
+    File(PathBuf),
+    Stdout,
+}
+
+impl Mailer {
+    /// It delivers an already formatted email.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the transport fails to deliver
+    /// the email.
+    pub async fn send_raw(&self, envelope: &Envelope, mail: &[u8]) -> Result<(), Error> {
+        match self {
+            Self::Smtp(transport) => transport.send_raw(envelope, mail).await.map(|_| ()).map_err(Error::Smtp),
+            Self::Sendmail(transport) => transport.send_raw(envelope, mail).await.map_err(Error::Sendmail),
+            Self::File(path) => write_to_maildir(path, mail).await.map(|_| ()).map_err(Error::File),
+            Self::Stdout => {
+                log::info!("{}", String::from_utf8_lossy(mail));
+                Ok(())
+            }
+        }
+    }
+}
+
+/// It writes an email to a Maildir directory and returns the path of the
+/// email file.
+///
+/// The email is written to the `tmp` subdirectory first and then moved to
+/// the `new` subdirectory, so mail readers never see half-written emails.
+async fn write_to_maildir(path: &Path, mail: &[u8]) -> Result<PathBuf, io::Error> {
+    for subdirectory in ["tmp", "new", "cur"] {
+        fs::create_dir_all(path.join(subdirectory)).await?;
+    }
+
+    let file_name = format!("{}.{}.torrust-index", clock::now(), uuid::Uuid::new_v4().simple());
+
+    let temp_path = path.join("tmp").join(&file_name);
+    let new_path = path.join("new").join(&file_name);
+
+    fs::write(&temp_path, mail).await?;
+    fs::rename(&temp_path, &new_path).await?;
+
+    Ok(new_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use lettre::Message;
+    use serde_json::json;
+    use tempfile::TempDir;
+    use tera::Context;
+
+    use super::templates::{render, RenderedMail, DEAD_TORRENT, NOTIFICATION};
+    use super::{build_letter, write_to_maildir};
+
+    #[test]
+    fn it_should_build_a_letter() {
+        let builder = Message::builder()
+            .from("from@a.b.c".parse().unwrap())
+            .reply_to("reply@a.b.c".parse().unwrap())
+            .to("to@a.b.c".parse().unwrap());
+
+        let mail = RenderedMail {
+            subject: "Torrust - Email verification".to_string(),
+            text: "Welcome to Torrust, user!".to_string(),
+            html: "<p>Welcome to Torrust, user!</p>".to_string(),
+        };
+
+        let _letter = build_letter(&mail, builder).unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_should_build_the_dead_torrent_content() {
+        let dir = TempDir::new().unwrap();
+
+        let mut context = Context::new();
+        context.insert("site_name", "Torrust");
+        context.insert("base_url", "");
+        context.insert("username", "user");
+        context.insert("torrent_title", "Ubuntu");
+        context.insert("info_hash", "79fa9e4a2927804fe4feab488a76c8c2d3d1cdca");
+        context.insert("grace_period_days", &30);
+
+        let mail = render(&DEAD_TORRENT, dir.path(), None, &context).await.unwrap();
+
+        assert_eq!(mail.subject, "Torrust - Your torrent has no seeders");
+        assert!(mail.text.contains("\"Ubuntu\" (79fa9e4a2927804fe4feab488a76c8c2d3d1cdca)"));
+        assert!(mail.text.contains("next 30 days"));
+    }
+
+    #[tokio::test]
+    async fn it_should_build_the_notification_content() {
+        let dir = TempDir::new().unwrap();
+
+        let mut context = Context::new();
+        context.insert("site_name", "Torrust");
+        context.insert("base_url", "");
+        context.insert("username", "user");
+        context.insert(
+            "notifications",
+            &vec![
+                json!({"description": "Your torrent was edited by an administrator", "torrent_title": "Ubuntu", "info_hash": "79fa9e4a2927804fe4feab488a76c8c2d3d1cdca", "detail": "title", "created_at": "2024-02-14 10:00:00"}),
+                json!({"description": "Your tracker keys were revoked by an administrator", "torrent_title": null, "info_hash": null, "detail": null, "created_at": "2024-02-14 11:00:00"}),
+            ],
+        );
+
+        let mail = render(&NOTIFICATION, dir.path(), None, &context).await.unwrap();
+
+        assert_eq!(mail.subject, "Torrust - You have 2 new notifications");
+        assert!(mail.text.contains(
+            "- Your torrent was edited by an administrator: \"Ubuntu\" (79fa9e4a2927804fe4feab488a76c8c2d3d1cdca) - title"
+        ));
+        assert!(mail
+            .text
+            .contains("- Your tracker keys were revoked by an administrator [2024-02-14 11:00:00]"));
+    }
+
+    #[tokio::test]
+    async fn it_should_write_emails_to_a_maildir_directory() {
+        let dir = TempDir::new().unwrap();
+
+        let letter = Message::builder()
+            .from("from@a.b.c".parse().unwrap())
+            .to("to@a.b.c".parse().unwrap())
+            .subject("Hello")
+            .body(String::from("Hello world"))
+            .unwrap();
+
+        let path = write_to_maildir(dir.path(), &letter.formatted()).await.unwrap();
+
+        assert_eq!(path.parent().unwrap(), dir.path().join("new"));
+        assert!(std::fs::read_to_string(path).unwrap().contains("Subject: Hello"));
+        assert_eq!(std::fs::read_dir(dir.path().join("tmp")).unwrap().count(), 0);
+    }
+}
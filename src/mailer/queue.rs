@@ -0,0 +1,282 @@
+//! Persistent queue for outgoing emails.
+//!
+//! Emails are not sent while handling the user request. They are stored in
+//! the database and delivered in the background by the
+//! [`mail_queue_worker`](crate::console::cronjobs::mail_queue_worker), so a
+//! mail server that is temporarily unavailable does not lose any email.
+//!
+//! When the delivery fails the email is retried with an exponential backoff.
+//! After `mail_queue.max_attempts` failed attempts the email is marked as
+//! [`Failed`](crate::models::queued_mail::Status::Failed) and it's not retried
+//! until an administrator resends it.
+//!
+//! Delivered emails are marked as [`Sent`](crate::models::queued_mail::Status::Sent).
+//! If that fails after the mail server accepted the email, the email is
+//! delivered again on the next attempt: a duplicate email is preferred to a
+//! lost one. Sent and failed emails are deleted after
+//! `mail_queue.retention_days` days.
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use lettre::address::Envelope;
+use lettre::transport::smtp::authentication::{Credentials, Mechanism};
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{AsyncSendmailTransport, AsyncSmtpTransport, Message, Tokio1Executor};
+use log::{error, info, warn};
+use tokio::sync::Notify;
+
+use super::{Error, Mailer};
+use crate::config::{Configuration, MailTransport, SmtpTlsMode};
+use crate::databases::database::{self, Database};
+use crate::models::queued_mail::{Kind, MailId, NewMail, QueuedMail, Status};
+use crate::models::user::UserId;
+use crate::utils::backoff::backoff_seconds;
+
+const LOG_TARGET: &str = "Mail Queue";
+
+pub struct MailQueue {
+    cfg: Arc<Configuration>,
+    database: Arc<Box<dyn Database>>,
+    new_mail: Notify,
+}
+
+impl MailQueue {
+    #[must_use]
+    pub fn new(cfg: Arc<Configuration>, database: Arc<Box<dyn Database>>) -> Self {
+        Self {
+            cfg,
+            database,
+            new_mail: Notify::new(),
+        }
+    }
+
+    /// It adds an email to the queue.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the email can't be stored in the database.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the email does not have a sender or a recipient. Emails
+    /// built by the [`mailer`](crate::mailer) always have both.
+    pub async fn enqueue(
+        &self,
+        kind: Kind,
+        user_id: Option<UserId>,
+        subject: &str,
+        mail: &Message,
+    ) -> Result<MailId, database::Error> {
+        let envelope = mail.envelope();
+
+        let sender = envelope.from().expect("the email should have a sender").to_string();
+        let recipient = envelope.to().first().expect("the email should have a recipient").to_string();
+
+        let mail_id = self
+            .database
+            .insert_mail(
+                &NewMail {
+                    kind,
+                    user_id,
+                    sender: &sender,
+                    recipient: &recipient,
+                    subject,
+                    message: &mail.formatted(),
+                },
+                now(),
+            )
+            .await?;
+
+        // Wake up the worker so the email is delivered as soon as possible.
+        self.new_mail.notify_one();
+
+        Ok(mail_id)
+    }
+
+    /// It discards the emails of a given `kind` waiting to be delivered to a
+    /// user, for example, a previous verification email when a new one is
+    /// requested.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the database query failed.
+    pub async fn discard_for_user(&self, user_id: UserId, kind: Kind) -> Result<(), database::Error> {
+        self.database.delete_mails_for_user(user_id, kind.as_str()).await
+    }
+
+    /// It waits until a new email is enqueued.
+    pub async fn wait_for_new_mails(&self) {
+        self.new_mail.notified().await;
+    }
+
+    /// It returns all the queued emails, optionally filtered by status.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the database query failed.
+    pub async fn get_mails(&self, status: Option<Status>) -> Result<Vec<QueuedMail>, database::Error> {
+        self.database.get_mails(status.as_ref().map(Status::as_str)).await
+    }
+
+    /// It schedules an email to be delivered again immediately, resetting the
+    /// number of attempts.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the email does not exist or the database query
+    /// failed.
+    pub async fn resend(&self, mail_id: MailId) -> Result<(), database::Error> {
+        self.database.reset_mail(mail_id, now()).await?;
+
+        self.new_mail.notify_one();
+
+        Ok(())
+    }
+
+    /// It deletes a queued email, whatever its status.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the email does not exist or the database query
+    /// failed.
+    pub async fn delete(&self, mail_id: MailId) -> Result<(), database::Error> {
+        self.database.delete_mail(mail_id).await
+    }
+
+    /// It deletes the sent and failed emails older than
+    /// `mail_queue.retention_days` days. It returns the number of deleted
+    /// emails.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the database query failed.
+    pub async fn delete_finished_mails(&self) -> Result<u64, database::Error> {
+        let retention_days = self.cfg.settings.read().await.mail_queue.retention_days;
+
+        if retention_days == 0 {
+            return Ok(0);
+        }
+
+        self.database
+            .delete_finished_mails_before(now() - Duration::days(retention_days.into()))
+            .await
+    }
+
+    /// It delivers the pending emails that are due. It returns the number of
+    /// emails processed.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the emails can't be loaded from the database.
+    pub async fn process_due_mails(&self) -> Result<usize, database::Error> {
+        let batch_size = self.cfg.settings.read().await.mail_queue.batch_size;
+
+        let mails = self.database.get_due_mails(now(), batch_size).await?;
+
+        let number_of_mails = mails.len();
+
+        for mail in mails {
+            self.process(mail).await;
+        }
+
+        Ok(number_of_mails)
+    }
+
+    async fn process(&self, mail: QueuedMail) {
+        match self.deliver(&mail).await {
+            Ok(()) => {
+                info!(target: LOG_TARGET, "Email #{} {} to {} delivered", mail.mail_id, mail.kind, mail.recipient);
+
+                if let Err(e) = self.database.mark_mail_as_sent(mail.mail_id, now()).await {
+                    error!(target: LOG_TARGET, "Failed to mark delivered email #{} as sent, it will be delivered again: {e:?}", mail.mail_id);
+                }
+            }
+            Err(err) => self.record_failure(&mail, &err.to_string()).await,
+        }
+    }
+
+    async fn deliver(&self, mail: &QueuedMail) -> Result<(), Error> {
+        let envelope = Envelope::new(
+            Some(mail.sender.parse().map_err(Error::Address)?),
+            vec![mail.recipient.parse().map_err(Error::Address)?],
+        )
+        .map_err(Error::Envelope)?;
+
+        self.get_mailer().await?.send_raw(&envelope, &mail.message).await
+    }
+
+    async fn record_failure(&self, mail: &QueuedMail, err: &str) {
+        let settings = self.cfg.settings.read().await;
+        let max_attempts = i64::from(settings.mail_queue.max_attempts);
+        let initial_backoff_seconds = settings.mail_queue.initial_backoff_seconds;
+        let max_backoff_seconds = settings.mail_queue.max_backoff_seconds;
+        drop(settings);
+
+        let attempts = mail.attempts + 1;
+
+        let status = if attempts >= max_attempts {
+            error!(target: LOG_TARGET, "Email #{} {} to {} failed after {attempts} attempts: {err}", mail.mail_id, mail.kind, mail.recipient);
+            Status::Failed
+        } else {
+            warn!(target: LOG_TARGET, "Email #{} {} to {} failed (attempt {attempts}): {err}", mail.mail_id, mail.kind, mail.recipient);
+            Status::Pending
+        };
+
+        let delay = backoff_seconds(attempts, initial_backoff_seconds, max_backoff_seconds);
+
+        let next_attempt_at = now() + Duration::seconds(i64::try_from(delay).expect("delay should fit in i64"));
+
+        if let Err(e) = self
+            .database
+            .update_failed_mail(mail.mail_id, status.as_str(), attempts, err, next_attempt_at)
+            .await
+        {
+            error!(target: LOG_TARGET, "Failed to update email #{}: {e:?}", mail.mail_id);
+        }
+    }
+
+    /// It builds the transport with the current mail settings, so changes
+    /// in the settings are applied without restarting the application.
+    async fn get_mailer(&self) -> Result<Mailer, Error> {
+        let settings = self.cfg.settings.read().await;
+
+        match settings.mail.transport {
+            MailTransport::Smtp => {
+                let server = settings.mail.server.clone();
+
+                let tls = match settings.mail.smtp_tls {
+                    SmtpTlsMode::None => Tls::None,
+                    SmtpTlsMode::Opportunistic => Tls::Opportunistic(TlsParameters::new(server).map_err(Error::Smtp)?),
+                    SmtpTlsMode::StartTls => Tls::Required(TlsParameters::new(server).map_err(Error::Smtp)?),
+                    SmtpTlsMode::Tls => Tls::Wrapper(TlsParameters::new(server).map_err(Error::Smtp)?),
+                };
+
+                let mut builder = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&settings.mail.server)
+                    .port(settings.mail.port)
+                    .tls(tls);
+
+                if !settings.mail.username.is_empty() && !settings.mail.password.is_empty() {
+                    // SMTP authentication
+                    let creds = Credentials::new(settings.mail.username.clone(), settings.mail.password.clone());
+
+                    builder =
+                        builder
+                            .credentials(creds)
+                            .authentication(vec![Mechanism::Login, Mechanism::Xoauth2, Mechanism::Plain]);
+                }
+
+                Ok(Mailer::Smtp(builder.build()))
+            }
+            MailTransport::Sendmail => Ok(Mailer::Sendmail(AsyncSendmailTransport::<Tokio1Executor>::new_with_command(
+                &settings.mail.sendmail_command,
+            ))),
+            MailTransport::File => Ok(Mailer::File(PathBuf::from(&settings.mail.file_path))),
+            MailTransport::Stdout => Ok(Mailer::Stdout),
+        }
+    }
+}
+
+fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
@@ -0,0 +1,244 @@
+//! Email templates.
+//!
+//! Every email has three [Tera](https://keats.github.io/tera/) templates: the
+//! subject, the plain-text part and the HTML part. For example, for the
+//! verification email:
+//!
+//! ```text
+//! verify_email.subject.txt
+//! verify_email.txt
+//! verify_email.html
+//! ```
+//!
+//! The templates are embedded in the binary, but they can be overridden by
+//! files with the same name in the `mail.templates_path` directory. Templates
+//! are read every time an email is sent, so changes are applied without
+//! restarting the application.
+//!
+//! Templates can have variants for every language, selected from the user's
+//! preferred locale. For the locale `pt-BR`, these files are tried in order:
+//!
+//! ```text
+//! verify_email.pt-BR.html
+//! verify_email.pt.html
+//! verify_email.html
+//! ```
+//!
+//! All the templates can use the variables `site_name` (`website.name`) and
+//! `base_url` (`net.base_url`, or the URL used to reach the API if it is not
+//! set).
+use std::io;
+use std::path::Path;
+
+use derive_more::Display;
+use tera::{Context, Tera};
+use tokio::fs;
+
+use super::do_nothing_filter;
+use crate::models::queued_mail::Kind;
+
+/// An email template with its default contents.
+pub struct Template {
+    pub name: &'static str,
+    pub kind: Kind,
+    subject: &'static str,
+    text: &'static str,
+    html: &'static str,
+}
+
+/// The verification email sent on signup.
+///
+/// Variables: `username` and `verification_url`.
+pub const VERIFY_EMAIL: Template = Template {
+    name: "verify_email",
+    kind: Kind::VerifyEmail,
+    subject: include_str!("../../templates/mail/verify_email.subject.txt"),
+    text: include_str!("../../templates/mail/verify_email.txt"),
+    html: include_str!("../../templates/mail/verify_email.html"),
+};
+
+/// The email sent to the uploader when a torrent is marked as dead.
+///
+/// Variables: `username`, `torrent_title`, `info_hash` and
+/// `grace_period_days`.
+pub const DEAD_TORRENT: Template = Template {
+    name: "dead_torrent",
+    kind: Kind::DeadTorrent,
+    subject: include_str!("../../templates/mail/dead_torrent.subject.txt"),
+    text: include_str!("../../templates/mail/dead_torrent.txt"),
+    html: include_str!("../../templates/mail/dead_torrent.html"),
+};
+
+/// The email with the user's notifications. It's sent for every new
+/// notification or periodically as a digest, depending on the user's
+/// preferences.
+///
+/// Variables: `username` and `notifications`, a list of notifications with
+/// the `description`, `torrent_title`, `info_hash`, `detail` and `created_at`
+/// attributes. Only `description` and `created_at` are always set.
+pub const NOTIFICATION: Template = Template {
+    name: "notification",
+    kind: Kind::Notification,
+    subject: include_str!("../../templates/mail/notification.subject.txt"),
+    text: include_str!("../../templates/mail/notification.txt"),
+    html: include_str!("../../templates/mail/notification.html"),
+};
+
+#[derive(Debug, Display)]
+pub enum Error {
+    #[display(fmt = "failed to read the email template {_0}: {_1}")]
+    Read(String, io::Error),
+    #[display(fmt = "failed to render the email template {_0}: {_1}")]
+    Render(String, tera::Error),
+}
+
+/// A rendered email.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedMail {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// It renders the subject, the plain-text and the HTML parts of an email,
+/// using the templates in the `templates_path` directory if there are any.
+///
+/// # Errors
+///
+/// This function will return an error if a template can't be read or
+/// rendered.
+pub async fn render(
+    template: &Template,
+    templates_path: &Path,
+    locale: Option<&str>,
+    context: &Context,
+) -> Result<RenderedMail, Error> {
+    let subject = render_part(
+        template.name,
+        "subject.txt",
+        template.subject,
+        templates_path,
+        locale,
+        context,
+    )
+    .await?;
+    let text = render_part(template.name, "txt", template.text, templates_path, locale, context).await?;
+    let html = render_part(template.name, "html", template.html, templates_path, locale, context).await?;
+
+    Ok(RenderedMail {
+        subject: subject.trim().to_string(),
+        text,
+        html,
+    })
+}
+
+async fn render_part(
+    name: &str,
+    extension: &str,
+    default_source: &str,
+    templates_path: &Path,
+    locale: Option<&str>,
+    context: &Context,
+) -> Result<String, Error> {
+    let (file_name, source) = match find_override(name, extension, templates_path, locale).await? {
+        Some(found) => found,
+        None => (format!("{name}.{extension}"), default_source.to_string()),
+    };
+
+    let mut tera = Tera::default();
+
+    // Only the HTML part is escaped, because the template name ends with `.html`.
+    tera.autoescape_on(vec![".html"]);
+    tera.register_filter("do_nothing", do_nothing_filter);
+
+    tera.add_raw_template(&file_name, &source)
+        .and_then(|()| tera.render(&file_name, context))
+        .map_err(|err| Error::Render(file_name.clone(), err))
+}
+
+/// It finds the template file overriding the default template, trying the
+/// locale variants first.
+async fn find_override(
+    name: &str,
+    extension: &str,
+    templates_path: &Path,
+    locale: Option<&str>,
+) -> Result<Option<(String, String)>, Error> {
+    for file_name in candidate_file_names(name, extension, locale) {
+        match fs::read_to_string(templates_path.join(&file_name)).await {
+            Ok(source) => return Ok(Some((file_name, source))),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(Error::Read(file_name, err)),
+        }
+    }
+
+    Ok(None)
+}
+
+/// The template file names for a locale, from the most to the least
+/// specific. For example, for `pt-BR`: `name.pt-BR.html`, `name.pt.html` and
+/// `name.html`.
+fn candidate_file_names(name: &str, extension: &str, locale: Option<&str>) -> Vec<String> {
+    let mut file_names = vec![];
+
+    if let Some(locale) = locale {
+        let subtags: Vec<&str> = locale.split('-').collect();
+
+        for len in (1..=subtags.len()).rev() {
+            file_names.push(format!("{name}.{}.{extension}", subtags[..len].join("-")));
+        }
+    }
+
+    file_names.push(format!("{name}.{extension}"));
+
+    file_names
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+    use tera::Context;
+
+    use super::{candidate_file_names, render, VERIFY_EMAIL};
+
+    fn context() -> Context {
+        let mut context = Context::new();
+        context.insert("site_name", "Torrust");
+        context.insert("base_url", "https://index.torrust.com");
+        context.insert("username", "<user>");
+        context.insert("verification_url", "https://index.torrust.com/v1/user/email/verify/TOKEN");
+        context
+    }
+
+    #[test]
+    fn it_should_try_the_most_specific_locale_first() {
+        assert_eq!(
+            candidate_file_names("verify_email", "html", Some("pt-BR")),
+            vec!["verify_email.pt-BR.html", "verify_email.pt.html", "verify_email.html"]
+        );
+        assert_eq!(candidate_file_names("verify_email", "txt", None), vec!["verify_email.txt"]);
+    }
+
+    #[tokio::test]
+    async fn it_should_render_the_default_templates() {
+        let dir = TempDir::new().unwrap();
+
+        let mail = render(&VERIFY_EMAIL, dir.path(), None, &context()).await.unwrap();
+
+        assert_eq!(mail.subject, "Torrust - Email verification");
+        assert!(mail.text.contains("Welcome to Torrust, <user>!"));
+        assert!(mail.html.contains("Welcome to Torrust, &lt;user&gt;."));
+    }
+
+    #[tokio::test]
+    async fn it_should_use_the_templates_overridden_for_the_user_locale() {
+        let dir = TempDir::new().unwrap();
+
+        std::fs::write(dir.path().join("verify_email.es.txt"), "¡Bienvenido a {{ site_name }}!").unwrap();
+
+        let mail = render(&VERIFY_EMAIL, dir.path(), Some("es-AR"), &context()).await.unwrap();
+
+        assert_eq!(mail.text, "¡Bienvenido a Torrust!");
+        assert_eq!(mail.subject, "Torrust - Email verification");
+    }
+}
@@ -0,0 +1,64 @@
+//! Images uploaded by users.
+//!
+//! Uploaded images can be used as torrent posters and screenshots, or as
+//! user avatars. The image files are stored in the `image_uploads.storage_path`
+//! directory, named after the SHA-1 hash of their contents.
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[allow(clippy::module_name_repetitions)]
+pub type ImageId = i64;
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, FromRow)]
+pub struct Image {
+    pub image_id: ImageId,
+    /// The user who uploaded the image.
+    pub user_id: i64,
+    /// SHA-1 hash of the image file.
+    pub content_hash: String,
+    pub content_type: String,
+    /// Size of the image file in bytes.
+    pub size: i64,
+    pub width: i64,
+    pub height: i64,
+    pub date_uploaded: String,
+}
+
+impl Image {
+    /// The API path where the image is served.
+    #[must_use]
+    pub fn path(&self) -> String {
+        path(self.image_id)
+    }
+}
+
+/// The API path where the image with `image_id` is served.
+#[must_use]
+pub fn path(image_id: ImageId) -> String {
+    format!("/v1/images/{image_id}")
+}
+
+/// How an image is used by a torrent.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum TorrentImageType {
+    Poster,
+    Screenshot,
+}
+
+impl TorrentImageType {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TorrentImageType::Poster => "Poster",
+            TorrentImageType::Screenshot => "Screenshot",
+        }
+    }
+}
+
+/// An image attached to a torrent.
+#[derive(Debug, PartialEq, Eq, Clone, FromRow)]
+pub struct DbTorrentImage {
+    pub image_type: String,
+    #[sqlx(flatten)]
+    pub image: Image,
+}
@@ -0,0 +1,25 @@
+//! Usage of the image cache proxy quota by a user.
+//!
+//! Users have a quota for the images they proxy through the index. The
+//! quota usage is stored in the database, so it's not reset when the index
+//! is restarted.
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::user::UserId;
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct DbImageCacheQuota {
+    pub user_id: UserId,
+    /// Bytes downloaded by the user in the current quota period.
+    pub usage_bytes: i64,
+    /// Part of the `usage_bytes` spent on resized variants of the images.
+    pub variant_usage_bytes: i64,
+    /// The `variant_usage_bytes` of every variant, as a JSON object with the
+    /// variant as key, for example, `{"200x2048:WebP":1024}`.
+    pub variant_usages: Option<String>,
+    /// When the current quota period started, in seconds since the Unix
+    /// epoch.
+    pub date_start_secs: i64,
+}
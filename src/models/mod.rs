@@ -1,8 +1,14 @@
 pub mod category;
+pub mod image;
+pub mod image_cache_quota;
 pub mod info_hash;
+pub mod notification;
+pub mod queued_mail;
 pub mod response;
 pub mod torrent;
 pub mod torrent_file;
 pub mod torrent_tag;
+pub mod tracker_job;
 pub mod tracker_key;
+pub mod tracker_mode_sync;
 pub mod user;
@@ -0,0 +1,200 @@
+//! Notifications for users.
+//!
+//! Users are notified when something happens to their torrents or their
+//! account that they did not do themselves, for example, when an
+//! administrator edits or deletes one of their torrents.
+//!
+//! Notifications are delivered according to the user's
+//! [`NotificationPreferences`]: in the app, in a periodic email digest or in
+//! an immediate email.
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::torrent::TorrentId;
+use super::user::UserId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type NotificationId = i64;
+
+/// The event that generated the notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Kind {
+    /// An administrator edited the user's torrent.
+    TorrentEdited,
+    /// An administrator deleted the user's torrent.
+    TorrentDeleted,
+    /// The category of the user's torrent was deleted.
+    TorrentCategoryRemoved,
+    /// The user's torrent does not have seeders anymore.
+    TorrentLostSeeders,
+    /// An administrator revoked the user's tracker keys.
+    TrackerKeysRevoked,
+}
+
+impl Kind {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Kind::TorrentEdited => "TorrentEdited",
+            Kind::TorrentDeleted => "TorrentDeleted",
+            Kind::TorrentCategoryRemoved => "TorrentCategoryRemoved",
+            Kind::TorrentLostSeeders => "TorrentLostSeeders",
+            Kind::TrackerKeysRevoked => "TrackerKeysRevoked",
+        }
+    }
+
+    /// A short description of the event, used in the notification emails.
+    #[must_use]
+    pub fn description(&self) -> &'static str {
+        match self {
+            Kind::TorrentEdited => "Your torrent was edited by an administrator",
+            Kind::TorrentDeleted => "Your torrent was deleted by an administrator",
+            Kind::TorrentCategoryRemoved => "The category of your torrent was removed",
+            Kind::TorrentLostSeeders => "Your torrent does not have seeders anymore",
+            Kind::TrackerKeysRevoked => "Your tracker keys were revoked by an administrator",
+        }
+    }
+}
+
+impl FromStr for Kind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "TorrentEdited" => Ok(Kind::TorrentEdited),
+            "TorrentDeleted" => Ok(Kind::TorrentDeleted),
+            "TorrentCategoryRemoved" => Ok(Kind::TorrentCategoryRemoved),
+            "TorrentLostSeeders" => Ok(Kind::TorrentLostSeeders),
+            "TrackerKeysRevoked" => Ok(Kind::TrackerKeysRevoked),
+            _ => Err(format!("unknown notification kind: {s}")),
+        }
+    }
+}
+
+/// A new notification for a user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewNotification {
+    pub kind: Kind,
+    pub torrent_id: Option<TorrentId>,
+    pub info_hash: Option<String>,
+    pub torrent_title: Option<String>,
+    /// Extra information about the event, for example, the name of the
+    /// removed category.
+    pub detail: Option<String>,
+}
+
+impl NewNotification {
+    /// A notification that is not about a torrent.
+    #[must_use]
+    pub fn new(kind: Kind) -> Self {
+        Self {
+            kind,
+            torrent_id: None,
+            info_hash: None,
+            torrent_title: None,
+            detail: None,
+        }
+    }
+
+    /// A notification about one of the user's torrents.
+    #[must_use]
+    pub fn for_torrent(kind: Kind, torrent_id: TorrentId, info_hash: &str, torrent_title: &str) -> Self {
+        Self {
+            kind,
+            torrent_id: Some(torrent_id),
+            info_hash: Some(info_hash.to_owned()),
+            torrent_title: Some(torrent_title.to_owned()),
+            detail: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_detail(mut self, detail: &str) -> Self {
+        self.detail = Some(detail.to_owned());
+        self
+    }
+
+    /// The content of the notification shown in the emails, for a
+    /// notification generated at `created_at`.
+    #[must_use]
+    pub fn summary(&self, created_at: &str) -> NotificationSummary {
+        NotificationSummary {
+            kind: self.kind.as_str().to_owned(),
+            torrent_title: self.torrent_title.clone(),
+            info_hash: self.info_hash.clone(),
+            detail: self.detail.clone(),
+            created_at: created_at.to_owned(),
+        }
+    }
+}
+
+/// The content of a notification shown in the notification emails.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationSummary {
+    pub kind: String,
+    pub torrent_title: Option<String>,
+    pub info_hash: Option<String>,
+    pub detail: Option<String>,
+    pub created_at: String,
+}
+
+impl From<Notification> for NotificationSummary {
+    fn from(notification: Notification) -> Self {
+        Self {
+            kind: notification.kind,
+            torrent_title: notification.torrent_title,
+            info_hash: notification.info_hash,
+            detail: notification.detail,
+            created_at: notification.created_at,
+        }
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct Notification {
+    pub notification_id: NotificationId,
+    pub kind: String,
+    pub torrent_id: Option<TorrentId>,
+    pub info_hash: Option<String>,
+    pub torrent_title: Option<String>,
+    pub detail: Option<String>,
+    pub is_read: bool,
+    pub created_at: String,
+}
+
+/// How the user wants to receive the notifications.
+#[allow(clippy::module_name_repetitions)]
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct NotificationPreferences {
+    /// Whether notifications are listed in the app.
+    pub in_app: bool,
+    /// Whether notifications are sent in a periodic email digest.
+    pub email_digest: bool,
+    /// Whether an email is sent as soon as a notification is generated. It
+    /// takes precedence over the email digest.
+    pub immediate_email: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            in_app: true,
+            email_digest: false,
+            immediate_email: false,
+        }
+    }
+}
+
+/// A torrent with its uploader, the user who gets the notifications about it.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct UploadedTorrent {
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+    pub title: String,
+    pub uploader_id: UserId,
+}
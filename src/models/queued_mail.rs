@@ -0,0 +1,118 @@
+//! Emails waiting to be delivered.
+//!
+//! Outgoing emails are stored in the database and delivered in the background
+//! by the [`MailQueue`](crate::mailer::queue::MailQueue), so they are not lost
+//! when the mail server is not available.
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::user::UserId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type MailId = i64;
+
+/// The email template used to build the email.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Kind {
+    /// The email with the link to verify the user's email address.
+    VerifyEmail,
+    /// The email sent to the uploader when a torrent is marked as dead.
+    DeadTorrent,
+    /// The email with the user's notifications, sent immediately or as a
+    /// digest.
+    Notification,
+}
+
+impl Kind {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Kind::VerifyEmail => "VerifyEmail",
+            Kind::DeadTorrent => "DeadTorrent",
+            Kind::Notification => "Notification",
+        }
+    }
+}
+
+impl FromStr for Kind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "VerifyEmail" => Ok(Kind::VerifyEmail),
+            "DeadTorrent" => Ok(Kind::DeadTorrent),
+            "Notification" => Ok(Kind::Notification),
+            _ => Err(format!("unknown mail kind: {s}")),
+        }
+    }
+}
+
+/// The state of the email.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Status {
+    /// The email is waiting to be delivered or retried.
+    Pending,
+    /// The email was delivered. It's kept for `mail_queue.retention_days`
+    /// days.
+    Sent,
+    /// The email reached the maximum number of attempts. It will not be
+    /// retried unless an administrator resends it. It's kept for
+    /// `mail_queue.retention_days` days.
+    Failed,
+}
+
+impl Status {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Pending => "Pending",
+            Status::Sent => "Sent",
+            Status::Failed => "Failed",
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Pending" => Ok(Status::Pending),
+            "Sent" => Ok(Status::Sent),
+            "Failed" => Ok(Status::Failed),
+            _ => Err(format!("unknown mail status: {s}")),
+        }
+    }
+}
+
+/// A new email to be added to the queue.
+pub struct NewMail<'a> {
+    pub kind: Kind,
+    pub user_id: Option<UserId>,
+    pub sender: &'a str,
+    pub recipient: &'a str,
+    pub subject: &'a str,
+    /// The whole email, with headers, as it's sent to the mail server.
+    pub message: &'a [u8],
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct QueuedMail {
+    pub mail_id: MailId,
+    pub kind: String,
+    pub user_id: Option<UserId>,
+    pub sender: String,
+    pub recipient: String,
+    pub subject: String,
+    #[serde(skip)]
+    pub message: Vec<u8>,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
@@ -2,7 +2,8 @@ use serde::{Deserialize, Serialize};
 
 use super::torrent::TorrentId;
 use crate::databases::database::Category;
-use crate::models::torrent::TorrentListing;
+use crate::models::image::{DbTorrentImage, Image, ImageId, TorrentImageType};
+use crate::models::torrent::{TorrentListing, TorrentTrackerStats};
 use crate::models::torrent_file::TorrentFile;
 use crate::models::torrent_tag::TorrentTag;
 
@@ -44,6 +45,57 @@ pub struct DeletedTorrentResponse {
     pub info_hash: String,
 }
 
+#[allow(clippy::module_name_repetitions)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ImageResponse {
+    pub image_id: ImageId,
+    /// The API path where the image is served. For example: `/v1/images/1`.
+    pub url: String,
+    pub content_type: String,
+    pub size: i64,
+    pub width: i64,
+    pub height: i64,
+    pub date_uploaded: String,
+}
+
+impl From<Image> for ImageResponse {
+    fn from(image: Image) -> Self {
+        Self {
+            url: image.path(),
+            image_id: image.image_id,
+            content_type: image.content_type,
+            size: image.size,
+            width: image.width,
+            height: image.height,
+            date_uploaded: image.date_uploaded,
+        }
+    }
+}
+
+/// The images attached to a torrent.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct TorrentImagesResponse {
+    pub poster: Option<ImageResponse>,
+    pub screenshots: Vec<ImageResponse>,
+}
+
+impl From<Vec<DbTorrentImage>> for TorrentImagesResponse {
+    fn from(torrent_images: Vec<DbTorrentImage>) -> Self {
+        let mut images = Self::default();
+
+        for torrent_image in torrent_images {
+            if torrent_image.image_type == TorrentImageType::Poster.as_str() {
+                images.poster = Some(ImageResponse::from(torrent_image.image));
+            } else {
+                images.screenshots.push(ImageResponse::from(torrent_image.image));
+            }
+        }
+
+        images
+    }
+}
+
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct TorrentResponse {
@@ -57,8 +109,24 @@ pub struct TorrentResponse {
     pub file_size: i64,
     pub seeders: i64,
     pub leechers: i64,
+    pub total_seeders: i64,
+    pub total_leechers: i64,
+    /// Number of times the torrent file was downloaded from the index.
+    pub times_downloaded: i64,
+    /// Maximum number of completed downloads reported by a single tracker.
+    pub times_completed: i64,
+    /// Statistics from each tracker.
+    pub tracker_stats: Vec<TorrentTrackerStats>,
     pub files: Vec<TorrentFile>,
     pub trackers: Vec<String>,
+    /// Web seed URLs. See [BEP 19](https://www.bittorrent.org/beps/bep_0019.html).
+    pub web_seeds: Vec<String>,
+    /// HTTP seed URLs. See [BEP 17](https://www.bittorrent.org/beps/bep_0017.html).
+    pub http_seeds: Vec<String>,
+    /// Uploaded poster image.
+    pub poster: Option<ImageResponse>,
+    /// Uploaded screenshots, in display order.
+    pub screenshots: Vec<ImageResponse>,
     pub magnet_link: String,
     pub tags: Vec<TorrentTag>,
     pub name: String,
@@ -66,6 +134,8 @@ pub struct TorrentResponse {
     pub creation_date: Option<i64>,
     pub created_by: Option<String>,
     pub encoding: Option<String>,
+    /// When the torrent was marked as dead. `None` if the torrent is alive.
+    pub dead_since: Option<String>,
 }
 
 impl TorrentResponse {
@@ -82,8 +152,17 @@ impl TorrentResponse {
             file_size: torrent_listing.file_size,
             seeders: torrent_listing.seeders,
             leechers: torrent_listing.leechers,
+            total_seeders: torrent_listing.total_seeders,
+            total_leechers: torrent_listing.total_leechers,
+            times_downloaded: torrent_listing.times_downloaded,
+            times_completed: torrent_listing.times_completed,
+            tracker_stats: vec![],
             files: vec![],
             trackers: vec![],
+            web_seeds: vec![],
+            http_seeds: vec![],
+            poster: None,
+            screenshots: vec![],
             magnet_link: String::new(),
             tags: vec![],
             name: torrent_listing.name,
@@ -91,6 +170,33 @@ impl TorrentResponse {
             creation_date: torrent_listing.creation_date,
             created_by: torrent_listing.created_by,
             encoding: torrent_listing.encoding,
+            dead_since: torrent_listing.dead_since,
+        }
+    }
+
+    /// It sets the statistics from each tracker and updates the aggregated
+    /// seeders, leechers and completed downloads.
+    pub fn set_tracker_stats(&mut self, tracker_stats: Vec<TorrentTrackerStats>) {
+        self.seeders = tracker_stats.iter().map(|stats| stats.seeders).max().unwrap_or(0);
+        self.leechers = tracker_stats.iter().map(|stats| stats.leechers).max().unwrap_or(0);
+        self.total_seeders = tracker_stats.iter().map(|stats| stats.seeders).sum();
+        self.total_leechers = tracker_stats.iter().map(|stats| stats.leechers).sum();
+        self.times_completed = tracker_stats.iter().map(|stats| stats.completed).max().unwrap_or(0);
+        self.tracker_stats = tracker_stats;
+    }
+
+    pub fn set_images(&mut self, images: TorrentImagesResponse) {
+        self.poster = images.poster;
+        self.screenshots = images.screenshots;
+    }
+
+    /// It adds the tracker URLs at the end of the tracker list, skipping the
+    /// ones already included.
+    pub fn include_urls_as_extra_trackers(&mut self, tracker_urls: &[String]) {
+        for tracker_url in tracker_urls {
+            if !self.trackers.contains(tracker_url) {
+                self.trackers.push(tracker_url.clone());
+            }
         }
     }
 
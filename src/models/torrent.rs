@@ -21,13 +21,64 @@ pub struct TorrentListing {
     pub category_id: Option<i64>,
     pub date_uploaded: String,
     pub file_size: i64,
+    /// Maximum number of seeders reported by a single tracker.
     pub seeders: i64,
+    /// Maximum number of leechers reported by a single tracker.
     pub leechers: i64,
+    /// Sum of the seeders reported by all the trackers. Peers connected to
+    /// more than one tracker are counted more than once.
+    pub total_seeders: i64,
+    /// Sum of the leechers reported by all the trackers.
+    pub total_leechers: i64,
+    /// Number of times the torrent file was downloaded from the index.
+    pub times_downloaded: i64,
+    /// Maximum number of completed downloads reported by a single tracker.
+    pub times_completed: i64,
     pub name: String,
     pub comment: Option<String>,
     pub creation_date: Option<i64>,
     pub created_by: Option<String>,
     pub encoding: Option<String>,
+    /// When the torrent was marked as dead because it had no seeders for a
+    /// long time. `None` if the torrent is alive.
+    pub dead_since: Option<String>,
+}
+
+/// Torrent statistics imported from one tracker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TorrentTrackerStats {
+    pub tracker_url: String,
+    pub seeders: i64,
+    pub leechers: i64,
+    /// Number of completed downloads reported by the tracker. It's `0` if
+    /// importing them is disabled.
+    pub completed: i64,
+}
+
+/// A snapshot of the torrent statistics taken after importing them from the
+/// trackers. Like in the torrent listing, the seeders and leechers are the
+/// maximum reported by a single tracker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TorrentStatsSnapshot {
+    pub seeders: i64,
+    pub leechers: i64,
+    pub created_at: String,
+}
+
+/// A torrent affected by the dead torrent policy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DeadTorrent {
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+    pub title: String,
+    pub uploader: String,
+    #[serde(skip)]
+    pub uploader_email: Option<String>,
+    #[serde(skip)]
+    pub uploader_email_verified: bool,
+    #[serde(skip)]
+    pub uploader_locale: Option<String>,
+    pub dead_since: Option<String>,
 }
 
 #[derive(Debug, Display, PartialEq, Eq, Error)]
@@ -1,10 +1,11 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_bencode::ser;
 use serde_bytes::ByteBuf;
 use sha1::{Digest, Sha1};
 
 use super::info_hash::InfoHash;
 use crate::utils::hex::{from_bytes, into_bytes};
+use crate::utils::validation::validate_web_seed_url;
 
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub struct Torrent {
@@ -15,8 +16,14 @@ pub struct Torrent {
     pub nodes: Option<Vec<(String, i64)>>,
     #[serde(default)]
     pub encoding: Option<String>,
+    /// HTTP seeds. See [BEP 17](https://www.bittorrent.org/beps/bep_0017.html).
     #[serde(default)]
     pub httpseeds: Option<Vec<String>>,
+    /// Web seeds. See [BEP 19](https://www.bittorrent.org/beps/bep_0019.html).
+    #[serde(default)]
+    #[serde(rename = "url-list")]
+    #[serde(deserialize_with = "deserialize_url_list")]
+    pub url_list: Option<Vec<String>>,
     #[serde(default)]
     #[serde(rename = "announce-list")]
     pub announce_list: Option<Vec<Vec<String>>>,
@@ -62,6 +69,24 @@ pub struct TorrentFile {
     pub md5sum: Option<String>,
 }
 
+/// The `url-list` can be a single URL or a list of URLs.
+fn deserialize_url_list<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum UrlList {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(Some(match UrlList::deserialize(deserializer)? {
+        UrlList::One(url) => vec![url],
+        UrlList::Many(urls) => urls,
+    }))
+}
+
 impl Torrent {
     /// It hydrates a `Torrent` struct from the database data.
     ///
@@ -70,7 +95,12 @@ impl Torrent {
     /// This function will panic if the `torrent_info.pieces` is not a valid
     /// hex string.
     #[must_use]
-    pub fn from_database(db_torrent: &DbTorrent, torrent_files: &[TorrentFile], torrent_announce_urls: Vec<Vec<String>>) -> Self {
+    pub fn from_database(
+        db_torrent: &DbTorrent,
+        torrent_files: &[TorrentFile],
+        torrent_announce_urls: Vec<Vec<String>>,
+        torrent_web_seeds: &[DbTorrentWebSeed],
+    ) -> Self {
         let info_dict = TorrentInfoDictionary::with(
             &db_torrent.name,
             db_torrent.piece_length,
@@ -85,7 +115,8 @@ impl Torrent {
             announce: None,
             nodes: None,
             encoding: db_torrent.encoding.clone(),
-            httpseeds: None,
+            httpseeds: web_seeds_of_type(torrent_web_seeds, WebSeedType::HttpSeed),
+            url_list: web_seeds_of_type(torrent_web_seeds, WebSeedType::UrlList),
             announce_list: Some(torrent_announce_urls),
             creation_date: db_torrent.creation_date,
             comment: db_torrent.comment.clone(),
@@ -132,6 +163,45 @@ impl Torrent {
         }
     }
 
+    /// Adds the tracker URLs to the end of the `announce_list`, each one in a
+    /// new tier. URLs already in the list are skipped.
+    ///
+    /// Private torrents are not changed because they must only be announced
+    /// to the index tracker.
+    pub fn add_urls_to_back_of_announce_list(&mut self, tracker_urls: &[String]) {
+        if self.is_private() || tracker_urls.is_empty() {
+            return;
+        }
+
+        // Keep the main tracker as the first tier when there was no list.
+        let list = self
+            .announce_list
+            .get_or_insert_with(|| self.announce.iter().map(|url| vec![url.clone()]).collect());
+
+        for tracker_url in tracker_urls {
+            if !list.iter().flatten().any(|url| url == tracker_url) {
+                list.push(vec![tracker_url.clone()]);
+            }
+        }
+    }
+
+    /// It removes the invalid and duplicate web seed and HTTP seed URLs.
+    pub fn retain_valid_web_seeds(&mut self) {
+        for seeds in [&mut self.url_list, &mut self.httpseeds] {
+            if let Some(urls) = seeds {
+                let mut valid_urls: Vec<String> = vec![];
+
+                for url in urls.drain(..) {
+                    if validate_web_seed_url(&url) && !valid_urls.contains(&url) {
+                        valid_urls.push(url);
+                    }
+                }
+
+                *seeds = if valid_urls.is_empty() { None } else { Some(valid_urls) };
+            }
+        }
+    }
+
     /// Removes all other trackers if the torrent is private.
     pub fn reset_announce_list_if_private(&mut self) {
         if self.is_private() {
@@ -331,6 +401,13 @@ pub struct DbTorrent {
     pub encoding: Option<String>,
 }
 
+impl DbTorrent {
+    #[must_use]
+    pub fn is_private(&self) -> bool {
+        self.private == Some(1)
+    }
+}
+
 #[allow(clippy::module_name_repetitions)]
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct DbTorrentFile {
@@ -345,6 +422,48 @@ pub struct DbTorrentAnnounceUrl {
     pub tracker_url: String,
 }
 
+/// The torrent file key a web seed URL is stored in.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum WebSeedType {
+    /// The `url-list` key. See [BEP 19](https://www.bittorrent.org/beps/bep_0019.html).
+    UrlList,
+    /// The `httpseeds` key. See [BEP 17](https://www.bittorrent.org/beps/bep_0017.html).
+    HttpSeed,
+}
+
+impl WebSeedType {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebSeedType::UrlList => "UrlList",
+            WebSeedType::HttpSeed => "HttpSeed",
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DbTorrentWebSeed {
+    pub seed_type: String,
+    pub url: String,
+}
+
+/// It returns the URLs of the web seeds of a type, or `None` if there are
+/// none.
+#[must_use]
+pub fn web_seeds_of_type(web_seeds: &[DbTorrentWebSeed], seed_type: WebSeedType) -> Option<Vec<String>> {
+    let urls: Vec<String> = web_seeds
+        .iter()
+        .filter(|web_seed| web_seed.seed_type == seed_type.as_str())
+        .map(|web_seed| web_seed.url.clone())
+        .collect();
+
+    if urls.is_empty() {
+        None
+    } else {
+        Some(urls)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -415,6 +534,7 @@ mod tests {
                 nodes: None,
                 encoding: None,
                 httpseeds: None,
+                url_list: None,
             };
 
             assert_eq!(torrent.canonical_info_hash_hex(), "79fa9e4a2927804fe4feab488a76c8c2d3d1cdca");
@@ -456,6 +576,7 @@ mod tests {
                     nodes: None,
                     encoding: None,
                     httpseeds: None,
+                    url_list: None,
                 };
 
                 assert_eq!(torrent.canonical_info_hash_hex(), "79fa9e4a2927804fe4feab488a76c8c2d3d1cdca");
@@ -495,6 +616,7 @@ mod tests {
                     nodes: None,
                     encoding: None,
                     httpseeds: None,
+                    url_list: None,
                 };
 
                 assert_eq!(torrent.canonical_info_hash_hex(), "aa2aca91ab650c4d249c475ca3fa604f2ccb0d2a");
@@ -530,6 +652,7 @@ mod tests {
                     nodes: None,
                     encoding: None,
                     httpseeds: None,
+                    url_list: None,
                 };
 
                 assert_eq!(torrent.canonical_info_hash_hex(), "ccc1cf4feb59f3fa85c96c9be1ebbafcfe8a9cc8");
@@ -565,10 +688,164 @@ mod tests {
                     nodes: None,
                     encoding: None,
                     httpseeds: None,
+                    url_list: None,
                 };
 
                 assert_eq!(torrent.canonical_info_hash_hex(), "d3a558d0a19aaa23ba6f9f430f40924d10fefa86");
             }
         }
     }
+
+    mod announce_list {
+
+        use crate::models::torrent_file::{Torrent, TorrentInfoDictionary};
+
+        fn sample_torrent(private: Option<u8>, announce_list: Option<Vec<Vec<String>>>) -> Torrent {
+            Torrent {
+                info: TorrentInfoDictionary {
+                    name: "sample.txt".to_string(),
+                    pieces: None,
+                    piece_length: 16384,
+                    md5sum: None,
+                    length: Some(11),
+                    files: None,
+                    private,
+                    path: None,
+                    root_hash: None,
+                    source: None,
+                },
+                announce: Some("udp://index.tracker:6969".to_string()),
+                announce_list,
+                creation_date: None,
+                comment: None,
+                created_by: None,
+                nodes: None,
+                encoding: None,
+                httpseeds: None,
+                url_list: None,
+            }
+        }
+
+        #[test]
+        fn extra_trackers_should_be_added_to_the_end_of_the_announce_list_without_duplicates() {
+            let mut torrent = sample_torrent(
+                None,
+                Some(vec![
+                    vec!["udp://index.tracker:6969".to_string()],
+                    vec!["udp://extra.tracker:6969".to_string()],
+                ]),
+            );
+
+            torrent.add_urls_to_back_of_announce_list(&[
+                "udp://extra.tracker:6969".to_string(),
+                "udp://other.tracker:6969".to_string(),
+            ]);
+
+            assert_eq!(
+                torrent.announce_list,
+                Some(vec![
+                    vec!["udp://index.tracker:6969".to_string()],
+                    vec!["udp://extra.tracker:6969".to_string()],
+                    vec!["udp://other.tracker:6969".to_string()],
+                ])
+            );
+        }
+
+        #[test]
+        fn the_announce_list_should_start_with_the_main_tracker_when_the_torrent_did_not_have_one() {
+            let mut torrent = sample_torrent(None, None);
+
+            torrent.add_urls_to_back_of_announce_list(&["udp://extra.tracker:6969".to_string()]);
+
+            assert_eq!(
+                torrent.announce_list,
+                Some(vec![
+                    vec!["udp://index.tracker:6969".to_string()],
+                    vec!["udp://extra.tracker:6969".to_string()],
+                ])
+            );
+        }
+
+        #[test]
+        fn extra_trackers_should_not_be_added_to_private_torrents() {
+            let mut torrent = sample_torrent(Some(1), None);
+
+            torrent.add_urls_to_back_of_announce_list(&["udp://extra.tracker:6969".to_string()]);
+
+            assert_eq!(torrent.announce_list, None);
+        }
+    }
+    mod web_seeds {
+        use crate::models::torrent_file::{Torrent, TorrentInfoDictionary};
+
+        fn sample_torrent(url_list: Option<Vec<String>>, httpseeds: Option<Vec<String>>) -> Torrent {
+            Torrent {
+                info: TorrentInfoDictionary {
+                    name: "sample.txt".to_string(),
+                    pieces: None,
+                    piece_length: 16384,
+                    md5sum: None,
+                    length: Some(11),
+                    files: None,
+                    private: None,
+                    path: None,
+                    root_hash: None,
+                    source: None,
+                },
+                announce: Some("udp://index.tracker:6969".to_string()),
+                announce_list: None,
+                creation_date: None,
+                comment: None,
+                created_by: None,
+                nodes: None,
+                encoding: None,
+                httpseeds,
+                url_list,
+            }
+        }
+
+        #[test]
+        fn the_url_list_should_be_parsed_from_a_list_of_urls() {
+            let torrent = sample_torrent(Some(vec!["https://seed.example.com/files/".to_string()]), None);
+
+            let bytes = serde_bencode::to_bytes(&torrent).unwrap();
+
+            let parsed_torrent: Torrent = serde_bencode::from_bytes(&bytes).unwrap();
+
+            assert_eq!(parsed_torrent, torrent);
+        }
+
+        #[test]
+        fn the_url_list_should_be_parsed_from_a_single_url() {
+            let bytes = serde_bencode::to_bytes(&sample_torrent(None, None)).unwrap();
+
+            // Add the `url-list` key as a string at the end of the dictionary.
+            let mut bytes = bytes[..bytes.len() - 1].to_vec();
+            bytes.extend_from_slice(b"8:url-list31:https://seed.example.com/files/e");
+
+            let parsed_torrent: Torrent = serde_bencode::from_bytes(&bytes).unwrap();
+
+            assert_eq!(
+                parsed_torrent.url_list,
+                Some(vec!["https://seed.example.com/files/".to_string()])
+            );
+        }
+
+        #[test]
+        fn invalid_and_duplicate_web_seeds_should_be_removed() {
+            let mut torrent = sample_torrent(
+                Some(vec![
+                    "https://seed.example.com/files/".to_string(),
+                    "not a url".to_string(),
+                    "https://seed.example.com/files/".to_string(),
+                ]),
+                Some(vec!["ftp://seed.example.com/seed".to_string()]),
+            );
+
+            torrent.retain_valid_web_seeds();
+
+            assert_eq!(torrent.url_list, Some(vec!["https://seed.example.com/files/".to_string()]));
+            assert_eq!(torrent.httpseeds, None);
+        }
+    }
 }
@@ -0,0 +1,95 @@
+//! Jobs to synchronize the tracker with the index.
+//!
+//! Some actions in the index have side effects on the tracker, for example,
+//! adding a torrent to the tracker whitelist when a torrent is uploaded. Those
+//! side effects are persisted as jobs and executed in the background, so the
+//! index keeps working when the tracker is not available.
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[allow(clippy::module_name_repetitions)]
+pub type TrackerJobId = i64;
+
+/// The action the job executes on the tracker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Kind {
+    /// Add the torrent to the tracker whitelist.
+    Whitelist,
+    /// Remove the torrent from the tracker whitelist.
+    RemoveFromWhitelist,
+    /// Import the torrent statistics (seeders and leechers) from the tracker.
+    ImportStatistics,
+}
+
+impl Kind {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Kind::Whitelist => "Whitelist",
+            Kind::RemoveFromWhitelist => "RemoveFromWhitelist",
+            Kind::ImportStatistics => "ImportStatistics",
+        }
+    }
+}
+
+impl FromStr for Kind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Whitelist" => Ok(Kind::Whitelist),
+            "RemoveFromWhitelist" => Ok(Kind::RemoveFromWhitelist),
+            "ImportStatistics" => Ok(Kind::ImportStatistics),
+            _ => Err(format!("unknown tracker job kind: {s}")),
+        }
+    }
+}
+
+/// The state of the job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Status {
+    /// The job is waiting to be executed or retried.
+    Pending,
+    /// The job reached the maximum number of attempts. It will not be retried
+    /// unless an administrator retries it manually.
+    Failed,
+}
+
+impl Status {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Pending => "Pending",
+            Status::Failed => "Failed",
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Pending" => Ok(Status::Pending),
+            "Failed" => Ok(Status::Failed),
+            _ => Err(format!("unknown tracker job status: {s}")),
+        }
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct TrackerJob {
+    pub job_id: TrackerJobId,
+    pub kind: String,
+    pub info_hash: String,
+    pub torrent_id: Option<i64>,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
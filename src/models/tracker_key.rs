@@ -7,6 +7,15 @@ pub struct TrackerKey {
     pub valid_until: i64,
 }
 
+/// A user tracker key with the personal announce URL that includes it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PersonalTrackerKey {
+    pub key: String,
+    /// Expiration time in seconds since the Unix epoch.
+    pub valid_until: i64,
+    pub announce_url: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Duration {
     pub secs: i64,
@@ -0,0 +1,106 @@
+//! Synchronization of the tracker with a new tracker mode.
+//!
+//! When the tracker mode changes, the tracker whitelist and the user tracker
+//! keys have to be updated. A sync is persisted so it can be resumed after a
+//! restart, and so administrators can check its progress.
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::config::TrackerMode;
+
+#[allow(clippy::module_name_repetitions)]
+pub type TrackerModeSyncId = i64;
+
+/// The action applied to every torrent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    /// Add the torrent to the tracker whitelist.
+    Whitelist,
+    /// Remove the torrent from the tracker whitelist.
+    RemoveFromWhitelist,
+}
+
+impl Action {
+    /// The action needed to synchronize the tracker whitelist with the
+    /// tracker `mode`.
+    #[must_use]
+    pub fn for_mode(mode: &TrackerMode) -> Self {
+        if mode.is_whitelisted() {
+            Action::Whitelist
+        } else {
+            Action::RemoveFromWhitelist
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::Whitelist => "Whitelist",
+            Action::RemoveFromWhitelist => "RemoveFromWhitelist",
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Whitelist" => Ok(Action::Whitelist),
+            "RemoveFromWhitelist" => Ok(Action::RemoveFromWhitelist),
+            _ => Err(format!("unknown tracker mode sync action: {s}")),
+        }
+    }
+}
+
+/// The state of the sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Status {
+    /// The torrents are being processed. An interrupted sync is resumed from
+    /// the last processed torrent.
+    Running,
+    /// All the torrents were processed.
+    Completed,
+}
+
+impl Status {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Running => "Running",
+            Status::Completed => "Completed",
+        }
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct TrackerModeSync {
+    pub sync_id: TrackerModeSyncId,
+    /// The tracker mode of the previous completed sync. `None` for the first
+    /// sync.
+    pub from_mode: Option<String>,
+    pub to_mode: String,
+    pub action: String,
+    pub status: String,
+    /// The last processed torrent. Torrents are processed in `torrent_id`
+    /// order.
+    pub last_torrent_id: i64,
+    pub torrents_total: i64,
+    pub torrents_processed: i64,
+    /// Number of torrents that could not be updated in the tracker.
+    pub errors: i64,
+    pub last_error: Option<String>,
+    pub started_at: String,
+    pub updated_at: String,
+    pub finished_at: Option<String>,
+}
+
+impl TrackerModeSync {
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.status == Status::Running.as_str()
+    }
+}
@@ -27,6 +27,7 @@ pub struct UserProfile {
     pub email_verified: bool,
     pub bio: String,
     pub avatar: String,
+    pub locale: Option<String>,
 }
 
 #[allow(clippy::module_name_repetitions)]
@@ -9,7 +9,7 @@ use super::user::{DbUserProfileRepository, DbUserRepository};
 use crate::config::Configuration;
 use crate::databases::database::{Database, Error};
 use crate::errors::ServiceError;
-use crate::models::user::{UserAuthentication, UserClaims, UserCompact, UserId};
+use crate::models::user::{UserAuthentication, UserClaims, UserCompact, UserId, UserProfile};
 use crate::utils::clock;
 
 pub struct Service {
@@ -50,26 +50,15 @@ impl Service {
     /// * An error if unable to verify the password.
     /// * An error if unable to get the user data from the database.
     pub async fn login(&self, username: &str, password: &str) -> Result<(String, UserCompact), ServiceError> {
-        // Get the user profile from database
-        let user_profile = self
-            .user_profile_repository
-            .get_user_profile_from_username(username)
-            .await
-            .map_err(|_| ServiceError::WrongPasswordOrUsername)?;
-
-        // Should not be able to fail if user_profile succeeded
-        let user_authentication = self
-            .user_authentication_repository
-            .get_user_authentication_from_id(&user_profile.user_id)
-            .await
-            .map_err(|_| ServiceError::InternalServerError)?;
-
-        verify_password(password.as_bytes(), &user_authentication)?;
+        let user_profile = self.verify_credentials(username, password).await?;
 
         let settings = self.configuration.settings.read().await;
 
         // Fail login if email verification is required and this email is not verified
-        if settings.mail.email_verification_enabled && !user_profile.email_verified {
+        if settings.mail.email_verification_enabled
+            && settings.email_verification.required_for_login
+            && !user_profile.email_verified
+        {
             return Err(ServiceError::EmailNotVerified);
         }
 
@@ -84,6 +73,37 @@ impl Service {
         Ok((token, user_compact))
     }
 
+    /// It checks the username and password of a user, without the other
+    /// login requirements like the email verification, and returns the user
+    /// profile.
+    ///
+    /// # Errors
+    ///
+    /// It returns:
+    ///
+    /// * A `ServiceError::WrongPasswordOrUsername` if unable to get user profile.
+    /// * A `ServiceError::InternalServerError` if unable to get user authentication data from the user id.
+    /// * An error if unable to verify the password.
+    pub async fn verify_credentials(&self, username: &str, password: &str) -> Result<UserProfile, ServiceError> {
+        // Get the user profile from database
+        let user_profile = self
+            .user_profile_repository
+            .get_user_profile_from_username(username)
+            .await
+            .map_err(|_| ServiceError::WrongPasswordOrUsername)?;
+
+        // Should not be able to fail if user_profile succeeded
+        let user_authentication = self
+            .user_authentication_repository
+            .get_user_authentication_from_id(&user_profile.user_id)
+            .await
+            .map_err(|_| ServiceError::InternalServerError)?;
+
+        verify_password(password.as_bytes(), &user_authentication)?;
+
+        Ok(user_profile)
+    }
+
     /// Renew a supplied JWT.
     ///
     /// # Errors
@@ -1,23 +1,31 @@
 //! Category service.
 use std::sync::Arc;
 
+use super::notification::Notifier;
 use super::user::DbUserRepository;
 use crate::databases::database::{Category, Database, Error as DatabaseError};
 use crate::errors::ServiceError;
 use crate::models::category::CategoryId;
+use crate::models::notification::{Kind, NewNotification, UploadedTorrent};
 use crate::models::user::UserId;
 
 pub struct Service {
     category_repository: Arc<DbCategoryRepository>,
     user_repository: Arc<DbUserRepository>,
+    notifier: Arc<Notifier>,
 }
 
 impl Service {
     #[must_use]
-    pub fn new(category_repository: Arc<DbCategoryRepository>, user_repository: Arc<DbUserRepository>) -> Service {
+    pub fn new(
+        category_repository: Arc<DbCategoryRepository>,
+        user_repository: Arc<DbUserRepository>,
+        notifier: Arc<Notifier>,
+    ) -> Service {
         Service {
             category_repository,
             user_repository,
+            notifier,
         }
     }
 
@@ -78,13 +86,37 @@ impl Service {
             return Err(ServiceError::Unauthorized);
         }
 
-        match self.category_repository.delete(category_name).await {
-            Ok(()) => Ok(()),
-            Err(e) => match e {
-                DatabaseError::CategoryNotFound => Err(ServiceError::CategoryNotFound),
-                _ => Err(ServiceError::DatabaseError),
-            },
+        // The torrents are left without category, so their uploaders are
+        // notified.
+        let torrents = self
+            .category_repository
+            .get_uploaded_torrents(category_name)
+            .await
+            .map_err(|_| ServiceError::DatabaseError)?;
+
+        if let Err(e) = self.category_repository.delete(category_name).await {
+            return Err(match e {
+                DatabaseError::CategoryNotFound => ServiceError::CategoryNotFound,
+                _ => ServiceError::DatabaseError,
+            });
         }
+
+        for torrent in torrents.iter().filter(|torrent| torrent.uploader_id != *user_id) {
+            self.notifier
+                .notify(
+                    torrent.uploader_id,
+                    NewNotification::for_torrent(
+                        Kind::TorrentCategoryRemoved,
+                        torrent.torrent_id,
+                        &torrent.info_hash,
+                        &torrent.title,
+                    )
+                    .with_detail(category_name),
+                )
+                .await;
+        }
+
+        Ok(())
     }
 }
 
@@ -125,6 +157,15 @@ impl DbCategoryRepository {
         self.database.delete_category(category_name).await
     }
 
+    /// It returns the torrents in a category with their uploaders.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_uploaded_torrents(&self, category_name: &str) -> Result<Vec<UploadedTorrent>, DatabaseError> {
+        self.database.get_uploaded_torrents_in_category(category_name).await
+    }
+
     /// It finds a category by name
     ///
     /// # Errors
@@ -0,0 +1,259 @@
+//! Dead torrent policy.
+//!
+//! Torrents without seeders for a long time are considered dead. When the
+//! policy is applied:
+//!
+//! 1. Dead torrents with seeders again are marked as alive.
+//! 2. Torrents without seeders for `dead_after_days` days are marked as dead.
+//!    The uploader is notified by email, if enabled. Like the other
+//!    notifications, it's not emailed to unverified addresses when email
+//!    verification is enabled.
+//! 3. Torrents dead for more than `grace_period_days` days are removed from the
+//!    tracker whitelist and archived or deleted, depending on `prune_action`.
+//!
+//! Dead torrents are marked in the torrent listing and they can be hidden.
+//! Archived torrents are kept in the database but they are not listed anymore.
+//!
+//! The policy is applied periodically by the
+//! [`dead_torrents`](crate::console::cronjobs::dead_torrents) cronjob.
+//! Administrators can also apply it, or get a dry-run report, using the API.
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+use super::torrent;
+use super::user::DbUserRepository;
+use crate::config::{Configuration, DeadTorrentAction};
+use crate::databases::database::Database;
+use crate::errors::ServiceError;
+use crate::mailer;
+use crate::models::torrent::DeadTorrent;
+use crate::models::user::UserId;
+use crate::tracker::job_queue::JobQueue;
+
+const LOG_TARGET: &str = "Dead Torrent Policy";
+
+/// Summary of the changes made by the dead torrent policy.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DeadTorrentsReport {
+    /// Whether changes were only reported but not applied.
+    pub dry_run: bool,
+    /// What is done with the pruned torrents.
+    pub prune_action: DeadTorrentAction,
+    /// Torrents marked as dead.
+    pub marked_as_dead: Vec<DeadTorrent>,
+    /// Dead torrents marked as alive because they have seeders again.
+    pub revived: Vec<DeadTorrent>,
+    /// Dead torrents removed from the tracker whitelist and archived or
+    /// deleted after the grace period.
+    pub pruned: Vec<DeadTorrent>,
+    /// Number of uploaders notified by email.
+    pub notified: usize,
+    /// Info-hashes of the torrents that could not be updated.
+    pub failed: Vec<String>,
+}
+
+pub struct DeadTorrentPolicy {
+    cfg: Arc<Configuration>,
+    database: Arc<Box<dyn Database>>,
+    mailer: Arc<mailer::Service>,
+    tracker_job_queue: Arc<JobQueue>,
+    torrent_index: Arc<torrent::Index>,
+}
+
+impl DeadTorrentPolicy {
+    #[must_use]
+    pub fn new(
+        cfg: Arc<Configuration>,
+        database: Arc<Box<dyn Database>>,
+        mailer: Arc<mailer::Service>,
+        tracker_job_queue: Arc<JobQueue>,
+        torrent_index: Arc<torrent::Index>,
+    ) -> Self {
+        Self {
+            cfg,
+            database,
+            mailer,
+            tracker_job_queue,
+            torrent_index,
+        }
+    }
+
+    /// It applies the dead torrent policy.
+    ///
+    /// When `dry_run` is `true` the changes are reported but not applied and
+    /// no emails are sent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error loading the torrents.
+    pub async fn apply(&self, dry_run: bool) -> Result<DeadTorrentsReport, ServiceError> {
+        let settings = self.cfg.settings.read().await;
+        let dead_after_days = settings.dead_torrents.dead_after_days;
+        let grace_period_days = settings.dead_torrents.grace_period_days;
+        let notify_uploader = settings.dead_torrents.notify_uploader;
+        let email_verification_enabled = settings.mail.email_verification_enabled;
+        let prune_action = settings.dead_torrents.prune_action.clone();
+        drop(settings);
+
+        let now = Utc::now().naive_utc();
+
+        if !dry_run {
+            self.database.update_last_seeded_at_for_seeded_torrents(now).await?;
+        }
+
+        let mut report = DeadTorrentsReport {
+            dry_run,
+            prune_action: prune_action.clone(),
+            revived: self.database.get_revived_dead_torrents().await?,
+            marked_as_dead: self
+                .database
+                .get_torrents_to_mark_as_dead(now - Duration::days(dead_after_days.into()))
+                .await?,
+            pruned: self
+                .database
+                .get_dead_torrents_to_prune(now - Duration::days(grace_period_days.into()))
+                .await?,
+            ..DeadTorrentsReport::default()
+        };
+
+        info!(target: LOG_TARGET, "Torrents revived: {}, marked as dead: {}, to prune: {}, dry run: {dry_run}", report.revived.len(), report.marked_as_dead.len(), report.pruned.len());
+
+        if dry_run {
+            if notify_uploader {
+                report.notified = report
+                    .marked_as_dead
+                    .iter()
+                    .filter(|torrent| can_email_uploader(torrent, email_verification_enabled))
+                    .count();
+            }
+
+            return Ok(report);
+        }
+
+        for torrent in &report.revived {
+            if let Err(err) = self.database.update_torrent_dead_since(torrent.torrent_id, None).await {
+                error!(target: LOG_TARGET, "Failed to mark torrent {} as alive: {err:?}", torrent.info_hash);
+                report.failed.push(torrent.info_hash.clone());
+            }
+        }
+
+        for torrent in &report.marked_as_dead {
+            if let Err(err) = self.database.update_torrent_dead_since(torrent.torrent_id, Some(now)).await {
+                error!(target: LOG_TARGET, "Failed to mark torrent {} as dead: {err:?}", torrent.info_hash);
+                report.failed.push(torrent.info_hash.clone());
+                continue;
+            }
+
+            if notify_uploader
+                && can_email_uploader(torrent, email_verification_enabled)
+                && self.notify_uploader(torrent, grace_period_days).await
+            {
+                report.notified += 1;
+            }
+        }
+
+        for torrent in &report.pruned {
+            if let Err(err) = self.prune(torrent, &prune_action, now).await {
+                error!(target: LOG_TARGET, "Failed to prune torrent {}: {err}", torrent.info_hash);
+                report.failed.push(torrent.info_hash.clone());
+            }
+        }
+
+        info!(target: LOG_TARGET, "Dead torrent policy applied. Notified: {}, failed: {}", report.notified, report.failed.len());
+
+        Ok(report)
+    }
+
+    /// It sends the email to the uploader. It returns `false` if the uploader
+    /// does not have an email or the email could not be sent.
+    async fn notify_uploader(&self, torrent: &DeadTorrent, grace_period_days: u32) -> bool {
+        let Some(email) = torrent.uploader_email.as_deref().filter(|email| !email.is_empty()) else {
+            return false;
+        };
+
+        match self
+            .mailer
+            .send_dead_torrent_mail(
+                email,
+                &torrent.uploader,
+                &torrent.title,
+                &torrent.info_hash,
+                grace_period_days,
+                torrent.uploader_locale.as_deref(),
+            )
+            .await
+        {
+            Ok(()) => true,
+            Err(err) => {
+                error!(target: LOG_TARGET, "Failed to notify the uploader of torrent {}: {err}", torrent.info_hash);
+                false
+            }
+        }
+    }
+
+    async fn prune(&self, torrent: &DeadTorrent, action: &DeadTorrentAction, now: NaiveDateTime) -> Result<(), ServiceError> {
+        match action {
+            DeadTorrentAction::Archive => {
+                self.database.archive_torrent(torrent.torrent_id, now).await?;
+
+                // Remove info-hash from tracker whitelist in the background
+                self.tracker_job_queue
+                    .enqueue_remove_from_whitelist(&torrent.info_hash)
+                    .await?;
+            }
+            DeadTorrentAction::Delete => {
+                self.torrent_index
+                    .remove_torrent(torrent.torrent_id, &torrent.info_hash)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Like the other notifications, the email is only sent if the uploader has
+/// an email, and it's verified when email verification is enabled.
+fn can_email_uploader(torrent: &DeadTorrent, email_verification_enabled: bool) -> bool {
+    torrent.uploader_email.as_deref().is_some_and(|email| !email.is_empty())
+        && (torrent.uploader_email_verified || !email_verification_enabled)
+}
+
+pub struct Service {
+    dead_torrent_policy: Arc<DeadTorrentPolicy>,
+    user_repository: Arc<DbUserRepository>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(dead_torrent_policy: Arc<DeadTorrentPolicy>, user_repository: Arc<DbUserRepository>) -> Service {
+        Service {
+            dead_torrent_policy,
+            user_repository,
+        }
+    }
+
+    /// It applies the dead torrent policy, or only reports the changes if
+    /// `dry_run` is `true`.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There is a database error.
+    pub async fn apply_policy(&self, dry_run: bool, user_id: &UserId) -> Result<DeadTorrentsReport, ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        // Check if user is administrator
+        // todo: extract authorization service
+        if !user.administrator {
+            return Err(ServiceError::Unauthorized);
+        }
+
+        self.dead_torrent_policy.apply(dry_run).await
+    }
+}
@@ -0,0 +1,423 @@
+//! Uploaded images service.
+//!
+//! Users can upload images to use them as torrent posters and screenshots,
+//! or as their avatar. Image files are stored in the `image_uploads.storage_path`
+//! directory, named after the SHA-1 hash of their contents, so identical
+//! images are only stored once:
+//!
+//! ```text
+//! <storage_path>/<first two chars of the hash>/<hash>
+//! ```
+use std::io::{self, Cursor};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::Utc;
+use image::io::Reader;
+use log::error;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+use super::torrent::DbTorrentListingGenerator;
+use super::user::{DbUserProfileRepository, DbUserRepository};
+use crate::cache::image::disk::{content_hash, write_atomically};
+use crate::cache::image::format::ImageFormat;
+use crate::config::Configuration;
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::image::{DbTorrentImage, Image, ImageId};
+use crate::models::info_hash::InfoHash;
+use crate::models::response::TorrentImagesResponse;
+use crate::models::torrent::TorrentId;
+use crate::models::user::UserId;
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    user_repository: Arc<DbUserRepository>,
+    user_profile_repository: Arc<DbUserProfileRepository>,
+    image_repository: Arc<DbImageRepository>,
+    torrent_image_repository: Arc<DbTorrentImageRepository>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    /// Uploads and deletions of the same image file must not interleave,
+    /// otherwise a deletion could remove the file an upload is reusing.
+    files_lock: Mutex<()>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        user_repository: Arc<DbUserRepository>,
+        user_profile_repository: Arc<DbUserProfileRepository>,
+        image_repository: Arc<DbImageRepository>,
+        torrent_image_repository: Arc<DbTorrentImageRepository>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    ) -> Self {
+        Self {
+            configuration,
+            user_repository,
+            user_profile_repository,
+            image_repository,
+            torrent_image_repository,
+            torrent_listing_generator,
+            files_lock: Mutex::new(()),
+        }
+    }
+
+    /// It stores an image uploaded by a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not exist.
+    /// * The image is not a JPEG, PNG, GIF or WebP image.
+    /// * The image or its dimensions are too big.
+    /// * The user upload quota is exceeded.
+    /// * There is a database or file system error.
+    pub async fn upload_image(&self, bytes: Bytes, user_id: &UserId) -> Result<Image, ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        let settings = self.configuration.settings.read().await;
+        let image_uploads = settings.image_uploads.clone();
+        drop(settings);
+
+        if bytes.len() > image_uploads.max_image_size {
+            return Err(ServiceError::ImageTooBig);
+        }
+
+        let format = match ImageFormat::detect(&bytes) {
+            Some(ImageFormat::Avif) | None => return Err(ServiceError::InvalidImage),
+            Some(format) => format,
+        };
+
+        let (width, height) = Reader::new(Cursor::new(&bytes))
+            .with_guessed_format()
+            .map_err(|_| ServiceError::InvalidImage)?
+            .into_dimensions()
+            .map_err(|_| ServiceError::InvalidImage)?;
+
+        if width > image_uploads.max_image_dimension || height > image_uploads.max_image_dimension {
+            return Err(ServiceError::ImageDimensionsTooBig);
+        }
+
+        let content_hash = content_hash(&bytes);
+
+        let path = image_path(Path::new(&image_uploads.storage_path), &content_hash);
+
+        let files_guard = self.files_lock.lock().await;
+
+        let image_id = self
+            .image_repository
+            .add(
+                &user.user_id,
+                &content_hash,
+                format.mime_type(),
+                bytes.len(),
+                i64::from(width),
+                i64::from(height),
+                image_uploads.user_quota_bytes,
+            )
+            .await?;
+
+        if !fs::try_exists(&path).await.unwrap_or(false) {
+            if let Err(err) = write_atomically(&path, &bytes).await {
+                error!("Failed to store uploaded image {}: {err}", path.display());
+
+                if let Err(err) = self.image_repository.delete(&image_id).await {
+                    error!("Failed to delete the uploaded image {image_id} without file: {err:?}");
+                }
+
+                return Err(ServiceError::InternalServerError);
+            }
+        }
+
+        drop(files_guard);
+
+        Ok(self.image_repository.get(&image_id).await?)
+    }
+
+    /// It returns an uploaded image and its contents.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the image does not exist or it can't be read.
+    pub async fn get_image(&self, image_id: &ImageId) -> Result<(Image, Bytes), ServiceError> {
+        let image = self.image_repository.get(image_id).await?;
+
+        let path = image_path(Path::new(&self.get_storage_path().await), &image.content_hash);
+
+        match fs::read(&path).await {
+            Ok(contents) => Ok((image, Bytes::from(contents))),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Err(ServiceError::ImageNotFound),
+            Err(err) => {
+                error!("Failed to read uploaded image {}: {err}", path.display());
+                Err(ServiceError::InternalServerError)
+            }
+        }
+    }
+
+    /// It deletes an uploaded image. It's removed from the torrents and
+    /// profiles using it.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The image does not exist.
+    /// * The user is not the owner of the image or an administrator.
+    /// * There is a database error.
+    pub async fn delete_image(&self, image_id: &ImageId, user_id: &UserId) -> Result<(), ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        let image = self.image_repository.get(image_id).await?;
+
+        if !(image.user_id == user.user_id || user.administrator) {
+            return Err(ServiceError::Unauthorized);
+        }
+
+        self.remove_image(&image).await
+    }
+
+    /// It deletes all the images uploaded by a user. It's used when the user
+    /// account is deleted, so that the image files are not left behind.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn delete_user_images(&self, user_id: &UserId) -> Result<(), ServiceError> {
+        for image in self.image_repository.get_by_user(user_id).await? {
+            self.remove_image(&image).await?;
+        }
+
+        Ok(())
+    }
+
+    /// It removes an uploaded image from the database, and its file when no
+    /// other uploaded image uses it.
+    async fn remove_image(&self, image: &Image) -> Result<(), ServiceError> {
+        let files_guard = self.files_lock.lock().await;
+
+        if self.image_repository.delete(&image.image_id).await? == 0 {
+            let path = image_path(Path::new(&self.get_storage_path().await), &image.content_hash);
+
+            if let Err(err) = fs::remove_file(&path).await {
+                error!("Failed to remove uploaded image {}: {err}", path.display());
+            }
+        }
+
+        drop(files_guard);
+
+        self.user_profile_repository.delete_avatar(&image.path()).await?;
+
+        Ok(())
+    }
+
+    /// It replaces the poster and the screenshots of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The torrent or any of the images does not exist.
+    /// * The user is not the torrent uploader or an administrator.
+    /// * Any of the images was uploaded by another user, unless the user is
+    ///   an administrator.
+    /// * There are too many screenshots.
+    /// * There is a database error.
+    pub async fn set_torrent_images(
+        &self,
+        info_hash: &InfoHash,
+        poster: Option<ImageId>,
+        screenshots: &[ImageId],
+        user_id: &UserId,
+    ) -> Result<TorrentImagesResponse, ServiceError> {
+        let mut unique_screenshots: Vec<ImageId> = vec![];
+
+        for image_id in screenshots {
+            if !unique_screenshots.contains(image_id) {
+                unique_screenshots.push(*image_id);
+            }
+        }
+
+        if unique_screenshots.len() > self.configuration.settings.read().await.image_uploads.max_screenshots {
+            return Err(ServiceError::TooManyScreenshots);
+        }
+
+        let updater = self.user_repository.get_compact(user_id).await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        // Check if user is owner or administrator
+        // todo: move this to an authorization service.
+        if !(torrent_listing.uploader == updater.username || updater.administrator) {
+            return Err(ServiceError::Unauthorized);
+        }
+
+        for image_id in poster.iter().chain(unique_screenshots.iter()) {
+            let image = self.image_repository.get(image_id).await?;
+
+            if !(image.user_id == updater.user_id || updater.administrator) {
+                return Err(ServiceError::Unauthorized);
+            }
+        }
+
+        self.torrent_image_repository
+            .update(&torrent_listing.torrent_id, poster, &unique_screenshots)
+            .await?;
+
+        let torrent_images = self
+            .torrent_image_repository
+            .get_by_torrent_id(&torrent_listing.torrent_id)
+            .await?;
+
+        Ok(TorrentImagesResponse::from(torrent_images))
+    }
+
+    /// It sets one of the user's uploaded images as their avatar, or removes
+    /// the avatar if `image_id` is `None`. It returns the new avatar URL.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The image does not exist.
+    /// * The image was uploaded by another user.
+    /// * There is a database error.
+    pub async fn set_avatar(&self, image_id: Option<ImageId>, user_id: &UserId) -> Result<Option<String>, ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        let avatar = match image_id {
+            Some(image_id) => {
+                let image = self.image_repository.get(&image_id).await?;
+
+                if image.user_id != user.user_id {
+                    return Err(ServiceError::Unauthorized);
+                }
+
+                Some(image.path())
+            }
+            None => None,
+        };
+
+        self.user_profile_repository
+            .update_avatar(&user.user_id, avatar.as_deref())
+            .await?;
+
+        Ok(avatar)
+    }
+
+    async fn get_storage_path(&self) -> String {
+        let settings = self.configuration.settings.read().await;
+        settings.image_uploads.storage_path.clone()
+    }
+}
+
+fn image_path(storage_path: &Path, content_hash: &str) -> PathBuf {
+    storage_path.join(&content_hash[..2]).join(content_hash)
+}
+
+pub struct DbImageRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbImageRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds a new uploaded image and returns its id.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the images uploaded by the user would take more
+    /// than `user_quota` bytes or there is a database error.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add(
+        &self,
+        user_id: &UserId,
+        content_hash: &str,
+        content_type: &str,
+        size: usize,
+        width: i64,
+        height: i64,
+        user_quota: usize,
+    ) -> Result<ImageId, Error> {
+        let size = i64::try_from(size).map_err(|_| Error::Error)?;
+        let user_quota = i64::try_from(user_quota).unwrap_or(i64::MAX);
+
+        self.database
+            .insert_image(
+                *user_id,
+                content_hash,
+                content_type,
+                size,
+                width,
+                height,
+                user_quota,
+                Utc::now().naive_utc(),
+            )
+            .await
+    }
+
+    /// It finds an uploaded image by id.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the image does not exist or there is a database
+    /// error.
+    pub async fn get(&self, image_id: &ImageId) -> Result<Image, Error> {
+        self.database.get_image_from_id(*image_id).await
+    }
+
+    /// It returns all the images uploaded by a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_by_user(&self, user_id: &UserId) -> Result<Vec<Image>, Error> {
+        self.database.get_user_images(*user_id).await
+    }
+
+    /// It deletes an uploaded image and returns how many uploaded images still
+    /// use the same image file.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the image does not exist or there is a database
+    /// error.
+    pub async fn delete(&self, image_id: &ImageId) -> Result<i64, Error> {
+        self.database.delete_image(*image_id).await
+    }
+}
+
+pub struct DbTorrentImageRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbTorrentImageRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It finds the images attached to a torrent, in display order.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_by_torrent_id(&self, torrent_id: &TorrentId) -> Result<Vec<DbTorrentImage>, Error> {
+        self.database.get_torrent_images_from_id(*torrent_id).await
+    }
+
+    /// It replaces the poster and the screenshots of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update(&self, torrent_id: &TorrentId, poster: Option<ImageId>, screenshots: &[ImageId]) -> Result<(), Error> {
+        self.database.update_torrent_images(*torrent_id, poster, screenshots).await
+    }
+}
@@ -0,0 +1,90 @@
+//! Mail queue services.
+//!
+//! Outgoing emails are delivered in the background by the [`MailQueue`].
+//! Administrators can list the emails waiting to be delivered, the ones that
+//! were sent or failed, and resend or delete them.
+use std::sync::Arc;
+
+use super::user::DbUserRepository;
+use crate::errors::ServiceError;
+use crate::mailer::queue::MailQueue;
+use crate::models::queued_mail::{MailId, QueuedMail, Status};
+use crate::models::user::UserId;
+
+pub struct Service {
+    mail_queue: Arc<MailQueue>,
+    user_repository: Arc<DbUserRepository>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(mail_queue: Arc<MailQueue>, user_repository: Arc<DbUserRepository>) -> Service {
+        Service {
+            mail_queue,
+            user_repository,
+        }
+    }
+
+    /// It returns the queued emails, optionally filtered by status.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There is a database error.
+    pub async fn get_mails(&self, status: Option<Status>, user_id: &UserId) -> Result<Vec<QueuedMail>, ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        // Check if user is administrator
+        // todo: extract authorization service
+        if !user.administrator {
+            return Err(ServiceError::Unauthorized);
+        }
+
+        Ok(self.mail_queue.get_mails(status).await?)
+    }
+
+    /// It schedules a queued email to be delivered again immediately.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The email does not exist.
+    /// * There is a database error.
+    pub async fn resend(&self, mail_id: MailId, user_id: &UserId) -> Result<(), ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        // Check if user is administrator
+        // todo: extract authorization service
+        if !user.administrator {
+            return Err(ServiceError::Unauthorized);
+        }
+
+        Ok(self.mail_queue.resend(mail_id).await?)
+    }
+
+    /// It deletes a queued email, for example, a failed email that should
+    /// not be resent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The email does not exist.
+    /// * There is a database error.
+    pub async fn delete(&self, mail_id: MailId, user_id: &UserId) -> Result<(), ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        // Check if user is administrator
+        // todo: extract authorization service
+        if !user.administrator {
+            return Err(ServiceError::Unauthorized);
+        }
+
+        Ok(self.mail_queue.delete(mail_id).await?)
+    }
+}
@@ -2,10 +2,15 @@
 pub mod about;
 pub mod authentication;
 pub mod category;
+pub mod dead_torrent;
 pub mod hasher;
+pub mod image;
+pub mod mail;
+pub mod notification;
 pub mod proxy;
 pub mod settings;
 pub mod tag;
 pub mod torrent;
 pub mod torrent_file;
+pub mod tracker;
 pub mod user;
@@ -0,0 +1,432 @@
+//! User notifications.
+//!
+//! Users are notified when something happens to their torrents or their
+//! account, for example, when an administrator edits or deletes one of their
+//! torrents. Other services generate the notifications using the
+//! [`Notifier`], which delivers them according to the user's preferences:
+//!
+//! - In the app: notifications are listed with an unread count, and users can
+//!   mark them as read.
+//! - Email digest: notifications are sent periodically in one email by the
+//!   [`notification_digest`](crate::console::cronjobs::notification_digest)
+//!   cronjob.
+//! - Immediate email: an email is sent for every new notification.
+//!
+//! When email verification is enabled, notifications are not emailed to
+//! unverified addresses.
+//!
+//! Notifications are deleted after `notifications.retention_days` days.
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use log::error;
+
+use super::user::DbUserProfileRepository;
+use crate::config::Configuration;
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::mailer;
+use crate::models::notification::{
+    Kind, NewNotification, Notification, NotificationId, NotificationPreferences, NotificationSummary, UploadedTorrent,
+};
+use crate::models::user::UserId;
+
+const LOG_TARGET: &str = "Notifications";
+
+/// The maximum number of notifications returned when listing them.
+pub const MAX_NOTIFICATIONS_LISTED: u32 = 100;
+
+/// It generates the notifications and delivers them according to the user's
+/// preferences.
+pub struct Notifier {
+    cfg: Arc<Configuration>,
+    notification_repository: Arc<DbNotificationRepository>,
+    user_profile_repository: Arc<DbUserProfileRepository>,
+    mailer: Arc<mailer::Service>,
+}
+
+impl Notifier {
+    #[must_use]
+    pub fn new(
+        cfg: Arc<Configuration>,
+        notification_repository: Arc<DbNotificationRepository>,
+        user_profile_repository: Arc<DbUserProfileRepository>,
+        mailer: Arc<mailer::Service>,
+    ) -> Self {
+        Self {
+            cfg,
+            notification_repository,
+            user_profile_repository,
+            mailer,
+        }
+    }
+
+    /// It notifies a user.
+    ///
+    /// Notifications are a side effect of other actions, so errors are only
+    /// logged and never returned to the caller.
+    pub async fn notify(&self, user_id: UserId, notification: NewNotification) {
+        if let Err(err) = self.deliver(user_id, &notification).await {
+            error!(target: LOG_TARGET, "Failed to notify user {user_id} of {}: {err}", notification.kind.as_str());
+        }
+    }
+
+    /// It notifies the uploaders of the torrents that had seeders on the
+    /// previous statistics import but not anymore. It returns the number of
+    /// notified torrents.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error updating the torrents.
+    pub async fn notify_torrents_without_seeders(&self) -> Result<usize, ServiceError> {
+        let torrents = self.notification_repository.update_torrents_has_seeders().await?;
+
+        for torrent in &torrents {
+            self.notify(
+                torrent.uploader_id,
+                NewNotification::for_torrent(
+                    Kind::TorrentLostSeeders,
+                    torrent.torrent_id,
+                    &torrent.info_hash,
+                    &torrent.title,
+                ),
+            )
+            .await;
+        }
+
+        Ok(torrents.len())
+    }
+
+    /// It sends the email digests with the notifications waiting for them. It
+    /// returns the number of digests sent.
+    ///
+    /// Notifications stay pending if the email can't be queued, so they are
+    /// sent in the next digest.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error loading the users.
+    pub async fn send_digests(&self) -> Result<usize, ServiceError> {
+        let mut sent = 0;
+
+        for user_id in self.notification_repository.get_users_with_pending_digest().await? {
+            match self.send_digest(user_id).await {
+                Ok(true) => sent += 1,
+                Ok(false) => {}
+                Err(err) => error!(target: LOG_TARGET, "Failed to send the notification digest to user {user_id}: {err}"),
+            }
+        }
+
+        Ok(sent)
+    }
+
+    /// It deletes the notifications older than `notifications.retention_days`
+    /// days. It returns the number of deleted notifications.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn delete_expired_notifications(&self) -> Result<u64, ServiceError> {
+        let retention_days = self.cfg.settings.read().await.notifications.retention_days;
+
+        if retention_days == 0 {
+            return Ok(0);
+        }
+
+        Ok(self
+            .notification_repository
+            .delete_before(now() - Duration::days(retention_days.into()))
+            .await?)
+    }
+
+    async fn deliver(&self, user_id: UserId, notification: &NewNotification) -> Result<(), ServiceError> {
+        let preferences = self.notification_repository.get_preferences(&user_id).await?;
+
+        let now = now();
+
+        // The immediate email replaces the digest
+        let digest_pending = preferences.email_digest && !preferences.immediate_email;
+
+        // Notifications only sent in the immediate email are not stored
+        if preferences.in_app || digest_pending {
+            self.notification_repository
+                .add(&user_id, notification, preferences.in_app, digest_pending, now)
+                .await?;
+        }
+
+        if preferences.immediate_email {
+            let summary = notification.summary(&now.format("%Y-%m-%d %H:%M:%S").to_string());
+
+            self.send_mail(user_id, &[summary]).await?;
+        }
+
+        Ok(())
+    }
+
+    /// It sends the digest to a user. It returns `false` if the user does not
+    /// have a verified email, in which case the notifications are not sent.
+    async fn send_digest(&self, user_id: UserId) -> Result<bool, ServiceError> {
+        let notifications = self.notification_repository.get_pending_digest(&user_id).await?;
+
+        let Some(last_notification) = notifications.last() else {
+            return Ok(false);
+        };
+
+        let last_notification_id = last_notification.notification_id;
+
+        let summaries: Vec<NotificationSummary> = notifications.into_iter().map(NotificationSummary::from).collect();
+
+        let sent = self.send_mail(user_id, &summaries).await?;
+
+        self.notification_repository
+            .clear_pending_digest(&user_id, last_notification_id)
+            .await?;
+
+        Ok(sent)
+    }
+
+    /// It sends an email with the notifications. It returns `false` if the
+    /// user does not have an email, or it's not verified while email
+    /// verification is enabled.
+    async fn send_mail(&self, user_id: UserId, notifications: &[NotificationSummary]) -> Result<bool, ServiceError> {
+        let user_profile = self.user_profile_repository.get_user_profile_from_id(&user_id).await?;
+
+        if user_profile.email.is_empty() {
+            return Ok(false);
+        }
+
+        if !user_profile.email_verified && self.cfg.settings.read().await.mail.email_verification_enabled {
+            return Ok(false);
+        }
+
+        self.mailer
+            .send_notification_mail(
+                &user_profile.email,
+                &user_profile.username,
+                user_id,
+                notifications,
+                user_profile.locale.as_deref(),
+            )
+            .await?;
+
+        Ok(true)
+    }
+}
+
+pub struct Service {
+    notification_repository: Arc<DbNotificationRepository>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(notification_repository: Arc<DbNotificationRepository>) -> Service {
+        Service { notification_repository }
+    }
+
+    /// It returns the latest notifications of the user, newest first.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_notifications(&self, unread_only: bool, user_id: &UserId) -> Result<Vec<Notification>, ServiceError> {
+        Ok(self
+            .notification_repository
+            .get_all(user_id, unread_only, MAX_NOTIFICATIONS_LISTED)
+            .await?)
+    }
+
+    /// It returns the number of unread notifications of the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn count_unread(&self, user_id: &UserId) -> Result<i64, ServiceError> {
+        Ok(self.notification_repository.count_unread(user_id).await?)
+    }
+
+    /// It marks a notification of the user as read.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The notification does not exist or belongs to another user.
+    /// * There is a database error.
+    pub async fn mark_as_read(&self, notification_id: NotificationId, user_id: &UserId) -> Result<(), ServiceError> {
+        Ok(self.notification_repository.mark_as_read(user_id, notification_id).await?)
+    }
+
+    /// It marks all the notifications of the user as read. It returns the
+    /// number of notifications marked.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn mark_all_as_read(&self, user_id: &UserId) -> Result<u64, ServiceError> {
+        Ok(self.notification_repository.mark_all_as_read(user_id).await?)
+    }
+
+    /// It returns the notification preferences of the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_preferences(&self, user_id: &UserId) -> Result<NotificationPreferences, ServiceError> {
+        Ok(self.notification_repository.get_preferences(user_id).await?)
+    }
+
+    /// It updates the notification preferences of the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update_preferences(
+        &self,
+        preferences: &NotificationPreferences,
+        user_id: &UserId,
+    ) -> Result<NotificationPreferences, ServiceError> {
+        self.notification_repository.update_preferences(user_id, preferences).await?;
+
+        Ok(*preferences)
+    }
+}
+
+pub struct DbNotificationRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbNotificationRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds a new notification for the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(
+        &self,
+        user_id: &UserId,
+        notification: &NewNotification,
+        in_app: bool,
+        digest_pending: bool,
+        now: NaiveDateTime,
+    ) -> Result<NotificationId, Error> {
+        self.database
+            .insert_notification(*user_id, notification, in_app, digest_pending, now)
+            .await
+    }
+
+    /// It gets the latest notifications of the user listed in the app.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self, user_id: &UserId, unread_only: bool, limit: u32) -> Result<Vec<Notification>, Error> {
+        self.database.get_notifications(*user_id, unread_only, limit).await
+    }
+
+    /// It counts the unread notifications of the user listed in the app.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn count_unread(&self, user_id: &UserId) -> Result<i64, Error> {
+        self.database.count_unread_notifications(*user_id).await
+    }
+
+    /// It marks a notification of the user as read.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the notification does not exist or there is a
+    /// database error.
+    pub async fn mark_as_read(&self, user_id: &UserId, notification_id: NotificationId) -> Result<(), Error> {
+        self.database.mark_notification_as_read(*user_id, notification_id).await
+    }
+
+    /// It marks all the notifications of the user as read.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn mark_all_as_read(&self, user_id: &UserId) -> Result<u64, Error> {
+        self.database.mark_all_notifications_as_read(*user_id).await
+    }
+
+    /// It gets the notification preferences of the user, or the default ones
+    /// if the user never changed them.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_preferences(&self, user_id: &UserId) -> Result<NotificationPreferences, Error> {
+        Ok(self
+            .database
+            .get_notification_preferences(*user_id)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// It updates the notification preferences of the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update_preferences(&self, user_id: &UserId, preferences: &NotificationPreferences) -> Result<(), Error> {
+        self.database.update_notification_preferences(*user_id, preferences).await
+    }
+
+    /// It gets the users with notifications waiting for the email digest.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_users_with_pending_digest(&self) -> Result<Vec<UserId>, Error> {
+        self.database.get_users_with_pending_digest().await
+    }
+
+    /// It gets the notifications of the user waiting for the email digest.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_pending_digest(&self, user_id: &UserId) -> Result<Vec<Notification>, Error> {
+        self.database.get_pending_digest_notifications(*user_id).await
+    }
+
+    /// It marks the notifications of the user up to `notification_id` as
+    /// sent in the email digest.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn clear_pending_digest(&self, user_id: &UserId, notification_id: NotificationId) -> Result<(), Error> {
+        self.database.clear_pending_digest(*user_id, notification_id).await
+    }
+
+    /// It deletes the notifications created before `before`.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn delete_before(&self, before: NaiveDateTime) -> Result<u64, Error> {
+        self.database.delete_notifications_before(before).await
+    }
+
+    /// It updates whether the torrents have seeders and returns the ones that
+    /// lost all their seeders since the previous update.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update_torrents_has_seeders(&self) -> Result<Vec<UploadedTorrent>, Error> {
+        self.database.update_torrents_has_seeders().await
+    }
+}
+
+fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
@@ -6,12 +6,16 @@
 //! Sample URL:
 //!
 //! <http://0.0.0.0:3001/v1/proxy/image/https%3A%2F%2Fupload.wikimedia.org%2Fwikipedia%2Fcommons%2Fthumb%2F2%2F21%2FMandel_zoom_00_mandelbrot_set.jpg%2F1280px-Mandel_zoom_00_mandelbrot_set.jpg>
+//!
+//! Administrators can see the cache statistics and purge cached images.
 use std::sync::Arc;
 
 use bytes::Bytes;
 
 use super::user::DbUserRepository;
-use crate::cache::image::manager::{Error, ImageCacheService};
+use crate::cache::image::manager::{Error, ImageCacheService, Statistics};
+use crate::cache::image::variant::ImageVariant;
+use crate::errors::ServiceError;
 use crate::models::user::UserId;
 
 pub struct Service {
@@ -28,7 +32,8 @@ impl Service {
         }
     }
 
-    /// It gets image by URL and caches it.
+    /// It gets image by URL and caches it. It returns the requested resized
+    /// variant of the image.
     ///
     /// # Errors
     ///
@@ -38,9 +43,61 @@ impl Service {
     /// * The image URL is not an image.
     /// * The image is too big.
     /// * The user quota is met.
-    pub async fn get_image_by_url(&self, url: &str, user_id: &UserId) -> Result<Bytes, Error> {
-        let user = self.user_repository.get_compact(user_id).await.ok();
+    /// * The image is not cached and the user is not authenticated.
+    pub async fn get_image_by_url(
+        &self,
+        url: &str,
+        variant: &ImageVariant,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Bytes, Error> {
+        let user = match maybe_user_id {
+            Some(user_id) => self.user_repository.get_compact(&user_id).await.ok(),
+            None => None,
+        };
+
+        self.image_cache_service.get_image_by_url(url, variant, user).await
+    }
+
+    /// It returns the image cache statistics.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the user is not an administrator.
+    pub async fn get_statistics(&self, user_id: &UserId) -> Result<Statistics, ServiceError> {
+        self.authorize_administrator(user_id).await?;
+
+        Ok(self.image_cache_service.statistics().await)
+    }
+
+    /// It removes an image and its resized variants from the cache, or all
+    /// the cached images if `all` is set instead of a `url`. It returns the
+    /// number of removed cache entries.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not an administrator.
+    /// * Neither or both a `url` and `all` are given.
+    pub async fn purge(&self, url: Option<&str>, all: bool, user_id: &UserId) -> Result<usize, ServiceError> {
+        self.authorize_administrator(user_id).await?;
+
+        match (url, all) {
+            (Some(url), false) => Ok(self.image_cache_service.purge_url(url).await),
+            (None, true) => Ok(self.image_cache_service.purge_all().await),
+            _ => Err(ServiceError::MissingPurgeTarget),
+        }
+    }
+
+    async fn authorize_administrator(&self, user_id: &UserId) -> Result<(), ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        // Check if user is administrator
+        // todo: extract authorization service
+        if !user.administrator {
+            return Err(ServiceError::Unauthorized);
+        }
 
-        self.image_cache_service.get_image_by_url(url, user).await
+        Ok(())
     }
 }
@@ -60,6 +60,37 @@ impl Service {
         Ok(torrust_index_configuration)
     }
 
+    /// It updates all the settings.
+    ///
+    /// Secrets masked with asterisks are kept unchanged, so the value returned
+    /// by [`get_all_masking_secrets`](Self::get_all_masking_secrets) can be
+    /// modified and sent back.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The new settings are not valid.
+    /// * The new settings can't be saved into the configuration file.
+    pub async fn update_all(&self, torrust_index: TorrustIndex, user_id: &UserId) -> Result<TorrustIndex, ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        // Check if user is administrator
+        // todo: extract authorization service
+        if !user.administrator {
+            return Err(ServiceError::Unauthorized);
+        }
+
+        self.configuration.update(torrust_index).await?;
+
+        let mut torrust_index_configuration = self.configuration.get_all().await;
+
+        torrust_index_configuration.remove_secrets();
+
+        Ok(torrust_index_configuration)
+    }
+
     /// It gets only the public settings.
     ///
     /// # Errors
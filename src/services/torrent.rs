@@ -1,38 +1,50 @@
 //! Torrent service.
+use std::collections::BTreeSet;
 use std::sync::Arc;
 
-use log::debug;
+use chrono::Utc;
+use log::{debug, error};
 use serde_derive::{Deserialize, Serialize};
 
 use super::category::DbCategoryRepository;
-use super::user::DbUserRepository;
+use super::image::DbTorrentImageRepository;
+use super::notification::Notifier;
+use super::user::{DbUserProfileRepository, DbUserRepository};
 use crate::config::{Configuration, TrackerMode};
 use crate::databases::database::{Database, Error, Sorting};
 use crate::errors::ServiceError;
 use crate::models::category::CategoryId;
 use crate::models::info_hash::InfoHash;
-use crate::models::response::{DeletedTorrentResponse, TorrentResponse, TorrentsResponse};
-use crate::models::torrent::{Metadata, TorrentId, TorrentListing};
-use crate::models::torrent_file::{DbTorrent, Torrent, TorrentFile};
+use crate::models::notification::{Kind as NotificationKind, NewNotification};
+use crate::models::response::{DeletedTorrentResponse, TorrentImagesResponse, TorrentResponse, TorrentsResponse};
+use crate::models::torrent::{Metadata, TorrentId, TorrentListing, TorrentStatsSnapshot, TorrentTrackerStats};
+use crate::models::torrent_file::{web_seeds_of_type, DbTorrent, DbTorrentWebSeed, Torrent, TorrentFile, WebSeedType};
 use crate::models::torrent_tag::{TagId, TorrentTag};
-use crate::models::user::UserId;
+use crate::models::user::{UserCompact, UserId};
+use crate::tracker::job_queue::JobQueue;
 use crate::tracker::statistics_importer::StatisticsImporter;
 use crate::utils::parse_torrent::decode_and_validate_torrent_file;
+use crate::utils::validation::validate_web_seed_url;
 use crate::{tracker, AsCSV};
 
 pub struct Index {
     configuration: Arc<Configuration>,
     tracker_statistics_importer: Arc<StatisticsImporter>,
     tracker_service: Arc<tracker::service::Service>,
+    tracker_job_queue: Arc<JobQueue>,
     user_repository: Arc<DbUserRepository>,
+    user_profile_repository: Arc<DbUserProfileRepository>,
     category_repository: Arc<DbCategoryRepository>,
     torrent_repository: Arc<DbTorrentRepository>,
     torrent_info_hash_repository: Arc<DbCanonicalInfoHashGroupRepository>,
     torrent_info_repository: Arc<DbTorrentInfoRepository>,
     torrent_file_repository: Arc<DbTorrentFileRepository>,
     torrent_announce_url_repository: Arc<DbTorrentAnnounceUrlRepository>,
+    torrent_web_seed_repository: Arc<DbTorrentWebSeedRepository>,
+    torrent_image_repository: Arc<DbTorrentImageRepository>,
     torrent_tag_repository: Arc<DbTorrentTagRepository>,
     torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    notifier: Arc<Notifier>,
 }
 
 pub struct AddTorrentRequest {
@@ -71,6 +83,8 @@ pub struct ListingSpecification {
     pub sort: Sorting,
     pub offset: u64,
     pub page_size: u8,
+    /// Whether torrents marked as dead are included.
+    pub include_dead: bool,
 }
 
 impl Index {
@@ -80,29 +94,39 @@ impl Index {
         configuration: Arc<Configuration>,
         tracker_statistics_importer: Arc<StatisticsImporter>,
         tracker_service: Arc<tracker::service::Service>,
+        tracker_job_queue: Arc<JobQueue>,
         user_repository: Arc<DbUserRepository>,
+        user_profile_repository: Arc<DbUserProfileRepository>,
         category_repository: Arc<DbCategoryRepository>,
         torrent_repository: Arc<DbTorrentRepository>,
         torrent_info_hash_repository: Arc<DbCanonicalInfoHashGroupRepository>,
         torrent_info_repository: Arc<DbTorrentInfoRepository>,
         torrent_file_repository: Arc<DbTorrentFileRepository>,
         torrent_announce_url_repository: Arc<DbTorrentAnnounceUrlRepository>,
+        torrent_web_seed_repository: Arc<DbTorrentWebSeedRepository>,
+        torrent_image_repository: Arc<DbTorrentImageRepository>,
         torrent_tag_repository: Arc<DbTorrentTagRepository>,
         torrent_listing_repository: Arc<DbTorrentListingGenerator>,
+        notifier: Arc<Notifier>,
     ) -> Self {
         Self {
             configuration,
             tracker_statistics_importer,
             tracker_service,
+            tracker_job_queue,
             user_repository,
+            user_profile_repository,
             category_repository,
             torrent_repository,
             torrent_info_hash_repository,
             torrent_info_repository,
             torrent_file_repository,
             torrent_announce_url_repository,
+            torrent_web_seed_repository,
+            torrent_image_repository,
             torrent_tag_repository,
             torrent_listing_generator: torrent_listing_repository,
+            notifier,
         }
     }
 
@@ -113,10 +137,12 @@ impl Index {
     /// This function will return an error if:
     ///
     /// * Unable to get the user from the database.
+    /// * The uploader did not verify their email and it's required.
     /// * Unable to get torrent request from payload.
     /// * Unable to get the category from the database.
     /// * Unable to insert the torrent into the database.
-    /// * Unable to add the torrent to the whitelist.
+    /// * Unable to enqueue the tracker jobs to whitelist the torrent and
+    ///   import its statistics.
     /// * Torrent title is too short.
     ///
     /// # Panics
@@ -132,12 +158,16 @@ impl Index {
         // Guard that the users exists
         let _user = self.user_repository.get_compact(&user_id).await?;
 
+        self.guard_email_verified(&user_id).await?;
+
         let metadata = self.validate_and_build_metadata(&add_torrent_req).await?;
 
         let (mut torrent, original_info_hash) = decode_and_validate_torrent_file(&add_torrent_req.torrent_buffer)?;
 
         self.customize_announcement_info_for(&mut torrent).await;
 
+        torrent.retain_valid_web_seeds();
+
         self.canonical_info_hash_group_checks(&original_info_hash, &torrent.canonical_info_hash())
             .await?;
 
@@ -146,27 +176,20 @@ impl Index {
             .add(&original_info_hash, &torrent, &metadata, user_id)
             .await?;
 
-        // Synchronous secondary tasks
+        // Secondary tasks on the tracker. They are executed in the background
+        // so the upload does not fail when the tracker is not available.
 
-        // code-review: consider moving this to a background task
-        self.import_torrent_statistics_from_tracker(torrent_id, &torrent.canonical_info_hash())
-            .await;
+        self.tracker_job_queue
+            .enqueue_import_statistics(torrent_id, &torrent.canonical_info_hash_hex())
+            .await?;
 
         // We always whitelist the torrent on the tracker because
         // even if the tracker mode is `public` it could be changed to `private`
-        // later on.
-        //
-        // code-review: maybe we should consider adding a new feature to
-        // whitelist  all torrents from the admin panel if that change happens.
-        if let Err(e) = self
-            .tracker_service
-            .whitelist_info_hash(torrent.canonical_info_hash_hex())
-            .await
-        {
-            // If the torrent can't be whitelisted somehow, remove the torrent from database
-            drop(self.torrent_repository.delete(&torrent_id).await);
-            return Err(e.into());
-        }
+        // later on. Administrators can also sync the whitelist with the tracker
+        // mode after changing it. See `TrackerModeSynchronizer`.
+        self.tracker_job_queue
+            .enqueue_whitelist(&torrent.canonical_info_hash_hex())
+            .await?;
 
         // Build response
 
@@ -177,6 +200,26 @@ impl Index {
         })
     }
 
+    /// It checks that the uploader verified their email when it's required
+    /// by `email_verification.required_for_upload`.
+    async fn guard_email_verified(&self, user_id: &UserId) -> Result<(), ServiceError> {
+        let settings = self.configuration.settings.read().await;
+        let required = settings.mail.email_verification_enabled && settings.email_verification.required_for_upload;
+        drop(settings);
+
+        if !required {
+            return Ok(());
+        }
+
+        let user_profile = self.user_profile_repository.get_user_profile_from_id(user_id).await?;
+
+        if !user_profile.email_verified {
+            return Err(ServiceError::UploadRequiresVerifiedEmail);
+        }
+
+        Ok(())
+    }
+
     async fn validate_and_build_metadata(&self, add_torrent_req: &AddTorrentRequest) -> Result<Metadata, ServiceError> {
         if add_torrent_req.category_name.is_empty() {
             return Err(ServiceError::MissingMandatoryMetadataFields);
@@ -239,14 +282,6 @@ impl Index {
         torrent.reset_announce_list_if_private();
     }
 
-    async fn import_torrent_statistics_from_tracker(&self, torrent_id: TorrentId, canonical_info_hash: &InfoHash) {
-        drop(
-            self.tracker_statistics_importer
-                .import_torrent_statistics(torrent_id, &canonical_info_hash.to_hex_string())
-                .await,
-        );
-    }
-
     /// Gets a torrent from the Index.
     ///
     /// # Errors
@@ -271,9 +306,28 @@ impl Index {
             torrent.include_url_as_main_tracker(&tracker_url);
         }
 
+        torrent.add_urls_to_back_of_announce_list(&self.get_extra_tracker_urls_for_announce_list().await);
+
         Ok(torrent)
     }
 
+    /// It counts a download of the torrent file. If the user is logged in, it
+    /// also counts the download for the user.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the torrent is not found or there
+    /// is a database error.
+    pub async fn record_download(&self, info_hash: &InfoHash, opt_user_id: Option<UserId>) -> Result<(), ServiceError> {
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        self.torrent_repository
+            .increase_downloads(&torrent_listing.torrent_id, opt_user_id)
+            .await?;
+
+        Ok(())
+    }
+
     /// Delete a Torrent from the Index
     ///
     /// # Errors
@@ -284,6 +338,8 @@ impl Index {
     /// * The user does not have permission to delete the torrent.
     /// * Unable to get the torrent listing from it's ID.
     /// * Unable to delete the torrent from the database.
+    /// * Unable to enqueue the tracker job to remove the torrent from the
+    ///   whitelist.
     pub async fn delete_torrent(&self, info_hash: &InfoHash, user_id: &UserId) -> Result<DeletedTorrentResponse, ServiceError> {
         let user = self.user_repository.get_compact(user_id).await?;
 
@@ -295,14 +351,20 @@ impl Index {
 
         let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
 
-        self.torrent_repository.delete(&torrent_listing.torrent_id).await?;
+        self.remove_torrent(torrent_listing.torrent_id, &info_hash.to_hex_string())
+            .await?;
 
-        // Remove info-hash from tracker whitelist
-        // todo: handle the error when the tracker is offline or not well configured.
-        let _unused = self
-            .tracker_service
-            .remove_info_hash_from_whitelist(info_hash.to_string())
-            .await;
+        self.notify_uploader(
+            &torrent_listing,
+            &user,
+            NewNotification::for_torrent(
+                NotificationKind::TorrentDeleted,
+                torrent_listing.torrent_id,
+                &torrent_listing.info_hash,
+                &torrent_listing.title,
+            ),
+        )
+        .await;
 
         Ok(DeletedTorrentResponse {
             torrent_id: torrent_listing.torrent_id,
@@ -310,6 +372,28 @@ impl Index {
         })
     }
 
+    /// It deletes a torrent without checking the permissions of any user. It's
+    /// used when the torrent is deleted by the index itself, for example, by
+    /// the dead torrent policy, or when its uploader is deleted.
+    ///
+    /// The torrent is removed from the tracker whitelist in the background.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    ///
+    /// * Unable to delete the torrent from the database.
+    /// * Unable to enqueue the tracker job to remove the torrent from the
+    ///   whitelist.
+    pub async fn remove_torrent(&self, torrent_id: TorrentId, info_hash: &str) -> Result<(), ServiceError> {
+        self.torrent_repository.delete(&torrent_id).await?;
+
+        // Remove info-hash from tracker whitelist in the background
+        self.tracker_job_queue.enqueue_remove_from_whitelist(info_hash).await?;
+
+        Ok(())
+    }
+
     /// Get torrent info from the Index
     ///
     /// # Errors
@@ -377,6 +461,24 @@ impl Index {
             }
         }
 
+        // Add extra trackers. Private torrents are only announced to the index tracker.
+        if !self.torrent_info_repository.get_by_info_hash(info_hash).await?.is_private() {
+            torrent_response.include_urls_as_extra_trackers(&self.get_extra_tracker_urls_for_announce_list().await);
+        }
+
+        // Add web seeds
+
+        let web_seeds = self.torrent_web_seed_repository.get_by_torrent_id(&torrent_id).await?;
+
+        torrent_response.web_seeds = web_seeds_of_type(&web_seeds, WebSeedType::UrlList).unwrap_or_default();
+        torrent_response.http_seeds = web_seeds_of_type(&web_seeds, WebSeedType::HttpSeed).unwrap_or_default();
+
+        // Add poster and screenshots
+
+        let torrent_images = self.torrent_image_repository.get_by_torrent_id(&torrent_id).await?;
+
+        torrent_response.set_images(TorrentImagesResponse::from(torrent_images));
+
         // Add magnet link
 
         // todo: extract a struct or function to build the magnet links
@@ -393,21 +495,35 @@ impl Index {
 
         torrent_response.magnet_link = magnet;
 
-        // Get realtime seeders and leechers
-        if let Ok(torrent_info) = self
-            .tracker_statistics_importer
-            .import_torrent_statistics(torrent_response.torrent_id, &torrent_response.info_hash)
-            .await
-        {
-            torrent_response.seeders = torrent_info.seeders;
-            torrent_response.leechers = torrent_info.leechers;
-        }
+        // Get realtime seeders and leechers from the index tracker. The
+        // importer stores them with the statistics from the extra trackers.
+        drop(
+            self.tracker_statistics_importer
+                .import_torrent_statistics(torrent_response.torrent_id, &torrent_response.info_hash)
+                .await,
+        );
+
+        torrent_response.set_tracker_stats(self.torrent_repository.get_tracker_stats(&torrent_id).await?);
 
         torrent_response.tags = self.torrent_tag_repository.get_tags_for_torrent(&torrent_id).await?;
 
         Ok(torrent_response)
     }
 
+    /// It returns the statistics history of a torrent, oldest snapshot first.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    ///
+    /// * Unable to get the torrent listing from the info-hash.
+    /// * Unable to get the statistics history from the database.
+    pub async fn get_torrent_stats_history(&self, info_hash: &InfoHash) -> Result<Vec<TorrentStatsSnapshot>, ServiceError> {
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        Ok(self.torrent_repository.get_stats_history(&torrent_listing.torrent_id).await?)
+    }
+
     /// It returns a list of torrents matching the search criteria.
     ///
     /// # Errors
@@ -430,6 +546,7 @@ impl Index {
         let settings = self.configuration.settings.read().await;
         let default_torrent_page_size = settings.api.default_torrent_page_size;
         let max_torrent_page_size = settings.api.max_torrent_page_size;
+        let hide_dead_torrents = settings.dead_torrents.hide_dead_torrents;
         drop(settings);
 
         let sort = request.sort.unwrap_or(Sorting::UploadedDesc);
@@ -456,6 +573,7 @@ impl Index {
             sort,
             offset,
             page_size,
+            include_dead: !hide_dead_torrents,
         }
     }
 
@@ -469,6 +587,8 @@ impl Index {
     /// * Unable to get listing from id.
     /// * Unable to update the torrent tile or description.
     /// * User does not have the permissions to update the torrent.
+    /// * A web seed URL is not valid.
+    #[allow(clippy::too_many_arguments)]
     pub async fn update_torrent_info(
         &self,
         info_hash: &InfoHash,
@@ -476,8 +596,15 @@ impl Index {
         description: &Option<String>,
         category_id: &Option<CategoryId>,
         tags: &Option<Vec<TagId>>,
+        web_seeds: &Option<Vec<String>>,
         user_id: &UserId,
     ) -> Result<TorrentResponse, ServiceError> {
+        if let Some(web_seeds) = web_seeds {
+            if !web_seeds.iter().all(|url| validate_web_seed_url(url)) {
+                return Err(ServiceError::InvalidWebSeedUrl);
+            }
+        }
+
         let updater = self.user_repository.get_compact(user_id).await?;
 
         let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
@@ -488,25 +615,141 @@ impl Index {
             return Err(ServiceError::Unauthorized);
         }
 
+        let changed_fields = self
+            .changed_fields(&torrent_listing, title, description, category_id, tags, web_seeds)
+            .await?;
+
         self.torrent_info_repository
             .update(&torrent_listing.torrent_id, title, description, category_id, tags)
             .await?;
 
+        if let Some(web_seeds) = web_seeds {
+            self.torrent_web_seed_repository
+                .update(&torrent_listing.torrent_id, web_seeds)
+                .await?;
+        }
+
         let torrent_listing = self
             .torrent_listing_generator
             .one_torrent_by_torrent_id(&torrent_listing.torrent_id)
             .await?;
 
+        if !changed_fields.is_empty() {
+            self.notify_uploader(
+                &torrent_listing,
+                &updater,
+                NewNotification::for_torrent(
+                    NotificationKind::TorrentEdited,
+                    torrent_listing.torrent_id,
+                    &torrent_listing.info_hash,
+                    &torrent_listing.title,
+                )
+                .with_detail(&format!("Changed: {}", changed_fields.join(", "))),
+            )
+            .await;
+        }
+
+        let torrent_id = torrent_listing.torrent_id;
+
         let category = match torrent_listing.category_id {
             Some(category_id) => Some(self.category_repository.get_by_id(&category_id).await?),
             None => None,
         };
 
-        let torrent_response = TorrentResponse::from_listing(torrent_listing, category);
+        let mut torrent_response = TorrentResponse::from_listing(torrent_listing, category);
+
+        let web_seeds = self.torrent_web_seed_repository.get_by_torrent_id(&torrent_id).await?;
+
+        torrent_response.web_seeds = web_seeds_of_type(&web_seeds, WebSeedType::UrlList).unwrap_or_default();
+        torrent_response.http_seeds = web_seeds_of_type(&web_seeds, WebSeedType::HttpSeed).unwrap_or_default();
+
+        let torrent_images = self.torrent_image_repository.get_by_torrent_id(&torrent_id).await?;
+
+        torrent_response.set_images(TorrentImagesResponse::from(torrent_images));
 
         Ok(torrent_response)
     }
 
+    /// It returns the fields of the torrent whose values are different in the
+    /// update. Clients usually send all the fields, even the ones that did not
+    /// change.
+    async fn changed_fields(
+        &self,
+        torrent_listing: &TorrentListing,
+        title: &Option<String>,
+        description: &Option<String>,
+        category_id: &Option<CategoryId>,
+        tags: &Option<Vec<TagId>>,
+        web_seeds: &Option<Vec<String>>,
+    ) -> Result<Vec<&'static str>, ServiceError> {
+        let mut changed_fields = vec![];
+
+        if title.as_ref().is_some_and(|title| *title != torrent_listing.title) {
+            changed_fields.push("title");
+        }
+
+        if description
+            .as_ref()
+            .is_some_and(|description| torrent_listing.description.as_ref() != Some(description))
+        {
+            changed_fields.push("description");
+        }
+
+        if category_id.is_some_and(|category_id| torrent_listing.category_id != Some(category_id)) {
+            changed_fields.push("category");
+        }
+
+        if let Some(tags) = tags {
+            let current_tags: BTreeSet<TagId> = self
+                .torrent_tag_repository
+                .get_tags_for_torrent(&torrent_listing.torrent_id)
+                .await?
+                .iter()
+                .map(|tag| tag.tag_id)
+                .collect();
+
+            if current_tags != tags.iter().copied().collect() {
+                changed_fields.push("tags");
+            }
+        }
+
+        if let Some(web_seeds) = web_seeds {
+            let current_web_seeds: BTreeSet<String> = web_seeds_of_type(
+                &self
+                    .torrent_web_seed_repository
+                    .get_by_torrent_id(&torrent_listing.torrent_id)
+                    .await?,
+                WebSeedType::UrlList,
+            )
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+
+            if current_web_seeds != web_seeds.iter().cloned().collect() {
+                changed_fields.push("web seeds");
+            }
+        }
+
+        Ok(changed_fields)
+    }
+
+    /// It notifies the uploader of a torrent about a change made by another
+    /// user, usually an administrator.
+    async fn notify_uploader(&self, torrent_listing: &TorrentListing, user: &UserCompact, notification: NewNotification) {
+        if torrent_listing.uploader == user.username {
+            return;
+        }
+
+        match self
+            .user_profile_repository
+            .get_user_profile_from_username(&torrent_listing.uploader)
+            .await
+        {
+            Ok(uploader) => self.notifier.notify(uploader.user_id, notification).await,
+            Err(err) => error!("Failed to get the uploader of torrent {}: {err:?}", torrent_listing.info_hash),
+        }
+    }
+
     async fn get_tracker_url(&self) -> String {
         let settings = self.configuration.settings.read().await;
         settings.tracker.url.clone()
@@ -516,6 +759,17 @@ impl Index {
         let settings = self.configuration.settings.read().await;
         settings.tracker.mode.clone()
     }
+
+    async fn get_extra_tracker_urls_for_announce_list(&self) -> Vec<String> {
+        let settings = self.configuration.settings.read().await;
+        settings
+            .tracker
+            .extra_trackers
+            .iter()
+            .filter(|extra_tracker| extra_tracker.add_to_announce_list)
+            .map(|extra_tracker| extra_tracker.url.clone())
+            .collect()
+    }
 }
 
 pub struct DbTorrentRepository {
@@ -562,6 +816,36 @@ impl DbTorrentRepository {
     pub async fn delete(&self, torrent_id: &TorrentId) -> Result<(), Error> {
         self.database.delete_torrent(*torrent_id).await
     }
+
+    /// It increases the download counters of the torrent and, if given, of
+    /// the user.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error there is a database error.
+    pub async fn increase_downloads(&self, torrent_id: &TorrentId, opt_user_id: Option<UserId>) -> Result<(), Error> {
+        self.database
+            .increase_torrent_downloads(*torrent_id, opt_user_id, Utc::now().naive_utc())
+            .await
+    }
+
+    /// It returns the torrent statistics imported from each tracker.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error there is a database error.
+    pub async fn get_tracker_stats(&self, torrent_id: &TorrentId) -> Result<Vec<TorrentTrackerStats>, Error> {
+        self.database.get_torrent_tracker_stats(*torrent_id).await
+    }
+
+    /// It returns the statistics history of the torrent.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error there is a database error.
+    pub async fn get_stats_history(&self, torrent_id: &TorrentId) -> Result<Vec<TorrentStatsSnapshot>, Error> {
+        self.database.get_torrent_stats_history(*torrent_id).await
+    }
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -761,6 +1045,46 @@ impl DbTorrentAnnounceUrlRepository {
     }
 }
 
+pub struct DbTorrentWebSeedRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbTorrentWebSeedRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It finds the web seeds and HTTP seeds by torrent id.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_by_torrent_id(&self, torrent_id: &TorrentId) -> Result<Vec<DbTorrentWebSeed>, Error> {
+        self.database.get_torrent_web_seeds_from_id(*torrent_id).await
+    }
+
+    /// It replaces the web seeds (`url-list`) of a torrent. Duplicate URLs
+    /// are only added once.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update(&self, torrent_id: &TorrentId, urls: &[String]) -> Result<(), Error> {
+        let mut unique_urls: Vec<String> = vec![];
+
+        for url in urls {
+            if !unique_urls.contains(url) {
+                unique_urls.push(url.clone());
+            }
+        }
+
+        self.database
+            .update_torrent_web_seeds(*torrent_id, WebSeedType::UrlList, &unique_urls)
+            .await
+    }
+}
+
 pub struct DbTorrentTagRepository {
     database: Arc<Box<dyn Database>>,
 }
@@ -859,6 +1183,7 @@ impl DbTorrentListingGenerator {
                 &specification.sort,
                 specification.offset,
                 specification.page_size,
+                specification.include_dead,
             )
             .await
     }
@@ -0,0 +1,621 @@
+//! Tracker administration services.
+//!
+//! The index keeps the tracker whitelist up to date when torrents are added or
+//! deleted. But the tracker whitelist can drift from the index, for example,
+//! if a whitelisting request fails or the tracker database is reset. The
+//! [`WhitelistReconciler`] fixes the differences it can detect.
+//!
+//! Changes to the tracker are executed in the background by the tracker
+//! [`JobQueue`]. Administrators can list the jobs and retry the failed ones.
+//!
+//! When the tracker mode changes, the [`TrackerModeSynchronizer`] whitelists
+//! or un-whitelists all the torrents and revokes the user tracker keys.
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::Utc;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, Notify};
+
+use super::user::DbUserRepository;
+use crate::config::{Configuration, TrackerMode};
+use crate::databases::database::Database;
+use crate::errors::ServiceError;
+use crate::models::tracker_job::{Status, TrackerJob, TrackerJobId};
+use crate::models::tracker_mode_sync::{Action, TrackerModeSync};
+use crate::models::user::UserId;
+use crate::tracker;
+use crate::tracker::job_queue::JobQueue;
+use crate::tracker::service::TrackerAPIError;
+
+const LOG_TARGET: &str = "Tracker Whitelist Reconciler";
+
+const MODE_SYNC_LOG_TARGET: &str = "Tracker Mode Sync";
+
+/// Number of torrents processed before saving the progress of a tracker mode
+/// sync.
+const MODE_SYNC_BATCH_SIZE: u32 = 100;
+
+/// Number of torrents requested to the tracker API per page.
+const TRACKER_TORRENTS_PAGE_SIZE: u32 = 1000;
+
+/// Summary of a whitelist reconciliation.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReconciliationReport {
+    /// Whether changes were only reported but not applied to the tracker.
+    pub dry_run: bool,
+    /// Whether the tracker runs in a whitelisted mode. The whitelist is only
+    /// reconciled in those modes.
+    pub whitelisted_mode: bool,
+    /// Number of torrents in the index.
+    pub torrents_in_index: usize,
+    /// Number of torrents tracked by the tracker, that is, the torrents with
+    /// peers.
+    pub torrents_in_tracker: usize,
+    /// Number of index torrents the tracker is not tracking. It's not the
+    /// number of torrents missing from the whitelist: most of them are
+    /// usually whitelisted but without peers.
+    pub not_seen_by_tracker: usize,
+    /// Number of tracked torrents that do not exist in the index.
+    pub stale: usize,
+    /// Number of tracker jobs enqueued to add a torrent to the whitelist.
+    pub added: usize,
+    /// Number of tracker jobs enqueued to remove a torrent from the
+    /// whitelist.
+    pub removed: usize,
+    /// Info-hashes of the torrents whose tracker jobs could not be enqueued.
+    pub failed: Vec<String>,
+}
+
+/// It compares the torrents in the index with the torrents tracked by the
+/// tracker and fixes the differences in the tracker whitelist.
+///
+/// The tracker API does not expose the whitelist itself. The only torrents
+/// the index can see are the ones tracked by the tracker, that is, the
+/// torrents with peers. That limits what the reconciler can do:
+///
+/// - Index torrents that are not tracked might be whitelisted without peers,
+///   or missing from the whitelist. They can't be told apart, so all of them
+///   are whitelisted again. Whitelisting a torrent is idempotent.
+/// - Tracked torrents that do not exist in the index are removed from the
+///   whitelist. Stale whitelist entries without peers can't be detected. They
+///   are removed once they get peers, in a later reconciliation.
+///
+/// The changes are not made by the reconciler but by the tracker
+/// [`JobQueue`], like the other changes to the tracker whitelist. Failed jobs
+/// are retried and they are listed to administrators.
+///
+/// The whitelist is only reconciled when the tracker runs in a whitelisted
+/// mode. In the other modes the tracker accepts any torrent, so tracked
+/// torrents are not stale.
+pub struct WhitelistReconciler {
+    cfg: Arc<Configuration>,
+    database: Arc<Box<dyn Database>>,
+    tracker_service: Arc<tracker::service::Service>,
+    job_queue: Arc<JobQueue>,
+}
+
+/// The differences between the torrents in the index and the torrents
+/// tracked by the tracker. Info-hashes are sorted.
+#[derive(Debug, PartialEq, Eq)]
+struct WhitelistDiff {
+    /// Index torrents the tracker is not tracking.
+    not_seen_by_tracker: Vec<String>,
+    /// Tracked torrents that do not exist in the index.
+    stale: Vec<String>,
+}
+
+impl WhitelistDiff {
+    fn new(index_info_hashes: &HashSet<String>, tracker_info_hashes: &HashSet<String>) -> Self {
+        let mut not_seen_by_tracker: Vec<String> = index_info_hashes.difference(tracker_info_hashes).cloned().collect();
+        let mut stale: Vec<String> = tracker_info_hashes.difference(index_info_hashes).cloned().collect();
+
+        not_seen_by_tracker.sort();
+        stale.sort();
+
+        Self {
+            not_seen_by_tracker,
+            stale,
+        }
+    }
+}
+
+impl WhitelistReconciler {
+    #[must_use]
+    pub fn new(
+        cfg: Arc<Configuration>,
+        database: Arc<Box<dyn Database>>,
+        tracker_service: Arc<tracker::service::Service>,
+        job_queue: Arc<JobQueue>,
+    ) -> Self {
+        Self {
+            cfg,
+            database,
+            tracker_service,
+            job_queue,
+        }
+    }
+
+    /// It reconciles the tracker whitelist with the torrents in the index,
+    /// enqueuing a tracker job for each torrent to add or remove.
+    ///
+    /// When `dry_run` is `true` the differences are reported but no jobs are
+    /// enqueued.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * There is a database error.
+    /// * The torrents tracked by the tracker can't be retrieved.
+    pub async fn reconcile(&self, dry_run: bool) -> Result<ReconciliationReport, ServiceError> {
+        let whitelisted_mode = self.cfg.settings.read().await.tracker.mode.is_whitelisted();
+
+        if !whitelisted_mode {
+            info!(target: LOG_TARGET, "The tracker does not run in a whitelisted mode. Nothing to reconcile.");
+
+            return Ok(ReconciliationReport {
+                dry_run,
+                ..ReconciliationReport::default()
+            });
+        }
+
+        let index_info_hashes: HashSet<String> = self
+            .database
+            .get_all_torrents_compact()
+            .await?
+            .into_iter()
+            .map(|torrent| torrent.info_hash.to_lowercase())
+            .collect();
+
+        let tracker_info_hashes = self.get_all_tracker_info_hashes().await?;
+
+        let diff = WhitelistDiff::new(&index_info_hashes, &tracker_info_hashes);
+
+        info!(target: LOG_TARGET, "Torrents in index: {}, in tracker: {}, not seen by tracker: {}, stale: {}, dry run: {dry_run}", index_info_hashes.len(), tracker_info_hashes.len(), diff.not_seen_by_tracker.len(), diff.stale.len());
+
+        let mut report = ReconciliationReport {
+            dry_run,
+            whitelisted_mode,
+            torrents_in_index: index_info_hashes.len(),
+            torrents_in_tracker: tracker_info_hashes.len(),
+            not_seen_by_tracker: diff.not_seen_by_tracker.len(),
+            stale: diff.stale.len(),
+            ..ReconciliationReport::default()
+        };
+
+        if dry_run {
+            return Ok(report);
+        }
+
+        for info_hash in &diff.not_seen_by_tracker {
+            match self.job_queue.enqueue_whitelist(info_hash).await {
+                Ok(_) => report.added += 1,
+                Err(err) => {
+                    error!(target: LOG_TARGET, "Failed to enqueue adding torrent {info_hash} to the whitelist: {err:?}");
+                    report.failed.push(info_hash.clone());
+                }
+            }
+        }
+
+        for info_hash in &diff.stale {
+            match self.job_queue.enqueue_remove_from_whitelist(info_hash).await {
+                Ok(_) => report.removed += 1,
+                Err(err) => {
+                    error!(target: LOG_TARGET, "Failed to enqueue removing torrent {info_hash} from the whitelist: {err:?}");
+                    report.failed.push(info_hash.clone());
+                }
+            }
+        }
+
+        info!(target: LOG_TARGET, "Whitelist reconciled. Jobs enqueued to add: {}, to remove: {}, failed: {}", report.added, report.removed, report.failed.len());
+
+        Ok(report)
+    }
+
+    async fn get_all_tracker_info_hashes(&self) -> Result<HashSet<String>, ServiceError> {
+        let mut info_hashes = HashSet::new();
+        let mut offset = 0;
+
+        loop {
+            let torrents = self.tracker_service.get_torrents(offset, TRACKER_TORRENTS_PAGE_SIZE).await?;
+
+            let page_len = torrents.len();
+
+            info_hashes.extend(torrents.into_iter().map(|torrent| torrent.info_hash.to_lowercase()));
+
+            if page_len < TRACKER_TORRENTS_PAGE_SIZE as usize {
+                break;
+            }
+
+            offset += TRACKER_TORRENTS_PAGE_SIZE;
+        }
+
+        Ok(info_hashes)
+    }
+}
+
+/// It synchronizes the tracker with the configured tracker mode.
+///
+/// A sync is started by an administrator after changing the tracker mode. It:
+///
+/// 1. Revokes the user tracker keys if the tracker changes from open to
+///    closed, or the other way around. New keys are issued when users
+///    download torrents. The keys are not revoked on the first sync, because
+///    the previous mode is not known.
+/// 2. Adds all the torrents to the tracker whitelist if the new mode is
+///    whitelisted, or removes them from the whitelist otherwise.
+///
+/// Torrents are processed in the background by the
+/// [`tracker_mode_sync`](crate::console::cronjobs::tracker_mode_sync) cronjob,
+/// which enqueues a [tracker job](crate::tracker::job_queue) for each torrent.
+/// The jobs are retried by the job queue when the tracker is not available.
+/// The progress is saved after each batch, so an interrupted sync is resumed
+/// from the last processed torrent.
+pub struct TrackerModeSynchronizer {
+    cfg: Arc<Configuration>,
+    database: Arc<Box<dyn Database>>,
+    tracker_service: Arc<tracker::service::Service>,
+    job_queue: Arc<JobQueue>,
+    /// Serializes starting and running syncs.
+    lock: Mutex<()>,
+    new_sync: Notify,
+}
+
+impl TrackerModeSynchronizer {
+    #[must_use]
+    pub fn new(
+        cfg: Arc<Configuration>,
+        database: Arc<Box<dyn Database>>,
+        tracker_service: Arc<tracker::service::Service>,
+        job_queue: Arc<JobQueue>,
+    ) -> Self {
+        Self {
+            cfg,
+            database,
+            tracker_service,
+            job_queue,
+            lock: Mutex::new(()),
+            new_sync: Notify::new(),
+        }
+    }
+
+    /// It starts a sync to the configured tracker mode. If there is a sync
+    /// running, it's returned instead.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The tracker mode did not change since the last completed sync, unless
+    ///   `force` is `true`.
+    /// * There is a database error.
+    pub async fn start(&self, force: bool) -> Result<TrackerModeSync, ServiceError> {
+        let _guard = self.lock.lock().await;
+
+        if let Some(sync) = self.database.get_last_tracker_mode_sync().await? {
+            if sync.is_running() {
+                return Ok(sync);
+            }
+        }
+
+        let to_mode = self.cfg.settings.read().await.tracker.mode.clone();
+
+        let from_mode = self
+            .database
+            .get_last_completed_tracker_mode_sync()
+            .await?
+            .and_then(|sync| sync.to_mode.parse::<TrackerMode>().ok());
+
+        if from_mode.as_ref() == Some(&to_mode) && !force {
+            return Err(ServiceError::TrackerModeUnchanged);
+        }
+
+        if from_mode
+            .as_ref()
+            .is_some_and(|from_mode| from_mode.is_close() != to_mode.is_close())
+        {
+            self.revoke_all_tracker_keys().await?;
+        }
+
+        let action = Action::for_mode(&to_mode);
+
+        let torrents_total = self.database.get_all_torrents_compact().await?.len();
+
+        let sync_id = self
+            .database
+            .insert_tracker_mode_sync(
+                from_mode.as_ref().map(TrackerMode::as_str),
+                to_mode.as_str(),
+                action.as_str(),
+                i64::try_from(torrents_total).unwrap_or(i64::MAX),
+                Utc::now().naive_utc(),
+            )
+            .await?;
+
+        info!(target: MODE_SYNC_LOG_TARGET, "Tracker mode sync #{sync_id} started: {:?} -> {}, {} torrents", from_mode, to_mode.as_str(), torrents_total);
+
+        // Wake up the worker so the sync starts as soon as possible.
+        self.new_sync.notify_one();
+
+        self.database
+            .get_last_tracker_mode_sync()
+            .await?
+            .ok_or(ServiceError::InternalServerError)
+    }
+
+    /// It revokes the tracker keys of all users. The keys are deleted from the
+    /// database even if the tracker is not available.
+    async fn revoke_all_tracker_keys(&self) -> Result<(), ServiceError> {
+        info!(target: MODE_SYNC_LOG_TARGET, "Revoking user tracker keys");
+
+        for user_id in self.database.get_users_with_tracker_keys().await? {
+            match self.tracker_service.revoke_user_tracker_keys(user_id).await {
+                Ok(_) => {}
+                Err(err @ (TrackerAPIError::TrackerOffline | TrackerAPIError::InvalidToken)) => {
+                    warn!(target: MODE_SYNC_LOG_TARGET, "Failed to revoke the tracker keys of user {user_id} from the tracker: {err}");
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// It returns the last sync, running or completed.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn last_sync(&self) -> Result<Option<TrackerModeSync>, ServiceError> {
+        Ok(self.database.get_last_tracker_mode_sync().await?)
+    }
+
+    /// It waits until a new sync is started.
+    pub async fn wait_for_new_sync(&self) {
+        self.new_sync.notified().await;
+    }
+
+    /// It processes the running sync, if any, enqueuing a tracker job for each
+    /// torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn run_pending(&self) -> Result<(), ServiceError> {
+        let _guard = self.lock.lock().await;
+
+        let Some(mut sync) = self.database.get_last_tracker_mode_sync().await? else {
+            return Ok(());
+        };
+
+        if !sync.is_running() {
+            return Ok(());
+        }
+
+        let action = Action::from_str(&sync.action).map_err(|_| ServiceError::InternalServerError)?;
+
+        loop {
+            let torrents = self
+                .database
+                .get_torrents_compact_after(sync.last_torrent_id, MODE_SYNC_BATCH_SIZE)
+                .await?;
+
+            if torrents.is_empty() {
+                self.database
+                    .complete_tracker_mode_sync(sync.sync_id, Utc::now().naive_utc())
+                    .await?;
+
+                info!(target: MODE_SYNC_LOG_TARGET, "Tracker mode sync #{} completed. Processed: {}, errors: {}", sync.sync_id, sync.torrents_processed, sync.errors);
+
+                return Ok(());
+            }
+
+            let mut last_error = None;
+
+            for torrent in &torrents {
+                let info_hash = torrent.info_hash.to_lowercase();
+
+                let result = match action {
+                    Action::Whitelist => self.job_queue.enqueue_whitelist(&info_hash).await,
+                    Action::RemoveFromWhitelist => self.job_queue.enqueue_remove_from_whitelist(&info_hash).await,
+                };
+
+                if let Err(err) = result {
+                    let message = format!("Failed to enqueue the tracker job for torrent {info_hash}: {err:?}");
+                    error!(target: MODE_SYNC_LOG_TARGET, "{message}");
+                    sync.errors += 1;
+                    last_error = Some(message);
+                }
+
+                sync.last_torrent_id = torrent.torrent_id;
+                sync.torrents_processed += 1;
+            }
+
+            self.save_progress(&sync, last_error.as_deref()).await?;
+        }
+    }
+
+    async fn save_progress(&self, sync: &TrackerModeSync, last_error: Option<&str>) -> Result<(), ServiceError> {
+        Ok(self
+            .database
+            .update_tracker_mode_sync_progress(
+                sync.sync_id,
+                sync.last_torrent_id,
+                sync.torrents_processed,
+                sync.errors,
+                last_error,
+                Utc::now().naive_utc(),
+            )
+            .await?)
+    }
+}
+
+pub struct Service {
+    whitelist_reconciler: Arc<WhitelistReconciler>,
+    job_queue: Arc<JobQueue>,
+    tracker_mode_synchronizer: Arc<TrackerModeSynchronizer>,
+    user_repository: Arc<DbUserRepository>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        whitelist_reconciler: Arc<WhitelistReconciler>,
+        job_queue: Arc<JobQueue>,
+        tracker_mode_synchronizer: Arc<TrackerModeSynchronizer>,
+        user_repository: Arc<DbUserRepository>,
+    ) -> Service {
+        Service {
+            whitelist_reconciler,
+            job_queue,
+            tracker_mode_synchronizer,
+            user_repository,
+        }
+    }
+
+    /// It reconciles the tracker whitelist with the torrents in the index.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There is a database error.
+    /// * The torrents tracked by the tracker can't be retrieved.
+    pub async fn reconcile_whitelist(&self, dry_run: bool, user_id: &UserId) -> Result<ReconciliationReport, ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        // Check if user is administrator
+        // todo: extract authorization service
+        if !user.administrator {
+            return Err(ServiceError::Unauthorized);
+        }
+
+        self.whitelist_reconciler.reconcile(dry_run).await
+    }
+
+    /// It returns the tracker jobs, optionally filtered by status.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There is a database error.
+    pub async fn get_jobs(&self, status: Option<Status>, user_id: &UserId) -> Result<Vec<TrackerJob>, ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        // Check if user is administrator
+        // todo: extract authorization service
+        if !user.administrator {
+            return Err(ServiceError::Unauthorized);
+        }
+
+        Ok(self.job_queue.get_jobs(status).await?)
+    }
+
+    /// It schedules a tracker job to be executed again immediately.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The job does not exist.
+    /// * There is a database error.
+    pub async fn retry_job(&self, job_id: TrackerJobId, user_id: &UserId) -> Result<(), ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        // Check if user is administrator
+        // todo: extract authorization service
+        if !user.administrator {
+            return Err(ServiceError::Unauthorized);
+        }
+
+        Ok(self.job_queue.retry(job_id).await?)
+    }
+
+    /// It starts synchronizing the tracker with the configured tracker mode.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The tracker mode did not change and `force` is `false`.
+    /// * There is a database error.
+    pub async fn start_mode_sync(&self, force: bool, user_id: &UserId) -> Result<TrackerModeSync, ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        // Check if user is administrator
+        // todo: extract authorization service
+        if !user.administrator {
+            return Err(ServiceError::Unauthorized);
+        }
+
+        self.tracker_mode_synchronizer.start(force).await
+    }
+
+    /// It returns the progress of the last tracker mode sync.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There is a database error.
+    pub async fn get_mode_sync(&self, user_id: &UserId) -> Result<Option<TrackerModeSync>, ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        // Check if user is administrator
+        // todo: extract authorization service
+        if !user.administrator {
+            return Err(ServiceError::Unauthorized);
+        }
+
+        self.tracker_mode_synchronizer.last_sync().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::WhitelistDiff;
+
+    fn info_hashes(info_hashes: &[&str]) -> HashSet<String> {
+        info_hashes.iter().map(|info_hash| (*info_hash).to_owned()).collect()
+    }
+
+    #[test]
+    fn it_should_find_the_index_torrents_the_tracker_is_not_tracking() {
+        let diff = WhitelistDiff::new(&info_hashes(&["c", "a", "b"]), &info_hashes(&["b"]));
+
+        assert_eq!(diff.not_seen_by_tracker, vec!["a".to_string(), "c".to_string()]);
+        assert!(diff.stale.is_empty());
+    }
+
+    #[test]
+    fn it_should_find_the_tracked_torrents_that_do_not_exist_in_the_index() {
+        let diff = WhitelistDiff::new(&info_hashes(&["a"]), &info_hashes(&["a", "d", "c"]));
+
+        assert!(diff.not_seen_by_tracker.is_empty());
+        assert_eq!(diff.stale, vec!["c".to_string(), "d".to_string()]);
+    }
+
+    #[test]
+    fn it_should_find_no_differences_when_the_index_and_the_tracker_have_the_same_torrents() {
+        let diff = WhitelistDiff::new(&info_hashes(&["a", "b"]), &info_hashes(&["b", "a"]));
+
+        assert_eq!(
+            diff,
+            WhitelistDiff {
+                not_seen_by_tracker: vec![],
+                stale: vec![]
+            }
+        );
+    }
+}
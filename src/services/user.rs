@@ -3,18 +3,24 @@ use std::sync::Arc;
 
 use argon2::password_hash::SaltString;
 use argon2::{Argon2, PasswordHasher};
-use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
-use log::{debug, info};
-use pbkdf2::password_hash::rand_core::OsRng;
+use chrono::{Duration, NaiveDateTime, Utc};
+use log::{debug, error, info};
+use pbkdf2::password_hash::rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
 
+use super::notification::Notifier;
+use super::{image, torrent};
 use crate::config::{Configuration, EmailOnSignup};
 use crate::databases::database::{Database, Error};
 use crate::errors::ServiceError;
-use crate::mailer;
-use crate::mailer::VerifyClaims;
+use crate::models::notification::{Kind as NotificationKind, NewNotification};
+use crate::models::tracker_key::{PersonalTrackerKey, TrackerKey};
 use crate::models::user::{UserCompact, UserId, UserProfile};
-use crate::utils::validation::validate_email_address;
+use crate::tracker::service::TrackerAPIError;
+use crate::utils::hex::from_bytes;
+use crate::utils::validation::{normalize_locale, validate_email_address};
 use crate::web::api::server::v1::contexts::user::forms::RegistrationForm;
+use crate::{mailer, tracker};
 
 /// Since user email could be optional, we need a way to represent "no email"
 /// in the database. This function returns the string that should be used for
@@ -23,11 +29,28 @@ fn no_email() -> String {
     String::new()
 }
 
+/// Verification links are never valid for more than a year, whatever the
+/// configured lifetime.
+const MAX_VERIFICATION_TOKEN_LIFETIME_SECONDS: i64 = 365 * 24 * 60 * 60;
+
+/// It generates a random email verification token. Only its hash is stored,
+/// so the links sent by email can't be rebuilt from the database.
+fn generate_verification_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    from_bytes(&bytes)
+}
+
+fn hash_verification_token(token: &str) -> String {
+    from_bytes(&Sha256::digest(token.as_bytes()))
+}
+
 pub struct RegistrationService {
     configuration: Arc<Configuration>,
     mailer: Arc<mailer::Service>,
     user_repository: Arc<DbUserRepository>,
     user_profile_repository: Arc<DbUserProfileRepository>,
+    email_verification_token_repository: Arc<DbEmailVerificationTokenRepository>,
 }
 
 impl RegistrationService {
@@ -37,12 +60,14 @@ impl RegistrationService {
         mailer: Arc<mailer::Service>,
         user_repository: Arc<DbUserRepository>,
         user_profile_repository: Arc<DbUserProfileRepository>,
+        email_verification_token_repository: Arc<DbEmailVerificationTokenRepository>,
     ) -> Self {
         Self {
             configuration,
             mailer,
             user_repository,
             user_profile_repository,
+            email_verification_token_repository,
         }
     }
 
@@ -58,6 +83,7 @@ impl RegistrationService {
     /// * `ServiceError::PasswordTooShort` if the supplied password is too short.
     /// * `ServiceError::PasswordTooLong` if the supplied password is too long.
     /// * `ServiceError::UsernameInvalid` if the supplied username is badly formatted.
+    /// * `ServiceError::InvalidLocale` if the supplied locale is badly formatted.
     /// * `ServiceError::FailedToSendVerificationEmail` if unable to send the required verification email.
     /// * An error if unable to successfully hash the password.
     /// * An error if unable to insert user into the database.
@@ -115,6 +141,11 @@ impl RegistrationService {
             return Err(ServiceError::UsernameInvalid);
         }
 
+        let opt_locale = match &registration_form.locale {
+            Some(locale) => Some(normalize_locale(locale).ok_or(ServiceError::InvalidLocale)?),
+            None => None,
+        };
+
         let user_id = self
             .user_repository
             .add(
@@ -129,11 +160,26 @@ impl RegistrationService {
             drop(self.user_repository.grant_admin_role(&user_id).await);
         }
 
-        if settings.mail.email_verification_enabled {
+        if let Some(locale) = &opt_locale {
+            drop(self.user_profile_repository.update_locale(&user_id, Some(locale)).await);
+        }
+
+        let email_verification_enabled = settings.mail.email_verification_enabled;
+
+        // The mailer reads the settings too. Holding this read lock while a
+        // settings update is waiting for the write lock would deadlock.
+        drop(settings);
+
+        if email_verification_enabled {
             if let Some(email) = opt_email {
                 let mail_res = self
-                    .mailer
-                    .send_verification_mail(&email, &registration_form.username, user_id, api_base_url)
+                    .send_verification_mail(
+                        &email,
+                        &registration_form.username,
+                        user_id,
+                        api_base_url,
+                        opt_locale.as_deref(),
+                    )
                     .await;
 
                 if mail_res.is_err() {
@@ -146,40 +192,153 @@ impl RegistrationService {
         Ok(user_id)
     }
 
-    /// It verifies the email address of a user via the token sent to the
-    /// user's email.
+    /// It sends the verification email again to a user, for example, when the
+    /// first one was lost. The links sent before are no longer valid and the
+    /// verification emails still waiting in the mail queue are discarded.
+    ///
+    /// Only one verification email is sent to a user every
+    /// `email_verification.resend_interval_seconds` seconds.
     ///
     /// # Errors
     ///
-    /// This function will return a `ServiceError::DatabaseError` if unable to
-    /// update the user's email verification status.
-    pub async fn verify_email(&self, token: &str) -> Result<bool, ServiceError> {
+    /// This function will return a:
+    ///
+    /// * `ServiceError::EmailVerificationNotEnabled` if email verification is disabled.
+    /// * `ServiceError::EmailMissing` if the user does not have an email.
+    /// * `ServiceError::EmailAlreadyVerified` if the email is already verified.
+    /// * `ServiceError::VerificationEmailSentRecently` if the last verification email was sent too recently.
+    /// * `ServiceError::FailedToSendVerificationEmail` if unable to queue the verification email.
+    pub async fn resend_verification_email(&self, user_profile: &UserProfile, api_base_url: &str) -> Result<(), ServiceError> {
         let settings = self.configuration.settings.read().await;
+        let email_verification_enabled = settings.mail.email_verification_enabled;
+        let resend_interval_seconds = settings.email_verification.resend_interval_seconds;
+        drop(settings);
 
-        let token_data = match decode::<VerifyClaims>(
-            token,
-            &DecodingKey::from_secret(settings.auth.secret_key.as_bytes()),
-            &Validation::new(Algorithm::HS256),
-        ) {
-            Ok(token_data) => {
-                if !token_data.claims.iss.eq("email-verification") {
-                    return Ok(false);
-                }
+        if !email_verification_enabled {
+            return Err(ServiceError::EmailVerificationNotEnabled);
+        }
 
-                token_data.claims
-            }
-            Err(_) => return Ok(false),
-        };
+        if user_profile.email.is_empty() {
+            return Err(ServiceError::EmailMissing);
+        }
 
-        drop(settings);
+        if user_profile.email_verified {
+            return Err(ServiceError::EmailAlreadyVerified);
+        }
 
-        let user_id = token_data.sub;
+        let resend_interval = Duration::seconds(i64::try_from(resend_interval_seconds).unwrap_or(i64::MAX));
 
-        if self.user_profile_repository.verify_email(&user_id).await.is_err() {
-            return Err(ServiceError::DatabaseError);
+        if self
+            .email_verification_token_repository
+            .was_added_after(user_profile.user_id, now() - resend_interval)
+            .await?
+        {
+            return Err(ServiceError::VerificationEmailSentRecently);
+        }
+
+        self.send_verification_mail(
+            &user_profile.email,
+            &user_profile.username,
+            user_profile.user_id,
+            api_base_url,
+            user_profile.locale.as_deref(),
+        )
+        .await
+    }
+
+    /// It verifies the email address of a user via the token sent to the
+    /// user's email. Tokens expire after `email_verification.token_lifetime_seconds`
+    /// and can be used only once.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `ServiceError::EmailVerificationTokenInvalid`
+    /// if the token does not exist, was already used or has expired, or an
+    /// error if unable to update the user's email verification status.
+    pub async fn verify_email(&self, token: &str) -> Result<(), ServiceError> {
+        let user_id = self
+            .email_verification_token_repository
+            .verify_email(&hash_verification_token(token), now())
+            .await?;
+
+        info!("email verified for user: {user_id}");
+
+        Ok(())
+    }
+
+    /// It issues a new verification token, invalidating the previous ones,
+    /// and sends the verification email with it.
+    async fn send_verification_mail(
+        &self,
+        to: &str,
+        username: &str,
+        user_id: UserId,
+        api_base_url: &str,
+        locale: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        let token_lifetime_seconds = self
+            .configuration
+            .settings
+            .read()
+            .await
+            .email_verification
+            .token_lifetime_seconds;
+
+        let token_lifetime_seconds = i64::try_from(token_lifetime_seconds)
+            .unwrap_or(i64::MAX)
+            .min(MAX_VERIFICATION_TOKEN_LIFETIME_SECONDS);
+
+        let now = now();
+        let expires_at = now + Duration::seconds(token_lifetime_seconds);
+
+        let token = generate_verification_token();
+
+        self.email_verification_token_repository
+            .add(user_id, &hash_verification_token(&token), expires_at, now)
+            .await?;
+
+        self.mailer
+            .send_verification_mail(to, username, user_id, &token, api_base_url, locale)
+            .await
+    }
+}
+
+fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
+
+pub struct ProfileService {
+    user_profile_repository: Arc<DbUserProfileRepository>,
+}
+
+impl ProfileService {
+    #[must_use]
+    pub fn new(user_profile_repository: Arc<DbUserProfileRepository>) -> Self {
+        Self { user_profile_repository }
+    }
+
+    /// It sets the preferred locale of the logged-in user, used to select the
+    /// language of the emails sent to them, or removes it if `None`.
+    ///
+    /// It returns the locale in its canonical case.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a:
+    ///
+    /// * `ServiceError::InvalidLocale` if the locale is badly formatted.
+    /// * An error if unable to update the user profile in the database.
+    pub async fn update_locale(&self, locale: Option<&str>, user_id: &UserId) -> Result<Option<String>, ServiceError> {
+        let opt_locale = match locale {
+            Some(locale) => Some(normalize_locale(locale).ok_or(ServiceError::InvalidLocale)?),
+            None => None,
         };
 
-        Ok(true)
+        self.user_profile_repository
+            .update_locale(user_id, opt_locale.as_deref())
+            .await?;
+
+        Ok(opt_locale)
     }
 }
 
@@ -187,6 +346,7 @@ pub struct BanService {
     user_repository: Arc<DbUserRepository>,
     user_profile_repository: Arc<DbUserProfileRepository>,
     banned_user_list: Arc<DbBannedUserList>,
+    tracker_service: Arc<tracker::service::Service>,
 }
 
 impl BanService {
@@ -195,16 +355,21 @@ impl BanService {
         user_repository: Arc<DbUserRepository>,
         user_profile_repository: Arc<DbUserProfileRepository>,
         banned_user_list: Arc<DbBannedUserList>,
+        tracker_service: Arc<tracker::service::Service>,
     ) -> Self {
         Self {
             user_repository,
             user_profile_repository,
             banned_user_list,
+            tracker_service,
         }
     }
 
     /// Ban a user from the Index.
     ///
+    /// The tracker keys of the banned user are revoked. If they can't be
+    /// revoked, the user is banned anyway and the error is logged.
+    ///
     /// # Errors
     ///
     /// This function will return a:
@@ -229,10 +394,268 @@ impl BanService {
 
         self.banned_user_list.add(&user_profile.user_id).await?;
 
+        if let Err(err) = self.tracker_service.revoke_user_tracker_keys(user_profile.user_id).await {
+            error!(
+                "Failed to revoke the tracker keys of banned user {}: {err}",
+                user_profile.user_id
+            );
+        }
+
         Ok(())
     }
 }
 
+pub struct DeletionService {
+    configuration: Arc<Configuration>,
+    database: Arc<Box<dyn Database>>,
+    user_repository: Arc<DbUserRepository>,
+    user_profile_repository: Arc<DbUserProfileRepository>,
+    tracker_service: Arc<tracker::service::Service>,
+    torrent_index: Arc<torrent::Index>,
+    image_service: Arc<image::Service>,
+}
+
+impl DeletionService {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        database: Arc<Box<dyn Database>>,
+        user_repository: Arc<DbUserRepository>,
+        user_profile_repository: Arc<DbUserProfileRepository>,
+        tracker_service: Arc<tracker::service::Service>,
+        torrent_index: Arc<torrent::Index>,
+        image_service: Arc<image::Service>,
+    ) -> Self {
+        Self {
+            configuration,
+            database,
+            user_repository,
+            user_profile_repository,
+            tracker_service,
+            torrent_index,
+            image_service,
+        }
+    }
+
+    /// Delete a user account from the Index.
+    ///
+    /// The torrents uploaded by the user are deleted through the torrent
+    /// index, so they are removed from the tracker whitelist, and the images
+    /// uploaded by the user are deleted with their files.
+    ///
+    /// The tracker keys of the user are revoked before deleting the account.
+    /// If they can't be revoked from the tracker, the account is deleted
+    /// anyway and the error is logged. The keys expire after
+    /// `tracker.token_valid_seconds`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a:
+    ///
+    /// * `ServiceError::Unauthorized` if the user is not an administrator.
+    /// * An error if unable to get user profile from supplied username.
+    /// * An error if unable to delete the user torrents or images.
+    /// * An error if unable to delete the user from the database.
+    pub async fn delete_user(&self, username_to_be_deleted: &str, user_id: &UserId) -> Result<(), ServiceError> {
+        debug!("user with ID {user_id} deleting username: {username_to_be_deleted}");
+
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        // Check if user is administrator
+        if !user.administrator {
+            return Err(ServiceError::Unauthorized);
+        }
+
+        let user_profile = self
+            .user_profile_repository
+            .get_user_profile_from_username(username_to_be_deleted)
+            .await?;
+
+        self.delete_user_data(user_profile.user_id).await?;
+
+        self.user_repository.delete(&user_profile.user_id).await?;
+
+        Ok(())
+    }
+
+    /// It deletes the accounts that were sent a verification email but never
+    /// verified it, after `email_verification.purge_unverified_after_days`
+    /// days. The accounts are deleted like the ones deleted by an
+    /// administrator, so their torrents and images are removed too. It
+    /// returns the number of deleted accounts.
+    ///
+    /// Nothing is deleted if email verification is disabled or the number of
+    /// days is `0`. Administrators and the users registered while email
+    /// verification was disabled are never deleted.
+    ///
+    /// The expired verification tokens are deleted with their accounts. When
+    /// the purge is disabled nothing uses them, so they are deleted right
+    /// away; otherwise they are kept to know which accounts were sent a
+    /// verification email.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if unable to get the unverified
+    /// users or to delete the expired tokens from the database.
+    pub async fn purge_unverified_accounts(&self) -> Result<u64, ServiceError> {
+        let settings = self.configuration.settings.read().await;
+        let email_verification_enabled = settings.mail.email_verification_enabled;
+        let purge_unverified_after_days = settings.email_verification.purge_unverified_after_days;
+        drop(settings);
+
+        if !email_verification_enabled || purge_unverified_after_days == 0 {
+            self.database.delete_expired_email_verification_tokens(now()).await?;
+
+            return Ok(0);
+        }
+
+        let registered_before = now() - Duration::days(i64::from(purge_unverified_after_days));
+
+        let mut deleted_users = 0;
+
+        for user_id in self.database.get_unverified_users(registered_before).await? {
+            let result = match self.delete_user_data(user_id).await {
+                Ok(()) => self.user_repository.delete(&user_id).await.map_err(ServiceError::from),
+                Err(err) => Err(err),
+            };
+
+            match result {
+                Ok(()) => deleted_users += 1,
+                Err(err) => error!("Failed to delete unverified user {user_id}: {err}"),
+            }
+        }
+
+        Ok(deleted_users)
+    }
+
+    /// It deletes the torrents, the images and the tracker keys of a user
+    /// before the account is deleted. The database would delete them in
+    /// cascade, but the tracker whitelist and the image files would be left
+    /// behind.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if unable to delete the user
+    /// torrents, images or tracker keys from the database.
+    pub async fn delete_user_data(&self, user_id: UserId) -> Result<(), ServiceError> {
+        for torrent in self.database.get_user_torrents(user_id).await? {
+            self.torrent_index
+                .remove_torrent(torrent.torrent_id, &torrent.info_hash)
+                .await?;
+        }
+
+        self.image_service.delete_user_images(&user_id).await?;
+
+        match self.tracker_service.revoke_user_tracker_keys(user_id).await {
+            Ok(_) => {}
+            Err(err @ (TrackerAPIError::TrackerOffline | TrackerAPIError::InvalidToken)) => {
+                error!("Failed to revoke the tracker keys of deleted user {user_id}: {err}");
+            }
+            Err(err) => return Err(err.into()),
+        }
+
+        Ok(())
+    }
+}
+
+pub struct TrackerKeyService {
+    user_repository: Arc<DbUserRepository>,
+    user_profile_repository: Arc<DbUserProfileRepository>,
+    tracker_service: Arc<tracker::service::Service>,
+    notifier: Arc<Notifier>,
+}
+
+impl TrackerKeyService {
+    #[must_use]
+    pub fn new(
+        user_repository: Arc<DbUserRepository>,
+        user_profile_repository: Arc<DbUserProfileRepository>,
+        tracker_service: Arc<tracker::service::Service>,
+        notifier: Arc<Notifier>,
+    ) -> Self {
+        Self {
+            user_repository,
+            user_profile_repository,
+            tracker_service,
+            notifier,
+        }
+    }
+
+    /// It returns the current tracker key of the user.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `ServiceError::TrackerKeyNotFound` if the
+    /// user does not have a valid tracker key. A new one is issued the next
+    /// time the user downloads a torrent or rotates the key.
+    pub async fn get_tracker_key(&self, user_id: &UserId) -> Result<PersonalTrackerKey, ServiceError> {
+        let tracker_key = self
+            .tracker_service
+            .get_user_tracker_key(*user_id)
+            .await
+            .ok_or(ServiceError::TrackerKeyNotFound)?;
+
+        Ok(self.personal_tracker_key(tracker_key).await)
+    }
+
+    /// It revokes the current tracker keys of the user and issues a new one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the old keys can't be revoked or
+    /// the new one can't be issued.
+    pub async fn rotate_tracker_key(&self, user_id: &UserId) -> Result<PersonalTrackerKey, ServiceError> {
+        let tracker_key = self.tracker_service.rotate_tracker_key(*user_id).await?;
+
+        Ok(self.personal_tracker_key(tracker_key).await)
+    }
+
+    /// It revokes all the tracker keys of another user. It returns the number
+    /// of revoked keys.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a:
+    ///
+    /// * `ServiceError::Unauthorized` if the user is not an administrator.
+    /// * An error if unable to get user profile from supplied username.
+    /// * An error if the keys can't be revoked.
+    pub async fn revoke_tracker_keys(&self, username: &str, user_id: &UserId) -> Result<usize, ServiceError> {
+        let user = self.user_repository.get_compact(user_id).await?;
+
+        // Check if user is administrator
+        // todo: extract authorization service
+        if !user.administrator {
+            return Err(ServiceError::Unauthorized);
+        }
+
+        let user_profile = self.user_profile_repository.get_user_profile_from_username(username).await?;
+
+        let revoked = self.tracker_service.revoke_user_tracker_keys(user_profile.user_id).await?;
+
+        if revoked > 0 && user_profile.user_id != *user_id {
+            self.notifier
+                .notify(
+                    user_profile.user_id,
+                    NewNotification::new(NotificationKind::TrackerKeysRevoked),
+                )
+                .await;
+        }
+
+        Ok(revoked)
+    }
+
+    async fn personal_tracker_key(&self, tracker_key: TrackerKey) -> PersonalTrackerKey {
+        let announce_url = self.tracker_service.announce_url_with_key(&tracker_key).await;
+
+        PersonalTrackerKey {
+            key: tracker_key.key,
+            valid_until: tracker_key.valid_until,
+            announce_url,
+        }
+    }
+}
+
 pub struct DbUserRepository {
     database: Arc<Box<dyn Database>>,
 }
@@ -295,13 +718,13 @@ impl DbUserProfileRepository {
         Self { database }
     }
 
-    /// It marks the user's email as verified.
+    /// It gets the user profile from the user id.
     ///
     /// # Errors
     ///
     /// It returns an error if there is a database error.
-    pub async fn verify_email(&self, user_id: &UserId) -> Result<(), Error> {
-        self.database.verify_email(*user_id).await
+    pub async fn get_user_profile_from_id(&self, user_id: &UserId) -> Result<UserProfile, Error> {
+        self.database.get_user_profile_from_id(*user_id).await
     }
 
     /// It get the user profile from the username.
@@ -312,6 +735,83 @@ impl DbUserProfileRepository {
     pub async fn get_user_profile_from_username(&self, username: &str) -> Result<UserProfile, Error> {
         self.database.get_user_profile_from_username(username).await
     }
+
+    /// It sets the user's avatar URL, or removes it if `None`.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update_avatar(&self, user_id: &UserId, avatar: Option<&str>) -> Result<(), Error> {
+        self.database.update_user_avatar(*user_id, avatar).await
+    }
+
+    /// It sets the user's preferred locale, or removes it if `None`.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update_locale(&self, user_id: &UserId, locale: Option<&str>) -> Result<(), Error> {
+        self.database.update_user_locale(*user_id, locale).await
+    }
+
+    /// It removes the avatar URL from all the users using it.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn delete_avatar(&self, avatar: &str) -> Result<(), Error> {
+        self.database.delete_avatar(avatar).await
+    }
+}
+
+pub struct DbEmailVerificationTokenRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbEmailVerificationTokenRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It stores the hash of a new verification token for the user. The
+    /// previous tokens of the user are deleted.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(
+        &self,
+        user_id: UserId,
+        token_hash: &str,
+        expires_at: NaiveDateTime,
+        now: NaiveDateTime,
+    ) -> Result<(), Error> {
+        self.database
+            .add_email_verification_token(user_id, token_hash, expires_at, now)
+            .await
+    }
+
+    /// It checks if a verification token was added for the user after
+    /// `since`.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn was_added_after(&self, user_id: UserId, since: NaiveDateTime) -> Result<bool, Error> {
+        self.database.email_verification_token_added_after(user_id, since).await
+    }
+
+    /// It marks the email of the token owner as verified and deletes the
+    /// user's tokens. It returns the `user_id`.
+    ///
+    /// # Errors
+    ///
+    /// It returns an `Error::EmailVerificationTokenNotFound` if the token does
+    /// not exist or has expired, or an error if there is a database error.
+    pub async fn verify_email(&self, token_hash: &str, now: NaiveDateTime) -> Result<UserId, Error> {
+        self.database.verify_email_with_token(token_hash, now).await
+    }
 }
 
 pub struct DbBannedUserList {
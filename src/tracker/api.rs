@@ -72,6 +72,17 @@ impl Client {
         self.client.post(request_url).query(&self.token_param).send().await
     }
 
+    /// Delete a tracker key.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the HTTP request fails.
+    pub async fn delete_tracker_key(&self, key: &str) -> Result<Response, Error> {
+        let request_url = format!("{}/key/{}", self.api_base_url, key);
+
+        self.client.delete(request_url).query(&self.token_param).send().await
+    }
+
     /// Retrieve the info for a torrent.
     ///
     /// # Errors
@@ -82,4 +93,21 @@ impl Client {
 
         self.client.get(request_url).query(&self.token_param).send().await
     }
+
+    /// Retrieve a page of the torrents tracked by the tracker.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the HTTP request fails.
+    pub async fn get_torrents(&self, offset: u32, limit: u32) -> Result<Response, Error> {
+        let request_url = format!("{}/torrents", self.api_base_url);
+
+        let params = [
+            ("token", self.connection_info.token.clone()),
+            ("offset", offset.to_string()),
+            ("limit", limit.to_string()),
+        ];
+
+        self.client.get(request_url).query(&params).send().await
+    }
 }
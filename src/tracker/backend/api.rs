@@ -0,0 +1,284 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use hyper::StatusCode;
+use log::{debug, error};
+
+use super::TrackerBackend;
+use crate::config::Configuration;
+use crate::models::tracker_key::TrackerKey;
+use crate::tracker::api::{Client, ConnectionInfo};
+use crate::tracker::service::{TorrentBasicInfo, TorrentInfo, TrackerAPIError};
+
+/// A backend for the Torrust Tracker REST API.
+///
+/// It does not keep a copy of the tracker API settings. A new API client is
+/// built with the current settings for every request.
+pub struct ApiBackend {
+    cfg: Arc<Configuration>,
+}
+
+impl ApiBackend {
+    #[must_use]
+    pub fn new(cfg: Arc<Configuration>) -> Self {
+        Self { cfg }
+    }
+
+    /// It builds a Tracker API client with the current tracker settings.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if it can't build a Tracker API client.
+    async fn api_client(&self) -> Client {
+        let settings = self.cfg.settings.read().await;
+
+        Client::new(ConnectionInfo::new(
+            settings.tracker.api_url.clone(),
+            settings.tracker.token.clone(),
+        ))
+        .expect("a reqwest client should be provided")
+    }
+
+    fn invalid_token_body() -> String {
+        "Unhandled rejection: Err { reason: \"token not valid\" }".to_string()
+    }
+
+    fn torrent_not_known_body() -> String {
+        "\"torrent not known\"".to_string()
+    }
+}
+
+#[async_trait]
+impl TrackerBackend for ApiBackend {
+    async fn whitelist_torrent(&self, info_hash: &str) -> Result<(), TrackerAPIError> {
+        let maybe_response = self.api_client().await.whitelist_torrent(info_hash).await;
+
+        debug!(target: "tracker-service", "add to whitelist response result: {:?}", maybe_response);
+
+        match maybe_response {
+            Ok(response) => {
+                let status: StatusCode = map_status_code(response.status());
+
+                let body = response.text().await.map_err(|_| {
+                    error!(target: "tracker-service", "response without body");
+                    TrackerAPIError::MissingResponseBody
+                })?;
+
+                match status {
+                    StatusCode::OK => Ok(()),
+                    StatusCode::INTERNAL_SERVER_ERROR => {
+                        if body == Self::invalid_token_body() {
+                            Err(TrackerAPIError::InvalidToken)
+                        } else {
+                            error!(target: "tracker-service", "add to whitelist 500 response: status {status}, body: {body}");
+                            Err(TrackerAPIError::InternalServerError)
+                        }
+                    }
+                    _ => {
+                        error!(target: "tracker-service", "add to whitelist unexpected response: status {status}, body: {body}");
+                        Err(TrackerAPIError::UnexpectedResponseStatus)
+                    }
+                }
+            }
+            Err(_) => Err(TrackerAPIError::TrackerOffline),
+        }
+    }
+
+    async fn remove_torrent_from_whitelist(&self, info_hash: &str) -> Result<(), TrackerAPIError> {
+        let maybe_response = self.api_client().await.remove_torrent_from_whitelist(info_hash).await;
+
+        debug!(target: "tracker-service", "remove from whitelist response result: {:?}", maybe_response);
+
+        match maybe_response {
+            Ok(response) => {
+                let status: StatusCode = map_status_code(response.status());
+
+                let body = response.text().await.map_err(|_| {
+                    error!(target: "tracker-service", "response without body");
+                    TrackerAPIError::MissingResponseBody
+                })?;
+
+                match status {
+                    StatusCode::OK => Ok(()),
+                    StatusCode::INTERNAL_SERVER_ERROR => {
+                        if body == Self::invalid_token_body() {
+                            Err(TrackerAPIError::InvalidToken)
+                        } else {
+                            error!(target: "tracker-service", "remove from whitelist 500 response: status {status}, body: {body}");
+                            Err(TrackerAPIError::InternalServerError)
+                        }
+                    }
+                    _ => {
+                        error!(target: "tracker-service", "remove from whitelist unexpected response: status {status}, body: {body}");
+                        Err(TrackerAPIError::UnexpectedResponseStatus)
+                    }
+                }
+            }
+            Err(_) => Err(TrackerAPIError::TrackerOffline),
+        }
+    }
+
+    async fn issue_key(&self, valid_seconds: u64) -> Result<TrackerKey, TrackerAPIError> {
+        let maybe_response = self.api_client().await.retrieve_new_tracker_key(valid_seconds).await;
+
+        debug!(target: "tracker-service", "retrieve key response result: {:?}", maybe_response);
+
+        match maybe_response {
+            Ok(response) => {
+                let status: StatusCode = map_status_code(response.status());
+
+                let body = response.text().await.map_err(|_| {
+                    error!(target: "tracker-service", "response without body");
+                    TrackerAPIError::MissingResponseBody
+                })?;
+
+                match status {
+                    StatusCode::OK => {
+                        serde_json::from_str(&body).map_err(|_| TrackerAPIError::FailedToParseTrackerResponse { body })
+                    }
+                    StatusCode::INTERNAL_SERVER_ERROR => {
+                        if body == Self::invalid_token_body() {
+                            Err(TrackerAPIError::InvalidToken)
+                        } else {
+                            error!(target: "tracker-service", "retrieve key 500 response: status {status}, body: {body}");
+                            Err(TrackerAPIError::InternalServerError)
+                        }
+                    }
+                    _ => {
+                        error!(target: "tracker-service", " retrieve key unexpected response: status {status}, body: {body}");
+                        Err(TrackerAPIError::UnexpectedResponseStatus)
+                    }
+                }
+            }
+            Err(_) => Err(TrackerAPIError::TrackerOffline),
+        }
+    }
+
+    async fn revoke_key(&self, key: &str) -> Result<(), TrackerAPIError> {
+        let maybe_response = self.api_client().await.delete_tracker_key(key).await;
+
+        debug!(target: "tracker-service", "delete key response result: {:?}", maybe_response);
+
+        match maybe_response {
+            Ok(response) => {
+                let status: StatusCode = map_status_code(response.status());
+
+                let body = response.text().await.map_err(|_| {
+                    error!(target: "tracker-service", "response without body");
+                    TrackerAPIError::MissingResponseBody
+                })?;
+
+                match status {
+                    StatusCode::OK => Ok(()),
+                    StatusCode::INTERNAL_SERVER_ERROR => {
+                        if body == Self::invalid_token_body() {
+                            Err(TrackerAPIError::InvalidToken)
+                        } else {
+                            error!(target: "tracker-service", "delete key 500 response: status {status}, body: {body}");
+                            Err(TrackerAPIError::InternalServerError)
+                        }
+                    }
+                    _ => {
+                        error!(target: "tracker-service", "delete key unexpected response: status {status}, body: {body}");
+                        Err(TrackerAPIError::UnexpectedResponseStatus)
+                    }
+                }
+            }
+            Err(_) => Err(TrackerAPIError::TrackerOffline),
+        }
+    }
+
+    async fn get_torrent_info(&self, info_hash: &str) -> Result<TorrentInfo, TrackerAPIError> {
+        let maybe_response = self.api_client().await.get_torrent_info(info_hash).await;
+
+        debug!(target: "tracker-service", "get torrent info response result: {:?}", maybe_response);
+
+        match maybe_response {
+            Ok(response) => {
+                let status: StatusCode = map_status_code(response.status());
+
+                let body = response.text().await.map_err(|_| {
+                    error!(target: "tracker-service", "response without body");
+                    TrackerAPIError::MissingResponseBody
+                })?;
+
+                match status {
+                    StatusCode::NOT_FOUND => Err(TrackerAPIError::TorrentNotFound),
+                    StatusCode::OK => {
+                        if body == Self::torrent_not_known_body() {
+                            // todo: temporary fix. the service should return a 404 (StatusCode::NOT_FOUND).
+                            return Err(TrackerAPIError::TorrentNotFound);
+                        }
+
+                        serde_json::from_str(&body).map_err(|e| {
+                            error!(
+                                target: "tracker-service", "Failed to parse torrent info from tracker response. Body: {}, Error: {}",
+                                body, e
+                            );
+                            TrackerAPIError::FailedToParseTrackerResponse { body }
+                        })
+                    }
+                    StatusCode::INTERNAL_SERVER_ERROR => {
+                        if body == Self::invalid_token_body() {
+                            Err(TrackerAPIError::InvalidToken)
+                        } else {
+                            error!(target: "tracker-service", "get torrent info 500 response: status {status}, body: {body}");
+                            Err(TrackerAPIError::InternalServerError)
+                        }
+                    }
+                    _ => {
+                        error!(target: "tracker-service", "get torrent info unhandled response: status {status}, body: {body}");
+                        Err(TrackerAPIError::UnexpectedResponseStatus)
+                    }
+                }
+            }
+            Err(_) => Err(TrackerAPIError::TrackerOffline),
+        }
+    }
+
+    async fn get_torrents(&self, offset: u32, limit: u32) -> Result<Vec<TorrentBasicInfo>, TrackerAPIError> {
+        let maybe_response = self.api_client().await.get_torrents(offset, limit).await;
+
+        debug!(target: "tracker-service", "get torrents response result: {:?}", maybe_response);
+
+        match maybe_response {
+            Ok(response) => {
+                let status: StatusCode = map_status_code(response.status());
+
+                let body = response.text().await.map_err(|_| {
+                    error!(target: "tracker-service", "response without body");
+                    TrackerAPIError::MissingResponseBody
+                })?;
+
+                match status {
+                    StatusCode::OK => serde_json::from_str(&body).map_err(|e| {
+                        error!(
+                            target: "tracker-service", "Failed to parse torrents from tracker response. Body: {}, Error: {}",
+                            body, e
+                        );
+                        TrackerAPIError::FailedToParseTrackerResponse { body }
+                    }),
+                    StatusCode::INTERNAL_SERVER_ERROR => {
+                        if body == Self::invalid_token_body() {
+                            Err(TrackerAPIError::InvalidToken)
+                        } else {
+                            error!(target: "tracker-service", "get torrents 500 response: status {status}, body: {body}");
+                            Err(TrackerAPIError::InternalServerError)
+                        }
+                    }
+                    _ => {
+                        error!(target: "tracker-service", "get torrents unhandled response: status {status}, body: {body}");
+                        Err(TrackerAPIError::UnexpectedResponseStatus)
+                    }
+                }
+            }
+            Err(_) => Err(TrackerAPIError::TrackerOffline),
+        }
+    }
+}
+
+/// Temporary patch to map `StatusCode` from crate `http` 0.2.11 to `http` v1.0.0
+/// until `reqwest` upgrades to hyper 1.0. See <https://github.com/seanmonstar/reqwest/issues/2039>
+fn map_status_code(status: reqwest::StatusCode) -> hyper::StatusCode {
+    StatusCode::from_u16(status.as_u16()).unwrap()
+}
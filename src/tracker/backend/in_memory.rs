@@ -0,0 +1,184 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use async_trait::async_trait;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use tokio::sync::Mutex;
+
+use super::TrackerBackend;
+use crate::models::tracker_key::TrackerKey;
+use crate::tracker::service::{TorrentBasicInfo, TorrentInfo, TrackerAPIError};
+use crate::utils::clock;
+
+const TRACKER_KEY_LENGTH: usize = 32;
+
+/// A tracker simulated in memory.
+///
+/// It keeps the whitelist, the issued keys and the torrent statistics in
+/// memory, so the index can be tested without a running tracker. Like a real
+/// tracker, it only knows the torrents peers have announced.
+#[derive(Default)]
+pub struct InMemoryBackend {
+    whitelist: Mutex<HashSet<String>>,
+    /// Issued keys with their expiration time in seconds since the Unix epoch.
+    keys: Mutex<HashMap<String, u64>>,
+    /// Torrent statistics ordered by info-hash, like the tracker API listing.
+    torrents: Mutex<BTreeMap<String, TorrentBasicInfo>>,
+}
+
+impl InMemoryBackend {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// It sets the statistics of a torrent as if its peers had announced it.
+    #[cfg(test)]
+    async fn announce(&self, info_hash: &str, seeders: i64, completed: i64, leechers: i64) {
+        self.torrents.lock().await.insert(
+            info_hash.to_owned(),
+            TorrentBasicInfo {
+                info_hash: info_hash.to_owned(),
+                seeders,
+                completed,
+                leechers,
+            },
+        );
+    }
+
+    #[cfg(test)]
+    async fn is_whitelisted(&self, info_hash: &str) -> bool {
+        self.whitelist.lock().await.contains(info_hash)
+    }
+
+    #[cfg(test)]
+    async fn is_valid_key(&self, key: &str) -> bool {
+        self.keys
+            .lock()
+            .await
+            .get(key)
+            .is_some_and(|valid_until| *valid_until > clock::now())
+    }
+}
+
+#[async_trait]
+impl TrackerBackend for InMemoryBackend {
+    async fn whitelist_torrent(&self, info_hash: &str) -> Result<(), TrackerAPIError> {
+        self.whitelist.lock().await.insert(info_hash.to_owned());
+        Ok(())
+    }
+
+    async fn remove_torrent_from_whitelist(&self, info_hash: &str) -> Result<(), TrackerAPIError> {
+        self.whitelist.lock().await.remove(info_hash);
+        Ok(())
+    }
+
+    async fn issue_key(&self, valid_seconds: u64) -> Result<TrackerKey, TrackerAPIError> {
+        let key: String = thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(TRACKER_KEY_LENGTH)
+            .map(char::from)
+            .collect();
+
+        let valid_until = clock::now().saturating_add(valid_seconds);
+
+        self.keys.lock().await.insert(key.clone(), valid_until);
+
+        Ok(TrackerKey {
+            key,
+            valid_until: i64::try_from(valid_until).unwrap_or(i64::MAX),
+        })
+    }
+
+    async fn revoke_key(&self, key: &str) -> Result<(), TrackerAPIError> {
+        self.keys.lock().await.remove(key);
+        Ok(())
+    }
+
+    async fn get_torrent_info(&self, info_hash: &str) -> Result<TorrentInfo, TrackerAPIError> {
+        let torrents = self.torrents.lock().await;
+
+        let torrent = torrents.get(info_hash).ok_or(TrackerAPIError::TorrentNotFound)?;
+
+        Ok(TorrentInfo {
+            info_hash: torrent.info_hash.clone(),
+            seeders: torrent.seeders,
+            completed: torrent.completed,
+            leechers: torrent.leechers,
+            peers: vec![],
+        })
+    }
+
+    async fn get_torrents(&self, offset: u32, limit: u32) -> Result<Vec<TorrentBasicInfo>, TrackerAPIError> {
+        Ok(self
+            .torrents
+            .lock()
+            .await
+            .values()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .map(|torrent| TorrentBasicInfo {
+                info_hash: torrent.info_hash.clone(),
+                seeders: torrent.seeders,
+                completed: torrent.completed,
+                leechers: torrent.leechers,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InMemoryBackend;
+    use crate::tracker::backend::TrackerBackend;
+    use crate::tracker::service::TrackerAPIError;
+
+    const INFO_HASH: &str = "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d";
+
+    #[tokio::test]
+    async fn it_should_add_and_remove_torrents_from_the_whitelist() {
+        let backend = InMemoryBackend::new();
+
+        backend.whitelist_torrent(INFO_HASH).await.unwrap();
+
+        assert!(backend.is_whitelisted(INFO_HASH).await);
+
+        backend.remove_torrent_from_whitelist(INFO_HASH).await.unwrap();
+
+        assert!(!backend.is_whitelisted(INFO_HASH).await);
+    }
+
+    #[tokio::test]
+    async fn it_should_issue_and_revoke_keys() {
+        let backend = InMemoryBackend::new();
+
+        let tracker_key = backend.issue_key(3600).await.unwrap();
+
+        assert!(backend.is_valid_key(&tracker_key.key).await);
+
+        backend.revoke_key(&tracker_key.key).await.unwrap();
+
+        assert!(!backend.is_valid_key(&tracker_key.key).await);
+    }
+
+    #[tokio::test]
+    async fn it_should_only_know_the_announced_torrents() {
+        let backend = InMemoryBackend::new();
+
+        assert_eq!(
+            backend.get_torrent_info(INFO_HASH).await,
+            Err(TrackerAPIError::TorrentNotFound)
+        );
+
+        backend.announce(INFO_HASH, 2, 5, 1).await;
+
+        let torrent_info = backend.get_torrent_info(INFO_HASH).await.unwrap();
+
+        assert_eq!(
+            (torrent_info.seeders, torrent_info.completed, torrent_info.leechers),
+            (2, 5, 1)
+        );
+        assert_eq!(backend.get_torrents(0, 10).await.unwrap().len(), 1);
+        assert_eq!(backend.get_torrents(1, 10).await.unwrap().len(), 0);
+    }
+}
@@ -0,0 +1,49 @@
+//! Tracker backends.
+//!
+//! The index delegates the tracker operations to a [`TrackerBackend`]:
+//!
+//! - Adding and removing torrents from the tracker whitelist.
+//! - Issuing and revoking user tracker keys.
+//! - Getting the torrent statistics.
+//!
+//! The backend is selected with the `tracker.backend` setting:
+//!
+//! - [`Api`](crate::tracker::backend::api::ApiBackend): the Torrust Tracker
+//! REST API.
+//! - [`None`](crate::tracker::backend::none::NoneBackend): there is no tracker
+//! integrated with the index. For example, a public index using an open
+//! tracker.
+//! - [`InMemory`](crate::tracker::backend::in_memory::InMemoryBackend): a
+//! tracker simulated in memory, used for testing without a running tracker.
+pub mod api;
+pub mod in_memory;
+pub mod none;
+
+use async_trait::async_trait;
+
+use super::service::{TorrentBasicInfo, TorrentInfo, TrackerAPIError};
+use crate::models::tracker_key::TrackerKey;
+
+#[async_trait]
+pub trait TrackerBackend: Sync + Send {
+    /// Add a torrent to the tracker whitelist.
+    async fn whitelist_torrent(&self, info_hash: &str) -> Result<(), TrackerAPIError>;
+
+    /// Remove a torrent from the tracker whitelist.
+    async fn remove_torrent_from_whitelist(&self, info_hash: &str) -> Result<(), TrackerAPIError>;
+
+    /// Issue a new tracker key valid for `valid_seconds`.
+    async fn issue_key(&self, valid_seconds: u64) -> Result<TrackerKey, TrackerAPIError>;
+
+    /// Revoke a tracker key.
+    async fn revoke_key(&self, key: &str) -> Result<(), TrackerAPIError>;
+
+    /// Get the statistics and peers of a torrent.
+    ///
+    /// It returns `TrackerAPIError::TorrentNotFound` if the tracker does not
+    /// know the torrent.
+    async fn get_torrent_info(&self, info_hash: &str) -> Result<TorrentInfo, TrackerAPIError>;
+
+    /// Get a page of the torrents tracked by the tracker.
+    async fn get_torrents(&self, offset: u32, limit: u32) -> Result<Vec<TorrentBasicInfo>, TrackerAPIError>;
+}
@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+
+use super::TrackerBackend;
+use crate::models::tracker_key::TrackerKey;
+use crate::tracker::service::{TorrentBasicInfo, TorrentInfo, TrackerAPIError};
+
+/// A backend for indexes without an integrated tracker, for example, a public
+/// index using an open tracker.
+///
+/// There is no whitelist to update, so whitelist changes succeed without doing
+/// anything. User keys can't be issued because there is no tracker to validate
+/// them. The tracker does not know any torrent, the statistics can be imported
+/// by scraping the tracker instead (`tracker.statistics_source`), or not
+/// imported at all.
+#[derive(Default)]
+pub struct NoneBackend;
+
+#[async_trait]
+impl TrackerBackend for NoneBackend {
+    async fn whitelist_torrent(&self, _info_hash: &str) -> Result<(), TrackerAPIError> {
+        Ok(())
+    }
+
+    async fn remove_torrent_from_whitelist(&self, _info_hash: &str) -> Result<(), TrackerAPIError> {
+        Ok(())
+    }
+
+    async fn issue_key(&self, _valid_seconds: u64) -> Result<TrackerKey, TrackerAPIError> {
+        Err(TrackerAPIError::UnsupportedOperation)
+    }
+
+    async fn revoke_key(&self, _key: &str) -> Result<(), TrackerAPIError> {
+        Ok(())
+    }
+
+    async fn get_torrent_info(&self, _info_hash: &str) -> Result<TorrentInfo, TrackerAPIError> {
+        Err(TrackerAPIError::TorrentNotFound)
+    }
+
+    async fn get_torrents(&self, _offset: u32, _limit: u32) -> Result<Vec<TorrentBasicInfo>, TrackerAPIError> {
+        Ok(vec![])
+    }
+}
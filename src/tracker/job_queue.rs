@@ -0,0 +1,238 @@
+//! Persistent queue for the actions the index has to execute on the tracker.
+//!
+//! Adding a torrent to the tracker whitelist, removing it, or importing its
+//! statistics requires the tracker API to be available. Instead of calling the
+//! tracker while handling the user request, those actions are stored in the
+//! database as jobs and executed in the background by the
+//! [`tracker_jobs_worker`](crate::console::cronjobs::tracker_jobs_worker).
+//!
+//! When a job fails it's retried with an exponential backoff. After
+//! `max_attempts` failed attempts the job is marked as
+//! [`Failed`](crate::models::tracker_job::Status::Failed) and it's not retried
+//! until an administrator retries it manually.
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use log::{error, info, warn};
+use tokio::sync::Notify;
+
+use super::service::{Service, TrackerAPIError};
+use super::statistics_importer::StatisticsImporter;
+use crate::config::Configuration;
+use crate::databases::database::{self, Database};
+use crate::models::tracker_job::{Kind, Status, TrackerJob, TrackerJobId};
+use crate::utils::backoff::backoff_seconds;
+
+const LOG_TARGET: &str = "Tracker Job Queue";
+
+pub struct JobQueue {
+    cfg: Arc<Configuration>,
+    database: Arc<Box<dyn Database>>,
+    tracker_service: Arc<Service>,
+    tracker_statistics_importer: Arc<StatisticsImporter>,
+    new_job: Notify,
+}
+
+impl JobQueue {
+    #[must_use]
+    pub fn new(
+        cfg: Arc<Configuration>,
+        database: Arc<Box<dyn Database>>,
+        tracker_service: Arc<Service>,
+        tracker_statistics_importer: Arc<StatisticsImporter>,
+    ) -> Self {
+        Self {
+            cfg,
+            database,
+            tracker_service,
+            tracker_statistics_importer,
+            new_job: Notify::new(),
+        }
+    }
+
+    /// It enqueues a job to add the torrent to the tracker whitelist.
+    ///
+    /// Pending removals for the same torrent are discarded, so a torrent that
+    /// is deleted and uploaded again ends up whitelisted.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the job can't be stored in the database.
+    pub async fn enqueue_whitelist(&self, info_hash: &str) -> Result<TrackerJobId, database::Error> {
+        self.database
+            .delete_tracker_jobs_for_info_hash(info_hash, Kind::RemoveFromWhitelist.as_str())
+            .await?;
+
+        self.enqueue(Kind::Whitelist, info_hash, None).await
+    }
+
+    /// It enqueues a job to remove the torrent from the tracker whitelist.
+    ///
+    /// Pending jobs to whitelist the torrent or import its statistics are
+    /// discarded because the torrent does not exist anymore.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the job can't be stored in the database.
+    pub async fn enqueue_remove_from_whitelist(&self, info_hash: &str) -> Result<TrackerJobId, database::Error> {
+        for kind in [Kind::Whitelist, Kind::ImportStatistics, Kind::RemoveFromWhitelist] {
+            self.database
+                .delete_tracker_jobs_for_info_hash(info_hash, kind.as_str())
+                .await?;
+        }
+
+        self.enqueue(Kind::RemoveFromWhitelist, info_hash, None).await
+    }
+
+    /// It enqueues a job to import the torrent statistics from the tracker.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the job can't be stored in the database.
+    pub async fn enqueue_import_statistics(&self, torrent_id: i64, info_hash: &str) -> Result<TrackerJobId, database::Error> {
+        self.enqueue(Kind::ImportStatistics, info_hash, Some(torrent_id)).await
+    }
+
+    async fn enqueue(&self, kind: Kind, info_hash: &str, torrent_id: Option<i64>) -> Result<TrackerJobId, database::Error> {
+        let job_id = self
+            .database
+            .insert_tracker_job(kind.as_str(), info_hash, torrent_id, now())
+            .await?;
+
+        // Wake up the worker so the job is executed as soon as possible.
+        self.new_job.notify_one();
+
+        Ok(job_id)
+    }
+
+    /// It waits until a new job is enqueued.
+    pub async fn wait_for_new_jobs(&self) {
+        self.new_job.notified().await;
+    }
+
+    /// It returns all the jobs, optionally filtered by status.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the database query failed.
+    pub async fn get_jobs(&self, status: Option<Status>) -> Result<Vec<TrackerJob>, database::Error> {
+        self.database.get_tracker_jobs(status.as_ref().map(Status::as_str)).await
+    }
+
+    /// It schedules a job to be executed again immediately, resetting the
+    /// number of attempts.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the job does not exist or the database query
+    /// failed.
+    pub async fn retry(&self, job_id: TrackerJobId) -> Result<(), database::Error> {
+        self.database.reset_tracker_job(job_id, now()).await?;
+
+        self.new_job.notify_one();
+
+        Ok(())
+    }
+
+    /// It executes the pending jobs that are due. It returns the number of
+    /// jobs executed.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the jobs can't be loaded from the database.
+    pub async fn process_due_jobs(&self) -> Result<usize, database::Error> {
+        let batch_size = self.cfg.settings.read().await.tracker_jobs.batch_size;
+
+        let jobs = self.database.get_due_tracker_jobs(now(), batch_size).await?;
+
+        let number_of_jobs = jobs.len();
+
+        for job in jobs {
+            self.process(job).await;
+        }
+
+        Ok(number_of_jobs)
+    }
+
+    async fn process(&self, job: TrackerJob) {
+        match self.execute(&job).await {
+            Ok(()) => {
+                info!(target: LOG_TARGET, "Job #{} {} for torrent {} completed", job.job_id, job.kind, job.info_hash);
+
+                if let Err(e) = self.database.delete_tracker_job(job.job_id).await {
+                    error!(target: LOG_TARGET, "Failed to delete completed job #{}: {e:?}", job.job_id);
+                }
+            }
+            Err(err) => self.record_failure(&job, &err).await,
+        }
+    }
+
+    async fn execute(&self, job: &TrackerJob) -> Result<(), String> {
+        let kind = Kind::from_str(&job.kind)?;
+
+        match kind {
+            Kind::Whitelist => self
+                .tracker_service
+                .whitelist_info_hash(job.info_hash.clone())
+                .await
+                .map_err(|e| e.to_string()),
+            Kind::RemoveFromWhitelist => self
+                .tracker_service
+                .remove_info_hash_from_whitelist(job.info_hash.clone())
+                .await
+                .map_err(|e| e.to_string()),
+            Kind::ImportStatistics => {
+                let Some(torrent_id) = job.torrent_id else {
+                    return Err("missing torrent id".to_owned());
+                };
+
+                match self
+                    .tracker_statistics_importer
+                    .import_torrent_statistics(torrent_id, &job.info_hash)
+                    .await
+                {
+                    // The tracker does not know the torrent until a peer
+                    // announces it. The importer already stored zero peers.
+                    // Statistics might also not be imported from the tracker.
+                    Ok(_) | Err(TrackerAPIError::TorrentNotFound | TrackerAPIError::UnsupportedOperation) => Ok(()),
+                    Err(e) => Err(e.to_string()),
+                }
+            }
+        }
+    }
+
+    async fn record_failure(&self, job: &TrackerJob, err: &str) {
+        let settings = self.cfg.settings.read().await;
+        let max_attempts = i64::from(settings.tracker_jobs.max_attempts);
+        let initial_backoff_seconds = settings.tracker_jobs.initial_backoff_seconds;
+        let max_backoff_seconds = settings.tracker_jobs.max_backoff_seconds;
+        drop(settings);
+
+        let attempts = job.attempts + 1;
+
+        let status = if attempts >= max_attempts {
+            error!(target: LOG_TARGET, "Job #{} {} for torrent {} failed after {attempts} attempts: {err}", job.job_id, job.kind, job.info_hash);
+            Status::Failed
+        } else {
+            warn!(target: LOG_TARGET, "Job #{} {} for torrent {} failed (attempt {attempts}): {err}", job.job_id, job.kind, job.info_hash);
+            Status::Pending
+        };
+
+        let delay = backoff_seconds(attempts, initial_backoff_seconds, max_backoff_seconds);
+
+        let next_attempt_at = now() + Duration::seconds(i64::try_from(delay).expect("delay should fit in i64"));
+
+        if let Err(e) = self
+            .database
+            .update_failed_tracker_job(job.job_id, status.as_str(), attempts, err, next_attempt_at)
+            .await
+        {
+            error!(target: LOG_TARGET, "Failed to update job #{}: {e:?}", job.job_id);
+        }
+    }
+}
+
+fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
@@ -1,3 +1,6 @@
 pub mod api;
+pub mod backend;
+pub mod job_queue;
+pub mod scrape;
 pub mod service;
 pub mod statistics_importer;
@@ -0,0 +1,255 @@
+//! HTTP tracker scrape client.
+//!
+//! By convention, the scrape URL is obtained by replacing `announce` with
+//! `scrape` in the last segment of the announce URL path, for example:
+//!
+//! ```text
+//! http://tracker.example.com:7070/announce -> http://tracker.example.com:7070/scrape
+//! ```
+//!
+//! The info-hashes are sent in the query as URL-encoded bytes. The response is
+//! a bencoded dictionary with the statistics for each info-hash:
+//!
+//! ```text
+//! d5:filesd20:<info-hash bytes>d8:completei5e10:downloadedi50e10:incompletei10eeee
+//! ```
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_bytes::ByteBuf;
+use url::Url;
+
+use super::{Error, ScrapeResponse, TorrentStats, REQUEST_TIMEOUT};
+use crate::models::info_hash::InfoHash;
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    #[serde(default)]
+    files: HashMap<ByteBuf, File>,
+    #[serde(rename = "failure reason")]
+    failure_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct File {
+    #[serde(default)]
+    complete: i64,
+    #[serde(default)]
+    downloaded: i64,
+    #[serde(default)]
+    incomplete: i64,
+}
+
+/// It scrapes the statistics for the given info-hashes from an HTTP tracker.
+///
+/// # Errors
+///
+/// Will return an error if the scrape URL can't be built from the announce
+/// URL, the tracker can't be reached or it returns an error or an invalid
+/// response.
+pub async fn scrape(announce_url: &Url, info_hashes: &[InfoHash]) -> Result<ScrapeResponse, Error> {
+    if info_hashes.is_empty() {
+        return Ok(ScrapeResponse::new());
+    }
+
+    let url = scrape_url(announce_url, info_hashes)?;
+
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| Error::ConnectionError { message: e.to_string() })?;
+
+    let response = client.get(url).send().await.map_err(|e| {
+        if e.is_timeout() {
+            Error::Timeout
+        } else {
+            Error::ConnectionError { message: e.to_string() }
+        }
+    })?;
+
+    if !response.status().is_success() {
+        return Err(Error::InvalidResponse {
+            message: format!("unexpected status {}", response.status()),
+        });
+    }
+
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| Error::ConnectionError { message: e.to_string() })?;
+
+    parse_response(&body)
+}
+
+/// It builds the scrape URL from the announce URL, adding the info-hashes to
+/// the query.
+///
+/// # Errors
+///
+/// Will return an error if the last segment of the announce URL path does not
+/// start with `announce`. Those trackers do not support scraping. An empty
+/// path is accepted and `/scrape` is used.
+pub fn scrape_url(announce_url: &Url, info_hashes: &[InfoHash]) -> Result<Url, Error> {
+    let mut url = announce_url.clone();
+
+    let path = url.path().to_owned();
+
+    let (base, last_segment) = path.rsplit_once('/').unwrap_or(("", &path));
+
+    if last_segment.is_empty() && base.is_empty() {
+        url.set_path("/scrape");
+    } else if let Some(suffix) = last_segment.strip_prefix("announce") {
+        url.set_path(&format!("{base}/scrape{suffix}"));
+    } else {
+        return Err(Error::InvalidTrackerUrl {
+            url: announce_url.to_string(),
+        });
+    }
+
+    let info_hashes_query = info_hashes
+        .iter()
+        .map(|info_hash| format!("info_hash={}", urlencoding::encode_binary(&info_hash.bytes())))
+        .collect::<Vec<String>>()
+        .join("&");
+
+    let query = match url.query() {
+        Some(query) if !query.is_empty() => format!("{query}&{info_hashes_query}"),
+        _ => info_hashes_query,
+    };
+
+    url.set_query(Some(&query));
+
+    Ok(url)
+}
+
+fn parse_response(body: &[u8]) -> Result<ScrapeResponse, Error> {
+    let response: Response = serde_bencode::from_bytes(body).map_err(|e| Error::InvalidResponse { message: e.to_string() })?;
+
+    if let Some(message) = response.failure_reason {
+        return Err(Error::TrackerError { message });
+    }
+
+    Ok(response
+        .files
+        .into_iter()
+        .filter(|(info_hash, _)| info_hash.len() == 20)
+        .map(|(info_hash, file)| {
+            (
+                InfoHash::from_bytes(&info_hash),
+                TorrentStats {
+                    seeders: file.complete,
+                    completed: file.downloaded,
+                    leechers: file.incomplete,
+                },
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use axum::extract::RawQuery;
+    use axum::routing::get;
+    use axum::Router;
+    use tokio::net::TcpListener;
+    use url::Url;
+
+    use super::{scrape, scrape_url};
+    use crate::models::info_hash::InfoHash;
+    use crate::tracker::scrape::{Error, TorrentStats};
+
+    fn sample_info_hash() -> InfoHash {
+        InfoHash::from_str("0102030000000000000000000000000000000000").unwrap()
+    }
+
+    /// A fake HTTP tracker that returns the statistics for the sample
+    /// info-hash if it's included in the query.
+    async fn start_fake_tracker() -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let app = Router::new().route(
+            "/scrape",
+            get(|RawQuery(query): RawQuery| async move {
+                let query = query.unwrap_or_default();
+
+                if !query.contains("info_hash=%01%02%03%00") {
+                    return b"d14:failure reason17:missing info hashe".to_vec();
+                }
+
+                let mut body = b"d5:filesd20:".to_vec();
+                body.extend_from_slice(&sample_info_hash().bytes());
+                body.extend_from_slice(b"d8:completei1e10:downloadedi2e10:incompletei3eeee");
+                body
+            }),
+        );
+
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        Url::parse(&format!("http://{addr}/announce")).unwrap()
+    }
+
+    #[test]
+    fn the_scrape_url_should_replace_announce_with_scrape_in_the_last_path_segment() {
+        let info_hashes = [sample_info_hash()];
+
+        let url = scrape_url(&Url::parse("http://tracker.com:7070/announce").unwrap(), &info_hashes).unwrap();
+        assert_eq!(url.path(), "/scrape");
+
+        let url = scrape_url(&Url::parse("http://tracker.com/x/announce.php").unwrap(), &info_hashes).unwrap();
+        assert_eq!(url.path(), "/x/scrape.php");
+
+        let url = scrape_url(&Url::parse("http://tracker.com:7070").unwrap(), &info_hashes).unwrap();
+        assert_eq!(url.path(), "/scrape");
+
+        assert!(scrape_url(&Url::parse("http://tracker.com/a").unwrap(), &info_hashes).is_err());
+    }
+
+    #[test]
+    fn the_scrape_url_should_include_the_url_encoded_info_hashes_in_the_query() {
+        let url = scrape_url(
+            &Url::parse("http://tracker.com/announce?key=abc").unwrap(),
+            &[sample_info_hash()],
+        )
+        .unwrap();
+
+        assert_eq!(
+            url.query(),
+            Some("key=abc&info_hash=%01%02%03%00%00%00%00%00%00%00%00%00%00%00%00%00%00%00%00%00")
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_scrape_the_statistics_from_the_bencoded_response() {
+        let url = start_fake_tracker().await;
+
+        let response = scrape(&url, &[sample_info_hash()]).await.unwrap();
+
+        assert_eq!(
+            response.get(&sample_info_hash()),
+            Some(&TorrentStats {
+                seeders: 1,
+                completed: 2,
+                leechers: 3
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_return_the_failure_reason_sent_by_the_tracker() {
+        let url = start_fake_tracker().await;
+
+        let other_info_hash = InfoHash::from_str("ff00000000000000000000000000000000000000").unwrap();
+
+        let result = scrape(&url, &[other_info_hash]).await;
+
+        assert_eq!(
+            result,
+            Err(Error::TrackerError {
+                message: "missing info hash".to_owned()
+            })
+        );
+    }
+}
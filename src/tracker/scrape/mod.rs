@@ -0,0 +1,88 @@
+//! Clients to get torrent statistics directly from a tracker using the scrape
+//! convention.
+//!
+//! The Torrust Tracker exposes the torrent statistics through its REST API,
+//! but any other tracker can be scraped:
+//!
+//! - UDP trackers with the scrape action of the [BEP 15](https://www.bittorrent.org/beps/bep_0015.html)
+//!   protocol. See [`udp`].
+//! - HTTP trackers with the `/scrape` endpoint and bencoded responses. See
+//!   [`http`].
+//!
+//! The client is chosen from the tracker URL scheme.
+pub mod http;
+pub mod udp;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use derive_more::{Display, Error};
+use url::Url;
+
+use crate::models::info_hash::InfoHash;
+
+/// Maximum time to wait for a response from an HTTP tracker. UDP requests are
+/// sent again when there is no response, see
+/// [`MAX_RETRANSMISSIONS`](udp::MAX_RETRANSMISSIONS).
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Statistics for one torrent returned by the tracker.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TorrentStats {
+    /// Number of peers with the whole torrent.
+    pub seeders: i64,
+    /// Number of times the torrent was downloaded completely.
+    pub completed: i64,
+    /// Number of peers still downloading the torrent.
+    pub leechers: i64,
+}
+
+/// Statistics returned by the tracker for each scraped info-hash.
+///
+/// Trackers can omit the torrents they do not know.
+pub type ScrapeResponse = HashMap<InfoHash, TorrentStats>;
+
+#[derive(Debug, Display, PartialEq, Eq, Error)]
+pub enum Error {
+    #[display(fmt = "Invalid tracker URL: {url}.")]
+    InvalidTrackerUrl { url: String },
+
+    #[display(fmt = "Tracker URL scheme not supported for scraping: {scheme}.")]
+    UnsupportedScheme { scheme: String },
+
+    #[display(fmt = "Error with tracker connection: {message}.")]
+    ConnectionError { message: String },
+
+    #[display(fmt = "Timeout waiting for the tracker response.")]
+    Timeout,
+
+    #[display(fmt = "Tracker returned an error: {message}.")]
+    TrackerError { message: String },
+
+    #[display(fmt = "Invalid tracker response: {message}.")]
+    InvalidResponse { message: String },
+}
+
+/// It scrapes the statistics for the given info-hashes from the tracker.
+///
+/// UDP trackers are scraped in batches of
+/// [`MAX_INFO_HASHES_PER_REQUEST`](udp::MAX_INFO_HASHES_PER_REQUEST)
+/// info-hashes.
+///
+/// # Errors
+///
+/// Will return an error if the tracker URL is not valid or not supported, or
+/// if the tracker can't be scraped.
+pub async fn scrape(tracker_url: &str, info_hashes: &[InfoHash]) -> Result<ScrapeResponse, Error> {
+    let url = Url::parse(tracker_url).map_err(|_| Error::InvalidTrackerUrl {
+        url: tracker_url.to_owned(),
+    })?;
+
+    match url.scheme() {
+        "udp" => udp::scrape(&url, info_hashes).await,
+        "http" | "https" => http::scrape(&url, info_hashes).await,
+        scheme => Err(Error::UnsupportedScheme {
+            scheme: scheme.to_owned(),
+        }),
+    }
+}
@@ -0,0 +1,404 @@
+//! UDP tracker scrape client.
+//!
+//! It implements the `connect` and `scrape` actions of the
+//! [BEP 15](https://www.bittorrent.org/beps/bep_0015.html) UDP tracker protocol.
+//!
+//! ```text
+//! connect request:  protocol_id (i64) | action = 0 (i32) | transaction_id (i32)
+//! connect response: action = 0 (i32) | transaction_id (i32) | connection_id (i64)
+//!
+//! scrape request:   connection_id (i64) | action = 2 (i32) | transaction_id (i32) | info_hash (20 bytes) ...
+//! scrape response:  action = 2 (i32) | transaction_id (i32) | (seeders (i32) | completed (i32) | leechers (i32)) ...
+//!
+//! error response:   action = 3 (i32) | transaction_id (i32) | message (string)
+//! ```
+//!
+//! All integers are big-endian. The tracker accepts up to
+//! [`MAX_INFO_HASHES_PER_REQUEST`] info-hashes per scrape request.
+//!
+//! UDP does not guarantee delivery, so requests without a response are sent
+//! again up to [`MAX_RETRANSMISSIONS`] times, doubling the time waiting for
+//! the response each time, as BEP 15 suggests.
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use tokio::net::{lookup_host, UdpSocket};
+use tokio::time::timeout;
+use url::Url;
+
+use super::{Error, ScrapeResponse, TorrentStats};
+use crate::models::info_hash::InfoHash;
+
+/// Maximum number of info-hashes in a single scrape request. It's the maximum
+/// that fits in a UDP packet.
+pub const MAX_INFO_HASHES_PER_REQUEST: usize = 74;
+
+/// Magic constant that identifies the protocol in the connect request.
+const PROTOCOL_ID: i64 = 0x0417_2710_1980;
+
+const ACTION_CONNECT: i32 = 0;
+const ACTION_SCRAPE: i32 = 2;
+const ACTION_ERROR: i32 = 3;
+
+/// Maximum number of times a request is sent again when there is no response.
+pub const MAX_RETRANSMISSIONS: u32 = 3;
+
+/// Time to wait for the response to the first transmission of a request. It's
+/// doubled on each retransmission.
+const INITIAL_RESPONSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A connection ID can be used for one minute after receiving it.
+const CONNECTION_ID_LIFETIME: Duration = Duration::from_secs(60);
+
+/// Size of the biggest response: the header plus the statistics for the
+/// maximum number of info-hashes.
+const MAX_RESPONSE_SIZE: usize = 8 + 12 * MAX_INFO_HASHES_PER_REQUEST;
+
+/// It scrapes the statistics for the given info-hashes from a UDP tracker.
+///
+/// # Errors
+///
+/// Will return an error if the tracker can't be reached, it does not respond
+/// in time or it returns an error or an invalid response.
+pub async fn scrape(tracker_url: &Url, info_hashes: &[InfoHash]) -> Result<ScrapeResponse, Error> {
+    let mut response = ScrapeResponse::new();
+
+    if info_hashes.is_empty() {
+        return Ok(response);
+    }
+
+    let client = Client::connect(tracker_url).await?;
+
+    let mut connection = client.new_connection().await?;
+
+    for batch in info_hashes.chunks(MAX_INFO_HASHES_PER_REQUEST) {
+        if connection.1.elapsed() >= CONNECTION_ID_LIFETIME {
+            connection = client.new_connection().await?;
+        }
+
+        let stats = client.scrape(connection.0, batch).await?;
+
+        response.extend(batch.iter().copied().zip(stats));
+    }
+
+    Ok(response)
+}
+
+struct Client {
+    socket: UdpSocket,
+}
+
+impl Client {
+    async fn connect(tracker_url: &Url) -> Result<Self, Error> {
+        let host = tracker_url.host_str().ok_or_else(|| Error::InvalidTrackerUrl {
+            url: tracker_url.to_string(),
+        })?;
+
+        let port = tracker_url.port().ok_or_else(|| Error::InvalidTrackerUrl {
+            url: tracker_url.to_string(),
+        })?;
+
+        // IPv6 hosts are enclosed in brackets in URLs.
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+
+        let remote_addr = lookup_host((host, port))
+            .await
+            .map_err(|e| Error::ConnectionError { message: e.to_string() })?
+            .next()
+            .ok_or_else(|| Error::ConnectionError {
+                message: format!("can't resolve {host}"),
+            })?;
+
+        let local_addr: SocketAddr = if remote_addr.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0u16; 8], 0).into()
+        };
+
+        let socket = UdpSocket::bind(local_addr)
+            .await
+            .map_err(|e| Error::ConnectionError { message: e.to_string() })?;
+
+        socket
+            .connect(remote_addr)
+            .await
+            .map_err(|e| Error::ConnectionError { message: e.to_string() })?;
+
+        Ok(Self { socket })
+    }
+
+    /// It gets a new connection ID from the tracker.
+    async fn new_connection(&self) -> Result<(i64, Instant), Error> {
+        let transaction_id = rand::random::<i32>();
+
+        let mut request = Vec::with_capacity(16);
+        request.extend_from_slice(&PROTOCOL_ID.to_be_bytes());
+        request.extend_from_slice(&ACTION_CONNECT.to_be_bytes());
+        request.extend_from_slice(&transaction_id.to_be_bytes());
+
+        let response = self.send(&request, transaction_id).await?;
+
+        let payload = parse_response(&response, ACTION_CONNECT, transaction_id)?;
+
+        let connection_id = payload
+            .get(..8)
+            .map(|bytes| i64::from_be_bytes(bytes.try_into().expect("slice should have 8 bytes")))
+            .ok_or_else(|| Error::InvalidResponse {
+                message: "connect response too short".to_owned(),
+            })?;
+
+        Ok((connection_id, Instant::now()))
+    }
+
+    async fn scrape(&self, connection_id: i64, info_hashes: &[InfoHash]) -> Result<Vec<TorrentStats>, Error> {
+        let transaction_id = rand::random::<i32>();
+
+        let mut request = Vec::with_capacity(16 + 20 * info_hashes.len());
+        request.extend_from_slice(&connection_id.to_be_bytes());
+        request.extend_from_slice(&ACTION_SCRAPE.to_be_bytes());
+        request.extend_from_slice(&transaction_id.to_be_bytes());
+        for info_hash in info_hashes {
+            request.extend_from_slice(&info_hash.bytes());
+        }
+
+        let response = self.send(&request, transaction_id).await?;
+
+        let payload = parse_response(&response, ACTION_SCRAPE, transaction_id)?;
+
+        Ok(payload
+            .chunks_exact(12)
+            .take(info_hashes.len())
+            .map(|entry| TorrentStats {
+                seeders: i64::from(read_i32(&entry[0..4])),
+                completed: i64::from(read_i32(&entry[4..8])),
+                leechers: i64::from(read_i32(&entry[8..12])),
+            })
+            .collect())
+    }
+
+    /// It sends the request and returns the response for the transaction. The
+    /// request is sent again when there is no response in time.
+    async fn send(&self, request: &[u8], transaction_id: i32) -> Result<Vec<u8>, Error> {
+        let mut response_timeout = INITIAL_RESPONSE_TIMEOUT;
+
+        for _ in 0..=MAX_RETRANSMISSIONS {
+            self.socket
+                .send(request)
+                .await
+                .map_err(|e| Error::ConnectionError { message: e.to_string() })?;
+
+            if let Ok(response) = timeout(response_timeout, self.recv(transaction_id)).await {
+                return response;
+            }
+
+            response_timeout *= 2;
+        }
+
+        Err(Error::Timeout)
+    }
+
+    /// It waits for the response for the transaction. Late responses to
+    /// previous requests are discarded.
+    async fn recv(&self, transaction_id: i32) -> Result<Vec<u8>, Error> {
+        loop {
+            let mut buffer = vec![0u8; MAX_RESPONSE_SIZE];
+
+            let size = self
+                .socket
+                .recv(&mut buffer)
+                .await
+                .map_err(|e| Error::ConnectionError { message: e.to_string() })?;
+
+            buffer.truncate(size);
+
+            if buffer.len() >= 8 && read_i32(&buffer[4..8]) == transaction_id {
+                return Ok(buffer);
+            }
+        }
+    }
+}
+
+/// It checks the response header and returns the payload after the header.
+fn parse_response(response: &[u8], expected_action: i32, expected_transaction_id: i32) -> Result<&[u8], Error> {
+    if response.len() < 8 {
+        return Err(Error::InvalidResponse {
+            message: "response too short".to_owned(),
+        });
+    }
+
+    let action = read_i32(&response[0..4]);
+    let transaction_id = read_i32(&response[4..8]);
+    let payload = &response[8..];
+
+    if transaction_id != expected_transaction_id {
+        return Err(Error::InvalidResponse {
+            message: "unexpected transaction ID".to_owned(),
+        });
+    }
+
+    if action == ACTION_ERROR {
+        return Err(Error::TrackerError {
+            message: String::from_utf8_lossy(payload).to_string(),
+        });
+    }
+
+    if action != expected_action {
+        return Err(Error::InvalidResponse {
+            message: format!("unexpected action {action}"),
+        });
+    }
+
+    Ok(payload)
+}
+
+fn read_i32(bytes: &[u8]) -> i32 {
+    i32::from_be_bytes(bytes.try_into().expect("slice should have 4 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use tokio::net::UdpSocket;
+    use url::Url;
+
+    use super::{scrape, MAX_INFO_HASHES_PER_REQUEST};
+    use crate::models::info_hash::InfoHash;
+    use crate::tracker::scrape::{Error, TorrentStats};
+
+    const CONNECTION_ID: i64 = 0x1234_5678;
+
+    /// A fake UDP tracker that returns, for each info-hash, the first byte of
+    /// the info-hash as seeders, the second as completed and the third as
+    /// leechers. It ignores the first `lost_requests` requests, as if they
+    /// were lost. It returns the number of scrape requests answered.
+    async fn start_fake_tracker(
+        error_message: Option<&'static str>,
+        mut lost_requests: usize,
+    ) -> (Url, tokio::task::JoinHandle<usize>) {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("udp://{}", socket.local_addr().unwrap())).unwrap();
+
+        let handle = tokio::spawn(async move {
+            let mut scrape_requests = 0;
+            let mut buffer = [0u8; 2048];
+
+            loop {
+                let Ok(Ok((size, peer))) =
+                    tokio::time::timeout(std::time::Duration::from_millis(2500), socket.recv_from(&mut buffer)).await
+                else {
+                    return scrape_requests;
+                };
+
+                if lost_requests > 0 {
+                    lost_requests -= 1;
+                    continue;
+                }
+
+                let request = &buffer[..size];
+                let action = i32::from_be_bytes(request[8..12].try_into().unwrap());
+                let transaction_id = &request[12..16];
+
+                let mut response = Vec::new();
+
+                if let Some(message) = error_message {
+                    response.extend_from_slice(&3i32.to_be_bytes());
+                    response.extend_from_slice(transaction_id);
+                    response.extend_from_slice(message.as_bytes());
+                } else if action == 0 {
+                    assert_eq!(&request[0..8], &0x0417_2710_1980i64.to_be_bytes());
+                    response.extend_from_slice(&0i32.to_be_bytes());
+                    response.extend_from_slice(transaction_id);
+                    response.extend_from_slice(&CONNECTION_ID.to_be_bytes());
+                } else {
+                    assert_eq!(&request[0..8], &CONNECTION_ID.to_be_bytes());
+                    scrape_requests += 1;
+                    response.extend_from_slice(&2i32.to_be_bytes());
+                    response.extend_from_slice(transaction_id);
+                    for info_hash in request[16..].chunks_exact(20) {
+                        for value in &info_hash[0..3] {
+                            response.extend_from_slice(&i32::from(*value).to_be_bytes());
+                        }
+                    }
+                }
+
+                socket.send_to(&response, peer).await.unwrap();
+            }
+        });
+
+        (url, handle)
+    }
+
+    fn info_hash(seeders: u8, completed: u8, leechers: u8) -> InfoHash {
+        InfoHash::from_str(&format!("{seeders:02x}{completed:02x}{leechers:02x}{}", "0".repeat(34))).unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_should_scrape_the_statistics_for_each_info_hash() {
+        let (url, _tracker) = start_fake_tracker(None, 0).await;
+
+        let response = scrape(&url, &[info_hash(1, 2, 3), info_hash(4, 5, 6)]).await.unwrap();
+
+        assert_eq!(
+            response.get(&info_hash(1, 2, 3)),
+            Some(&TorrentStats {
+                seeders: 1,
+                completed: 2,
+                leechers: 3
+            })
+        );
+        assert_eq!(
+            response.get(&info_hash(4, 5, 6)),
+            Some(&TorrentStats {
+                seeders: 4,
+                completed: 5,
+                leechers: 6
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_split_the_info_hashes_in_batches() {
+        let (url, tracker) = start_fake_tracker(None, 0).await;
+
+        let info_hashes: Vec<InfoHash> = (0..=MAX_INFO_HASHES_PER_REQUEST)
+            .map(|i| info_hash(u8::try_from(i).unwrap(), 0, 0))
+            .collect();
+
+        let response = scrape(&url, &info_hashes).await.unwrap();
+
+        assert_eq!(response.len(), MAX_INFO_HASHES_PER_REQUEST + 1);
+        assert_eq!(tracker.await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_return_the_error_message_sent_by_the_tracker() {
+        let (url, _tracker) = start_fake_tracker(Some("scrape not allowed"), 0).await;
+
+        let result = scrape(&url, &[info_hash(1, 2, 3)]).await;
+
+        assert_eq!(
+            result,
+            Err(Error::TrackerError {
+                message: "scrape not allowed".to_owned()
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_send_the_requests_again_when_they_are_lost() {
+        let (url, tracker) = start_fake_tracker(None, 1).await;
+
+        let response = scrape(&url, &[info_hash(1, 2, 3)]).await.unwrap();
+
+        assert_eq!(
+            response.get(&info_hash(1, 2, 3)),
+            Some(&TorrentStats {
+                seeders: 1,
+                completed: 2,
+                leechers: 3
+            })
+        );
+        assert_eq!(tracker.await.unwrap(), 1);
+    }
+}
@@ -1,12 +1,14 @@
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use derive_more::{Display, Error};
-use hyper::StatusCode;
 use log::{debug, error};
 use serde::{Deserialize, Serialize};
 
-use super::api::{Client, ConnectionInfo};
-use crate::config::Configuration;
+use super::backend::api::ApiBackend;
+use super::backend::in_memory::InMemoryBackend;
+use super::backend::none::NoneBackend;
+use super::backend::TrackerBackend;
+use crate::config::{Configuration, TrackerBackendKind};
 use crate::databases::database::Database;
 use crate::models::tracker_key::TrackerKey;
 use crate::models::user::UserId;
@@ -29,6 +31,12 @@ pub enum TrackerAPIError {
     #[display(fmt = "Could not save the newly generated user key into the database.")]
     CannotSaveUserKey,
 
+    #[display(fmt = "Could not get the user keys from the database.")]
+    CannotGetUserKeys,
+
+    #[display(fmt = "Could not delete the user keys from the database.")]
+    CannotDeleteUserKeys,
+
     #[display(fmt = "Torrent not found.")]
     TorrentNotFound,
 
@@ -37,6 +45,9 @@ pub enum TrackerAPIError {
 
     #[display(fmt = "Expected body in tracker response, received empty body.")]
     FailedToParseTrackerResponse { body: String },
+
+    #[display(fmt = "The tracker backend does not support this operation.")]
+    UnsupportedOperation,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -48,6 +59,15 @@ pub struct TorrentInfo {
     pub peers: Vec<Peer>,
 }
 
+/// Torrent data returned by the tracker when listing torrents.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct TorrentBasicInfo {
+    pub info_hash: String,
+    pub seeders: i64,
+    pub completed: i64,
+    pub leechers: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct Peer {
     pub peer_id: Option<PeerId>,
@@ -65,32 +85,31 @@ pub struct PeerId {
     pub client: Option<String>,
 }
 
+/// The tracker service.
+///
+/// It delegates the tracker operations to the [`TrackerBackend`] selected in
+/// the `tracker.backend` setting.
+///
+/// It does not keep a copy of the tracker settings. They are read from the
+/// configuration every time they are needed, so changes in the settings are
+/// applied without restarting the application.
 pub struct Service {
+    cfg: Arc<Configuration>,
     database: Arc<Box<dyn Database>>,
-    api_client: Client,
-    token_valid_seconds: u64,
-    tracker_url: String,
+    api_backend: ApiBackend,
+    none_backend: NoneBackend,
+    /// Only built if it's selected, which only happens in tests.
+    in_memory_backend: OnceLock<InMemoryBackend>,
 }
 
 impl Service {
-    /// # Panics
-    ///
-    /// Will panic if it can't build a Tracker API client.
     pub async fn new(cfg: Arc<Configuration>, database: Arc<Box<dyn Database>>) -> Service {
-        let settings = cfg.settings.read().await;
-        let api_client = Client::new(ConnectionInfo::new(
-            settings.tracker.api_url.clone(),
-            settings.tracker.token.clone(),
-        ))
-        .expect("a reqwest client should be provided");
-        let token_valid_seconds = settings.tracker.token_valid_seconds;
-        let tracker_url = settings.tracker.url.clone();
-        drop(settings);
         Service {
+            cfg: cfg.clone(),
             database,
-            api_client,
-            token_valid_seconds,
-            tracker_url,
+            api_backend: ApiBackend::new(cfg),
+            none_backend: NoneBackend,
+            in_memory_backend: OnceLock::new(),
         }
     }
 
@@ -103,37 +122,7 @@ impl Service {
     pub async fn whitelist_info_hash(&self, info_hash: String) -> Result<(), TrackerAPIError> {
         debug!(target: "tracker-service", "add to whitelist: {info_hash}");
 
-        let maybe_response = self.api_client.whitelist_torrent(&info_hash).await;
-
-        debug!(target: "tracker-service", "add to whitelist response result: {:?}", maybe_response);
-
-        match maybe_response {
-            Ok(response) => {
-                let status: StatusCode = map_status_code(response.status());
-
-                let body = response.text().await.map_err(|_| {
-                    error!(target: "tracker-service", "response without body");
-                    TrackerAPIError::MissingResponseBody
-                })?;
-
-                match status {
-                    StatusCode::OK => Ok(()),
-                    StatusCode::INTERNAL_SERVER_ERROR => {
-                        if body == "Unhandled rejection: Err { reason: \"token not valid\" }" {
-                            Err(TrackerAPIError::InvalidToken)
-                        } else {
-                            error!(target: "tracker-service", "add to whitelist 500 response: status {status}, body: {body}");
-                            Err(TrackerAPIError::InternalServerError)
-                        }
-                    }
-                    _ => {
-                        error!(target: "tracker-service", "add to whitelist unexpected response: status {status}, body: {body}");
-                        Err(TrackerAPIError::UnexpectedResponseStatus)
-                    }
-                }
-            }
-            Err(_) => Err(TrackerAPIError::TrackerOffline),
-        }
+        self.backend().await.whitelist_torrent(&info_hash).await
     }
 
     /// Remove a torrent from the tracker whitelist.
@@ -145,37 +134,7 @@ impl Service {
     pub async fn remove_info_hash_from_whitelist(&self, info_hash: String) -> Result<(), TrackerAPIError> {
         debug!(target: "tracker-service", "remove from whitelist: {info_hash}");
 
-        let maybe_response = self.api_client.remove_torrent_from_whitelist(&info_hash).await;
-
-        debug!(target: "tracker-service", "remove from whitelist response result: {:?}", maybe_response);
-
-        match maybe_response {
-            Ok(response) => {
-                let status: StatusCode = map_status_code(response.status());
-
-                let body = response.text().await.map_err(|_| {
-                    error!(target: "tracker-service", "response without body");
-                    TrackerAPIError::MissingResponseBody
-                })?;
-
-                match status {
-                    StatusCode::OK => Ok(()),
-                    StatusCode::INTERNAL_SERVER_ERROR => {
-                        if body == Self::invalid_token_body() {
-                            Err(TrackerAPIError::InvalidToken)
-                        } else {
-                            error!(target: "tracker-service", "remove from whitelist 500 response: status {status}, body: {body}");
-                            Err(TrackerAPIError::InternalServerError)
-                        }
-                    }
-                    _ => {
-                        error!(target: "tracker-service", "remove from whitelist unexpected response: status {status}, body: {body}");
-                        Err(TrackerAPIError::UnexpectedResponseStatus)
-                    }
-                }
-            }
-            Err(_) => Err(TrackerAPIError::TrackerOffline),
-        }
+        self.backend().await.remove_torrent_from_whitelist(&info_hash).await
     }
 
     /// Get personal tracker announce url of a user.
@@ -195,14 +154,87 @@ impl Service {
         let tracker_key = self.database.get_user_tracker_key(user_id).await;
 
         match tracker_key {
-            Some(tracker_key) => Ok(self.announce_url_with_key(&tracker_key)),
+            Some(tracker_key) => Ok(self.announce_url_with_key(&tracker_key).await),
             None => match self.retrieve_new_tracker_key(user_id).await {
-                Ok(new_tracker_key) => Ok(self.announce_url_with_key(&new_tracker_key)),
+                Ok(new_tracker_key) => Ok(self.announce_url_with_key(&new_tracker_key).await),
+                Err(TrackerAPIError::UnsupportedOperation) => Err(TrackerAPIError::UnsupportedOperation),
                 Err(_) => Err(TrackerAPIError::TrackerOffline),
             },
         }
     }
 
+    /// Get the current tracker key of a user. It returns `None` if the user
+    /// does not have a tracker key valid for at least one more hour.
+    pub async fn get_user_tracker_key(&self, user_id: UserId) -> Option<TrackerKey> {
+        self.database.get_user_tracker_key(user_id).await
+    }
+
+    /// It revokes the tracker keys of a user and issues a new one.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the old keys can't be revoked or the HTTP
+    /// request to generate the new key failed.
+    pub async fn rotate_tracker_key(&self, user_id: UserId) -> Result<TrackerKey, TrackerAPIError> {
+        debug!(target: "tracker-service", "rotate key for user: {user_id}");
+
+        self.revoke_user_tracker_keys(user_id).await?;
+
+        self.retrieve_new_tracker_key(user_id).await
+    }
+
+    /// It revokes all the tracker keys of a user. The keys are deleted from
+    /// the tracker and from the database. It returns the number of revoked
+    /// keys that were not expired.
+    ///
+    /// Keys the tracker fails to delete, for example, because it does not
+    /// know them anymore, are only deleted from the database.
+    ///
+    /// The keys are always deleted from the database, even when the tracker
+    /// is not available. In that case the keys remain valid in the tracker
+    /// until they expire.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if there is a database error, or, once the keys
+    /// have been deleted from the database, if the tracker is not available or
+    /// the tracker token is not valid.
+    pub async fn revoke_user_tracker_keys(&self, user_id: UserId) -> Result<usize, TrackerAPIError> {
+        debug!(target: "tracker-service", "revoke keys for user: {user_id}");
+
+        let tracker_keys = self
+            .database
+            .get_user_tracker_keys(user_id)
+            .await
+            .map_err(|_| TrackerAPIError::CannotGetUserKeys)?;
+
+        let mut tracker_error = None;
+
+        for tracker_key in &tracker_keys {
+            match self.delete_tracker_key(&tracker_key.key).await {
+                Ok(()) => {}
+                Err(err @ (TrackerAPIError::TrackerOffline | TrackerAPIError::InvalidToken)) => {
+                    // The rest of the keys would fail too
+                    tracker_error = Some(err);
+                    break;
+                }
+                Err(err) => {
+                    error!(target: "tracker-service", "failed to delete key of user {user_id} from tracker: {err}");
+                }
+            }
+        }
+
+        self.database
+            .delete_user_tracker_keys(user_id)
+            .await
+            .map_err(|_| TrackerAPIError::CannotDeleteUserKeys)?;
+
+        match tracker_error {
+            Some(err) => Err(err),
+            None => Ok(tracker_keys.len()),
+        }
+    }
+
     /// Get torrent info from tracker.
     ///
     /// # Errors
@@ -212,119 +244,59 @@ impl Service {
     pub async fn get_torrent_info(&self, info_hash: &str) -> Result<TorrentInfo, TrackerAPIError> {
         debug!(target: "tracker-service", "get torrent info: {info_hash}");
 
-        let maybe_response = self.api_client.get_torrent_info(info_hash).await;
-
-        debug!(target: "tracker-service", "get torrent info response result: {:?}", maybe_response);
-
-        match maybe_response {
-            Ok(response) => {
-                let status: StatusCode = map_status_code(response.status());
-
-                let body = response.text().await.map_err(|_| {
-                    error!(target: "tracker-service", "response without body");
-                    TrackerAPIError::MissingResponseBody
-                })?;
-
-                match status {
-                    StatusCode::NOT_FOUND => Err(TrackerAPIError::TorrentNotFound),
-                    StatusCode::OK => {
-                        if body == Self::torrent_not_known_body() {
-                            // todo: temporary fix. the service should return a 404 (StatusCode::NOT_FOUND).
-                            return Err(TrackerAPIError::TorrentNotFound);
-                        }
-
-                        serde_json::from_str(&body).map_err(|e| {
-                            error!(
-                                target: "tracker-service", "Failed to parse torrent info from tracker response. Body: {}, Error: {}",
-                                body, e
-                            );
-                            TrackerAPIError::FailedToParseTrackerResponse { body }
-                        })
-                    }
-                    StatusCode::INTERNAL_SERVER_ERROR => {
-                        if body == Self::invalid_token_body() {
-                            Err(TrackerAPIError::InvalidToken)
-                        } else {
-                            error!(target: "tracker-service", "get torrent info 500 response: status {status}, body: {body}");
-                            Err(TrackerAPIError::InternalServerError)
-                        }
-                    }
-                    _ => {
-                        error!(target: "tracker-service", "get torrent info unhandled response: status {status}, body: {body}");
-                        Err(TrackerAPIError::UnexpectedResponseStatus)
-                    }
-                }
-            }
-            Err(_) => Err(TrackerAPIError::TrackerOffline),
-        }
+        self.backend().await.get_torrent_info(info_hash).await
+    }
+
+    /// Get a page of the torrents tracked by the tracker.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the HTTP request to get the torrents fails or
+    /// if the response cannot be parsed.
+    pub async fn get_torrents(&self, offset: u32, limit: u32) -> Result<Vec<TorrentBasicInfo>, TrackerAPIError> {
+        debug!(target: "tracker-service", "get torrents: offset {offset}, limit {limit}");
+
+        self.backend().await.get_torrents(offset, limit).await
     }
 
     /// Issue a new tracker key from tracker.
     async fn retrieve_new_tracker_key(&self, user_id: i64) -> Result<TrackerKey, TrackerAPIError> {
         debug!(target: "tracker-service", "retrieve key: {user_id}");
 
-        let maybe_response = self.api_client.retrieve_new_tracker_key(self.token_valid_seconds).await;
-
-        debug!(target: "tracker-service", "retrieve key response result: {:?}", maybe_response);
-
-        match maybe_response {
-            Ok(response) => {
-                let status: StatusCode = map_status_code(response.status());
-
-                let body = response.text().await.map_err(|_| {
-                    error!(target: "tracker-service", "response without body");
-                    TrackerAPIError::MissingResponseBody
-                })?;
-
-                match status {
-                    StatusCode::OK => {
-                        // Parse tracker key from response
-                        let tracker_key =
-                            serde_json::from_str(&body).map_err(|_| TrackerAPIError::FailedToParseTrackerResponse { body })?;
-
-                        // Add tracker key to database (tied to a user)
-                        self.database
-                            .add_tracker_key(user_id, &tracker_key)
-                            .await
-                            .map_err(|_| TrackerAPIError::CannotSaveUserKey)?;
-
-                        Ok(tracker_key)
-                    }
-                    StatusCode::INTERNAL_SERVER_ERROR => {
-                        if body == Self::invalid_token_body() {
-                            Err(TrackerAPIError::InvalidToken)
-                        } else {
-                            error!(target: "tracker-service", "retrieve key 500 response: status {status}, body: {body}");
-                            Err(TrackerAPIError::InternalServerError)
-                        }
-                    }
-                    _ => {
-                        error!(target: "tracker-service", " retrieve key unexpected response: status {status}, body: {body}");
-                        Err(TrackerAPIError::UnexpectedResponseStatus)
-                    }
-                }
-            }
-            Err(_) => Err(TrackerAPIError::TrackerOffline),
-        }
+        let token_valid_seconds = self.cfg.settings.read().await.tracker.token_valid_seconds;
+
+        let tracker_key = self.backend().await.issue_key(token_valid_seconds).await?;
+
+        // Add tracker key to database (tied to a user)
+        self.database
+            .add_tracker_key(user_id, &tracker_key)
+            .await
+            .map_err(|_| TrackerAPIError::CannotSaveUserKey)?;
+
+        Ok(tracker_key)
+    }
+
+    /// Delete a tracker key from the tracker.
+    async fn delete_tracker_key(&self, key: &str) -> Result<(), TrackerAPIError> {
+        self.backend().await.revoke_key(key).await
     }
 
     /// It builds the announce url appending the user tracker key.
     /// Eg: <https://tracker:7070/USER_TRACKER_KEY>
-    fn announce_url_with_key(&self, tracker_key: &TrackerKey) -> String {
-        format!("{}/{}", self.tracker_url, tracker_key.key)
-    }
+    pub async fn announce_url_with_key(&self, tracker_key: &TrackerKey) -> String {
+        let tracker_url = self.cfg.settings.read().await.tracker.url.clone();
 
-    fn invalid_token_body() -> String {
-        "Unhandled rejection: Err { reason: \"token not valid\" }".to_string()
+        format!("{}/{}", tracker_url, tracker_key.key)
     }
 
-    fn torrent_not_known_body() -> String {
-        "\"torrent not known\"".to_string()
-    }
-}
+    /// It returns the backend selected in the current tracker settings.
+    async fn backend(&self) -> &dyn TrackerBackend {
+        let backend = self.cfg.settings.read().await.tracker.backend;
 
-/// Temporary patch to map `StatusCode` from crate `http` 0.2.11 to `http` v1.0.0
-/// until `reqwest` upgrades to hyper 1.0. See <https://github.com/seanmonstar/reqwest/issues/2039>
-fn map_status_code(status: reqwest::StatusCode) -> hyper::StatusCode {
-    StatusCode::from_u16(status.as_u16()).unwrap()
+        match backend {
+            TrackerBackendKind::Api => &self.api_backend,
+            TrackerBackendKind::None => &self.none_backend,
+            TrackerBackendKind::InMemory => self.in_memory_backend.get_or_init(InMemoryBackend::new),
+        }
+    }
 }
@@ -1,67 +1,256 @@
+//! Importer of torrent statistics (seeders and leechers) from the tracker.
+//!
+//! Each tracker has its own `statistics_source` setting. The statistics from
+//! the index tracker are obtained from the Torrust Tracker REST API or by
+//! [scraping](super::scrape) the tracker. The statistics from the
+//! `tracker.extra_trackers` can only be scraped.
+//!
+//! Statistics are stored per tracker, and the statistics from trackers that
+//! are no longer configured, or whose `statistics_source` is `None`, are
+//! removed.
+//!
+//! After importing the statistics for all the torrents, a snapshot is added to
+//! the statistics history. Old snapshots are downsampled to one per day and
+//! removed after the configured retention period.
+//!
+//! Each run only imports the torrents whose statistics are outdated, up to a
+//! maximum number of torrents per run, sending several requests to the tracker
+//! concurrently. The progress of the run is available with
+//! [`StatisticsImporter::progress`].
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Instant;
 
+use chrono::{Duration, Utc};
+use futures::stream::{self, StreamExt};
 use log::{error, info};
+use serde::{Deserialize, Serialize};
 use text_colorizer::Colorize;
+use tokio::sync::watch;
 
+use super::scrape::{self, udp};
 use super::service::{Service, TorrentInfo, TrackerAPIError};
-use crate::config::Configuration;
-use crate::databases::database::{self, Database};
+use crate::config::{Configuration, StatisticsSource};
+use crate::databases::database::{self, Database, TorrentCompact};
+use crate::models::info_hash::InfoHash;
 
 const LOG_TARGET: &str = "Tracker Stats Importer";
 
+/// Torrents uploaded in this period are imported before older torrents.
+const RECENTLY_UPLOADED_DAYS: i64 = 7;
+
+/// Progress of the current import run, or the last one if there is no import
+/// running.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportProgress {
+    /// Number of torrents selected to be imported in this run. A torrent is
+    /// counted once for each tracker.
+    pub torrents_to_import: usize,
+    /// Number of torrents processed, including the failed ones.
+    pub torrents_processed: usize,
+    /// Number of torrents whose statistics could not be imported.
+    pub errors: usize,
+    /// The last error.
+    pub last_error: Option<String>,
+    /// Whether the run has finished.
+    pub finished: bool,
+}
+
 pub struct StatisticsImporter {
+    cfg: Arc<Configuration>,
     database: Arc<Box<dyn Database>>,
     tracker_service: Arc<Service>,
-    tracker_url: String,
+    progress: watch::Sender<ImportProgress>,
 }
 
 impl StatisticsImporter {
     pub async fn new(cfg: Arc<Configuration>, tracker_service: Arc<Service>, database: Arc<Box<dyn Database>>) -> Self {
-        let settings = cfg.settings.read().await;
-        let tracker_url = settings.tracker.url.clone();
-        drop(settings);
         Self {
+            cfg,
             database,
             tracker_service,
-            tracker_url,
+            progress: watch::Sender::new(ImportProgress {
+                finished: true,
+                ..Default::default()
+            }),
         }
     }
 
-    /// Import torrents statistics from tracker and update them in database.
+    /// It returns the progress of the current import run, or the last one if
+    /// there is no import running.
+    #[must_use]
+    pub fn progress(&self) -> ImportProgress {
+        self.progress.borrow().clone()
+    }
+
+    /// Import torrents statistics from the trackers and update them in
+    /// database.
+    ///
+    /// Only the torrents whose statistics were not updated in the last half
+    /// `torrent_info_update_interval` are imported, so the ones imported in the
+    /// previous run are imported again. Up to `max_torrents_per_run` torrents
+    /// are imported from each tracker. Torrents never imported, recently
+    /// uploaded and popular torrents are imported first.
     ///
     /// # Errors
     ///
     /// Will return an error if the database query failed.
-    pub async fn import_all_torrents_statistics(&self) -> Result<(), database::Error> {
-        let torrents = self.database.get_all_torrents_compact().await?;
+    pub async fn import_torrents_statistics(&self) -> Result<ImportProgress, database::Error> {
+        let settings = self.cfg.settings.read().await;
+        let update_interval = settings.tracker_statistics_importer.torrent_info_update_interval;
+        let budget = settings.tracker_statistics_importer.max_torrents_per_run;
+        let concurrency = settings.tracker_statistics_importer.max_concurrent_requests.max(1) as usize;
+        drop(settings);
+
+        let tracker_url = self.tracker_url().await;
+        let statistics_source = self.statistics_source().await;
+        let extra_tracker_urls = self.extra_tracker_urls_to_import().await;
 
-        info!(target: LOG_TARGET, "Importing {} torrents statistics from tracker {} ...", torrents.len().to_string().yellow(), self.tracker_url.yellow());
+        let now = Utc::now().naive_utc();
+        let updated_before = now - Duration::seconds(i64::try_from(update_interval / 2).unwrap_or(i64::MAX));
+        let uploaded_after = now - Duration::days(RECENTLY_UPLOADED_DAYS);
+
+        self.progress.send_replace(ImportProgress::default());
 
         // Start the timer before the loop
         let start_time = Instant::now();
 
-        for torrent in torrents {
-            info!(target: LOG_TARGET, "Importing torrent #{} ...", torrent.torrent_id.to_string().yellow());
+        if statistics_source == StatisticsSource::None {
+            info!(target: LOG_TARGET, "Statistics are not imported from tracker {}", tracker_url.yellow());
+        } else {
+            let torrents = self
+                .database
+                .get_torrents_compact_for_statistics_import(&tracker_url, updated_before, uploaded_after, budget)
+                .await?;
+
+            self.progress
+                .send_modify(|progress| progress.torrents_to_import += torrents.len());
 
-            let ret = self.import_torrent_statistics(torrent.torrent_id, &torrent.info_hash).await;
+            info!(target: LOG_TARGET, "Importing {} torrents statistics from tracker {} ...", torrents.len().to_string().yellow(), tracker_url.yellow());
 
-            if let Some(err) = ret.err() {
-                if err != TrackerAPIError::TorrentNotFound {
-                    let message = format!(
-                        "Error updating torrent tracker stats for torrent. Torrent: id {}; infohash {}. Error: {:?}",
-                        torrent.torrent_id, torrent.info_hash, err
-                    );
-                    error!(target: "statistics_importer", "{}", message);
-                }
+            if statistics_source == StatisticsSource::Scrape {
+                self.scrape_torrents_statistics(&tracker_url, &torrents, concurrency).await;
+            } else {
+                self.import_torrents_statistics_from_api(&torrents, concurrency).await;
             }
         }
 
+        for extra_tracker_url in &extra_tracker_urls {
+            let torrents = self
+                .database
+                .get_torrents_compact_for_statistics_import(extra_tracker_url, updated_before, uploaded_after, budget)
+                .await?;
+
+            self.progress
+                .send_modify(|progress| progress.torrents_to_import += torrents.len());
+
+            info!(target: LOG_TARGET, "Importing {} torrents statistics from extra tracker {} ...", torrents.len().to_string().yellow(), extra_tracker_url.yellow());
+
+            self.scrape_torrents_statistics(extra_tracker_url, &torrents, concurrency)
+                .await;
+        }
+
+        let imported_tracker_url = (statistics_source != StatisticsSource::None).then_some(tracker_url.as_str());
+
+        self.delete_statistics_from_removed_trackers(imported_tracker_url, &extra_tracker_urls)
+            .await?;
+
+        self.update_statistics_history().await?;
+
+        self.progress.send_modify(|progress| progress.finished = true);
+
         let elapsed_time = start_time.elapsed();
 
         info!(target: LOG_TARGET, "Statistics import completed in {:.2?}", elapsed_time);
 
-        Ok(())
+        Ok(self.progress())
+    }
+
+    async fn import_torrents_statistics_from_api(&self, torrents: &[TorrentCompact], concurrency: usize) {
+        let imports: Vec<_> = torrents
+            .iter()
+            .map(|torrent| self.import_torrent_statistics_from_api(torrent))
+            .collect();
+
+        stream::iter(imports).buffer_unordered(concurrency).collect::<Vec<()>>().await;
+    }
+
+    async fn import_torrent_statistics_from_api(&self, torrent: &TorrentCompact) {
+        match self.import_torrent_statistics(torrent.torrent_id, &torrent.info_hash).await {
+            // The tracker does not know the torrent until a peer announces it.
+            Ok(_) | Err(TrackerAPIError::TorrentNotFound) => self.record_processed(1, None),
+            Err(err) => {
+                let message = format!(
+                    "Error updating torrent tracker stats for torrent. Torrent: id {}; infohash {}. Error: {:?}",
+                    torrent.torrent_id, torrent.info_hash, err
+                );
+                error!(target: LOG_TARGET, "{}", message);
+                self.record_processed(1, Some(message));
+            }
+        }
+    }
+
+    /// It scrapes the statistics for the torrents in batches. Torrents not
+    /// included in the tracker response are stored with zero peers.
+    async fn scrape_torrents_statistics(&self, tracker_url: &str, torrents: &[TorrentCompact], concurrency: usize) {
+        let scrapes: Vec<_> = torrents
+            .chunks(udp::MAX_INFO_HASHES_PER_REQUEST)
+            .map(|batch| self.scrape_batch_statistics(tracker_url, batch))
+            .collect();
+
+        stream::iter(scrapes).buffer_unordered(concurrency).collect::<Vec<()>>().await;
+    }
+
+    async fn scrape_batch_statistics(&self, tracker_url: &str, batch: &[TorrentCompact]) {
+        let info_hashes: Vec<InfoHash> = batch
+            .iter()
+            .filter_map(|torrent| InfoHash::from_str(&torrent.info_hash).ok())
+            .collect();
+
+        let (response, error) = match scrape::scrape(tracker_url, &info_hashes).await {
+            Ok(response) => (response, None),
+            Err(err) => {
+                let message = format!("Error scraping tracker {tracker_url}: {err}");
+                error!(target: LOG_TARGET, "{message}");
+                (scrape::ScrapeResponse::new(), Some(message))
+            }
+        };
+
+        let import_completed_counts = self.import_completed_counts().await;
+
+        for torrent in batch {
+            let stats = InfoHash::from_str(&torrent.info_hash)
+                .ok()
+                .and_then(|info_hash| response.get(&info_hash).copied())
+                .unwrap_or_default();
+
+            drop(
+                self.database
+                    .update_tracker_info(
+                        torrent.torrent_id,
+                        tracker_url,
+                        stats.seeders,
+                        stats.leechers,
+                        if import_completed_counts { stats.completed } else { 0 },
+                    )
+                    .await,
+            );
+        }
+
+        self.record_processed(batch.len(), error);
+    }
+
+    /// It updates the progress after processing `torrents` torrents. If there
+    /// was an error, all of them are counted as errors.
+    fn record_processed(&self, torrents: usize, error: Option<String>) {
+        self.progress.send_modify(|progress| {
+            progress.torrents_processed += torrents;
+
+            if let Some(error) = error {
+                progress.errors += torrents;
+                progress.last_error = Some(error);
+            }
+        });
     }
 
     /// Import torrent statistics from tracker and update them in database.
@@ -69,21 +258,142 @@ impl StatisticsImporter {
     /// # Errors
     ///
     /// Will return an error if the HTTP request failed or the torrent is not
-    /// found.
+    /// found. It returns [`TrackerAPIError::UnsupportedOperation`] if the
+    /// statistics are not imported from the index tracker.
     pub async fn import_torrent_statistics(&self, torrent_id: i64, info_hash: &str) -> Result<TorrentInfo, TrackerAPIError> {
-        match self.tracker_service.get_torrent_info(info_hash).await {
+        let tracker_url = self.tracker_url().await;
+
+        let torrent_info = match self.statistics_source().await {
+            StatisticsSource::Api => self.tracker_service.get_torrent_info(info_hash).await,
+            StatisticsSource::Scrape => scrape_torrent_info(&tracker_url, info_hash).await,
+            StatisticsSource::None => return Err(TrackerAPIError::UnsupportedOperation),
+        };
+
+        match torrent_info {
             Ok(torrent_info) => {
+                let completed = if self.import_completed_counts().await {
+                    torrent_info.completed
+                } else {
+                    0
+                };
+
                 drop(
                     self.database
-                        .update_tracker_info(torrent_id, &self.tracker_url, torrent_info.seeders, torrent_info.leechers)
+                        .update_tracker_info(
+                            torrent_id,
+                            &tracker_url,
+                            torrent_info.seeders,
+                            torrent_info.leechers,
+                            completed,
+                        )
                         .await,
                 );
                 Ok(torrent_info)
             }
             Err(err) => {
-                drop(self.database.update_tracker_info(torrent_id, &self.tracker_url, 0, 0).await);
+                drop(self.database.update_tracker_info(torrent_id, &tracker_url, 0, 0, 0).await);
                 Err(err)
             }
         }
     }
+
+    /// It removes the statistics imported from trackers that are not the
+    /// index tracker or one of the extra trackers, or whose statistics are
+    /// not imported anymore.
+    async fn delete_statistics_from_removed_trackers(
+        &self,
+        tracker_url: Option<&str>,
+        extra_tracker_urls: &[String],
+    ) -> Result<(), database::Error> {
+        for stored_tracker_url in self.database.get_tracker_urls_with_stats().await? {
+            if Some(stored_tracker_url.as_str()) != tracker_url && !extra_tracker_urls.contains(&stored_tracker_url) {
+                info!(target: LOG_TARGET, "Deleting statistics from removed tracker {} ...", stored_tracker_url.yellow());
+
+                self.database
+                    .delete_tracker_stats_for_tracker_url(&stored_tracker_url)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// It adds a snapshot of the current statistics to the history, and
+    /// downsamples and removes the old snapshots.
+    async fn update_statistics_history(&self) -> Result<(), database::Error> {
+        let settings = self.cfg.settings.read().await;
+        let retention_days = settings.tracker_statistics_importer.stats_history_retention_days;
+        let full_resolution_days = settings.tracker_statistics_importer.stats_history_full_resolution_days;
+        drop(settings);
+
+        if retention_days == 0 {
+            return Ok(());
+        }
+
+        let now = Utc::now().naive_utc();
+
+        let snapshots = self.database.insert_torrent_stats_snapshots(now).await?;
+
+        info!(target: LOG_TARGET, "Added {} snapshots to the statistics history", snapshots.to_string().yellow());
+
+        self.database
+            .downsample_torrent_stats_history(now - Duration::days(i64::from(full_resolution_days)))
+            .await?;
+
+        self.database
+            .delete_torrent_stats_history_before(now - Duration::days(i64::from(retention_days)))
+            .await
+    }
+
+    async fn tracker_url(&self) -> String {
+        self.cfg.settings.read().await.tracker.url.clone()
+    }
+
+    async fn statistics_source(&self) -> StatisticsSource {
+        self.cfg.settings.read().await.tracker.statistics_source.clone()
+    }
+
+    async fn import_completed_counts(&self) -> bool {
+        self.cfg
+            .settings
+            .read()
+            .await
+            .tracker_statistics_importer
+            .import_completed_counts
+    }
+
+    async fn extra_tracker_urls_to_import(&self) -> Vec<String> {
+        self.cfg
+            .settings
+            .read()
+            .await
+            .tracker
+            .extra_trackers
+            .iter()
+            .filter(|extra_tracker| extra_tracker.statistics_source == StatisticsSource::Scrape)
+            .map(|extra_tracker| extra_tracker.url.clone())
+            .collect()
+    }
+}
+
+async fn scrape_torrent_info(tracker_url: &str, info_hash: &str) -> Result<TorrentInfo, TrackerAPIError> {
+    let parsed_info_hash = InfoHash::from_str(info_hash).map_err(|_| TrackerAPIError::TorrentNotFound)?;
+
+    let response = scrape::scrape(tracker_url, &[parsed_info_hash]).await.map_err(|err| {
+        error!(target: LOG_TARGET, "Error scraping tracker {tracker_url}: {err}");
+        match err {
+            scrape::Error::ConnectionError { .. } | scrape::Error::Timeout => TrackerAPIError::TrackerOffline,
+            _ => TrackerAPIError::UnexpectedResponseStatus,
+        }
+    })?;
+
+    let stats = response.get(&parsed_info_hash).ok_or(TrackerAPIError::TorrentNotFound)?;
+
+    Ok(TorrentInfo {
+        info_hash: info_hash.to_owned(),
+        seeders: stats.seeders,
+        completed: stats.completed,
+        leechers: stats.leechers,
+        peers: vec![],
+    })
 }
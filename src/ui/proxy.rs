@@ -12,6 +12,7 @@ static mut ERROR_IMAGE_URL_IS_NOT_AN_IMAGE: Bytes = Bytes::new();
 static mut ERROR_IMAGE_TOO_BIG: Bytes = Bytes::new();
 static mut ERROR_IMAGE_USER_QUOTA_MET: Bytes = Bytes::new();
 static mut ERROR_IMAGE_UNAUTHENTICATED: Bytes = Bytes::new();
+static mut ERROR_IMAGE_INVALID_VARIANT: Bytes = Bytes::new();
 
 const ERROR_IMG_FONT_SIZE: u8 = 16;
 const ERROR_IMG_COLOR: &str = "Red";
@@ -21,6 +22,7 @@ const ERROR_IMAGE_URL_IS_NOT_AN_IMAGE_TEXT: &str = "Invalid image.";
 const ERROR_IMAGE_TOO_BIG_TEXT: &str = "Image is too big.";
 const ERROR_IMAGE_USER_QUOTA_MET_TEXT: &str = "Image proxy quota met.";
 const ERROR_IMAGE_UNAUTHENTICATED_TEXT: &str = "Sign in to see image.";
+const ERROR_IMAGE_INVALID_VARIANT_TEXT: &str = "Invalid image size or format.";
 
 pub fn load_error_images() {
     ERROR_IMAGE_LOADER.call_once(|| unsafe {
@@ -29,6 +31,7 @@ pub fn load_error_images() {
         ERROR_IMAGE_TOO_BIG = generate_img_from_text(ERROR_IMAGE_TOO_BIG_TEXT);
         ERROR_IMAGE_USER_QUOTA_MET = generate_img_from_text(ERROR_IMAGE_USER_QUOTA_MET_TEXT);
         ERROR_IMAGE_UNAUTHENTICATED = generate_img_from_text(ERROR_IMAGE_UNAUTHENTICATED_TEXT);
+        ERROR_IMAGE_INVALID_VARIANT = generate_img_from_text(ERROR_IMAGE_INVALID_VARIANT_TEXT);
     });
 }
 
@@ -41,6 +44,7 @@ pub fn map_error_to_image(error: &Error) -> Bytes {
             Error::ImageTooBig => ERROR_IMAGE_TOO_BIG.clone(),
             Error::UserQuotaMet => ERROR_IMAGE_USER_QUOTA_MET.clone(),
             Error::Unauthenticated => ERROR_IMAGE_UNAUTHENTICATED.clone(),
+            Error::InvalidVariant => ERROR_IMAGE_INVALID_VARIANT.clone(),
         }
     }
 }
@@ -0,0 +1,40 @@
+//! Exponential backoff for the background queues that retry failed tasks, like
+//! the [tracker jobs](crate::tracker::job_queue) and the
+//! [outbound emails](crate::mailer::queue).
+
+/// Upper limit for the delay between retries, regardless of the configured
+/// `max_backoff_seconds`.
+const MAX_BACKOFF_SECONDS: u64 = 365 * 24 * 3600;
+
+/// Delay before the next attempt after `attempts` failed attempts. It starts
+/// with `initial` seconds and it's doubled after each attempt up to `max`
+/// seconds.
+#[must_use]
+pub fn backoff_seconds(attempts: i64, initial: u64, max: u64) -> u64 {
+    let exponent = u32::try_from(attempts.saturating_sub(1).clamp(0, 63)).unwrap_or(63);
+
+    initial
+        .saturating_mul(2u64.saturating_pow(exponent))
+        .min(max)
+        .min(MAX_BACKOFF_SECONDS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::backoff_seconds;
+
+    #[test]
+    fn the_backoff_should_double_after_each_failed_attempt() {
+        assert_eq!(backoff_seconds(1, 5, 3600), 5);
+        assert_eq!(backoff_seconds(2, 5, 3600), 10);
+        assert_eq!(backoff_seconds(3, 5, 3600), 20);
+    }
+
+    #[test]
+    fn the_backoff_should_not_exceed_the_maximum() {
+        assert_eq!(backoff_seconds(10, 5, 3600), 2560);
+        assert_eq!(backoff_seconds(11, 5, 3600), 3600);
+        assert_eq!(backoff_seconds(100, 5, 3600), 3600);
+        assert_eq!(backoff_seconds(100, 5, u64::MAX), 365 * 24 * 3600);
+    }
+}
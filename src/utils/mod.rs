@@ -1,3 +1,4 @@
+pub mod backoff;
 pub mod clock;
 pub mod hex;
 pub mod parse_torrent;
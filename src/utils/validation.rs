@@ -2,8 +2,11 @@ use std::str::FromStr;
 
 use email_address::EmailAddress;
 use regex::Regex;
+use url::Url;
 
 const MIN_DOMAIN_LENGTH: usize = 4;
+const MAX_WEB_SEED_URL_LENGTH: usize = 2048;
+const MAX_LOCALE_LENGTH: usize = 35;
 
 /// Validates an email address.
 ///
@@ -34,6 +37,53 @@ fn domain_has_extension(domain: &str) -> bool {
     Regex::new(r".*\..*").expect("Invalid regex").is_match(domain)
 }
 
+/// Validates a web seed or HTTP seed URL. It must be an absolute `http` or
+/// `https` URL with a host.
+#[must_use]
+pub fn validate_web_seed_url(url_to_be_checked: &str) -> bool {
+    if url_to_be_checked.len() > MAX_WEB_SEED_URL_LENGTH {
+        return false;
+    }
+
+    match Url::parse(url_to_be_checked) {
+        Ok(url) => (url.scheme() == "http" || url.scheme() == "https") && url.host_str().is_some(),
+        Err(_) => false,
+    }
+}
+
+/// Validates a locale, a BCP 47 language tag like `en` or `pt-BR`, and
+/// returns it in its canonical case: lowercase language and uppercase region.
+///
+/// It returns `None` if the locale is not valid.
+#[must_use]
+pub fn normalize_locale(locale_to_be_checked: &str) -> Option<String> {
+    if locale_to_be_checked.len() > MAX_LOCALE_LENGTH {
+        return None;
+    }
+
+    let locale_regex = Regex::new(r"^[A-Za-z]{2,3}(-[A-Za-z0-9]{2,8})*$").expect("Invalid regex");
+
+    if !locale_regex.is_match(locale_to_be_checked) {
+        return None;
+    }
+
+    let subtags: Vec<String> = locale_to_be_checked
+        .split('-')
+        .enumerate()
+        .map(|(index, subtag)| {
+            if index == 0 {
+                subtag.to_lowercase()
+            } else if subtag.len() == 2 {
+                subtag.to_uppercase()
+            } else {
+                subtag.to_string()
+            }
+        })
+        .collect();
+
+    Some(subtags.join("-"))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -76,4 +126,42 @@ mod tests {
             assert!(!domain_has_extension("a."));
         }
     }
+    mod for_web_seed_url_validation {
+        use crate::utils::validation::validate_web_seed_url;
+
+        #[test]
+        fn it_should_accept_http_and_https_urls() {
+            assert!(validate_web_seed_url("http://example.com/files/"));
+            assert!(validate_web_seed_url("https://example.com/files/sample.txt"));
+        }
+
+        #[test]
+        fn it_should_not_accept_other_urls() {
+            assert!(!validate_web_seed_url(""));
+            assert!(!validate_web_seed_url("example.com/files/"));
+            assert!(!validate_web_seed_url("ftp://example.com/files/"));
+            assert!(!validate_web_seed_url("udp://tracker.example.com:6969"));
+            assert!(!validate_web_seed_url(&format!("https://example.com/{}", "a".repeat(2048))));
+        }
+    }
+
+    mod for_locale_validation {
+        use crate::utils::validation::normalize_locale;
+
+        #[test]
+        fn it_should_accept_language_tags_in_canonical_case() {
+            assert_eq!(normalize_locale("en"), Some("en".to_string()));
+            assert_eq!(normalize_locale("PT-br"), Some("pt-BR".to_string()));
+            assert_eq!(normalize_locale("zh-Hant-TW"), Some("zh-Hant-TW".to_string()));
+        }
+
+        #[test]
+        fn it_should_not_accept_invalid_language_tags() {
+            assert_eq!(normalize_locale(""), None);
+            assert_eq!(normalize_locale("e"), None);
+            assert_eq!(normalize_locale("en_US"), None);
+            assert_eq!(normalize_locale("../en"), None);
+            assert_eq!(normalize_locale(&format!("en-{}", "-abc".repeat(10))), None);
+        }
+    }
 }
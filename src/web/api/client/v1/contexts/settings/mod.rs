@@ -3,13 +3,16 @@ pub mod responses;
 use serde::{Deserialize, Serialize};
 
 use crate::config::{
-    Api as DomainApi, Auth as DomainAuth, Database as DomainDatabase, ImageCache as DomainImageCache, Mail as DomainMail,
-    Network as DomainNetwork, TorrustIndex as DomainSettings, Tracker as DomainTracker,
-    TrackerStatisticsImporter as DomainTrackerStatisticsImporter, Website as DomainWebsite,
+    Api as DomainApi, Auth as DomainAuth, Database as DomainDatabase, DeadTorrents as DomainDeadTorrents,
+    EmailVerification as DomainEmailVerification, ExtraTracker as DomainExtraTracker, ImageCache as DomainImageCache,
+    ImageUploads as DomainImageUploads, Mail as DomainMail, MailQueue as DomainMailQueue, Network as DomainNetwork,
+    Notifications as DomainNotifications, TorrustIndex as DomainSettings, Tracker as DomainTracker,
+    TrackerJobs as DomainTrackerJobs, TrackerStatisticsImporter as DomainTrackerStatisticsImporter, Website as DomainWebsite,
 };
 
 #[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
 pub struct Settings {
+    pub log_level: Option<String>,
     pub website: Website,
     pub tracker: Tracker,
     pub net: Network,
@@ -17,8 +20,14 @@ pub struct Settings {
     pub database: Database,
     pub mail: Mail,
     pub image_cache: ImageCache,
+    pub image_uploads: ImageUploads,
     pub api: Api,
     pub tracker_statistics_importer: TrackerStatisticsImporter,
+    pub tracker_jobs: TrackerJobs,
+    pub mail_queue: MailQueue,
+    pub email_verification: EmailVerification,
+    pub dead_torrents: DeadTorrents,
+    pub notifications: Notifications,
 }
 
 #[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
@@ -33,6 +42,16 @@ pub struct Tracker {
     pub api_url: String,
     pub token: String,
     pub token_valid_seconds: u64,
+    pub backend: String,
+    pub statistics_source: String,
+    pub extra_trackers: Vec<ExtraTracker>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct ExtraTracker {
+    pub url: String,
+    pub statistics_source: String,
+    pub add_to_announce_list: bool,
 }
 
 #[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
@@ -63,6 +82,11 @@ pub struct Mail {
     pub password: String,
     pub server: String,
     pub port: u16,
+    pub transport: String,
+    pub smtp_tls: String,
+    pub sendmail_command: String,
+    pub file_path: String,
+    pub templates_path: String,
 }
 
 #[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
@@ -72,6 +96,24 @@ pub struct ImageCache {
     pub entry_size_limit: usize,
     pub user_quota_period_seconds: u64,
     pub user_quota_bytes: usize,
+    pub disk_cache_enabled: bool,
+    pub disk_cache_path: String,
+    pub disk_capacity: usize,
+    pub ttl_seconds: u64,
+    pub max_redirects: usize,
+    pub allowed_private_hosts: Vec<String>,
+    pub variant_format: String,
+    pub variant_quality: u8,
+    pub max_variant_dimension: u32,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct ImageUploads {
+    pub storage_path: String,
+    pub max_image_size: usize,
+    pub max_image_dimension: u32,
+    pub user_quota_bytes: usize,
+    pub max_screenshots: usize,
 }
 
 #[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
@@ -84,11 +126,64 @@ pub struct Api {
 pub struct TrackerStatisticsImporter {
     pub torrent_info_update_interval: u64,
     port: u16,
+    pub stats_history_retention_days: u32,
+    pub stats_history_full_resolution_days: u32,
+    pub max_torrents_per_run: u32,
+    pub max_concurrent_requests: u32,
+    pub import_completed_counts: bool,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct TrackerJobs {
+    pub worker_interval_seconds: u64,
+    pub batch_size: u32,
+    pub max_attempts: u32,
+    pub initial_backoff_seconds: u64,
+    pub max_backoff_seconds: u64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct MailQueue {
+    pub worker_interval_seconds: u64,
+    pub batch_size: u32,
+    pub max_attempts: u32,
+    pub initial_backoff_seconds: u64,
+    pub max_backoff_seconds: u64,
+    pub retention_days: u32,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct EmailVerification {
+    pub token_lifetime_seconds: u64,
+    pub required_for_login: bool,
+    pub required_for_upload: bool,
+    pub purge_unverified_after_days: u32,
+    pub purge_interval_seconds: u64,
+    pub resend_interval_seconds: u64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct DeadTorrents {
+    pub enabled: bool,
+    pub check_interval_seconds: u64,
+    pub dead_after_days: u32,
+    pub hide_dead_torrents: bool,
+    pub notify_uploader: bool,
+    pub grace_period_days: u32,
+    pub prune_action: String,
+    pub dry_run: bool,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Notifications {
+    pub digest_interval_seconds: u64,
+    pub retention_days: u32,
 }
 
 impl From<DomainSettings> for Settings {
     fn from(settings: DomainSettings) -> Self {
         Settings {
+            log_level: settings.log_level,
             website: Website::from(settings.website),
             tracker: Tracker::from(settings.tracker),
             net: Network::from(settings.net),
@@ -96,8 +191,14 @@ impl From<DomainSettings> for Settings {
             database: Database::from(settings.database),
             mail: Mail::from(settings.mail),
             image_cache: ImageCache::from(settings.image_cache),
+            image_uploads: ImageUploads::from(settings.image_uploads),
             api: Api::from(settings.api),
             tracker_statistics_importer: TrackerStatisticsImporter::from(settings.tracker_statistics_importer),
+            tracker_jobs: TrackerJobs::from(settings.tracker_jobs),
+            mail_queue: MailQueue::from(settings.mail_queue),
+            email_verification: EmailVerification::from(settings.email_verification),
+            dead_torrents: DeadTorrents::from(settings.dead_torrents),
+            notifications: Notifications::from(settings.notifications),
         }
     }
 }
@@ -116,6 +217,19 @@ impl From<DomainTracker> for Tracker {
             api_url: tracker.api_url,
             token: tracker.token,
             token_valid_seconds: tracker.token_valid_seconds,
+            backend: format!("{:?}", tracker.backend),
+            statistics_source: format!("{:?}", tracker.statistics_source),
+            extra_trackers: tracker.extra_trackers.into_iter().map(ExtraTracker::from).collect(),
+        }
+    }
+}
+
+impl From<DomainExtraTracker> for ExtraTracker {
+    fn from(extra_tracker: DomainExtraTracker) -> Self {
+        Self {
+            url: extra_tracker.url,
+            statistics_source: format!("{:?}", extra_tracker.statistics_source),
+            add_to_announce_list: extra_tracker.add_to_announce_list,
         }
     }
 }
@@ -158,6 +272,11 @@ impl From<DomainMail> for Mail {
             password: mail.password,
             server: mail.server,
             port: mail.port,
+            transport: format!("{:?}", mail.transport),
+            smtp_tls: format!("{:?}", mail.smtp_tls),
+            sendmail_command: mail.sendmail_command,
+            file_path: mail.file_path,
+            templates_path: mail.templates_path,
         }
     }
 }
@@ -170,6 +289,27 @@ impl From<DomainImageCache> for ImageCache {
             entry_size_limit: image_cache.entry_size_limit,
             user_quota_period_seconds: image_cache.user_quota_period_seconds,
             user_quota_bytes: image_cache.user_quota_bytes,
+            disk_cache_enabled: image_cache.disk_cache_enabled,
+            disk_cache_path: image_cache.disk_cache_path,
+            disk_capacity: image_cache.disk_capacity,
+            ttl_seconds: image_cache.ttl_seconds,
+            max_redirects: image_cache.max_redirects,
+            allowed_private_hosts: image_cache.allowed_private_hosts,
+            variant_format: format!("{:?}", image_cache.variant_format),
+            variant_quality: image_cache.variant_quality,
+            max_variant_dimension: image_cache.max_variant_dimension,
+        }
+    }
+}
+
+impl From<DomainImageUploads> for ImageUploads {
+    fn from(image_uploads: DomainImageUploads) -> Self {
+        Self {
+            storage_path: image_uploads.storage_path,
+            max_image_size: image_uploads.max_image_size,
+            max_image_dimension: image_uploads.max_image_dimension,
+            user_quota_bytes: image_uploads.user_quota_bytes,
+            max_screenshots: image_uploads.max_screenshots,
         }
     }
 }
@@ -188,6 +328,73 @@ impl From<DomainTrackerStatisticsImporter> for TrackerStatisticsImporter {
         Self {
             torrent_info_update_interval: tracker_statistics_importer.torrent_info_update_interval,
             port: tracker_statistics_importer.port,
+            stats_history_retention_days: tracker_statistics_importer.stats_history_retention_days,
+            stats_history_full_resolution_days: tracker_statistics_importer.stats_history_full_resolution_days,
+            max_torrents_per_run: tracker_statistics_importer.max_torrents_per_run,
+            max_concurrent_requests: tracker_statistics_importer.max_concurrent_requests,
+            import_completed_counts: tracker_statistics_importer.import_completed_counts,
+        }
+    }
+}
+
+impl From<DomainTrackerJobs> for TrackerJobs {
+    fn from(tracker_jobs: DomainTrackerJobs) -> Self {
+        Self {
+            worker_interval_seconds: tracker_jobs.worker_interval_seconds,
+            batch_size: tracker_jobs.batch_size,
+            max_attempts: tracker_jobs.max_attempts,
+            initial_backoff_seconds: tracker_jobs.initial_backoff_seconds,
+            max_backoff_seconds: tracker_jobs.max_backoff_seconds,
+        }
+    }
+}
+
+impl From<DomainMailQueue> for MailQueue {
+    fn from(mail_queue: DomainMailQueue) -> Self {
+        Self {
+            worker_interval_seconds: mail_queue.worker_interval_seconds,
+            batch_size: mail_queue.batch_size,
+            max_attempts: mail_queue.max_attempts,
+            initial_backoff_seconds: mail_queue.initial_backoff_seconds,
+            max_backoff_seconds: mail_queue.max_backoff_seconds,
+            retention_days: mail_queue.retention_days,
+        }
+    }
+}
+
+impl From<DomainEmailVerification> for EmailVerification {
+    fn from(email_verification: DomainEmailVerification) -> Self {
+        Self {
+            token_lifetime_seconds: email_verification.token_lifetime_seconds,
+            required_for_login: email_verification.required_for_login,
+            required_for_upload: email_verification.required_for_upload,
+            purge_unverified_after_days: email_verification.purge_unverified_after_days,
+            purge_interval_seconds: email_verification.purge_interval_seconds,
+            resend_interval_seconds: email_verification.resend_interval_seconds,
+        }
+    }
+}
+
+impl From<DomainDeadTorrents> for DeadTorrents {
+    fn from(dead_torrents: DomainDeadTorrents) -> Self {
+        Self {
+            enabled: dead_torrents.enabled,
+            check_interval_seconds: dead_torrents.check_interval_seconds,
+            dead_after_days: dead_torrents.dead_after_days,
+            hide_dead_torrents: dead_torrents.hide_dead_torrents,
+            notify_uploader: dead_torrents.notify_uploader,
+            grace_period_days: dead_torrents.grace_period_days,
+            prune_action: format!("{:?}", dead_torrents.prune_action),
+            dry_run: dead_torrents.dry_run,
+        }
+    }
+}
+
+impl From<DomainNotifications> for Notifications {
+    fn from(notifications: DomainNotifications) -> Self {
+        Self {
+            digest_interval_seconds: notifications.digest_interval_seconds,
+            retention_days: notifications.retention_days,
         }
     }
 }
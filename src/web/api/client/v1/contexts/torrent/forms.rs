@@ -9,6 +9,7 @@ pub struct UpdateTorrentForm {
     pub description: Option<String>,
     pub category: Option<i64>,
     pub tags: Option<Vec<i64>>,
+    pub web_seeds: Option<Vec<String>>,
 }
 
 use reqwest::multipart::Form;
@@ -66,6 +66,8 @@ pub struct TorrentDetails {
     pub leechers: u64,
     pub files: Vec<File>,
     pub trackers: Vec<String>,
+    pub web_seeds: Vec<String>,
+    pub http_seeds: Vec<String>,
     pub magnet_link: String,
     pub tags: Vec<Tag>,
     pub name: String,
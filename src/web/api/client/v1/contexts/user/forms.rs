@@ -6,6 +6,7 @@ pub struct RegistrationForm {
     pub email: Option<String>,
     pub password: String,
     pub confirm_password: String,
+    pub locale: Option<String>,
 }
 
 pub type RegisteredUser = RegistrationForm;
@@ -0,0 +1,35 @@
+use axum::response::{IntoResponse, Response};
+use derive_more::{Display, Error};
+use hyper::StatusCode;
+
+use crate::web::api::server::v1::responses::{json_error_response, ErrorResponseData};
+
+#[derive(Debug, Display, PartialEq, Eq, Error)]
+pub enum Request {
+    #[display(fmt = "upload image request should contain an `image` field.")]
+    MissingImage,
+
+    #[display(fmt = "Uploaded image is too big.")]
+    ImageTooBig,
+
+    #[display(fmt = "cannot read a chunk of bytes from the uploaded image. Review the request body size limit.")]
+    CannotReadChunkFromUploadedBinary,
+}
+
+impl IntoResponse for Request {
+    fn into_response(self) -> Response {
+        json_error_response(
+            http_status_code_for_handler_error(&self),
+            &ErrorResponseData { error: self.to_string() },
+        )
+    }
+}
+
+#[must_use]
+pub fn http_status_code_for_handler_error(error: &Request) -> StatusCode {
+    match error {
+        Request::MissingImage | Request::ImageTooBig => StatusCode::BAD_REQUEST,
+        // Internal errors processing the request
+        Request::CannotReadChunkFromUploadedBinary => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
@@ -0,0 +1,132 @@
+//! API handlers for the the [`image`](crate::web::api::server::v1::contexts::image) API
+//! context.
+use std::sync::Arc;
+
+use axum::extract::{Multipart, Path, State};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use bytes::{Bytes, BytesMut};
+use hyper::{header, HeaderMap};
+
+use super::errors;
+use super::responses::{etag, image_response, not_modified_response};
+use crate::common::AppData;
+use crate::models::image::ImageId;
+use crate::models::response::ImageResponse;
+use crate::web::api::server::v1::extractors::user_id::ExtractLoggedInUser;
+use crate::web::api::server::v1::responses::OkResponseData;
+
+/// Upload a new image.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The request does not contain an image.
+/// - The image is not valid, too big or the user upload quota is exceeded.
+#[allow(clippy::unused_async)]
+pub async fn upload_image_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    multipart: Multipart,
+) -> Response {
+    let max_image_size = app_data.cfg.settings.read().await.image_uploads.max_image_size;
+
+    let bytes = match image_from_payload(multipart, max_image_size).await {
+        Ok(bytes) => bytes,
+        Err(error) => return error.into_response(),
+    };
+
+    match app_data.image_service.upload_image(bytes, &user_id).await {
+        Ok(image) => Json(OkResponseData {
+            data: ImageResponse::from(image),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Returns an uploaded image.
+///
+/// It returns `304 Not Modified` if the `If-None-Match` request header
+/// matches the image `ETag`.
+///
+/// # Errors
+///
+/// This function will return an error if the image does not exist.
+#[allow(clippy::unused_async)]
+pub async fn get_image_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(image_id): Path<ImageId>,
+    headers: HeaderMap,
+) -> Response {
+    match app_data.image_service.get_image(&image_id).await {
+        Ok((image, bytes)) => {
+            let if_none_match = headers.get(header::IF_NONE_MATCH).and_then(|value| value.to_str().ok());
+
+            if if_none_match == Some(etag(&image).as_str()) {
+                not_modified_response(&image)
+            } else {
+                image_response(&image, bytes)
+            }
+        }
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Delete an uploaded image. Only the user who uploaded it and administrators
+/// can delete it.
+///
+/// # Errors
+///
+/// This function will return an error if the image does not exist or the
+/// user is not allowed to delete it.
+#[allow(clippy::unused_async)]
+pub async fn delete_image_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    Path(image_id): Path<ImageId>,
+) -> Response {
+    match app_data.image_service.delete_image(&image_id, &user_id).await {
+        Ok(()) => Json(OkResponseData { data: image_id }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Extracts the image bytes from the `image` field of the multipart form
+/// payload. It stops reading as soon as the image is bigger than
+/// `max_image_size` bytes.
+///
+/// # Errors
+///
+/// It will return an error if the payload does not contain an `image` field,
+/// it can't be read or the image is too big.
+async fn image_from_payload(mut payload: Multipart, max_image_size: usize) -> Result<Bytes, errors::Request> {
+    while let Some(mut field) = payload
+        .next_field()
+        .await
+        .map_err(|_| errors::Request::CannotReadChunkFromUploadedBinary)?
+    {
+        if field.name() != Some("image") {
+            continue;
+        }
+
+        let mut bytes = BytesMut::new();
+
+        while let Some(chunk) = field
+            .chunk()
+            .await
+            .map_err(|_| errors::Request::CannotReadChunkFromUploadedBinary)?
+        {
+            if bytes.len() + chunk.len() > max_image_size {
+                return Err(errors::Request::ImageTooBig);
+            }
+
+            bytes.extend_from_slice(&chunk);
+        }
+
+        return Ok(bytes.freeze());
+    }
+
+    Err(errors::Request::MissingImage)
+}
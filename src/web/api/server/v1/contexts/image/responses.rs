@@ -0,0 +1,45 @@
+use axum::response::{IntoResponse, Response};
+use bytes::Bytes;
+use hyper::{header, StatusCode};
+
+use crate::models::image::Image;
+
+/// Uploaded images never change, so clients can cache them forever.
+const CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// It returns the uploaded image with caching headers. The `ETag` is the
+/// image content hash. Browsers must not sniff the content type, so an
+/// uploaded file is never handled as anything but the image it was stored as.
+#[must_use]
+pub fn image_response(image: &Image, bytes: Bytes) -> Response {
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, image.content_type.clone()),
+            (header::CACHE_CONTROL, CACHE_CONTROL.to_string()),
+            (header::ETAG, etag(image)),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        bytes,
+    )
+        .into_response()
+}
+
+/// It returns an empty response telling the client its cached copy of the
+/// image is still valid.
+#[must_use]
+pub fn not_modified_response(image: &Image) -> Response {
+    (
+        StatusCode::NOT_MODIFIED,
+        [
+            (header::CACHE_CONTROL, CACHE_CONTROL.to_string()),
+            (header::ETAG, etag(image)),
+        ],
+    )
+        .into_response()
+}
+
+#[must_use]
+pub fn etag(image: &Image) -> String {
+    format!("\"{}\"", image.content_hash)
+}
@@ -0,0 +1,18 @@
+//! API routes for the [`image`](crate::web::api::server::v1::contexts::image) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::image).
+use std::sync::Arc;
+
+use axum::routing::{delete, get, post};
+use axum::Router;
+
+use super::handlers::{delete_image_handler, get_image_handler, upload_image_handler};
+use crate::common::AppData;
+
+/// Routes for the [`image`](crate::web::api::server::v1::contexts::image) API context.
+pub fn router(app_data: Arc<AppData>) -> Router {
+    Router::new()
+        .route("/", post(upload_image_handler).with_state(app_data.clone()))
+        .route("/:image_id", get(get_image_handler).with_state(app_data.clone()))
+        .route("/:image_id", delete(delete_image_handler).with_state(app_data))
+}
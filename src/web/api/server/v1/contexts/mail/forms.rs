@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::queued_mail::Status;
+
+/// Query parameters to filter the list of queued emails.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MailQueueQuery {
+    pub status: Option<Status>,
+}
@@ -0,0 +1,67 @@
+//! API handlers for the [`mail`](crate::web::api::server::v1::contexts::mail) API
+//! context.
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Json, Response};
+
+use super::forms::MailQueueQuery;
+use crate::common::AppData;
+use crate::models::queued_mail::MailId;
+use crate::web::api::server::v1::extractors::user_id::ExtractLoggedInUser;
+use crate::web::api::server::v1::responses;
+
+/// It returns the emails waiting to be delivered and the ones that were sent
+/// or failed.
+///
+/// # Errors
+///
+/// It returns an error if the user does not have permission to list the
+/// emails or there is a database error.
+#[allow(clippy::unused_async)]
+pub async fn get_mails_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    Query(query): Query<MailQueueQuery>,
+) -> Response {
+    match app_data.mail_admin_service.get_mails(query.status, &user_id).await {
+        Ok(mails) => Json(responses::OkResponseData { data: mails }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It schedules a queued email to be delivered again immediately.
+///
+/// # Errors
+///
+/// It returns an error if the user does not have permission to resend the
+/// email, the email does not exist or there is a database error.
+#[allow(clippy::unused_async)]
+pub async fn resend_mail_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    Path(mail_id): Path<MailId>,
+) -> Response {
+    match app_data.mail_admin_service.resend(mail_id, &user_id).await {
+        Ok(()) => Json(responses::OkResponseData { data: mail_id }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It deletes a queued email.
+///
+/// # Errors
+///
+/// It returns an error if the user does not have permission to delete the
+/// email, the email does not exist or there is a database error.
+#[allow(clippy::unused_async)]
+pub async fn delete_mail_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    Path(mail_id): Path<MailId>,
+) -> Response {
+    match app_data.mail_admin_service.delete(mail_id, &user_id).await {
+        Ok(()) => Json(responses::OkResponseData { data: mail_id }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
@@ -0,0 +1,18 @@
+//! API routes for the [`mail`](crate::web::api::server::v1::contexts::mail) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::mail).
+use std::sync::Arc;
+
+use axum::routing::{delete, get, post};
+use axum::Router;
+
+use super::handlers::{delete_mail_handler, get_mails_handler, resend_mail_handler};
+use crate::common::AppData;
+
+/// Routes for the [`mail`](crate::web::api::server::v1::contexts::mail) API context.
+pub fn router(app_data: Arc<AppData>) -> Router {
+    Router::new()
+        .route("/queue", get(get_mails_handler).with_state(app_data.clone()))
+        .route("/queue/:mail_id", delete(delete_mail_handler).with_state(app_data.clone()))
+        .route("/queue/:mail_id/resend", post(resend_mail_handler).with_state(app_data))
+}
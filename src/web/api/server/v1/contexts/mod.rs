@@ -4,16 +4,24 @@
 //! ---|---|---
 //! `About` | Metadata about the API | [`v1`](crate::web::api::server::v1::contexts::about)
 //! `Category` | Torrent categories | [`v1`](crate::web::api::server::v1::contexts::category)
+//! `Image` | Uploaded images | [`v1`](crate::web::api::server::v1::contexts::image)
+//! `Mail` | Outbound mail queue | [`v1`](crate::web::api::server::v1::contexts::mail)
+//! `Notification` | User notifications | [`v1`](crate::web::api::server::v1::contexts::notification)
 //! `Proxy` | Image proxy cache | [`v1`](crate::web::api::server::v1::contexts::proxy)
 //! `Settings` | Index settings | [`v1`](crate::web::api::server::v1::contexts::settings)
 //! `Tag` | Torrent tags | [`v1`](crate::web::api::server::v1::contexts::tag)
 //! `Torrent` | Indexed torrents | [`v1`](crate::web::api::server::v1::contexts::torrent)
+//! `Tracker` | Tracker administration | [`v1`](crate::web::api::server::v1::contexts::tracker)
 //! `User` | Users | [`v1`](crate::web::api::server::v1::contexts::user)
 //!
 pub mod about;
 pub mod category;
+pub mod image;
+pub mod mail;
+pub mod notification;
 pub mod proxy;
 pub mod settings;
 pub mod tag;
 pub mod torrent;
+pub mod tracker;
 pub mod user;
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::notification::NotificationPreferences;
+
+/// Query parameters to filter the list of notifications.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NotificationsQuery {
+    /// Only the notifications that were not read.
+    pub unread_only: Option<bool>,
+}
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PreferencesForm {
+    pub in_app: bool,
+    pub email_digest: bool,
+    pub immediate_email: bool,
+}
+
+impl From<PreferencesForm> for NotificationPreferences {
+    fn from(form: PreferencesForm) -> Self {
+        Self {
+            in_app: form.in_app,
+            email_digest: form.email_digest,
+            immediate_email: form.immediate_email,
+        }
+    }
+}
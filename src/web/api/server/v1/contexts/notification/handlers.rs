@@ -0,0 +1,120 @@
+//! API handlers for the [`notification`](crate::web::api::server::v1::contexts::notification)
+//! API context.
+use std::sync::Arc;
+
+use axum::extract::{self, Path, Query, State};
+use axum::response::{IntoResponse, Json, Response};
+
+use super::forms::{NotificationsQuery, PreferencesForm};
+use crate::common::AppData;
+use crate::models::notification::NotificationId;
+use crate::web::api::server::v1::extractors::user_id::ExtractLoggedInUser;
+use crate::web::api::server::v1::responses;
+
+/// It returns the latest notifications of the logged-in user, newest first.
+///
+/// # Errors
+///
+/// It returns an error if there is a database error.
+#[allow(clippy::unused_async)]
+pub async fn get_notifications_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    Query(query): Query<NotificationsQuery>,
+) -> Response {
+    match app_data
+        .notification_service
+        .get_notifications(query.unread_only.unwrap_or_default(), &user_id)
+        .await
+    {
+        Ok(notifications) => Json(responses::OkResponseData { data: notifications }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the number of unread notifications of the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if there is a database error.
+#[allow(clippy::unused_async)]
+pub async fn get_unread_count_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+) -> Response {
+    match app_data.notification_service.count_unread(&user_id).await {
+        Ok(unread) => Json(responses::OkResponseData { data: unread }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It marks a notification of the logged-in user as read.
+///
+/// # Errors
+///
+/// It returns an error if the notification does not exist or there is a
+/// database error.
+#[allow(clippy::unused_async)]
+pub async fn mark_as_read_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    Path(notification_id): Path<NotificationId>,
+) -> Response {
+    match app_data.notification_service.mark_as_read(notification_id, &user_id).await {
+        Ok(()) => Json(responses::OkResponseData { data: notification_id }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It marks all the notifications of the logged-in user as read.
+///
+/// # Errors
+///
+/// It returns an error if there is a database error.
+#[allow(clippy::unused_async)]
+pub async fn mark_all_as_read_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+) -> Response {
+    match app_data.notification_service.mark_all_as_read(&user_id).await {
+        Ok(marked) => Json(responses::OkResponseData { data: marked }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the notification preferences of the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if there is a database error.
+#[allow(clippy::unused_async)]
+pub async fn get_preferences_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+) -> Response {
+    match app_data.notification_service.get_preferences(&user_id).await {
+        Ok(preferences) => Json(responses::OkResponseData { data: preferences }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It updates the notification preferences of the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if there is a database error.
+#[allow(clippy::unused_async)]
+pub async fn update_preferences_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    extract::Json(preferences_form): extract::Json<PreferencesForm>,
+) -> Response {
+    match app_data
+        .notification_service
+        .update_preferences(&preferences_form.into(), &user_id)
+        .await
+    {
+        Ok(preferences) => Json(responses::OkResponseData { data: preferences }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
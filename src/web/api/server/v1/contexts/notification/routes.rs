@@ -0,0 +1,27 @@
+//! API routes for the [`notification`](crate::web::api::server::v1::contexts::notification) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::notification).
+use std::sync::Arc;
+
+use axum::routing::{get, post, put};
+use axum::Router;
+
+use super::handlers::{
+    get_notifications_handler, get_preferences_handler, get_unread_count_handler, mark_all_as_read_handler, mark_as_read_handler,
+    update_preferences_handler,
+};
+use crate::common::AppData;
+
+/// Routes for the [`notification`](crate::web::api::server::v1::contexts::notification) API context.
+pub fn router(app_data: Arc<AppData>) -> Router {
+    Router::new()
+        .route("/", get(get_notifications_handler).with_state(app_data.clone()))
+        .route("/unread-count", get(get_unread_count_handler).with_state(app_data.clone()))
+        .route("/read-all", post(mark_all_as_read_handler).with_state(app_data.clone()))
+        .route(
+            "/:notification_id/read",
+            post(mark_as_read_handler).with_state(app_data.clone()),
+        )
+        .route("/preferences", get(get_preferences_handler).with_state(app_data.clone()))
+        .route("/preferences", put(update_preferences_handler).with_state(app_data))
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+use crate::cache::image::variant::ImageVariant;
+use crate::config::ImageVariantFormat;
+
+/// Query parameters to request a resized variant of the image.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ImageVariantQuery {
+    /// Maximum width in pixels.
+    pub width: Option<u32>,
+    /// Maximum height in pixels.
+    pub height: Option<u32>,
+    /// Format of the resized image.
+    pub format: Option<ImageVariantFormat>,
+}
+
+impl From<ImageVariantQuery> for ImageVariant {
+    fn from(query: ImageVariantQuery) -> Self {
+        Self {
+            width: query.width,
+            height: query.height,
+            format: query.format,
+        }
+    }
+}
+
+/// Query parameters to purge the image cache.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct PurgeCacheQuery {
+    /// URL of the image to purge.
+    pub url: Option<String>,
+    /// Purge all the cached images. It can't be combined with `url`.
+    #[serde(default)]
+    pub all: bool,
+}
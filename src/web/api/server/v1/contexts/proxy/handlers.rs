@@ -2,44 +2,59 @@
 //! context.
 use std::sync::Arc;
 
-use axum::extract::{Path, State};
-use axum::response::Response;
+use axum::extract::rejection::QueryRejection;
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
 
-use super::responses::png_image;
+use super::forms::{ImageVariantQuery, PurgeCacheQuery};
+use super::responses::{image, png_image};
 use crate::cache::image::manager::Error;
 use crate::common::AppData;
 use crate::ui::proxy::map_error_to_image;
 use crate::web::api::server::v1::extractors::bearer_token::Extract;
+use crate::web::api::server::v1::extractors::user_id::ExtractLoggedInUser;
+use crate::web::api::server::v1::responses::OkResponseData;
 
 /// Get the remote image. It uses the cached image if available.
+///
+/// Guests can only get images that are already cached. Like the other errors,
+/// invalid query parameters are returned as an error image.
 #[allow(clippy::unused_async)]
 pub async fn get_proxy_image_handler(
     State(app_data): State<Arc<AppData>>,
     Extract(maybe_bearer_token): Extract,
     Path(url): Path<String>,
+    variant: Result<Query<ImageVariantQuery>, QueryRejection>,
 ) -> Response {
-    if maybe_bearer_token.is_none() {
-        return png_image(map_error_to_image(&Error::Unauthenticated));
-    }
+    let Ok(Query(variant)) = variant else {
+        return png_image(map_error_to_image(&Error::InvalidVariant));
+    };
 
-    let Ok(user_id) = app_data.auth.get_user_id_from_bearer_token(&maybe_bearer_token).await else {
-        return png_image(map_error_to_image(&Error::Unauthenticated));
+    let maybe_user_id = match maybe_bearer_token {
+        None => None,
+        Some(_) => {
+            let Ok(user_id) = app_data.auth.get_user_id_from_bearer_token(&maybe_bearer_token).await else {
+                return png_image(map_error_to_image(&Error::Unauthenticated));
+            };
+            Some(user_id)
+        }
     };
 
     // code-review: Handling status codes in the index-gui other tan OK is quite a pain.
     // Return OK for now.
 
-    // todo: it also work for other image types but we are always returning the
-    // same content type: `image/png`. If we only support PNG images we should
-    // change the documentation and return an error for other image types.
-
     // Get image URL from URL path parameter.
     let image_url = urlencoding::decode(&url).unwrap_or_default().into_owned();
 
-    match app_data.proxy_service.get_image_by_url(&image_url, &user_id).await {
+    match app_data
+        .proxy_service
+        .get_image_by_url(&image_url, &variant.into(), maybe_user_id)
+        .await
+    {
         Ok(image_bytes) => {
             // Returns the cached image.
-            png_image(image_bytes)
+            image(image_bytes)
         }
         Err(e) => {
             // Returns an error image.
@@ -47,3 +62,40 @@ pub async fn get_proxy_image_handler(
         }
     }
 }
+
+/// Get the image cache statistics. Only administrators can see them.
+///
+/// # Errors
+///
+/// It returns an error if the user is not an administrator.
+#[allow(clippy::unused_async)]
+pub async fn get_cache_statistics_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+) -> Response {
+    match app_data.proxy_service.get_statistics(&user_id).await {
+        Ok(statistics) => Json(OkResponseData { data: statistics }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Purge an image from the cache with the `url` query parameter, or the whole
+/// cache with `all=true`. Only administrators can purge the cache.
+///
+/// It returns the number of removed cache entries.
+///
+/// # Errors
+///
+/// It returns an error if the user is not an administrator or the query
+/// parameters don't tell what to purge.
+#[allow(clippy::unused_async)]
+pub async fn purge_cache_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    Query(query): Query<PurgeCacheQuery>,
+) -> Response {
+    match app_data.proxy_service.purge(query.url.as_deref(), query.all, &user_id).await {
+        Ok(removed_entries) => Json(OkResponseData { data: removed_entries }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
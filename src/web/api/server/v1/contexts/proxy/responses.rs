@@ -2,7 +2,18 @@ use axum::response::{IntoResponse, Response};
 use bytes::Bytes;
 use hyper::{header, StatusCode};
 
+use crate::cache::image::format::ImageFormat;
+
 #[must_use]
 pub fn png_image(bytes: Bytes) -> Response {
     (StatusCode::OK, [(header::CONTENT_TYPE, "image/png")], bytes).into_response()
 }
+
+/// It returns the image with the content type of its format. Images in an
+/// unknown format are returned as PNG images.
+#[must_use]
+pub fn image(bytes: Bytes) -> Response {
+    let content_type = ImageFormat::detect(&bytes).map_or("image/png", |format| format.mime_type());
+
+    (StatusCode::OK, [(header::CONTENT_TYPE, content_type)], bytes).into_response()
+}
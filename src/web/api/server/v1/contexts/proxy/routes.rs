@@ -3,13 +3,16 @@
 //! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::proxy).
 use std::sync::Arc;
 
-use axum::routing::get;
+use axum::routing::{delete, get};
 use axum::Router;
 
-use super::handlers::get_proxy_image_handler;
+use super::handlers::{get_cache_statistics_handler, get_proxy_image_handler, purge_cache_handler};
 use crate::common::AppData;
 
 /// Routes for the [`about`](crate::web::api::server::v1::contexts::about) API context.
 pub fn router(app_data: Arc<AppData>) -> Router {
-    Router::new().route("/image/:url", get(get_proxy_image_handler).with_state(app_data))
+    Router::new()
+        .route("/image/:url", get(get_proxy_image_handler).with_state(app_data.clone()))
+        .route("/cache", get(get_cache_statistics_handler).with_state(app_data.clone()))
+        .route("/cache", delete(purge_cache_handler).with_state(app_data))
 }
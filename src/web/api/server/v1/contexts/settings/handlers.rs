@@ -2,10 +2,11 @@
 //! context.
 use std::sync::Arc;
 
-use axum::extract::State;
+use axum::extract::{self, State};
 use axum::response::{IntoResponse, Json, Response};
 
 use crate::common::AppData;
+use crate::config::TorrustIndex;
 use crate::web::api::server::v1::extractors::user_id::ExtractLoggedInUser;
 use crate::web::api::server::v1::responses;
 
@@ -28,6 +29,26 @@ pub async fn get_all_handler(
     Json(responses::OkResponseData { data: all_settings }).into_response()
 }
 
+/// Update all settings.
+///
+/// # Errors
+///
+/// This function will return an error if the user does not have permission to
+/// update the settings, the new settings are not valid or they can't be saved.
+#[allow(clippy::unused_async)]
+pub async fn update_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    extract::Json(torrust_index): extract::Json<TorrustIndex>,
+) -> Response {
+    let new_settings = match app_data.settings_service.update_all(torrust_index, &user_id).await {
+        Ok(new_settings) => new_settings,
+        Err(error) => return error.into_response(),
+    };
+
+    Json(responses::OkResponseData { data: new_settings }).into_response()
+}
+
 /// Get public Settings.
 #[allow(clippy::unused_async)]
 pub async fn get_public_handler(State(app_data): State<Arc<AppData>>) -> Response {
@@ -1,6 +1,7 @@
 use serde::Deserialize;
 
 use crate::models::category::CategoryId;
+use crate::models::image::ImageId;
 use crate::models::torrent_tag::TagId;
 
 #[derive(Debug, Deserialize)]
@@ -9,4 +10,18 @@ pub struct UpdateTorrentInfoForm {
     pub description: Option<String>,
     pub category: Option<CategoryId>,
     pub tags: Option<Vec<TagId>>,
+    /// Web seed URLs (`url-list`). They replace the current ones.
+    pub web_seeds: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateTorrentImagesForm {
+    pub poster: Option<ImageId>,
+    #[serde(default)]
+    pub screenshots: Vec<ImageId>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApplyDeadTorrentPolicyForm {
+    pub dry_run: bool,
 }
@@ -7,12 +7,12 @@ use std::sync::Arc;
 use axum::extract::{self, Multipart, Path, Query, State};
 use axum::response::{IntoResponse, Redirect, Response};
 use axum::Json;
-use log::debug;
+use log::{debug, error};
 use serde::Deserialize;
 use uuid::Uuid;
 
 use super::errors;
-use super::forms::UpdateTorrentInfoForm;
+use super::forms::{ApplyDeadTorrentPolicyForm, UpdateTorrentImagesForm, UpdateTorrentInfoForm};
 use super::responses::{new_torrent_response, torrent_file_response};
 use crate::common::AppData;
 use crate::errors::ServiceError;
@@ -90,6 +90,14 @@ pub async fn download_torrent_handler(
             return ServiceError::InternalServerError.into_response();
         };
 
+        // The download counters must not prevent the download.
+        if let Err(error) = app_data.torrent_service.record_download(&info_hash, opt_user_id).await {
+            error!(
+                "Failed to record the download of torrent {}: {error}",
+                info_hash.to_hex_string()
+            );
+        }
+
         torrent_file_response(
             bytes,
             &format!("{}.torrent", torrent.info.name),
@@ -139,6 +147,25 @@ pub async fn get_torrents_handler(State(app_data): State<Arc<AppData>>, Query(cr
     }
 }
 
+/// It applies the dead torrent policy, or only reports the changes with
+/// `dry_run`.
+///
+/// # Errors
+///
+/// It returns an error if the user does not have permission to apply the
+/// policy or there is a database error.
+#[allow(clippy::unused_async)]
+pub async fn apply_dead_torrent_policy_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    extract::Json(form): extract::Json<ApplyDeadTorrentPolicyForm>,
+) -> Response {
+    match app_data.dead_torrent_service.apply_policy(form.dry_run, &user_id).await {
+        Ok(report) => Json(OkResponseData { data: report }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
 /// Get Torrent from the Index
 ///
 /// # Errors
@@ -167,6 +194,30 @@ pub async fn get_torrent_info_handler(
     }
 }
 
+/// Get the statistics history of a torrent.
+///
+/// # Errors
+///
+/// This function returns an error if:
+///
+/// - The info-hash is not valid.
+/// - The torrent does not exist.
+/// - There was a problem getting the statistics history from the database.
+#[allow(clippy::unused_async)]
+pub async fn get_torrent_stats_history_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(info_hash): Path<InfoHashParam>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data.torrent_service.get_torrent_stats_history(&info_hash).await {
+        Ok(stats_history) => Json(OkResponseData { data: stats_history }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
 async fn redirect_to_details_url_using_canonical_info_hash_if_needed(
     app_data: &Arc<AppData>,
     info_hash: &InfoHash,
@@ -221,6 +272,7 @@ pub async fn update_torrent_info_handler(
             &update_torrent_info_form.description,
             &update_torrent_info_form.category,
             &update_torrent_info_form.tags,
+            &update_torrent_info_form.web_seeds,
             &user_id,
         )
         .await
@@ -230,6 +282,41 @@ pub async fn update_torrent_info_handler(
     }
 }
 
+/// Update the poster and the screenshots of a torrent.
+///
+/// # Errors
+///
+/// This function will return an error if unable to:
+///
+/// * Get the user id from the request.
+/// * Get the torrent info-hash from the request.
+/// * Update the torrent images.
+#[allow(clippy::unused_async)]
+pub async fn update_torrent_images_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+    extract::Json(update_torrent_images_form): extract::Json<UpdateTorrentImagesForm>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .image_service
+        .set_torrent_images(
+            &info_hash,
+            update_torrent_images_form.poster,
+            &update_torrent_images_form.screenshots,
+            &user_id,
+        )
+        .await
+    {
+        Ok(torrent_images) => Json(OkResponseData { data: torrent_images }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
 /// Delete a torrent.
 ///
 /// # Errors
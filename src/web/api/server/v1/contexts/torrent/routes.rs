@@ -7,8 +7,9 @@ use axum::routing::{delete, get, post, put};
 use axum::Router;
 
 use super::handlers::{
-    create_random_torrent_handler, delete_torrent_handler, download_torrent_handler, get_torrent_info_handler,
-    get_torrents_handler, update_torrent_info_handler, upload_torrent_handler,
+    apply_dead_torrent_policy_handler, create_random_torrent_handler, delete_torrent_handler, download_torrent_handler,
+    get_torrent_info_handler, get_torrent_stats_history_handler, get_torrents_handler, update_torrent_images_handler,
+    update_torrent_info_handler, upload_torrent_handler,
 };
 use crate::common::AppData;
 
@@ -17,7 +18,12 @@ pub fn router_for_single_resources(app_data: Arc<AppData>) -> Router {
     let torrent_info_routes = Router::new()
         .route("/", get(get_torrent_info_handler).with_state(app_data.clone()))
         .route("/", put(update_torrent_info_handler).with_state(app_data.clone()))
-        .route("/", delete(delete_torrent_handler).with_state(app_data.clone()));
+        .route("/", delete(delete_torrent_handler).with_state(app_data.clone()))
+        .route("/images", put(update_torrent_images_handler).with_state(app_data.clone()))
+        .route(
+            "/stats/history",
+            get(get_torrent_stats_history_handler).with_state(app_data.clone()),
+        );
 
     Router::new()
         .route("/upload", post(upload_torrent_handler).with_state(app_data.clone()))
@@ -34,5 +40,10 @@ pub fn router_for_single_resources(app_data: Arc<AppData>) -> Router {
 
 /// Routes for the [`torrent`](crate::web::api::server::v1::contexts::torrent) API context for multiple resources.
 pub fn router_for_multiple_resources(app_data: Arc<AppData>) -> Router {
-    Router::new().route("/", get(get_torrents_handler).with_state(app_data))
+    Router::new()
+        .route("/", get(get_torrents_handler).with_state(app_data.clone()))
+        .route(
+            "/dead/apply-policy",
+            post(apply_dead_torrent_policy_handler).with_state(app_data),
+        )
 }
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::tracker_job::Status;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReconcileWhitelistForm {
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StartModeSyncForm {
+    /// Start a sync even if the tracker mode did not change.
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Query parameters to filter the list of tracker jobs.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JobsQuery {
+    pub status: Option<Status>,
+}
@@ -0,0 +1,110 @@
+//! API handlers for the [`tracker`](crate::web::api::server::v1::contexts::tracker) API
+//! context.
+use std::sync::Arc;
+
+use axum::extract::{self, Path, Query, State};
+use axum::response::{IntoResponse, Json, Response};
+
+use super::forms::{JobsQuery, ReconcileWhitelistForm, StartModeSyncForm};
+use crate::common::AppData;
+use crate::models::tracker_job::TrackerJobId;
+use crate::web::api::server::v1::extractors::user_id::ExtractLoggedInUser;
+use crate::web::api::server::v1::responses;
+
+/// It reconciles the tracker whitelist with the torrents in the index.
+///
+/// # Errors
+///
+/// It returns an error if the user does not have permission to reconcile the
+/// whitelist, or the tracker or the database are not available.
+#[allow(clippy::unused_async)]
+pub async fn reconcile_whitelist_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    extract::Json(reconcile_whitelist_form): extract::Json<ReconcileWhitelistForm>,
+) -> Response {
+    match app_data
+        .tracker_admin_service
+        .reconcile_whitelist(reconcile_whitelist_form.dry_run, &user_id)
+        .await
+    {
+        Ok(report) => Json(responses::OkResponseData { data: report }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the jobs pending to be executed on the tracker and the ones that
+/// failed.
+///
+/// # Errors
+///
+/// It returns an error if the user does not have permission to list the jobs
+/// or there is a database error.
+#[allow(clippy::unused_async)]
+pub async fn get_jobs_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    Query(query): Query<JobsQuery>,
+) -> Response {
+    match app_data.tracker_admin_service.get_jobs(query.status, &user_id).await {
+        Ok(jobs) => Json(responses::OkResponseData { data: jobs }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It schedules a tracker job to be executed again immediately.
+///
+/// # Errors
+///
+/// It returns an error if the user does not have permission to retry the job,
+/// the job does not exist or there is a database error.
+#[allow(clippy::unused_async)]
+pub async fn retry_job_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    Path(job_id): Path<TrackerJobId>,
+) -> Response {
+    match app_data.tracker_admin_service.retry_job(job_id, &user_id).await {
+        Ok(()) => Json(responses::OkResponseData { data: job_id }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It starts synchronizing the tracker with the configured tracker mode.
+///
+/// # Errors
+///
+/// It returns an error if the user does not have permission to start the sync,
+/// the tracker mode did not change or there is a database error.
+#[allow(clippy::unused_async)]
+pub async fn start_mode_sync_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    extract::Json(start_mode_sync_form): extract::Json<StartModeSyncForm>,
+) -> Response {
+    match app_data
+        .tracker_admin_service
+        .start_mode_sync(start_mode_sync_form.force, &user_id)
+        .await
+    {
+        Ok(sync) => Json(responses::OkResponseData { data: sync }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the progress of the last tracker mode sync.
+///
+/// # Errors
+///
+/// It returns an error if the user does not have permission to get the sync
+/// or there is a database error.
+#[allow(clippy::unused_async)]
+pub async fn get_mode_sync_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+) -> Response {
+    match app_data.tracker_admin_service.get_mode_sync(&user_id).await {
+        Ok(sync) => Json(responses::OkResponseData { data: sync }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
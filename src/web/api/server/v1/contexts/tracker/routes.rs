@@ -0,0 +1,27 @@
+//! API routes for the [`tracker`](crate::web::api::server::v1::contexts::tracker) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::tracker).
+use std::sync::Arc;
+
+use axum::routing::{get, post};
+use axum::Router;
+
+use super::handlers::{
+    get_jobs_handler, get_mode_sync_handler, reconcile_whitelist_handler, retry_job_handler, start_mode_sync_handler,
+};
+use crate::common::AppData;
+
+/// Routes for the [`tracker`](crate::web::api::server::v1::contexts::tracker) API context.
+pub fn router(app_data: Arc<AppData>) -> Router {
+    Router::new()
+        .route(
+            "/whitelist/reconcile",
+            post(reconcile_whitelist_handler).with_state(app_data.clone()),
+        )
+        .route("/jobs", get(get_jobs_handler).with_state(app_data.clone()))
+        .route("/jobs/:job_id/retry", post(retry_job_handler).with_state(app_data.clone()))
+        .route(
+            "/mode/sync",
+            get(get_mode_sync_handler).post(start_mode_sync_handler).with_state(app_data),
+        )
+}
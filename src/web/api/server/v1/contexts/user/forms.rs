@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::models::image::ImageId;
+
 // Registration
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -8,6 +10,9 @@ pub struct RegistrationForm {
     pub email: Option<String>,
     pub password: String,
     pub confirm_password: String,
+    /// The preferred locale, for example `en` or `pt-BR`.
+    #[serde(default)]
+    pub locale: Option<String>,
 }
 
 // Authentication
@@ -22,3 +27,20 @@ pub struct LoginForm {
 pub struct JsonWebToken {
     pub token: String, // // todo: rename to `encoded` or `value`
 }
+
+// Avatar
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AvatarForm {
+    /// The uploaded image to use as avatar, or `None` to remove the avatar.
+    pub image_id: Option<ImageId>,
+}
+
+// Locale
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LocaleForm {
+    /// The preferred locale, for example `en` or `pt-BR`, or `None` to remove
+    /// it.
+    pub locale: Option<String>,
+}
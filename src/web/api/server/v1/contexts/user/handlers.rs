@@ -7,7 +7,7 @@ use axum::response::{IntoResponse, Response};
 use axum::Json;
 use serde::Deserialize;
 
-use super::forms::{JsonWebToken, LoginForm, RegistrationForm};
+use super::forms::{AvatarForm, JsonWebToken, LocaleForm, LoginForm, RegistrationForm};
 use super::responses::{self};
 use crate::common::AppData;
 use crate::web::api::server::v1::extractors::user_id::ExtractLoggedInUser;
@@ -49,11 +49,54 @@ pub struct TokenParam(String);
 #[allow(clippy::unused_async)]
 pub async fn email_verification_handler(State(app_data): State<Arc<AppData>>, Path(token): Path<TokenParam>) -> String {
     match app_data.registration_service.verify_email(&token.0).await {
-        Ok(_) => String::from("Email verified, you can close this page."),
+        Ok(()) => String::from("Email verified, you can close this page."),
         Err(error) => error.to_string(),
     }
 }
 
+/// It sends the verification email again. Users with an unverified email
+/// can't log in, so the request is authenticated with the username and
+/// password instead of a token.
+///
+/// # Errors
+///
+/// It returns an error if the credentials are wrong, email verification is
+/// disabled, the user does not have an email, the email is already verified
+/// or the email could not be queued.
+#[allow(clippy::unused_async)]
+pub async fn resend_verification_email_handler(
+    State(app_data): State<Arc<AppData>>,
+    Host(host_from_header): Host,
+    extract::Json(login_form): extract::Json<LoginForm>,
+) -> Response {
+    let user_profile = match app_data
+        .authentication_service
+        .verify_credentials(&login_form.login, &login_form.password)
+        .await
+    {
+        Ok(user_profile) => user_profile,
+        Err(error) => return error.into_response(),
+    };
+
+    let api_base_url = app_data
+        .cfg
+        .get_api_base_url()
+        .await
+        .unwrap_or(api_base_url(&host_from_header));
+
+    match app_data
+        .registration_service
+        .resend_verification_email(&user_profile, &api_base_url)
+        .await
+    {
+        Ok(()) => Json(OkResponseData {
+            data: "Verification email queued".to_string(),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
 // Authentication
 
 /// It handles the user login.
@@ -148,6 +191,137 @@ pub async fn ban_handler(
     }
 }
 
+/// It deletes a user account from the index.
+///
+/// # Errors
+///
+/// This function will return if:
+///
+/// - The user is not an administrator.
+/// - The user could not be deleted: it does not exist, etcetera.
+#[allow(clippy::unused_async)]
+pub async fn delete_user_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(to_be_deleted_username): Path<UsernameParam>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+) -> Response {
+    match app_data
+        .deletion_service
+        .delete_user(&to_be_deleted_username.0, &user_id)
+        .await
+    {
+        Ok(()) => Json(OkResponseData {
+            data: format!("Deleted user: {}", to_be_deleted_username.0),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+// Tracker keys
+
+/// It returns the tracker key of the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if the user does not have a valid tracker key.
+#[allow(clippy::unused_async)]
+pub async fn get_tracker_key_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+) -> Response {
+    match app_data.tracker_key_service.get_tracker_key(&user_id).await {
+        Ok(tracker_key) => Json(OkResponseData { data: tracker_key }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It revokes the tracker keys of the logged-in user and issues a new one.
+///
+/// # Errors
+///
+/// It returns an error if the old keys can't be revoked or the new one can't
+/// be issued.
+#[allow(clippy::unused_async)]
+pub async fn rotate_tracker_key_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+) -> Response {
+    match app_data.tracker_key_service.rotate_tracker_key(&user_id).await {
+        Ok(tracker_key) => Json(OkResponseData { data: tracker_key }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It revokes all the tracker keys of a user.
+///
+/// # Errors
+///
+/// This function will return if:
+///
+/// - The user is not an administrator.
+/// - The keys could not be revoked.
+#[allow(clippy::unused_async)]
+pub async fn revoke_tracker_keys_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(username): Path<UsernameParam>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+) -> Response {
+    match app_data.tracker_key_service.revoke_tracker_keys(&username.0, &user_id).await {
+        Ok(revoked_keys) => Json(OkResponseData {
+            data: format!("Revoked {revoked_keys} tracker keys of user: {}", username.0),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+// Avatar
+
+/// It sets one of the logged-in user's uploaded images as their avatar, or
+/// removes the avatar.
+///
+/// # Errors
+///
+/// This function will return if:
+///
+/// - The image does not exist.
+/// - The image was uploaded by another user.
+#[allow(clippy::unused_async)]
+pub async fn update_avatar_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    extract::Json(avatar_form): extract::Json<AvatarForm>,
+) -> Response {
+    match app_data.image_service.set_avatar(avatar_form.image_id, &user_id).await {
+        Ok(avatar) => Json(OkResponseData { data: avatar }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+// Locale
+
+/// It sets the preferred locale of the logged-in user, or removes it.
+///
+/// # Errors
+///
+/// This function will return if the locale is not a valid language tag.
+#[allow(clippy::unused_async)]
+pub async fn update_locale_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    extract::Json(locale_form): extract::Json<LocaleForm>,
+) -> Response {
+    match app_data
+        .profile_service
+        .update_locale(locale_form.locale.as_deref(), &user_id)
+        .await
+    {
+        Ok(locale) => Json(OkResponseData { data: locale }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
 /// It returns the base API URL without the port. For example: `http://localhost`.
 fn api_base_url(host: &str) -> String {
     // HTTPS is not supported yet.
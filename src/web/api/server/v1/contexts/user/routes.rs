@@ -3,11 +3,13 @@
 //! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::user).
 use std::sync::Arc;
 
-use axum::routing::{delete, get, post};
+use axum::routing::{delete, get, post, put};
 use axum::Router;
 
 use super::handlers::{
-    ban_handler, email_verification_handler, login_handler, registration_handler, renew_token_handler, verify_token_handler,
+    ban_handler, delete_user_handler, email_verification_handler, get_tracker_key_handler, login_handler, registration_handler,
+    renew_token_handler, resend_verification_email_handler, revoke_tracker_keys_handler, rotate_tracker_key_handler,
+    update_avatar_handler, update_locale_handler, verify_token_handler,
 };
 use crate::common::AppData;
 
@@ -24,11 +26,31 @@ pub fn router(app_data: Arc<AppData>) -> Router {
             "/email/verify/:token",
             get(email_verification_handler).with_state(app_data.clone()),
         )
+        .route(
+            "/email/resend-verification",
+            post(resend_verification_email_handler).with_state(app_data.clone()),
+        )
         // Authentication
         .route("/login", post(login_handler).with_state(app_data.clone()))
         .route("/token/verify", post(verify_token_handler).with_state(app_data.clone()))
         .route("/token/renew", post(renew_token_handler).with_state(app_data.clone()))
         // User ban
         // code-review: should not this be a POST method? We add the user to the blacklist. We do not delete the user.
-        .route("/ban/:user", delete(ban_handler).with_state(app_data))
+        .route("/ban/:user", delete(ban_handler).with_state(app_data.clone()))
+        // Account deletion
+        .route("/account/:user", delete(delete_user_handler).with_state(app_data.clone()))
+        // Tracker keys
+        .route("/tracker-key", get(get_tracker_key_handler).with_state(app_data.clone()))
+        .route(
+            "/tracker-key/rotate",
+            post(rotate_tracker_key_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/tracker-key/:user",
+            delete(revoke_tracker_keys_handler).with_state(app_data.clone()),
+        )
+        // Avatar
+        .route("/avatar", put(update_avatar_handler).with_state(app_data.clone()))
+        // Locale
+        .route("/locale", put(update_locale_handler).with_state(app_data))
 }
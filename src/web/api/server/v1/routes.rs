@@ -17,7 +17,7 @@ use tower_http::request_id::{MakeRequestUuid, SetRequestIdLayer};
 use tower_http::trace::{DefaultMakeSpan, TraceLayer};
 use tracing::{Level, Span};
 
-use super::contexts::{about, category, proxy, settings, tag, torrent, user};
+use super::contexts::{about, category, image, mail, notification, proxy, settings, tag, torrent, tracker, user};
 use crate::bootstrap::config::ENV_VAR_CORS_PERMISSIVE;
 use crate::common::AppData;
 
@@ -40,7 +40,11 @@ pub fn router(app_data: Arc<AppData>) -> Router {
         .nest("/settings", settings::routes::router(app_data.clone()))
         .nest("/torrent", torrent::routes::router_for_single_resources(app_data.clone()))
         .nest("/torrents", torrent::routes::router_for_multiple_resources(app_data.clone()))
-        .nest("/proxy", proxy::routes::router(app_data.clone()));
+        .nest("/proxy", proxy::routes::router(app_data.clone()))
+        .nest("/images", image::routes::router(app_data.clone()))
+        .nest("/tracker", tracker::routes::router(app_data.clone()))
+        .nest("/mail", mail::routes::router(app_data.clone()))
+        .nest("/notifications", notification::routes::router(app_data.clone()));
 
     let router = Router::new()
         .route("/", get(redirect_to_about))
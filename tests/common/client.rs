@@ -5,10 +5,15 @@ use serde::Serialize;
 
 use super::connection_info::ConnectionInfo;
 use super::contexts::category::forms::{AddCategoryForm, DeleteCategoryForm};
+use super::contexts::notification::forms::PreferencesForm;
+use super::contexts::settings::Settings;
 use super::contexts::tag::forms::{AddTagForm, DeleteTagForm};
-use super::contexts::torrent::forms::UpdateTorrentFrom;
+use super::contexts::torrent::forms::{ApplyDeadTorrentPolicyForm, UpdateTorrentFrom, UpdateTorrentImagesForm};
 use super::contexts::torrent::requests::InfoHash;
-use super::contexts::user::forms::{LoginForm, RegistrationForm, TokenRenewalForm, TokenVerificationForm, Username};
+use super::contexts::tracker::forms::{ReconcileWhitelistForm, StartModeSyncForm};
+use super::contexts::user::forms::{
+    AvatarForm, LocaleForm, LoginForm, RegistrationForm, TokenRenewalForm, TokenVerificationForm, Username,
+};
 use super::http::{Query, ReqwestQuery};
 use super::responses::{self, BinaryResponse, TextResponse};
 
@@ -84,6 +89,39 @@ impl Client {
         self.http_client.delete_with_body("/tag", &delete_tag_form).await
     }
 
+    // Context: proxy
+
+    pub async fn get_proxy_image(&self, url: &str) -> BinaryResponse {
+        self.http_client
+            .get_binary(&format!("/proxy/image/{}", urlencoding::encode(url)), Query::empty())
+            .await
+    }
+
+    pub async fn get_proxy_image_variant(&self, url: &str, params: Query) -> BinaryResponse {
+        self.http_client
+            .get_binary(&format!("/proxy/image/{}", urlencoding::encode(url)), params)
+            .await
+    }
+
+    pub async fn get_image_cache_statistics(&self) -> TextResponse {
+        self.http_client.get("/proxy/cache", Query::empty()).await
+    }
+
+    pub async fn purge_image_cache(&self, url: Option<&str>) -> TextResponse {
+        match url {
+            Some(url) => {
+                self.http_client
+                    .delete(&format!("/proxy/cache?url={}", urlencoding::encode(url)))
+                    .await
+            }
+            None => self.http_client.delete("/proxy/cache").await,
+        }
+    }
+
+    pub async fn purge_whole_image_cache(&self) -> TextResponse {
+        self.http_client.delete("/proxy/cache?all=true").await
+    }
+
     // Context: root
 
     pub async fn root(&self) -> TextResponse {
@@ -104,6 +142,10 @@ impl Client {
         self.http_client.get("/settings", Query::empty()).await
     }
 
+    pub async fn update_settings(&self, update_settings_form: &Settings) -> TextResponse {
+        self.http_client.put("/settings", &update_settings_form).await
+    }
+
     // Context: torrent
 
     pub async fn get_torrents(&self, params: Query) -> TextResponse {
@@ -114,6 +156,12 @@ impl Client {
         self.http_client.get(&format!("/torrent/{info_hash}"), Query::empty()).await
     }
 
+    pub async fn get_torrent_stats_history(&self, info_hash: &InfoHash) -> TextResponse {
+        self.http_client
+            .get(&format!("/torrent/{info_hash}/stats/history"), Query::empty())
+            .await
+    }
+
     pub async fn delete_torrent(&self, info_hash: &InfoHash) -> TextResponse {
         self.http_client.delete(&format!("/torrent/{info_hash}")).await
     }
@@ -134,6 +182,100 @@ impl Client {
             .await
     }
 
+    pub async fn update_torrent_images(
+        &self,
+        info_hash: &InfoHash,
+        update_torrent_images_form: UpdateTorrentImagesForm,
+    ) -> TextResponse {
+        self.http_client
+            .put(&format!("/torrent/{info_hash}/images"), &update_torrent_images_form)
+            .await
+    }
+
+    // Context: image
+
+    pub async fn upload_image(&self, form: multipart::Form) -> TextResponse {
+        self.http_client.post_multipart("/images", form).await
+    }
+
+    pub async fn get_image(&self, image_id: i64) -> responses::BinaryResponse {
+        self.http_client
+            .get_binary(&format!("/images/{image_id}"), Query::empty())
+            .await
+    }
+
+    pub async fn delete_image(&self, image_id: i64) -> TextResponse {
+        self.http_client.delete(&format!("/images/{image_id}")).await
+    }
+
+    // Context: tracker
+
+    pub async fn apply_dead_torrent_policy(&self, apply_dead_torrent_policy_form: ApplyDeadTorrentPolicyForm) -> TextResponse {
+        self.http_client
+            .post("/torrents/dead/apply-policy", &apply_dead_torrent_policy_form)
+            .await
+    }
+
+    pub async fn reconcile_tracker_whitelist(&self, reconcile_whitelist_form: ReconcileWhitelistForm) -> TextResponse {
+        self.http_client.post("/tracker/whitelist/reconcile", &reconcile_whitelist_form).await
+    }
+
+    pub async fn get_tracker_jobs(&self, params: Query) -> TextResponse {
+        self.http_client.get("/tracker/jobs", params).await
+    }
+
+    pub async fn retry_tracker_job(&self, job_id: i64) -> TextResponse {
+        self.http_client.post(&format!("/tracker/jobs/{job_id}/retry"), &()).await
+    }
+
+    pub async fn get_mail_queue(&self, params: Query) -> TextResponse {
+        self.http_client.get("/mail/queue", params).await
+    }
+
+    pub async fn resend_mail(&self, mail_id: i64) -> TextResponse {
+        self.http_client.post(&format!("/mail/queue/{mail_id}/resend"), &()).await
+    }
+
+    pub async fn delete_mail(&self, mail_id: i64) -> TextResponse {
+        self.http_client.delete(&format!("/mail/queue/{mail_id}")).await
+    }
+
+    pub async fn start_tracker_mode_sync(&self, start_mode_sync_form: StartModeSyncForm) -> TextResponse {
+        self.http_client.post("/tracker/mode/sync", &start_mode_sync_form).await
+    }
+
+    pub async fn get_tracker_mode_sync(&self) -> TextResponse {
+        self.http_client.get("/tracker/mode/sync", Query::empty()).await
+    }
+
+    // Context: notification
+
+    pub async fn get_notifications(&self, params: Query) -> TextResponse {
+        self.http_client.get("/notifications", params).await
+    }
+
+    pub async fn get_unread_notifications_count(&self) -> TextResponse {
+        self.http_client.get("/notifications/unread-count", Query::empty()).await
+    }
+
+    pub async fn mark_notification_as_read(&self, notification_id: i64) -> TextResponse {
+        self.http_client
+            .post(&format!("/notifications/{notification_id}/read"), &())
+            .await
+    }
+
+    pub async fn mark_all_notifications_as_read(&self) -> TextResponse {
+        self.http_client.post("/notifications/read-all", &()).await
+    }
+
+    pub async fn get_notification_preferences(&self) -> TextResponse {
+        self.http_client.get("/notifications/preferences", Query::empty()).await
+    }
+
+    pub async fn update_notification_preferences(&self, preferences_form: PreferencesForm) -> TextResponse {
+        self.http_client.put("/notifications/preferences", &preferences_form).await
+    }
+
     // Context: user
 
     pub async fn register_user(&self, registration_form: RegistrationForm) -> TextResponse {
@@ -155,6 +297,36 @@ impl Client {
     pub async fn ban_user(&self, username: Username) -> TextResponse {
         self.http_client.delete(&format!("/user/ban/{}", &username.value)).await
     }
+
+    pub async fn delete_user(&self, username: Username) -> TextResponse {
+        self.http_client.delete(&format!("/user/account/{}", &username.value)).await
+    }
+
+    pub async fn get_tracker_key(&self) -> TextResponse {
+        self.http_client.get("/user/tracker-key", Query::empty()).await
+    }
+
+    pub async fn rotate_tracker_key(&self) -> TextResponse {
+        self.http_client.post("/user/tracker-key/rotate", &()).await
+    }
+
+    pub async fn revoke_tracker_keys(&self, username: Username) -> TextResponse {
+        self.http_client
+            .delete(&format!("/user/tracker-key/{}", &username.value))
+            .await
+    }
+
+    pub async fn update_avatar(&self, avatar_form: AvatarForm) -> TextResponse {
+        self.http_client.put("/user/avatar", &avatar_form).await
+    }
+
+    pub async fn resend_verification_email(&self, login_form: LoginForm) -> TextResponse {
+        self.http_client.post("/user/email/resend-verification", &login_form).await
+    }
+
+    pub async fn update_locale(&self, locale_form: LocaleForm) -> TextResponse {
+        self.http_client.put("/user/locale", &locale_form).await
+    }
 }
 
 /// Generic HTTP Client
@@ -0,0 +1,14 @@
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageOutputFormat};
+
+/// It generates a PNG image with the given dimensions.
+pub fn png_image(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    DynamicImage::new_rgba8(width, height)
+        .write_to(&mut Cursor::new(&mut bytes), ImageOutputFormat::Png)
+        .unwrap();
+
+    bytes
+}
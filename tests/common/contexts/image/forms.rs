@@ -0,0 +1,11 @@
+use reqwest::multipart::{Form, Part};
+
+pub struct UploadImageMultipartForm {
+    pub bytes: Vec<u8>,
+}
+
+impl From<UploadImageMultipartForm> for Form {
+    fn from(form: UploadImageMultipartForm) -> Self {
+        Form::new().part("image", Part::bytes(form.bytes).file_name("image"))
+    }
+}
@@ -0,0 +1,35 @@
+use serde::Deserialize;
+
+pub type Id = i64;
+
+#[derive(Deserialize, Debug)]
+pub struct UploadedImageResponse {
+    pub data: Image,
+}
+
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+pub struct Image {
+    pub image_id: Id,
+    pub url: String,
+    pub content_type: String,
+    pub size: i64,
+    pub width: i64,
+    pub height: i64,
+    pub date_uploaded: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TorrentImagesResponse {
+    pub data: TorrentImages,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct TorrentImages {
+    pub poster: Option<Image>,
+    pub screenshots: Vec<Image>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AvatarResponse {
+    pub data: Option<String>,
+}
@@ -0,0 +1,22 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct MailQueueResponse {
+    pub data: Vec<QueuedMail>,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct QueuedMail {
+    pub mail_id: i64,
+    pub kind: String,
+    pub user_id: Option<i64>,
+    pub sender: String,
+    pub recipient: String,
+    pub subject: String,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Serialize, Debug)]
+pub struct PreferencesForm {
+    pub in_app: bool,
+    pub email_digest: bool,
+    pub immediate_email: bool,
+}
@@ -0,0 +1,36 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct NotificationsResponse {
+    pub data: Vec<Notification>,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct Notification {
+    pub notification_id: i64,
+    pub kind: String,
+    pub torrent_id: Option<i64>,
+    pub info_hash: Option<String>,
+    pub torrent_title: Option<String>,
+    pub detail: Option<String>,
+    pub is_read: bool,
+    pub created_at: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UnreadCountResponse {
+    pub data: i64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PreferencesResponse {
+    pub data: Preferences,
+}
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct Preferences {
+    pub in_app: bool,
+    pub email_digest: bool,
+    pub immediate_email: bool,
+}
@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct CacheStatisticsResponse {
+    pub data: CacheStatistics,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct CacheStatistics {
+    pub memory_cache: TierStatistics,
+    pub disk_cache: Option<TierStatistics>,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_ratio: f64,
+    pub miss_ratio: f64,
+    pub top_users: Vec<UserUsage>,
+    pub top_urls: Vec<UrlRequests>,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct TierStatistics {
+    pub entries: usize,
+    pub total_size: usize,
+    pub capacity: usize,
+    pub evictions: u64,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct UserUsage {
+    pub user_id: i64,
+    pub usage: usize,
+    pub variant_usage: usize,
+    pub variant_usages: BTreeMap<String, usize>,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct UrlRequests {
+    pub url: String,
+    pub requests: u64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PurgedEntriesResponse {
+    pub data: usize,
+}
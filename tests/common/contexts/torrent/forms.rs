@@ -9,6 +9,18 @@ pub struct UpdateTorrentFrom {
     pub description: Option<String>,
     pub category: Option<i64>,
     pub tags: Option<Vec<i64>>,
+    pub web_seeds: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct UpdateTorrentImagesForm {
+    pub poster: Option<i64>,
+    pub screenshots: Vec<i64>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ApplyDeadTorrentPolicyForm {
+    pub dry_run: bool,
 }
 
 use reqwest::multipart::Form;
@@ -1,5 +1,7 @@
 use serde::Deserialize;
 
+use crate::common::contexts::image::responses::Image;
+
 pub type Id = i64;
 pub type CategoryId = i64;
 pub type TagId = i64;
@@ -39,11 +41,14 @@ pub struct ListItem {
     pub file_size: i64,
     pub seeders: i64,
     pub leechers: i64,
+    pub times_downloaded: i64,
+    pub times_completed: i64,
     pub name: String,
     pub comment: Option<String>,
     pub creation_date: Option<i64>,
     pub created_by: Option<String>,
     pub encoding: Option<String>,
+    pub dead_since: Option<UtcDateTime>,
 }
 
 #[derive(Deserialize, PartialEq, Debug)]
@@ -65,6 +70,10 @@ pub struct TorrentDetails {
     pub leechers: u64,
     pub files: Vec<File>,
     pub trackers: Vec<String>,
+    pub web_seeds: Vec<String>,
+    pub http_seeds: Vec<String>,
+    pub poster: Option<Image>,
+    pub screenshots: Vec<Image>,
     pub magnet_link: String,
     pub tags: Vec<Tag>,
     pub name: String,
@@ -96,6 +105,18 @@ pub struct File {
     pub md5sum: Option<String>,
 }
 
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct TorrentStatsHistoryResponse {
+    pub data: Vec<TorrentStatsSnapshot>,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct TorrentStatsSnapshot {
+    pub seeders: u64,
+    pub leechers: u64,
+    pub created_at: UtcDateTime,
+}
+
 #[derive(Deserialize, PartialEq, Debug)]
 pub struct UploadedTorrentResponse {
     pub data: UploadedTorrent,
@@ -123,3 +144,34 @@ pub struct UpdatedTorrentResponse {
 }
 
 pub type UpdatedTorrent = TorrentDetails;
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct DeadTorrentsReportResponse {
+    pub data: DeadTorrentsReport,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct DeadTorrentsReport {
+    pub dry_run: bool,
+    pub prune_action: String,
+    pub marked_as_dead: Vec<DeadTorrent>,
+    pub revived: Vec<DeadTorrent>,
+    pub pruned: Vec<DeadTorrent>,
+    pub notified: usize,
+    pub failed: Vec<String>,
+}
+
+impl DeadTorrentsReport {
+    pub fn torrent_ids(torrents: &[DeadTorrent]) -> Vec<Id> {
+        torrents.iter().map(|torrent| torrent.torrent_id).collect()
+    }
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct DeadTorrent {
+    pub torrent_id: Id,
+    pub info_hash: String,
+    pub title: String,
+    pub uploader: String,
+    pub dead_since: Option<UtcDateTime>,
+}
@@ -0,0 +1,11 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct ReconcileWhitelistForm {
+    pub dry_run: bool,
+}
+
+#[derive(Serialize)]
+pub struct StartModeSyncForm {
+    pub force: bool,
+}
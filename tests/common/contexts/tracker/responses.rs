@@ -0,0 +1,65 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct ReconciliationReportResponse {
+    pub data: ReconciliationReport,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct ReconciliationReport {
+    pub dry_run: bool,
+    pub whitelisted_mode: bool,
+    pub torrents_in_index: usize,
+    pub torrents_in_tracker: usize,
+    pub not_seen_by_tracker: usize,
+    pub stale: usize,
+    pub added: usize,
+    pub removed: usize,
+    pub failed: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TrackerJobsResponse {
+    pub data: Vec<TrackerJob>,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct TrackerJob {
+    pub job_id: i64,
+    pub kind: String,
+    pub info_hash: String,
+    pub torrent_id: Option<i64>,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TrackerModeSyncResponse {
+    pub data: TrackerModeSync,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OptionalTrackerModeSyncResponse {
+    pub data: Option<TrackerModeSync>,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct TrackerModeSync {
+    pub sync_id: i64,
+    pub from_mode: Option<String>,
+    pub to_mode: String,
+    pub action: String,
+    pub status: String,
+    pub last_torrent_id: i64,
+    pub torrents_total: i64,
+    pub torrents_processed: i64,
+    pub errors: i64,
+    pub last_error: Option<String>,
+    pub started_at: String,
+    pub updated_at: String,
+    pub finished_at: Option<String>,
+}
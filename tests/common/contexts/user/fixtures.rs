@@ -9,6 +9,7 @@ pub fn random_user_registration_form() -> RegistrationForm {
         email: Some(format!("email_{user_id}@email.com")),
         password: "password".to_string(),
         confirm_password: "password".to_string(),
+        locale: None,
     }
 }
 
@@ -6,6 +6,7 @@ pub struct RegistrationForm {
     pub email: Option<String>,
     pub password: String,
     pub confirm_password: String,
+    pub locale: Option<String>,
 }
 
 pub type RegisteredUser = RegistrationForm;
@@ -35,3 +36,13 @@ impl Username {
         Self { value }
     }
 }
+
+#[derive(Serialize)]
+pub struct AvatarForm {
+    pub image_id: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct LocaleForm {
+    pub locale: Option<String>,
+}
@@ -32,6 +32,28 @@ pub struct BannedUserResponse {
     pub data: String,
 }
 
+#[derive(Deserialize)]
+pub struct DeletedUserResponse {
+    pub data: String,
+}
+
+#[derive(Deserialize)]
+pub struct TrackerKeyResponse {
+    pub data: TrackerKeyData,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct TrackerKeyData {
+    pub key: String,
+    pub valid_until: i64,
+    pub announce_url: String,
+}
+
+#[derive(Deserialize)]
+pub struct RevokedTrackerKeysResponse {
+    pub data: String,
+}
+
 #[derive(Deserialize)]
 pub struct TokenRenewalResponse {
     pub data: TokenRenewalData,
@@ -43,3 +65,8 @@ pub struct TokenRenewalData {
     pub username: String,
     pub admin: bool,
 }
+
+#[derive(Deserialize)]
+pub struct LocaleResponse {
+    pub data: Option<String>,
+}
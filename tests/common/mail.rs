@@ -0,0 +1,44 @@
+//! Emails captured by the `File` mail transport.
+//!
+//! The isolated test environment writes the emails to a Maildir directory
+//! instead of sending them to an SMTP server.
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// It returns the raw emails sent to the `recipient`.
+pub fn captured_emails_to(mail_dir: &str, recipient: &str) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(Path::new(mail_dir).join("new")) else {
+        return vec![];
+    };
+
+    entries
+        .filter_map(|entry| fs::read_to_string(entry.ok()?.path()).ok())
+        .filter(|email| email.lines().any(|line| line.starts_with("To: ") && line.contains(recipient)))
+        .collect()
+}
+
+/// It waits until at least `count` emails were sent to the `recipient` and
+/// returns them. Emails are delivered in the background by the mail queue, so
+/// they are not written yet when the request that sends them finishes.
+///
+/// It returns the emails captured so far if they don't arrive in 10 seconds.
+pub async fn wait_for_emails_to(mail_dir: &str, recipient: &str, count: usize) -> Vec<String> {
+    for _ in 0..100 {
+        let emails = captured_emails_to(mail_dir, recipient);
+
+        if emails.len() >= count {
+            return emails;
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    captured_emails_to(mail_dir, recipient)
+}
+
+/// It joins the lines split by quoted-printable soft line breaks, so long
+/// lines like URLs can be found in the email.
+pub fn unfold_soft_line_breaks(email: &str) -> String {
+    email.replace("=\r\n", "")
+}
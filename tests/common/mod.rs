@@ -3,5 +3,6 @@ pub mod client;
 pub mod connection_info;
 pub mod contexts;
 pub mod http;
+pub mod mail;
 pub mod random;
 pub mod responses;
@@ -17,6 +17,10 @@ const ENV_VAR_API_ADMIN_TOKEN: &str = "TORRUST_INDEX_E2E_TRACKER_API_TOKEN";
 /// Secret key used to encrypt and decrypt
 const ENV_VAR_AUTH_SECRET_KEY: &str = "TORRUST_INDEX_E2E_AUTH_SECRET_KEY";
 
+/// Prefix for the env vars overriding a single setting. For example:
+/// `TORRUST_INDEX_E2E_CONFIG__TRACKER__TOKEN`.
+const ENV_VAR_PREFIX_OVERRIDES: &str = "TORRUST_INDEX_E2E_CONFIG";
+
 /// The `index.toml` file location.
 pub const ENV_VAR_PATH_CONFIG: &str = "TORRUST_INDEX_E2E_PATH_CONFIG";
 
@@ -52,6 +56,7 @@ pub fn initialize_configuration() -> Configuration {
         DEFAULT_PATH_CONFIG.to_string(),
         ENV_VAR_API_ADMIN_TOKEN.to_string(),
         ENV_VAR_AUTH_SECRET_KEY.to_string(),
+        ENV_VAR_PREFIX_OVERRIDES.to_string(),
     )
     .unwrap();
 
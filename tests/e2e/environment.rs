@@ -73,10 +73,11 @@ impl TestEnv {
         }
     }
 
-    /// Some test requires the real tracker to be running, so they can only
-    /// be run in shared mode.
+    /// Some test requires a tracker. The shared environment runs the real
+    /// tracker and the isolated environment uses the in-memory tracker
+    /// backend.
     pub fn provides_a_tracker(&self) -> bool {
-        self.is_shared()
+        self.is_shared() || self.is_isolated()
     }
 
     /// Returns the server starting settings if the servers was already started.
@@ -93,6 +94,9 @@ impl TestEnv {
                 settings.tracker.token = "***".to_owned();
                 settings.database.connect_url = "***".to_owned();
                 settings.mail.password = "***".to_owned();
+                settings.mail.sendmail_command = "***".to_owned();
+                settings.mail.file_path = "***".to_owned();
+                settings.mail.templates_path = "***".to_owned();
                 settings.auth.secret_key = "***".to_owned();
                 Some(settings)
             }
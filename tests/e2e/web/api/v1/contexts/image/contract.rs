@@ -0,0 +1,355 @@
+//! API contract for `image` context.
+
+use torrust_index::web::api;
+
+use crate::common::asserts::assert_json_ok_response;
+use crate::common::client::Client;
+use crate::common::contexts::image::fixtures::png_image;
+use crate::common::contexts::image::forms::UploadImageMultipartForm;
+use crate::common::contexts::image::responses::{AvatarResponse, TorrentImagesResponse, UploadedImageResponse};
+use crate::common::contexts::torrent::forms::UpdateTorrentImagesForm;
+use crate::common::contexts::torrent::responses::TorrentDetailsResponse;
+use crate::common::contexts::user::forms::AvatarForm;
+use crate::e2e::environment::TestEnv;
+use crate::e2e::web::api::v1::contexts::image::steps::upload_image;
+use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
+use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user};
+
+#[tokio::test]
+async fn it_should_allow_logged_in_users_to_upload_images() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let uploader = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let image_bytes = png_image(40, 20);
+
+    let response = client
+        .upload_image(
+            UploadImageMultipartForm {
+                bytes: image_bytes.clone(),
+            }
+            .into(),
+        )
+        .await;
+
+    let res: UploadedImageResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.url, format!("/v1/images/{}", res.data.image_id));
+    assert_eq!(res.data.content_type, "image/png");
+    assert_eq!((res.data.width, res.data.height), (40, 20));
+    assert_json_ok_response(&response);
+
+    let response = client.get_image(res.data.image_id).await;
+
+    assert_eq!(response.status, 200);
+    assert_eq!(response.content_type, Some("image/png".to_string()));
+    assert_eq!(response.bytes, image_bytes);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_guests_to_upload_images() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client
+        .upload_image(
+            UploadImageMultipartForm {
+                bytes: png_image(40, 20),
+            }
+            .into(),
+        )
+        .await;
+
+    assert_eq!(response.status, 401);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_uploading_files_that_are_not_images() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let uploader = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client
+        .upload_image(
+            UploadImageMultipartForm {
+                bytes: b"<html><body></body></html>".to_vec(),
+            }
+            .into(),
+        )
+        .await;
+
+    assert_eq!(response.status, 400);
+}
+
+#[tokio::test]
+async fn it_should_serve_uploaded_images_with_caching_headers() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let uploader = new_logged_in_user(&env).await;
+    let image = upload_image(&uploader, &env).await;
+
+    let url = format!("http://{}{}", env.server_socket_addr().unwrap(), image.url);
+
+    let response = reqwest::get(&url).await.unwrap();
+
+    assert_eq!(
+        response.headers().get("cache-control").unwrap(),
+        "public, max-age=31536000, immutable"
+    );
+    assert_eq!(response.headers().get("x-content-type-options").unwrap(), "nosniff");
+
+    let etag = response.headers().get("etag").unwrap().clone();
+
+    let response = reqwest::Client::new()
+        .get(&url)
+        .header("if-none-match", etag)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 304);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_users_to_exceed_their_image_upload_quota() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if env.is_shared() {
+        println!("test skipped. It modifies the settings of the shared environment.");
+        return;
+    }
+
+    let image_bytes = png_image(40, 20);
+
+    let admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let mut new_settings = env.server_settings_masking_secrets().unwrap();
+    new_settings.image_uploads.user_quota_bytes = image_bytes.len() + 1;
+    client.update_settings(&new_settings).await;
+
+    let uploader = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client
+        .upload_image(
+            UploadImageMultipartForm {
+                bytes: image_bytes.clone(),
+            }
+            .into(),
+        )
+        .await;
+
+    assert_json_ok_response(&response);
+
+    let response = client
+        .upload_image(UploadImageMultipartForm { bytes: image_bytes }.into())
+        .await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_concurrent_uploads_to_exceed_the_image_upload_quota() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if env.is_shared() {
+        println!("test skipped. It modifies the settings of the shared environment.");
+        return;
+    }
+
+    let image_bytes = png_image(40, 20);
+
+    let admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let mut new_settings = env.server_settings_masking_secrets().unwrap();
+    new_settings.image_uploads.user_quota_bytes = image_bytes.len() + 1;
+    client.update_settings(&new_settings).await;
+
+    let uploader = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let (first, second) = tokio::join!(
+        client.upload_image(
+            UploadImageMultipartForm {
+                bytes: image_bytes.clone(),
+            }
+            .into(),
+        ),
+        client.upload_image(UploadImageMultipartForm { bytes: image_bytes }.into())
+    );
+
+    let mut statuses = vec![first.status, second.status];
+    statuses.sort_unstable();
+
+    assert_eq!(statuses, vec![200, 403]);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_uploading_images_bigger_than_the_max_image_size() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if env.is_shared() {
+        println!("test skipped. It modifies the settings of the shared environment.");
+        return;
+    }
+
+    let image_bytes = png_image(40, 20);
+
+    let admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let mut new_settings = env.server_settings_masking_secrets().unwrap();
+    new_settings.image_uploads.max_image_size = image_bytes.len() - 1;
+    client.update_settings(&new_settings).await;
+
+    let uploader = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client
+        .upload_image(UploadImageMultipartForm { bytes: image_bytes }.into())
+        .await;
+
+    assert_eq!(response.status, 400);
+    assert!(response.body.contains("Uploaded image is too big."));
+}
+
+#[tokio::test]
+async fn it_should_allow_users_to_delete_their_images() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let uploader = new_logged_in_user(&env).await;
+    let image = upload_image(&uploader, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client.delete_image(image.image_id).await;
+
+    assert_json_ok_response(&response);
+
+    let response = client.get_image(image.image_id).await;
+
+    assert_eq!(response.status, 404);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_users_to_delete_images_uploaded_by_other_users() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let uploader = new_logged_in_user(&env).await;
+    let image = upload_image(&uploader, &env).await;
+
+    let other_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &other_user.token);
+
+    let response = client.delete_image(image.image_id).await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_allow_torrent_owners_to_set_the_poster_and_screenshots_of_their_torrents() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let poster = upload_image(&uploader, &env).await;
+    let screenshot = upload_image(&uploader, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client
+        .update_torrent_images(
+            &test_torrent.file_info_hash(),
+            UpdateTorrentImagesForm {
+                poster: Some(poster.image_id),
+                screenshots: vec![screenshot.image_id],
+            },
+        )
+        .await;
+
+    let res: TorrentImagesResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.poster, Some(poster.clone()));
+    assert_eq!(res.data.screenshots, vec![screenshot.clone()]);
+
+    let response = client.get_torrent(&test_torrent.file_info_hash()).await;
+
+    let torrent_details_response: TorrentDetailsResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(torrent_details_response.data.poster, Some(poster));
+    assert_eq!(torrent_details_response.data.screenshots, vec![screenshot]);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_using_images_uploaded_by_other_users_as_torrent_images() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let other_user = new_logged_in_user(&env).await;
+    let image = upload_image(&other_user, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client
+        .update_torrent_images(
+            &test_torrent.file_info_hash(),
+            UpdateTorrentImagesForm {
+                poster: Some(image.image_id),
+                screenshots: vec![],
+            },
+        )
+        .await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_allow_users_to_use_an_uploaded_image_as_their_avatar() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let user = new_logged_in_user(&env).await;
+    let image = upload_image(&user, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client
+        .update_avatar(AvatarForm {
+            image_id: Some(image.image_id),
+        })
+        .await;
+
+    let res: AvatarResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data, Some(image.url));
+    assert_json_ok_response(&response);
+}
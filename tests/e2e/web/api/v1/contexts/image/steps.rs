@@ -0,0 +1,24 @@
+use crate::common::client::Client;
+use crate::common::contexts::image::fixtures::png_image;
+use crate::common::contexts::image::forms::UploadImageMultipartForm;
+use crate::common::contexts::image::responses::{Image, UploadedImageResponse};
+use crate::common::contexts::user::responses::LoggedInUserData;
+use crate::e2e::environment::TestEnv;
+
+/// Upload a small PNG image to the index.
+pub async fn upload_image(uploader: &LoggedInUserData, env: &TestEnv) -> Image {
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client
+        .upload_image(
+            UploadImageMultipartForm {
+                bytes: png_image(40, 20),
+            }
+            .into(),
+        )
+        .await;
+
+    let res: UploadedImageResponse = serde_json::from_str(&response.body).unwrap();
+
+    res.data
+}
@@ -0,0 +1,163 @@
+//! API contract for `mail` context.
+
+use std::time::Duration;
+
+use torrust_index::web::api;
+
+use crate::common::asserts::assert_json_ok_response;
+use crate::common::client::Client;
+use crate::common::contexts::mail::responses::{MailQueueResponse, QueuedMail};
+use crate::common::contexts::settings::Settings;
+use crate::common::contexts::user::fixtures::random_user_registration_form;
+use crate::common::http::{Query, QueryParam};
+use crate::common::mail::wait_for_emails_to;
+use crate::e2e::environment::TestEnv;
+use crate::e2e::web::api::v1::contexts::user::steps::{enable_email_verification, new_logged_in_admin, new_logged_in_user};
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_list_the_mail_queue() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.get_mail_queue(Query::empty()).await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_return_a_not_found_error_resending_an_email_that_does_not_exist() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client.resend_mail(i64::MAX).await;
+
+    assert_eq!(response.status, 404);
+}
+
+#[tokio::test]
+async fn it_should_keep_the_emails_that_could_not_be_delivered_and_allow_admins_to_resend_them() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    // Nothing is listening on the SMTP port, so the delivery fails at once.
+    let Some(admin) = enable_email_verification(&env, |settings| {
+        settings.mail.transport = "Smtp".to_string();
+        settings.mail.server = "127.0.0.1".to_string();
+        settings.mail.port = 1;
+        settings.mail.smtp_tls = "None".to_string();
+        settings.mail_queue.max_attempts = 1;
+    })
+    .await
+    else {
+        return;
+    };
+
+    let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let file_transport_settings = env.server_settings_masking_secrets().unwrap();
+
+    let form = random_user_registration_form();
+    let email = form.email.clone().unwrap();
+
+    Client::unauthenticated(&env.server_socket_addr().unwrap())
+        .register_user(form)
+        .await;
+
+    let failed_mail = wait_for_failed_mail_to(&admin_client, &email)
+        .await
+        .expect("the verification email should be marked as failed");
+
+    assert_eq!(failed_mail.kind, "VerifyEmail");
+    assert_eq!(failed_mail.attempts, 1);
+    assert!(failed_mail.last_error.is_some());
+
+    // Once the mail server is available again, the admin resends it.
+    use_the_file_transport(&admin_client, &file_transport_settings).await;
+
+    let response = admin_client.resend_mail(failed_mail.mail_id).await;
+
+    assert_json_ok_response(&response);
+
+    assert_eq!(
+        wait_for_emails_to(&env.server_settings().unwrap().mail.file_path, &email, 1)
+            .await
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn it_should_mark_the_delivered_emails_as_sent_and_allow_admins_to_delete_them() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let Some(admin) = enable_email_verification(&env, |_| {}).await else {
+        return;
+    };
+
+    let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let form = random_user_registration_form();
+    let email = form.email.clone().unwrap();
+
+    Client::unauthenticated(&env.server_socket_addr().unwrap())
+        .register_user(form)
+        .await;
+
+    let sent_mail = wait_for_mail_to(&admin_client, "Sent", &email)
+        .await
+        .expect("the verification email should be marked as sent");
+
+    assert_eq!(sent_mail.kind, "VerifyEmail");
+
+    let response = admin_client.delete_mail(sent_mail.mail_id).await;
+
+    assert_json_ok_response(&response);
+
+    let response = admin_client.get_mail_queue(Query::empty()).await;
+
+    let res: MailQueueResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert!(!res.data.iter().any(|mail| mail.mail_id == sent_mail.mail_id));
+
+    let response = admin_client.delete_mail(sent_mail.mail_id).await;
+
+    assert_eq!(response.status, 404);
+}
+
+async fn wait_for_failed_mail_to(client: &Client, recipient: &str) -> Option<QueuedMail> {
+    wait_for_mail_to(client, "Failed", recipient).await
+}
+
+async fn wait_for_mail_to(client: &Client, status: &str, recipient: &str) -> Option<QueuedMail> {
+    for _ in 0..100 {
+        let response = client
+            .get_mail_queue(Query::with_params([QueryParam::new("status", status)].to_vec()))
+            .await;
+
+        let res: MailQueueResponse = serde_json::from_str(&response.body).unwrap();
+
+        if let Some(mail) = res.data.into_iter().find(|mail| mail.recipient.contains(recipient)) {
+            return Some(mail);
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    None
+}
+
+async fn use_the_file_transport(client: &Client, file_transport_settings: &Settings) {
+    let mut new_settings = file_transport_settings.clone();
+    new_settings.mail.email_verification_enabled = true;
+
+    let response = client.update_settings(&new_settings).await;
+
+    assert_eq!(response.status, 200, "the settings should be updated: {}", response.body);
+}
@@ -1,8 +1,12 @@
 pub mod about;
 pub mod category;
+pub mod image;
+pub mod mail;
+pub mod notification;
 pub mod proxy;
 pub mod root;
 pub mod settings;
 pub mod tag;
 pub mod torrent;
+pub mod tracker;
 pub mod user;
@@ -0,0 +1,480 @@
+//! API contract for `notification` context.
+
+use torrust_index::databases::database;
+use torrust_index::web::api;
+
+use crate::common::asserts::assert_json_ok_response;
+use crate::common::client::Client;
+use crate::common::contexts::category::forms::DeleteCategoryForm;
+use crate::common::contexts::notification::forms::PreferencesForm;
+use crate::common::contexts::notification::responses::{
+    NotificationsResponse, Preferences, PreferencesResponse, UnreadCountResponse,
+};
+use crate::common::contexts::torrent::fixtures::random_torrent;
+use crate::common::contexts::torrent::forms::UpdateTorrentFrom;
+use crate::common::contexts::torrent::responses::TorrentDetailsResponse;
+use crate::common::contexts::user::fixtures::random_user_registration_form;
+use crate::common::contexts::user::forms::LoginForm;
+use crate::common::contexts::user::responses::{LoggedInUserData, SuccessfulLoginResponse};
+use crate::common::http::{Query, QueryParam};
+use crate::common::mail::{unfold_soft_line_breaks, wait_for_emails_to};
+use crate::e2e::environment::TestEnv;
+use crate::e2e::web::api::v1::contexts::category::steps::add_random_category;
+use crate::e2e::web::api::v1::contexts::torrent::steps::{upload_random_torrent_to_index, upload_torrent};
+use crate::e2e::web::api::v1::contexts::user::steps::{enable_email_verification, new_logged_in_admin, new_logged_in_user};
+
+#[tokio::test]
+async fn it_should_not_allow_guests_to_list_notifications() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client.get_notifications(Query::empty()).await;
+
+    assert_eq!(response.status, 401);
+}
+
+#[tokio::test]
+async fn it_should_notify_the_uploader_when_an_admin_edits_their_torrent() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let new_title = format!("{}-new-title", test_torrent.index_info.title);
+
+    let response = admin_client
+        .update_torrent(
+            &test_torrent.file_info_hash(),
+            UpdateTorrentFrom {
+                title: Some(new_title.clone()),
+                description: Some(format!("{}-new-description", test_torrent.index_info.description)),
+                category: None,
+                tags: None,
+                web_seeds: None,
+            },
+        )
+        .await;
+
+    assert_eq!(response.status, 200);
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client.get_notifications(Query::empty()).await;
+
+    let notifications: NotificationsResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(notifications.data.len(), 1);
+    let notification = &notifications.data[0];
+    assert_eq!(notification.kind, "TorrentEdited");
+    assert_eq!(notification.torrent_id, Some(uploaded_torrent.torrent_id));
+    assert_eq!(notification.torrent_title, Some(new_title));
+    assert_eq!(notification.detail, Some("Changed: title, description".to_string()));
+    assert!(!notification.is_read);
+    assert_json_ok_response(&response);
+
+    let response = client.get_unread_notifications_count().await;
+    let unread_count: UnreadCountResponse = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(unread_count.data, 1);
+
+    let response = client.mark_notification_as_read(notification.notification_id).await;
+    assert_eq!(response.status, 200);
+
+    let response = client.get_unread_notifications_count().await;
+    let unread_count: UnreadCountResponse = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(unread_count.data, 0);
+}
+
+#[tokio::test]
+async fn it_should_not_notify_the_uploader_when_an_admin_saves_their_torrent_without_changes() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = admin_client.get_torrent(&test_torrent.file_info_hash()).await;
+
+    let torrent: TorrentDetailsResponse = serde_json::from_str(&response.body).unwrap();
+
+    // Clients send all the fields, even the ones that did not change
+    let response = admin_client
+        .update_torrent(
+            &test_torrent.file_info_hash(),
+            UpdateTorrentFrom {
+                title: Some(torrent.data.title),
+                description: Some(torrent.data.description),
+                category: Some(torrent.data.category.category_id),
+                tags: Some(torrent.data.tags.iter().map(|tag| tag.tag_id).collect()),
+                web_seeds: Some(torrent.data.web_seeds),
+            },
+        )
+        .await;
+
+    assert_eq!(response.status, 200);
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client.get_unread_notifications_count().await;
+    let unread_count: UnreadCountResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(unread_count.data, 0);
+}
+
+#[tokio::test]
+async fn it_should_notify_the_uploader_when_an_admin_deletes_their_torrent() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = admin_client.delete_torrent(&test_torrent.file_info_hash()).await;
+
+    assert_eq!(response.status, 200);
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client
+        .get_notifications(Query::with_params([QueryParam::new("unread_only", "true")].to_vec()))
+        .await;
+
+    let notifications: NotificationsResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(notifications.data.len(), 1);
+    assert_eq!(notifications.data[0].kind, "TorrentDeleted");
+    assert_eq!(notifications.data[0].torrent_id, Some(uploaded_torrent.torrent_id));
+
+    let response = client.mark_all_notifications_as_read().await;
+    assert_eq!(response.status, 200);
+
+    let response = client
+        .get_notifications(Query::with_params([QueryParam::new("unread_only", "true")].to_vec()))
+        .await;
+
+    let notifications: NotificationsResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert!(notifications.data.is_empty());
+}
+
+#[tokio::test]
+async fn it_should_notify_the_uploader_when_an_admin_removes_the_category_of_their_torrent() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let category_name = add_random_category(&env).await;
+
+    let uploader = new_logged_in_user(&env).await;
+    let mut test_torrent = random_torrent();
+    test_torrent.index_info.category.clone_from(&category_name);
+    let uploaded_torrent = upload_torrent(&uploader, &test_torrent.index_info, &env).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = admin_client
+        .delete_category(DeleteCategoryForm {
+            name: category_name.clone(),
+            icon: None,
+        })
+        .await;
+
+    assert_eq!(response.status, 200);
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client.get_notifications(Query::empty()).await;
+
+    let notifications: NotificationsResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(notifications.data.len(), 1);
+    assert_eq!(notifications.data[0].kind, "TorrentCategoryRemoved");
+    assert_eq!(notifications.data[0].torrent_id, Some(uploaded_torrent.torrent_id));
+    assert_eq!(notifications.data[0].detail, Some(category_name));
+}
+
+#[tokio::test]
+async fn it_should_report_the_torrents_that_lost_all_their_seeders_once() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.is_isolated() {
+        println!("test skipped. It changes the tracker statistics of the torrents in the database.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let database = database::connect(&env.database_connect_url().unwrap())
+        .await
+        .expect("database connection to be established.");
+
+    let tracker_url = env.server_settings().unwrap().tracker.url;
+
+    // The torrent has seeders
+    database
+        .update_tracker_info(uploaded_torrent.torrent_id, &tracker_url, 1, 0, 0)
+        .await
+        .unwrap();
+
+    let torrents_without_seeders = database.update_torrents_has_seeders().await.unwrap();
+
+    assert!(torrents_without_seeders.is_empty());
+
+    // The torrent lost all its seeders
+    database
+        .update_tracker_info(uploaded_torrent.torrent_id, &tracker_url, 0, 0, 0)
+        .await
+        .unwrap();
+
+    let torrents_without_seeders = database.update_torrents_has_seeders().await.unwrap();
+
+    assert_eq!(torrents_without_seeders.len(), 1);
+    assert_eq!(torrents_without_seeders[0].torrent_id, uploaded_torrent.torrent_id);
+    assert_eq!(torrents_without_seeders[0].title, test_torrent.index_info.title);
+
+    // The uploader is notified only once
+    let torrents_without_seeders = database.update_torrents_has_seeders().await.unwrap();
+
+    assert!(torrents_without_seeders.is_empty());
+}
+
+#[tokio::test]
+async fn it_should_send_the_notifications_in_the_email_digest() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.is_isolated() {
+        println!("test skipped. It requires the emails captured by the file mail transport.");
+        return;
+    }
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let (uploader, email) = new_logged_in_user_with_email(&env).await;
+    let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    client
+        .update_notification_preferences(PreferencesForm {
+            in_app: false,
+            email_digest: true,
+            immediate_email: false,
+        })
+        .await;
+
+    let response = admin_client.delete_torrent(&test_torrent.file_info_hash()).await;
+
+    assert_eq!(response.status, 200);
+
+    let emails = wait_for_emails_to(&env.server_settings().unwrap().mail.file_path, &email, 1).await;
+
+    assert_eq!(emails.len(), 1);
+    assert!(emails[0].contains("Subject: Torrust - Your torrent was deleted by an administrator"));
+    assert!(unfold_soft_line_breaks(&emails[0]).contains(&test_torrent.index_info.title));
+}
+
+#[tokio::test]
+async fn it_should_not_email_the_notifications_to_unverified_emails_when_verification_is_enabled() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let Some(logged_in_admin) = enable_email_verification(&env, |settings| {
+        settings.email_verification.required_for_login = false;
+        settings.email_verification.required_for_upload = false;
+    })
+    .await
+    else {
+        return;
+    };
+
+    let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let (uploader, email) = new_logged_in_user_with_email(&env).await;
+    let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    client
+        .update_notification_preferences(PreferencesForm {
+            in_app: true,
+            email_digest: false,
+            immediate_email: true,
+        })
+        .await;
+
+    let response = admin_client.delete_torrent(&test_torrent.file_info_hash()).await;
+
+    assert_eq!(response.status, 200);
+
+    // Only the verification email is sent
+    let emails = wait_for_emails_to(&env.server_settings().unwrap().mail.file_path, &email, 2).await;
+
+    assert_eq!(emails.len(), 1);
+    assert!(emails[0].contains("Subject: Torrust - Email verification"));
+
+    let response = client.get_unread_notifications_count().await;
+    let unread_count: UnreadCountResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(unread_count.data, 1);
+}
+
+#[tokio::test]
+async fn it_should_not_notify_users_about_their_own_changes() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client
+        .update_torrent(
+            &test_torrent.file_info_hash(),
+            UpdateTorrentFrom {
+                title: Some(format!("{}-new-title", test_torrent.index_info.title)),
+                description: None,
+                category: None,
+                tags: None,
+                web_seeds: None,
+            },
+        )
+        .await;
+
+    assert_eq!(response.status, 200);
+
+    let response = client.get_unread_notifications_count().await;
+    let unread_count: UnreadCountResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(unread_count.data, 0);
+}
+
+#[tokio::test]
+async fn it_should_return_a_not_found_error_marking_a_notification_that_does_not_exist_as_read() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.mark_notification_as_read(i64::MAX).await;
+
+    assert_eq!(response.status, 404);
+}
+
+#[tokio::test]
+async fn it_should_only_deliver_notifications_in_the_app_by_default() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.get_notification_preferences().await;
+
+    let res: PreferencesResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(
+        res.data,
+        Preferences {
+            in_app: true,
+            email_digest: false,
+            immediate_email: false,
+        }
+    );
+}
+
+#[tokio::test]
+async fn it_should_allow_users_to_update_their_delivery_preferences() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client
+        .update_notification_preferences(PreferencesForm {
+            in_app: false,
+            email_digest: true,
+            immediate_email: false,
+        })
+        .await;
+
+    assert_eq!(response.status, 200);
+
+    let response = client.get_notification_preferences().await;
+
+    let res: PreferencesResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(
+        res.data,
+        Preferences {
+            in_app: false,
+            email_digest: true,
+            immediate_email: false,
+        }
+    );
+}
+
+/// It registers and logs in a new user. It returns the email of the user too,
+/// to find the emails sent to them. An administrator must be registered
+/// before, so the new user is not the first one.
+async fn new_logged_in_user_with_email(env: &TestEnv) -> (LoggedInUserData, String) {
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let form = random_user_registration_form();
+    let email = form.email.clone().unwrap();
+
+    client.register_user(form.clone()).await;
+
+    let response = client
+        .login_user(LoginForm {
+            login: form.username,
+            password: form.password,
+        })
+        .await;
+
+    let res: SuccessfulLoginResponse = serde_json::from_str(&response.body).unwrap();
+
+    (res.data, email)
+}
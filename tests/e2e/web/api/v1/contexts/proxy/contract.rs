@@ -1,3 +1,278 @@
 //! API contract for `proxy` context.
+use std::net::SocketAddr;
 
-// todo
+use image::GenericImageView;
+use torrust_index::web::api;
+
+use crate::common::asserts::assert_json_ok_response;
+use crate::common::client::Client;
+use crate::common::contexts::proxy::responses::{CacheStatisticsResponse, PurgedEntriesResponse};
+use crate::common::contexts::user::responses::LoggedInUserData;
+use crate::common::http::{Query, QueryParam};
+use crate::e2e::environment::TestEnv;
+use crate::e2e::web::api::v1::contexts::image::steps::upload_image;
+use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user};
+
+/// It allows the image cache to download images from the index itself.
+async fn allow_proxying_images_from_the_index(admin: &LoggedInUserData, env: &TestEnv) {
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let mut new_settings = env.server_settings_masking_secrets().unwrap();
+    new_settings.image_cache.allowed_private_hosts = vec!["127.0.0.1".to_string()];
+    client.update_settings(&new_settings).await;
+}
+
+/// The URL of an image uploaded to the index, to be proxied. The server
+/// listens on all interfaces, so the image is requested from the loopback
+/// address.
+async fn image_url(uploader: &LoggedInUserData, env: &TestEnv) -> String {
+    let image = upload_image(uploader, env).await;
+
+    let server_socket_addr: SocketAddr = env.server_socket_addr().unwrap().parse().unwrap();
+
+    format!("http://127.0.0.1:{}{}", server_socket_addr.port(), image.url)
+}
+
+#[tokio::test]
+async fn it_should_not_allow_guests_to_see_the_image_cache_statistics() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client.get_image_cache_statistics().await;
+
+    assert_eq!(response.status, 401);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_see_the_image_cache_statistics() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client.get_image_cache_statistics().await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_count_the_image_cache_hits_and_misses() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if env.is_shared() {
+        println!("test skipped. It modifies the settings of the shared environment.");
+        return;
+    }
+
+    let admin = new_logged_in_admin(&env).await;
+    allow_proxying_images_from_the_index(&admin, &env).await;
+
+    let user = new_logged_in_user(&env).await;
+    let url = image_url(&user, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let first_response = client.get_proxy_image(&url).await;
+    let second_response = client.get_proxy_image(&url).await;
+
+    assert_eq!(first_response.content_type, Some("image/png".to_string()));
+    assert_eq!(first_response.bytes, second_response.bytes);
+
+    // Guests can't get images that are not cached, so it's a miss that is not
+    // ranked.
+    let guest_client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+    let _failed_response = guest_client.get_proxy_image(&format!("{url}?not-cached")).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let response = client.get_image_cache_statistics().await;
+
+    let res: CacheStatisticsResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!((res.data.hits, res.data.misses), (1, 2));
+    assert_eq!(res.data.memory_cache.entries, 1);
+    assert_eq!(res.data.memory_cache.total_size, first_response.bytes.len());
+    assert_eq!(res.data.top_urls.len(), 1);
+    assert_eq!(res.data.top_urls[0].url, url);
+    assert_eq!(res.data.top_urls[0].requests, 2);
+    assert_eq!(res.data.top_users.len(), 1);
+    assert_eq!(res.data.top_users[0].usage, first_response.bytes.len());
+    assert_json_ok_response(&response);
+}
+
+#[tokio::test]
+async fn it_should_resize_the_images_with_the_query_parameters() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if env.is_shared() {
+        println!("test skipped. It modifies the settings of the shared environment.");
+        return;
+    }
+
+    let admin = new_logged_in_admin(&env).await;
+    allow_proxying_images_from_the_index(&admin, &env).await;
+
+    let user = new_logged_in_user(&env).await;
+    let url = image_url(&user, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let variant = client
+        .get_proxy_image_variant(
+            &url,
+            Query::with_params([QueryParam::new("width", "10"), QueryParam::new("format", "Jpeg")].to_vec()),
+        )
+        .await;
+
+    assert_eq!(variant.content_type, Some("image/jpeg".to_string()));
+
+    // The uploaded image is 40x20
+    let resized = image::load_from_memory(&variant.bytes).unwrap();
+
+    assert_eq!(resized.dimensions(), (10, 5));
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let response = client.get_image_cache_statistics().await;
+
+    let res: CacheStatisticsResponse = serde_json::from_str(&response.body).unwrap();
+
+    let user_usage = &res.data.top_users[0];
+
+    assert_eq!(user_usage.variant_usage, variant.bytes.len());
+    assert_eq!(
+        user_usage.variant_usages.keys().collect::<Vec<_>>(),
+        vec![&format!(
+            "10x{}:Jpeg",
+            env.server_settings().unwrap().image_cache.max_variant_dimension
+        )]
+    );
+}
+
+#[tokio::test]
+async fn it_should_return_an_error_image_when_the_query_parameters_are_not_valid() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if env.is_shared() {
+        println!("test skipped. It modifies the settings of the shared environment.");
+        return;
+    }
+
+    let admin = new_logged_in_admin(&env).await;
+    allow_proxying_images_from_the_index(&admin, &env).await;
+
+    let url = image_url(&admin, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let original = client.get_proxy_image(&url).await;
+
+    for params in [
+        [QueryParam::new("format", "Gif")].to_vec(),
+        [QueryParam::new("width", "wide")].to_vec(),
+    ] {
+        let response = client.get_proxy_image_variant(&url, Query::with_params(params)).await;
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.content_type, Some("image/png".to_string()));
+        assert_ne!(response.bytes, original.bytes);
+    }
+}
+
+#[tokio::test]
+async fn it_should_allow_admins_to_purge_an_image_from_the_cache() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if env.is_shared() {
+        println!("test skipped. It modifies the settings of the shared environment.");
+        return;
+    }
+
+    let admin = new_logged_in_admin(&env).await;
+    allow_proxying_images_from_the_index(&admin, &env).await;
+
+    let url = image_url(&admin, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let cached_image = client.get_proxy_image(&url).await;
+
+    let response = client.purge_image_cache(Some(&url)).await;
+
+    let res: PurgedEntriesResponse = serde_json::from_str(&response.body).unwrap();
+
+    // The image is removed from the memory and the disk cache
+    assert_eq!(res.data, 2);
+    assert_json_ok_response(&response);
+
+    // Guests can only get cached images
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client.get_proxy_image(&url).await;
+
+    assert_ne!(response.bytes, cached_image.bytes);
+}
+
+#[tokio::test]
+async fn it_should_allow_admins_to_purge_the_whole_image_cache() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if env.is_shared() {
+        println!("test skipped. It modifies the settings of the shared environment.");
+        return;
+    }
+
+    let admin = new_logged_in_admin(&env).await;
+    allow_proxying_images_from_the_index(&admin, &env).await;
+
+    let url = image_url(&admin, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let _cached_image = client.get_proxy_image(&url).await;
+
+    let response = client.purge_whole_image_cache().await;
+
+    assert_json_ok_response(&response);
+
+    let response = client.get_image_cache_statistics().await;
+
+    let res: CacheStatisticsResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.memory_cache.entries, 0);
+    assert_eq!(res.data.disk_cache.map(|disk_cache| disk_cache.entries), Some(0));
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_purge_the_image_cache() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client.purge_whole_image_cache().await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_not_purge_the_image_cache_when_the_purge_target_is_not_given() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let response = client.purge_image_cache(None).await;
+
+    assert_eq!(response.status, 400);
+}
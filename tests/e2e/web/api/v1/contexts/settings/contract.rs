@@ -1,12 +1,13 @@
 //! API contract for `settings` context.
 
 use torrust_index::web::api;
+use torrust_index::web::api::server::v1::responses::ErrorResponseData;
 
 use crate::common::asserts::assert_json_ok_response;
 use crate::common::client::Client;
 use crate::common::contexts::settings::responses::{AllSettingsResponse, Public, PublicSettingsResponse, SiteNameResponse};
 use crate::e2e::environment::TestEnv;
-use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_admin;
+use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user};
 
 #[tokio::test]
 async fn it_should_allow_guests_to_get_the_public_settings() {
@@ -65,3 +66,108 @@ async fn it_should_allow_admins_to_get_all_the_settings() {
 
     assert_json_ok_response(&response);
 }
+
+#[tokio::test]
+async fn it_should_allow_admins_to_update_all_the_settings() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if env.is_shared() {
+        println!("test skipped. It modifies the settings of the shared environment.");
+        return;
+    }
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let mut new_settings = env.server_settings_masking_secrets().unwrap();
+
+    new_settings.website.name = "UPDATED NAME".to_string();
+
+    let response = client.update_settings(&new_settings).await;
+
+    let res: AllSettingsResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data, new_settings);
+
+    assert_json_ok_response(&response);
+
+    let response = client.get_site_name().await;
+
+    let res: SiteNameResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data, "UPDATED NAME");
+}
+
+#[tokio::test]
+async fn it_should_not_allow_admins_to_update_the_settings_with_invalid_values() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let mut new_settings = env.server_settings_masking_secrets().unwrap();
+
+    new_settings.tracker.url = "invalid tracker url".to_string();
+
+    let response = client.update_settings(&new_settings).await;
+
+    let res: ErrorResponseData = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.error, "Invalid settings.");
+    assert_eq!(response.status, 400);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_admins_to_update_the_settings_that_require_restarting_the_application() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let mut new_settings = env.server_settings_masking_secrets().unwrap();
+
+    new_settings.net.port += 1;
+
+    let response = client.update_settings(&new_settings).await;
+
+    let res: ErrorResponseData = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.error, "This setting can't be changed without restarting the application.");
+    assert_eq!(response.status, 400);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_admins_to_update_the_mail_commands_and_paths() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let mut new_settings = env.server_settings_masking_secrets().unwrap();
+
+    new_settings.mail.sendmail_command = "/bin/sh".to_owned();
+
+    let response = client.update_settings(&new_settings).await;
+
+    let res: ErrorResponseData = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.error, "This setting can only be changed in the configuration file.");
+    assert_eq!(response.status, 400);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_update_the_settings() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.update_settings(&env.server_settings_masking_secrets().unwrap()).await;
+
+    assert_eq!(response.status, 403);
+}
@@ -22,17 +22,18 @@ mod for_guests {
 
     use crate::common::client::Client;
     use crate::common::contexts::category::fixtures::software_predefined_category_id;
+    use crate::common::contexts::settings::ExtraTracker;
     use crate::common::contexts::torrent::asserts::assert_expected_torrent_details;
     use crate::common::contexts::torrent::fixtures::{random_torrent, TestTorrent};
     use crate::common::contexts::torrent::forms::UploadTorrentMultipartForm;
     use crate::common::contexts::torrent::requests::InfoHash;
     use crate::common::contexts::torrent::responses::{
-        Category, File, TorrentDetails, TorrentDetailsResponse, TorrentListResponse,
+        Category, File, TorrentDetails, TorrentDetailsResponse, TorrentListResponse, TorrentStatsHistoryResponse,
     };
     use crate::common::http::{Query, QueryParam};
     use crate::e2e::environment::TestEnv;
     use crate::e2e::web::api::v1::contexts::torrent::steps::{upload_random_torrent_to_index, upload_test_torrent};
-    use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_user;
+    use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user};
 
     #[tokio::test]
     async fn it_should_allow_guests_to_get_torrents() {
@@ -202,6 +203,10 @@ mod for_guests {
             // is logged in. If the user is not logged in, it adds the default
             // tracker again, and it ends up with two trackers.
             trackers: vec![tracker_url.clone()],
+            web_seeds: vec![],
+            http_seeds: vec![],
+            poster: None,
+            screenshots: vec![],
             magnet_link: format!(
                 // cspell:disable-next-line
                 "magnet:?xt=urn:btih:{}&dn={}&tr={}",
@@ -405,6 +410,133 @@ mod for_guests {
         );
     }
 
+    #[tokio::test]
+    async fn it_should_add_the_extra_trackers_to_the_announce_list_of_the_downloaded_torrent() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        if env.is_shared() {
+            println!("test skipped. It modifies the settings of the shared environment.");
+            return;
+        }
+
+        let extra_tracker_url = "udp://tracker.example.com:6969/announce".to_string();
+
+        let logged_in_admin = new_logged_in_admin(&env).await;
+        let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+        let mut new_settings = env.server_settings_masking_secrets().unwrap();
+        new_settings.tracker.extra_trackers = vec![ExtraTracker {
+            url: extra_tracker_url.clone(),
+            statistics_source: "None".to_string(),
+            add_to_announce_list: true,
+        }];
+        admin_client.update_settings(&new_settings).await;
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+        let uploader = new_logged_in_user(&env).await;
+        let (test_torrent, _torrent_listed_in_index) = upload_random_torrent_to_index(&uploader, &env).await;
+
+        let response = client.download_torrent(&test_torrent.file_info_hash()).await;
+
+        let torrent = decode_torrent(&response.bytes).expect("could not decode downloaded torrent");
+
+        assert_eq!(torrent.announce_list.unwrap().last(), Some(&vec![extra_tracker_url.clone()]));
+
+        let response = client.get_torrent(&test_torrent.file_info_hash()).await;
+
+        let torrent_details_response: TorrentDetailsResponse = serde_json::from_str(&response.body).unwrap();
+
+        assert!(torrent_details_response.data.trackers.contains(&extra_tracker_url));
+    }
+
+    #[tokio::test]
+    async fn it_should_allow_guests_to_get_torrents_sorted_by_trending() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let uploader = new_logged_in_user(&env).await;
+        let (_test_torrent, _indexed_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+        let response = client
+            .get_torrents(Query::with_params([QueryParam::new("sort", "TrendingDesc")].to_vec()))
+            .await;
+
+        let torrent_list_response: TorrentListResponse = serde_json::from_str(&response.body).unwrap();
+
+        assert!(torrent_list_response.data.total > 0);
+        assert!(response.is_json_and_ok());
+    }
+
+    #[tokio::test]
+    async fn it_should_count_the_torrent_file_downloads() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let uploader = new_logged_in_user(&env).await;
+        let (test_torrent, _indexed_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+        let downloader = new_logged_in_user(&env).await;
+        let authenticated_client = Client::authenticated(&env.server_socket_addr().unwrap(), &downloader.token);
+
+        client.download_torrent(&test_torrent.file_info_hash()).await;
+        authenticated_client.download_torrent(&test_torrent.file_info_hash()).await;
+
+        let response = client
+            .get_torrents(Query::with_params(
+                [
+                    QueryParam::new("search", &test_torrent.index_info.title),
+                    QueryParam::new("sort", "TimesDownloadedDesc"),
+                ]
+                .to_vec(),
+            ))
+            .await;
+
+        let torrent_list_response: TorrentListResponse = serde_json::from_str(&response.body).unwrap();
+
+        assert!(response.is_json_and_ok());
+        assert_eq!(torrent_list_response.data.total, 1);
+        assert_eq!(torrent_list_response.data.results[0].times_downloaded, 2);
+        assert_eq!(torrent_list_response.data.results[0].times_completed, 0);
+    }
+
+    #[tokio::test]
+    async fn it_should_allow_guests_to_get_the_torrent_stats_history() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let uploader = new_logged_in_user(&env).await;
+        let (test_torrent, _indexed_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+        let response = client.get_torrent_stats_history(&test_torrent.file_info_hash()).await;
+
+        let stats_history_response: TorrentStatsHistoryResponse = serde_json::from_str(&response.body).unwrap();
+
+        // No snapshots until the statistics importer runs
+        assert!(stats_history_response.data.is_empty());
+        assert!(response.is_json_and_ok());
+    }
+
+    #[tokio::test]
+    async fn it_should_return_a_not_found_trying_to_get_the_stats_history_for_a_non_existing_torrent() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let non_existing_info_hash: InfoHash = "443c7602b4fde83d1154d6d9da48808418b181b6".to_string();
+
+        let response = client.get_torrent_stats_history(&non_existing_info_hash).await;
+
+        assert_eq!(response.status, 404);
+    }
+
     #[tokio::test]
     async fn it_should_return_a_not_found_response_trying_to_get_the_torrent_info_for_a_non_existing_torrent() {
         let mut env = TestEnv::new();
@@ -774,11 +906,26 @@ mod for_authenticated_users {
         use torrust_index::web::api;
 
         use crate::common::client::Client;
-        use crate::common::contexts::torrent::forms::UpdateTorrentFrom;
+        use crate::common::contexts::torrent::forms::{ApplyDeadTorrentPolicyForm, UpdateTorrentFrom};
         use crate::e2e::environment::TestEnv;
         use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
         use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_user;
 
+        #[tokio::test]
+        async fn it_should_not_allow_non_admins_to_apply_the_dead_torrent_policy() {
+            let mut env = TestEnv::new();
+            env.start(api::Version::V1).await;
+
+            let logged_in_user = new_logged_in_user(&env).await;
+            let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+            let response = client
+                .apply_dead_torrent_policy(ApplyDeadTorrentPolicyForm { dry_run: true })
+                .await;
+
+            assert_eq!(response.status, 403);
+        }
+
         #[tokio::test]
         async fn it_should_not_allow_non_admins_to_delete_torrents() {
             let mut env = TestEnv::new();
@@ -828,6 +975,7 @@ mod for_authenticated_users {
                         description: Some(new_description.clone()),
                         category: None,
                         tags: None,
+                        web_seeds: None,
                     },
                 )
                 .await;
@@ -838,11 +986,12 @@ mod for_authenticated_users {
 
     mod and_torrent_owners {
 
+        use torrust_index::utils::parse_torrent::decode_torrent;
         use torrust_index::web::api;
 
         use crate::common::client::Client;
         use crate::common::contexts::torrent::forms::UpdateTorrentFrom;
-        use crate::common::contexts::torrent::responses::UpdatedTorrentResponse;
+        use crate::common::contexts::torrent::responses::{TorrentDetailsResponse, UpdatedTorrentResponse};
         use crate::e2e::environment::TestEnv;
         use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
         use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_user;
@@ -873,6 +1022,7 @@ mod for_authenticated_users {
                         description: Some(new_description.clone()),
                         category: None,
                         tags: None,
+                        web_seeds: None,
                     },
                 )
                 .await;
@@ -885,18 +1035,99 @@ mod for_authenticated_users {
             assert_eq!(torrent.description, new_description);
             assert!(response.is_json_and_ok());
         }
+
+        #[tokio::test]
+        async fn it_should_allow_torrent_owners_to_update_the_web_seeds_of_their_torrents() {
+            let mut env = TestEnv::new();
+            env.start(api::Version::V1).await;
+
+            if !env.provides_a_tracker() {
+                println!("test skipped. It requires a tracker to be running.");
+                return;
+            }
+
+            let uploader = new_logged_in_user(&env).await;
+            let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+            let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+            let web_seed_url = "https://seed.example.com/files/".to_string();
+
+            let response = client
+                .update_torrent(
+                    &test_torrent.file_info_hash(),
+                    UpdateTorrentFrom {
+                        title: None,
+                        description: None,
+                        category: None,
+                        tags: None,
+                        web_seeds: Some(vec![web_seed_url.clone(), web_seed_url.clone()]),
+                    },
+                )
+                .await;
+
+            let updated_torrent_response: UpdatedTorrentResponse = serde_json::from_str(&response.body).unwrap();
+
+            assert_eq!(updated_torrent_response.data.web_seeds, vec![web_seed_url.clone()]);
+
+            let response = client.get_torrent(&test_torrent.file_info_hash()).await;
+
+            let torrent_details_response: TorrentDetailsResponse = serde_json::from_str(&response.body).unwrap();
+
+            assert_eq!(torrent_details_response.data.web_seeds, vec![web_seed_url.clone()]);
+
+            // The web seeds are included in the downloaded torrent file
+            let response = client.download_torrent(&test_torrent.file_info_hash()).await;
+
+            let torrent = decode_torrent(&response.bytes).expect("could not decode downloaded torrent");
+
+            assert_eq!(torrent.url_list, Some(vec![web_seed_url]));
+        }
+
+        #[tokio::test]
+        async fn it_should_not_allow_invalid_web_seed_urls() {
+            let mut env = TestEnv::new();
+            env.start(api::Version::V1).await;
+
+            let uploader = new_logged_in_user(&env).await;
+            let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+            let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+            let response = client
+                .update_torrent(
+                    &test_torrent.file_info_hash(),
+                    UpdateTorrentFrom {
+                        title: None,
+                        description: None,
+                        category: None,
+                        tags: None,
+                        web_seeds: Some(vec!["ftp://seed.example.com/files/".to_string()]),
+                    },
+                )
+                .await;
+
+            assert_eq!(response.status, 400);
+        }
     }
 
     mod and_admins {
 
+        use std::time::Duration;
+
         use torrust_index::web::api;
 
         use crate::common::client::Client;
-        use crate::common::contexts::torrent::forms::UpdateTorrentFrom;
-        use crate::common::contexts::torrent::responses::{DeletedTorrentResponse, UpdatedTorrentResponse};
+        use crate::common::contexts::torrent::forms::{ApplyDeadTorrentPolicyForm, UpdateTorrentFrom};
+        use crate::common::contexts::torrent::responses::{
+            DeadTorrentsReport, DeadTorrentsReportResponse, DeletedTorrentResponse, TorrentListResponse, UpdatedTorrentResponse,
+        };
+        use crate::common::http::{Query, QueryParam};
         use crate::e2e::environment::TestEnv;
         use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
-        use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user};
+        use crate::e2e::web::api::v1::contexts::user::steps::{
+            enable_email_verification, new_logged_in_admin, new_logged_in_user,
+        };
 
         #[tokio::test]
         async fn it_should_allow_admins_to_delete_torrents_searching_by_info_hash() {
@@ -949,6 +1180,7 @@ mod for_authenticated_users {
                         description: Some(new_description.clone()),
                         category: None,
                         tags: None,
+                        web_seeds: None,
                     },
                 )
                 .await;
@@ -961,5 +1193,119 @@ mod for_authenticated_users {
             assert_eq!(torrent.description, new_description);
             assert!(response.is_json_and_ok());
         }
+
+        #[tokio::test]
+        async fn it_should_allow_admins_to_mark_torrents_without_seeders_as_dead_and_archive_them() {
+            let mut env = TestEnv::new();
+            env.start(api::Version::V1).await;
+
+            if env.is_shared() {
+                println!("test skipped. It modifies the settings of the shared environment.");
+                return;
+            }
+
+            let logged_in_admin = new_logged_in_admin(&env).await;
+            let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+            let mut new_settings = env.server_settings_masking_secrets().unwrap();
+            new_settings.dead_torrents.dead_after_days = 0;
+            new_settings.dead_torrents.grace_period_days = 0;
+            new_settings.dead_torrents.hide_dead_torrents = true;
+            new_settings.dead_torrents.notify_uploader = false;
+            admin_client.update_settings(&new_settings).await;
+
+            // The torrent is never seeded because there is no tracker
+            let uploader = new_logged_in_user(&env).await;
+            let (test_torrent, uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+            let search_by_title = || Query::with_params([QueryParam::new("search", &test_torrent.index_info.title)].to_vec());
+
+            // Dates are stored with a precision of one second
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let response = admin_client
+                .apply_dead_torrent_policy(ApplyDeadTorrentPolicyForm { dry_run: true })
+                .await;
+
+            let report = serde_json::from_str::<DeadTorrentsReportResponse>(&response.body)
+                .unwrap()
+                .data;
+
+            assert!(report.dry_run);
+            assert!(DeadTorrentsReport::torrent_ids(&report.marked_as_dead).contains(&uploaded_torrent.torrent_id));
+
+            let response = admin_client.get_torrents(search_by_title()).await;
+            let torrent_list_response: TorrentListResponse = serde_json::from_str(&response.body).unwrap();
+            assert_eq!(torrent_list_response.data.total, 1);
+            assert_eq!(torrent_list_response.data.results[0].dead_since, None);
+
+            let response = admin_client
+                .apply_dead_torrent_policy(ApplyDeadTorrentPolicyForm { dry_run: false })
+                .await;
+
+            let report = serde_json::from_str::<DeadTorrentsReportResponse>(&response.body)
+                .unwrap()
+                .data;
+
+            assert!(DeadTorrentsReport::torrent_ids(&report.marked_as_dead).contains(&uploaded_torrent.torrent_id));
+            assert!(report.pruned.is_empty());
+
+            let response = admin_client.get_torrents(search_by_title()).await;
+            let torrent_list_response: TorrentListResponse = serde_json::from_str(&response.body).unwrap();
+            assert_eq!(torrent_list_response.data.total, 0);
+
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let response = admin_client
+                .apply_dead_torrent_policy(ApplyDeadTorrentPolicyForm { dry_run: false })
+                .await;
+
+            let report = serde_json::from_str::<DeadTorrentsReportResponse>(&response.body)
+                .unwrap()
+                .data;
+
+            assert_eq!(report.prune_action, "Archive");
+            assert_eq!(
+                DeadTorrentsReport::torrent_ids(&report.pruned),
+                vec![uploaded_torrent.torrent_id]
+            );
+            assert!(report.failed.is_empty());
+        }
+
+        #[tokio::test]
+        async fn it_should_not_email_the_uploaders_of_dead_torrents_with_an_unverified_email() {
+            let mut env = TestEnv::new();
+            env.start(api::Version::V1).await;
+
+            let Some(logged_in_admin) = enable_email_verification(&env, |settings| {
+                settings.email_verification.required_for_login = false;
+                settings.dead_torrents.dead_after_days = 0;
+                settings.dead_torrents.notify_uploader = true;
+            })
+            .await
+            else {
+                return;
+            };
+
+            let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+            // The torrent is never seeded because there is no tracker
+            let uploader = new_logged_in_user(&env).await;
+            let (_test_torrent, uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+            // Dates are stored with a precision of one second
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let response = admin_client
+                .apply_dead_torrent_policy(ApplyDeadTorrentPolicyForm { dry_run: true })
+                .await;
+
+            let report = serde_json::from_str::<DeadTorrentsReportResponse>(&response.body)
+                .unwrap()
+                .data;
+
+            assert!(DeadTorrentsReport::torrent_ids(&report.marked_as_dead).contains(&uploaded_torrent.torrent_id));
+            assert_eq!(report.notified, 0);
+        }
     }
 }
@@ -0,0 +1,216 @@
+//! API contract for `tracker` context.
+
+use std::time::Duration;
+
+use torrust_index::web::api;
+
+use crate::common::asserts::assert_json_ok_response;
+use crate::common::client::Client;
+use crate::common::contexts::tracker::forms::{ReconcileWhitelistForm, StartModeSyncForm};
+use crate::common::contexts::tracker::responses::{
+    OptionalTrackerModeSyncResponse, ReconciliationReportResponse, TrackerJobsResponse, TrackerModeSync, TrackerModeSyncResponse,
+};
+use crate::common::contexts::user::responses::LoggedInUserData;
+use crate::common::http::{Query, QueryParam};
+use crate::e2e::environment::TestEnv;
+use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
+use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user};
+
+#[tokio::test]
+async fn it_should_allow_admins_to_reconcile_the_tracker_whitelist_in_dry_run_mode() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client.reconcile_tracker_whitelist(ReconcileWhitelistForm { dry_run: true }).await;
+
+    let res: ReconciliationReportResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert!(res.data.dry_run);
+    assert_eq!(res.data.added, 0);
+    assert_eq!(res.data.removed, 0);
+
+    assert_json_ok_response(&response);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_reconcile_the_tracker_whitelist() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.reconcile_tracker_whitelist(ReconcileWhitelistForm { dry_run: true }).await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_accept_uploads_and_keep_the_tracker_jobs_pending_when_the_tracker_is_not_available() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if env.is_shared() {
+        println!("test skipped. It modifies the settings of the shared environment.");
+        return;
+    }
+
+    let uploader = new_logged_in_admin(&env).await;
+
+    use_an_unavailable_tracker(&uploader, &env).await;
+
+    let (test_torrent, _indexed_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client
+        .get_tracker_jobs(Query::with_params([QueryParam::new("status", "Pending")].to_vec()))
+        .await;
+
+    let res: TrackerJobsResponse = serde_json::from_str(&response.body).unwrap();
+
+    let jobs_for_torrent: Vec<&str> = res
+        .data
+        .iter()
+        .filter(|job| job.info_hash == test_torrent.file_info_hash())
+        .map(|job| job.kind.as_str())
+        .collect();
+
+    assert!(jobs_for_torrent.contains(&"Whitelist"));
+    assert!(jobs_for_torrent.contains(&"ImportStatistics"));
+
+    assert_json_ok_response(&response);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_list_the_tracker_jobs() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.get_tracker_jobs(Query::empty()).await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_return_a_not_found_error_retrying_a_tracker_job_that_does_not_exist() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client.retry_tracker_job(i64::MAX).await;
+
+    assert_eq!(response.status, 404);
+}
+
+#[tokio::test]
+async fn it_should_allow_admins_to_sync_the_tracker_mode_enqueuing_a_tracker_job_for_each_torrent() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if env.is_shared() {
+        println!("test skipped. It modifies the tracker whitelist and the tracker keys of the shared environment.");
+        return;
+    }
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    // The jobs are kept in the queue, so they can be checked
+    use_an_unavailable_tracker(&logged_in_admin, &env).await;
+
+    let response = client.get_tracker_mode_sync().await;
+
+    let res: OptionalTrackerModeSyncResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data, None);
+
+    let (test_torrent, _indexed_torrent) = upload_random_torrent_to_index(&logged_in_admin, &env).await;
+
+    let response = client.start_tracker_mode_sync(StartModeSyncForm { force: false }).await;
+
+    let started_sync = serde_json::from_str::<TrackerModeSyncResponse>(&response.body).unwrap().data;
+
+    assert_eq!(started_sync.from_mode, None);
+    assert_eq!(started_sync.to_mode, "Public");
+    assert_eq!(started_sync.action, "RemoveFromWhitelist");
+    assert_eq!(started_sync.torrents_total, 1);
+
+    let completed_sync = wait_for_the_tracker_mode_sync_to_complete(&client).await;
+
+    assert_eq!(completed_sync.sync_id, started_sync.sync_id);
+    assert_eq!(completed_sync.torrents_processed, 1);
+    assert_eq!(completed_sync.errors, 0);
+
+    let response = client.get_tracker_jobs(Query::empty()).await;
+
+    let res: TrackerJobsResponse = serde_json::from_str(&response.body).unwrap();
+
+    let jobs_for_torrent: Vec<&str> = res
+        .data
+        .iter()
+        .filter(|job| job.info_hash == test_torrent.file_info_hash())
+        .map(|job| job.kind.as_str())
+        .collect();
+
+    // The pending whitelisting job is replaced by the removal
+    assert_eq!(jobs_for_torrent, vec!["RemoveFromWhitelist"]);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_sync_the_tracker_mode() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.start_tracker_mode_sync(StartModeSyncForm { force: true }).await;
+
+    assert_eq!(response.status, 403);
+
+    let response = client.get_tracker_mode_sync().await;
+
+    assert_eq!(response.status, 403);
+}
+
+/// It switches the tracker backend to the tracker API, which is not running in
+/// the isolated environment.
+async fn wait_for_the_tracker_mode_sync_to_complete(client: &Client) -> TrackerModeSync {
+    for _ in 0..50 {
+        let response = client.get_tracker_mode_sync().await;
+
+        let res: OptionalTrackerModeSyncResponse = serde_json::from_str(&response.body).unwrap();
+
+        if let Some(sync) = res.data {
+            if sync.status == "Completed" {
+                return sync;
+            }
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    panic!("the tracker mode sync did not complete");
+}
+
+async fn use_an_unavailable_tracker(logged_in_admin: &LoggedInUserData, env: &TestEnv) {
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let mut new_settings = env.server_settings_masking_secrets().unwrap();
+    new_settings.tracker.backend = "Api".to_string();
+    client.update_settings(&new_settings).await;
+}
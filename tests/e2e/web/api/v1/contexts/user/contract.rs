@@ -10,20 +10,11 @@ demonstrate how to write E2E tests. Some important pending tests could be:
 todo:
 
 - It should allow renewing a token one week before it expires.
-- It should allow verifying user registration via email.
 
-The first one requires to mock the time. Consider extracting the mod
+It requires to mock the time. Consider extracting the mod
 <https://github.com/torrust/torrust-tracker/tree/develop/src/shared/clock> into
 an independent crate.
 
-The second one requires:
-- To call the mailcatcher API to get the verification URL.
-- To enable email verification in the configuration.
-- To fix current tests to verify the email for newly created users.
-- To find out which email is the one that contains the verification URL for a
-given test. That maybe done using the email recipient if that's possible with
-the mailcatcher API.
-
 */
 
 mod registration {
@@ -50,6 +41,399 @@ mod registration {
     }
 }
 
+mod email_verification {
+
+    use std::path::Path;
+    use std::time::Duration;
+
+    use torrust_index::web::api;
+
+    use crate::common::asserts::assert_json_ok_response;
+    use crate::common::client::Client;
+    use crate::common::contexts::torrent::fixtures::random_torrent;
+    use crate::common::contexts::user::asserts::assert_added_user_response;
+    use crate::common::contexts::user::fixtures::random_user_registration_form;
+    use crate::common::contexts::user::forms::LoginForm;
+    use crate::common::mail::{unfold_soft_line_breaks, wait_for_emails_to};
+    use crate::e2e::environment::TestEnv;
+    use crate::e2e::web::api::v1::contexts::torrent::steps::upload_test_torrent;
+    use crate::e2e::web::api::v1::contexts::user::steps::{enable_email_verification, new_logged_in_user, new_registered_user};
+
+    const VERIFICATION_PATH: &str = "/v1/user/email/verify/";
+
+    #[tokio::test]
+    async fn it_should_allow_verifying_the_user_registration_via_email() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let Some(_admin) = enable_email_verification(&env, |_| {}).await else {
+            return;
+        };
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let form = random_user_registration_form();
+        let email = form.email.clone().unwrap();
+
+        let response = client.register_user(form).await;
+
+        assert_added_user_response(&response);
+
+        let emails = wait_for_emails_to(&env.server_settings().unwrap().mail.file_path, &email, 1).await;
+
+        assert_eq!(emails.len(), 1);
+        assert!(emails[0].contains("Subject: Torrust - Email verification"));
+
+        let token = verification_token_in(&emails[0]);
+
+        assert_eq!(verify_email(&env, &token).await, "Email verified, you can close this page.");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_using_a_verification_link_twice() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let Some(_admin) = enable_email_verification(&env, |_| {}).await else {
+            return;
+        };
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let form = random_user_registration_form();
+        let email = form.email.clone().unwrap();
+
+        client.register_user(form).await;
+
+        let emails = wait_for_emails_to(&env.server_settings().unwrap().mail.file_path, &email, 1).await;
+
+        let token = verification_token_in(&emails[0]);
+
+        assert_eq!(verify_email(&env, &token).await, "Email verified, you can close this page.");
+        assert_eq!(
+            verify_email(&env, &token).await,
+            "The email verification link is invalid or has expired."
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_using_a_verification_link_twice_after_purging_the_unverified_accounts() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let Some(_admin) = enable_email_verification(&env, |settings| {
+            settings.email_verification.purge_unverified_after_days = 1;
+        })
+        .await
+        else {
+            return;
+        };
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let form = random_user_registration_form();
+        let email = form.email.clone().unwrap();
+
+        client.register_user(form).await;
+
+        let emails = wait_for_emails_to(&env.server_settings().unwrap().mail.file_path, &email, 1).await;
+
+        let token = verification_token_in(&emails[0]);
+
+        assert_eq!(verify_email(&env, &token).await, "Email verified, you can close this page.");
+
+        // Let the cronjob purge the unverified accounts
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        assert_eq!(
+            verify_email(&env, &token).await,
+            "The email verification link is invalid or has expired."
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_using_an_expired_verification_link_after_deleting_the_expired_tokens() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        // The expired tokens are deleted when the purge of unverified accounts is disabled
+        let Some(_admin) = enable_email_verification(&env, |settings| {
+            settings.email_verification.token_lifetime_seconds = 1;
+            settings.email_verification.purge_unverified_after_days = 0;
+        })
+        .await
+        else {
+            return;
+        };
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let form = random_user_registration_form();
+        let email = form.email.clone().unwrap();
+
+        client.register_user(form).await;
+
+        let emails = wait_for_emails_to(&env.server_settings().unwrap().mail.file_path, &email, 1).await;
+
+        let token = verification_token_in(&emails[0]);
+
+        // Let the token expire and the cronjob delete it
+        tokio::time::sleep(Duration::from_secs(3)).await;
+
+        assert_eq!(
+            verify_email(&env, &token).await,
+            "The email verification link is invalid or has expired."
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_send_the_verification_email_in_the_user_locale() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let Some(_admin) = enable_email_verification(&env, |_| {}).await else {
+            return;
+        };
+
+        // The templates path is masked in the settings returned by the API
+        let templates_path = env.server_settings().unwrap().mail.templates_path;
+
+        std::fs::create_dir_all(&templates_path).unwrap();
+        std::fs::write(
+            Path::new(&templates_path).join("verify_email.es.subject.txt"),
+            "Verifique su email en {{ site_name }}",
+        )
+        .unwrap();
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let mut form = random_user_registration_form();
+        form.locale = Some("es-AR".to_string());
+        let email = form.email.clone().unwrap();
+
+        let response = client.register_user(form).await;
+
+        assert_added_user_response(&response);
+
+        let emails = wait_for_emails_to(&env.server_settings().unwrap().mail.file_path, &email, 1).await;
+
+        assert_eq!(emails.len(), 1);
+        assert!(emails[0].contains("Subject: Verifique su email en Torrust"));
+    }
+
+    #[tokio::test]
+    async fn it_should_allow_resending_the_verification_email() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let Some(_admin) = enable_email_verification(&env, |settings| {
+            settings.email_verification.resend_interval_seconds = 0;
+        })
+        .await
+        else {
+            return;
+        };
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let form = random_user_registration_form();
+        let email = form.email.clone().unwrap();
+
+        let response = client.register_user(form.clone()).await;
+
+        assert_added_user_response(&response);
+
+        let emails = wait_for_emails_to(&env.server_settings().unwrap().mail.file_path, &email, 1).await;
+
+        assert_eq!(emails.len(), 1);
+
+        let old_token = verification_token_in(&emails[0]);
+
+        let response = client
+            .resend_verification_email(LoginForm {
+                login: form.username.clone(),
+                password: form.password.clone(),
+            })
+            .await;
+
+        assert_json_ok_response(&response);
+
+        let emails = wait_for_emails_to(&env.server_settings().unwrap().mail.file_path, &email, 2).await;
+
+        assert_eq!(emails.len(), 2);
+
+        // Only the link in the last email is valid
+        let new_token = emails
+            .iter()
+            .map(|email| verification_token_in(email))
+            .find(|token| *token != old_token)
+            .unwrap();
+
+        assert_eq!(
+            verify_email(&env, &old_token).await,
+            "The email verification link is invalid or has expired."
+        );
+        assert_eq!(
+            verify_email(&env, &new_token).await,
+            "Email verified, you can close this page."
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_uploading_torrents_before_verifying_the_email_when_it_is_required() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let Some(_admin) = enable_email_verification(&env, |settings| {
+            settings.email_verification.required_for_login = false;
+            settings.email_verification.required_for_upload = true;
+        })
+        .await
+        else {
+            return;
+        };
+
+        // Unverified users can log in
+        let uploader = new_logged_in_user(&env).await;
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+        let result = upload_test_torrent(&client, &random_torrent()).await;
+
+        assert_eq!(
+            result.unwrap_err().error,
+            "Please verify your email before uploading torrents"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_not_resend_the_verification_email_too_often() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let Some(_admin) = enable_email_verification(&env, |settings| {
+            settings.email_verification.resend_interval_seconds = 3600;
+        })
+        .await
+        else {
+            return;
+        };
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let form = random_user_registration_form();
+
+        let response = client.register_user(form.clone()).await;
+
+        assert_added_user_response(&response);
+
+        // The verification email was just sent after the registration
+        let response = client
+            .resend_verification_email(LoginForm {
+                login: form.username.clone(),
+                password: form.password.clone(),
+            })
+            .await;
+
+        assert_eq!(response.status, 429);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_resend_the_verification_email_with_wrong_credentials() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let registered_user = new_registered_user(&env).await;
+
+        let response = client
+            .resend_verification_email(LoginForm {
+                login: registered_user.username.clone(),
+                password: "wrong password".to_string(),
+            })
+            .await;
+
+        assert_eq!(response.status, 403);
+    }
+
+    /// It returns the token in the verification link of an email.
+    fn verification_token_in(email: &str) -> String {
+        let email_contents = unfold_soft_line_breaks(email);
+
+        let token_start = email_contents.find(VERIFICATION_PATH).unwrap() + VERIFICATION_PATH.len();
+
+        email_contents[token_start..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || ['-', '_', '.'].contains(c))
+            .collect()
+    }
+
+    /// It opens the verification link and returns the text page shown.
+    async fn verify_email(env: &TestEnv, token: &str) -> String {
+        reqwest::get(format!(
+            "http://{}{VERIFICATION_PATH}{token}",
+            env.server_socket_addr().unwrap()
+        ))
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap()
+    }
+}
+
+mod locale {
+
+    use torrust_index::web::api;
+
+    use crate::common::asserts::assert_json_ok_response;
+    use crate::common::client::Client;
+    use crate::common::contexts::user::forms::LocaleForm;
+    use crate::common::contexts::user::responses::LocaleResponse;
+    use crate::e2e::environment::TestEnv;
+    use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_user;
+
+    #[tokio::test]
+    async fn it_should_allow_a_logged_in_user_to_set_their_locale() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let logged_in_user = new_logged_in_user(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+        let response = client
+            .update_locale(LocaleForm {
+                locale: Some("PT-br".to_string()),
+            })
+            .await;
+
+        let res: LocaleResponse = serde_json::from_str(&response.body).unwrap();
+
+        assert_eq!(res.data, Some("pt-BR".to_string()));
+        assert_json_ok_response(&response);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_setting_an_invalid_locale() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let logged_in_user = new_logged_in_user(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+        let response = client
+            .update_locale(LocaleForm {
+                locale: Some("../en".to_string()),
+            })
+            .await;
+
+        assert_eq!(response.status, 400);
+        assert!(response.body.contains("Invalid locale"));
+    }
+}
+
 mod authentication {
 
     use torrust_index::web::api;
@@ -174,3 +558,185 @@ mod banned_user_list {
         assert_eq!(response.status, 401);
     }
 }
+
+mod deleting_users {
+
+    use torrust_index::web::api;
+
+    use crate::common::client::Client;
+    use crate::common::contexts::user::forms::{LoginForm, Username};
+    use crate::common::contexts::user::responses::DeletedUserResponse;
+    use crate::e2e::environment::TestEnv;
+    use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
+    use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user, new_registered_user};
+
+    #[tokio::test]
+    async fn it_should_allow_an_admin_to_delete_a_user() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let logged_in_admin = new_logged_in_admin(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+        let registered_user = new_registered_user(&env).await;
+
+        let response = client.delete_user(Username::new(registered_user.username.clone())).await;
+
+        let deleted_user_response: DeletedUserResponse = serde_json::from_str(&response.body).unwrap();
+
+        assert_eq!(
+            deleted_user_response.data,
+            format!("Deleted user: {}", registered_user.username)
+        );
+        assert!(response.is_json_and_ok());
+
+        let response = Client::unauthenticated(&env.server_socket_addr().unwrap())
+            .login_user(LoginForm {
+                login: registered_user.username.clone(),
+                password: registered_user.password.clone(),
+            })
+            .await;
+
+        assert_ne!(response.status, 200);
+    }
+
+    #[tokio::test]
+    async fn it_should_delete_the_torrents_uploaded_by_the_deleted_user() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        if !env.provides_a_tracker() {
+            println!("test skipped. It requires a tracker to be running.");
+            return;
+        }
+
+        let uploader = new_logged_in_user(&env).await;
+        let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+        let logged_in_admin = new_logged_in_admin(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+        let response = client.delete_user(Username::new(uploader.username.clone())).await;
+
+        assert!(response.is_json_and_ok());
+
+        let response = client.get_torrent(&test_torrent.file_info_hash()).await;
+
+        assert_eq!(response.status, 404);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_a_non_admin_to_delete_a_user() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let logged_non_admin = new_logged_in_user(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_non_admin.token);
+
+        let registered_user = new_registered_user(&env).await;
+
+        let response = client.delete_user(Username::new(registered_user.username.clone())).await;
+
+        assert_eq!(response.status, 403);
+    }
+}
+
+mod tracker_keys {
+
+    use torrust_index::web::api;
+
+    use crate::common::client::Client;
+    use crate::common::contexts::user::forms::Username;
+    use crate::common::contexts::user::responses::{RevokedTrackerKeysResponse, TrackerKeyResponse};
+    use crate::e2e::environment::TestEnv;
+    use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user};
+
+    #[tokio::test]
+    async fn it_should_return_a_not_found_response_when_the_user_does_not_have_a_tracker_key() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let logged_in_user = new_logged_in_user(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+        let response = client.get_tracker_key().await;
+
+        assert_eq!(response.status, 404);
+    }
+
+    #[tokio::test]
+    async fn it_should_allow_a_logged_in_user_to_rotate_the_tracker_key() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        if !env.provides_a_tracker() {
+            println!("test skipped. It requires a tracker to be running.");
+            return;
+        }
+
+        let logged_in_user = new_logged_in_user(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+        let response = client.rotate_tracker_key().await;
+
+        let first_key = serde_json::from_str::<TrackerKeyResponse>(&response.body).unwrap().data;
+
+        let response = client.rotate_tracker_key().await;
+
+        let second_key = serde_json::from_str::<TrackerKeyResponse>(&response.body).unwrap().data;
+
+        assert_ne!(first_key.key, second_key.key);
+        assert!(second_key.announce_url.ends_with(&second_key.key));
+
+        let response = client.get_tracker_key().await;
+
+        let current_key = serde_json::from_str::<TrackerKeyResponse>(&response.body).unwrap().data;
+
+        assert_eq!(current_key, second_key);
+    }
+
+    #[tokio::test]
+    async fn it_should_allow_an_admin_to_revoke_the_tracker_keys_of_a_user() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let logged_in_admin = new_logged_in_admin(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+        let logged_in_user = new_logged_in_user(&env).await;
+
+        let response = client
+            .revoke_tracker_keys(Username::new(logged_in_user.username.clone()))
+            .await;
+
+        let revoked_tracker_keys_response: RevokedTrackerKeysResponse = serde_json::from_str(&response.body).unwrap();
+
+        assert_eq!(
+            revoked_tracker_keys_response.data,
+            format!("Revoked 0 tracker keys of user: {}", logged_in_user.username)
+        );
+        assert!(response.is_json_and_ok());
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_a_non_admin_to_revoke_the_tracker_keys_of_a_user() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let logged_non_admin = new_logged_in_user(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_non_admin.token);
+
+        let response = client
+            .revoke_tracker_keys(Username::new(logged_non_admin.username.clone()))
+            .await;
+
+        assert_eq!(response.status, 403);
+    }
+}
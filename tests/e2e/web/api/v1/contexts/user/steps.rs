@@ -3,6 +3,7 @@ use std::sync::Arc;
 use torrust_index::databases::database;
 
 use crate::common::client::Client;
+use crate::common::contexts::settings::Settings;
 use crate::common::contexts::user::fixtures::random_user_registration_form;
 use crate::common::contexts::user::forms::{LoginForm, RegisteredUser};
 use crate::common::contexts::user::responses::{LoggedInUserData, SuccessfulLoginResponse};
@@ -75,3 +76,32 @@ pub async fn new_registered_user(env: &TestEnv) -> RegisteredUser {
 
     registered_user
 }
+
+/// It enables email verification, changing the other settings with
+/// `update_settings` too, and returns the admin who changed them.
+///
+/// It returns `None` in the shared environment, whose settings can't be
+/// changed, so the test must be skipped.
+///
+/// # Panics
+///
+/// It panics if the settings are not updated.
+pub async fn enable_email_verification(env: &TestEnv, update_settings: impl FnOnce(&mut Settings)) -> Option<LoggedInUserData> {
+    if env.is_shared() {
+        println!("test skipped. It enables email verification in the settings of the shared environment.");
+        return None;
+    }
+
+    let admin = new_logged_in_admin(env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let mut new_settings = env.server_settings_masking_secrets().unwrap();
+    new_settings.mail.email_verification_enabled = true;
+    update_settings(&mut new_settings);
+
+    let response = client.update_settings(&new_settings).await;
+
+    assert_eq!(response.status, 200, "the settings should be updated: {}", response.body);
+
+    Some(admin)
+}
@@ -1,7 +1,7 @@
 use std::net::SocketAddr;
 
 use log::info;
-use tokio::sync::{oneshot, RwLock};
+use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 use torrust_index::config::Configuration;
 use torrust_index::web::api::Version;
@@ -33,10 +33,7 @@ impl AppStarter {
     ///
     /// Will panic if the app was dropped after spawning it.
     pub async fn start(&mut self, api_version: Version) {
-        let configuration = Configuration {
-            settings: RwLock::new(self.configuration.clone()),
-            config_path: self.config_path.clone(),
-        };
+        let configuration = Configuration::new(self.configuration.clone(), self.config_path.clone());
 
         // Open a channel to communicate back with this function
         let (tx, rx) = oneshot::channel::<AppStartedMessage>();
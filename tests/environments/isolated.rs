@@ -1,6 +1,6 @@
 use tempfile::TempDir;
 use torrust_index::config;
-use torrust_index::config::FREE_PORT;
+use torrust_index::config::{MailTransport, TrackerBackendKind, FREE_PORT};
 use torrust_index::web::api::Version;
 
 use super::app_starter::AppStarter;
@@ -85,9 +85,29 @@ fn ephemeral(temp_dir: &TempDir) -> config::TorrustIndex {
     // Ephemeral Importer API port
     configuration.tracker_statistics_importer.port = FREE_PORT;
 
+    // In-memory tracker, so there is no need to run a tracker
+    configuration.tracker.backend = TrackerBackendKind::InMemory;
+
     // Ephemeral SQLite database
     configuration.database.connect_url = format!("sqlite://{}?mode=rwc", random_database_file_path_in(temp_dir));
 
+    // Ephemeral image disk cache
+    configuration.image_cache.disk_cache_path = temp_dir.path().join("image_cache").to_string_lossy().to_string();
+
+    // Ephemeral uploaded images directory
+    configuration.image_uploads.storage_path = temp_dir.path().join("images").to_string_lossy().to_string();
+
+    // Emails are written to a temporary Maildir directory, so tests can read them
+    configuration.mail.transport = MailTransport::File;
+    configuration.mail.file_path = temp_dir.path().join("mail").to_string_lossy().to_string();
+    configuration.mail.templates_path = temp_dir.path().join("templates").to_string_lossy().to_string();
+
+    // Unverified accounts and expired verification tokens are purged often
+    configuration.email_verification.purge_interval_seconds = 1;
+
+    // Notification digests are sent often
+    configuration.notifications.digest_interval_seconds = 1;
+
     configuration
 }
 